@@ -1,6 +1,8 @@
 use crate::tools::converters::{
     json_yaml, csv_json, markdown_html, number_base, timestamp, color, units, cron, case,
+    xml_json, binary_json,
 };
+use crate::tools::encoders::ByteEncoding;
 
 #[tauri::command]
 pub fn json_to_yaml_command(json: String, indent: usize) -> Result<String, String> {
@@ -12,13 +14,89 @@ pub fn yaml_to_json_command(yaml: String, indent: usize) -> Result<String, Strin
     json_yaml::yaml_to_json_command(yaml, indent)
 }
 
+#[tauri::command]
+pub fn json_to_yaml_with_options_command(
+    json: String,
+    options: Option<json_yaml::JsonYamlOptions>,
+) -> Result<json_yaml::JsonYamlConversion, String> {
+    json_yaml::json_to_yaml_with_options_command(json, options)
+}
+
+#[tauri::command]
+pub fn yaml_to_json_with_options_command(
+    yaml: String,
+    options: Option<json_yaml::JsonYamlOptions>,
+) -> Result<json_yaml::JsonYamlConversion, String> {
+    json_yaml::yaml_to_json_with_options_command(yaml, options)
+}
+
 #[tauri::command]
 pub fn csv_to_json_command(
     csv: String,
     delimiter: Option<String>,
     has_header: bool,
+    options: Option<csv_json::CsvToJsonOptions>,
+) -> Result<String, String> {
+    csv_json::csv_to_json_command(csv, delimiter, has_header, options)
+}
+
+#[tauri::command]
+pub fn csv_file_to_json_command(
+    file_path: String,
+    delimiter: Option<String>,
+    has_header: bool,
+    options: Option<csv_json::CsvToJsonOptions>,
+) -> Result<String, String> {
+    csv_json::csv_file_to_json_command(file_path, delimiter, has_header, options)
+}
+
+#[tauri::command]
+pub fn json_to_csv_command(
+    json: String,
+    options: Option<csv_json::JsonToCsvOptions>,
+) -> Result<String, String> {
+    csv_json::json_to_csv_command(json, options)
+}
+
+#[tauri::command]
+pub fn detect_csv_delimiter_command(csv: String, quote: Option<char>) -> String {
+    let quote = quote.filter(char::is_ascii).unwrap_or('"') as u8;
+    csv_json::detect_delimiter(&csv, quote).to_string()
+}
+
+#[tauri::command]
+pub fn xml_to_json_command(
+    xml: String,
+    options: Option<xml_json::XmlJsonOptions>,
 ) -> Result<String, String> {
-    csv_json::csv_to_json_command(csv, delimiter, has_header)
+    xml_json::xml_to_json_command(xml, options)
+}
+
+#[tauri::command]
+pub fn json_to_xml_command(
+    json: String,
+    options: Option<xml_json::XmlJsonOptions>,
+) -> Result<String, String> {
+    xml_json::json_to_xml_command(json, options)
+}
+
+#[tauri::command]
+pub fn decode_binary_format_command(
+    input: String,
+    format: binary_json::BinaryFormat,
+    encoding: ByteEncoding,
+    indent: usize,
+) -> Result<binary_json::BinaryDecodeResult, String> {
+    binary_json::decode_binary_format_command(input, format, encoding, indent)
+}
+
+#[tauri::command]
+pub fn encode_binary_format_command(
+    json: String,
+    format: binary_json::BinaryFormat,
+    encoding: ByteEncoding,
+) -> Result<binary_json::BinaryEncodeResult, String> {
+    binary_json::encode_binary_format_command(json, format, encoding)
 }
 
 #[tauri::command]
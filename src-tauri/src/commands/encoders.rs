@@ -1,40 +1,127 @@
-use crate::tools::encoders::base64::{decode_base64_text, encode_base64_text};
-use crate::tools::encoders::url::{decode_url, encode_url};
-use crate::tools::encoders::hex::{hex_to_string, string_to_hex};
-use crate::tools::encoders::html::{decode_html, encode_html_named, encode_html_numeric, encode_html_hex};
-use crate::tools::encoders::punycode::{encode_punycode, decode_punycode};
-use crate::tools::encoders::morse::{encode_morse, decode_morse};
+use crate::tools::encoders::base64::{decode_base64, encode_base64};
+use crate::tools::encoders::url::{decode_url_detailed, decode_url_mode, encode_url_mode, UrlDecodeReport, UrlEncodeMode};
+use crate::tools::encoders::hex::hex_to_bytes;
+use crate::tools::encoders::html::{
+    decode_html, decode_html_attribute, encode_html_hex, encode_html_named, encode_html_numeric, escape_html_context,
+    HtmlContext,
+};
+use crate::tools::encoders::punycode::{convert_idna, IdnaDirection, IdnaResult};
+use crate::tools::encoders::morse::{decode_morse_with, encode_morse_with, MorseAlphabet};
+use crate::tools::encoders::morse_audio::{decode_morse_wav, morse_to_audio, MorseAudio, MorseAudioDecoded, MorseAudioOptions};
+use crate::tools::encoders::data_uri::{
+    encode_data_uri, parse_data_uri, save_data_uri, DataUri, DataUriOptions, ParsedDataUri,
+};
 use crate::tools::encoders::image::{encode_image_to_base64, decode_image_from_base64, get_extension_from_mime};
+use crate::tools::encoders::base_n::{decode_base_n, detect_base_n, encode_base_n, BaseEncoding, BaseNDecoded, BaseNOptions};
+use crate::tools::encoders::text_encoding::{
+    decode_bytes, encode_text, read_input_bytes, render_output_bytes, DecodedText, InputSource, TextCodecOptions,
+    TextEncoding, UnmappableChar,
+};
+use crate::tools::encoders::hexdump::{hexdump_bytes, hexdump_file, reverse_hexdump, HexdumpOptions, HexdumpPage};
+use crate::tools::encoders::unicode::{inspect_unicode, normalize_unicode, NormalizationForm, UnicodeInspection};
+use crate::tools::encoders::escape::{escape_string, unescape_string, EscapeFormat};
+use crate::tools::encoders::cipher::{
+    atbash, caesar, caesar_brute_force, decode_vigenere, encode_vigenere, frequency_analysis, rot13, rot47,
+    xor_repeating_key, CaesarCandidate, FrequencyReport,
+};
+use crate::tools::encoders::ByteEncoding;
 
+// Encoders that take `options` read their input as text in the chosen encoding,
+// a file path, hex or base64, and decoders can emit text in any encoding or raw bytes.
 #[tauri::command]
-pub async fn encode_base64_command(text: String) -> String {
-    encode_base64_text(&text)
+pub async fn encode_base64_command(text: String, options: Option<TextCodecOptions>) -> Result<String, String> {
+    let bytes = read_input_bytes(&text, &options.unwrap_or_default()).map_err(|e| e.to_string())?;
+    Ok(encode_base64(&bytes))
 }
 
 #[tauri::command]
-pub async fn decode_base64_command(encoded: String) -> Result<String, String> {
-    decode_base64_text(&encoded).map_err(|e| e.to_string())
+pub async fn decode_base64_command(encoded: String, options: Option<TextCodecOptions>) -> Result<String, String> {
+    let bytes = decode_base64(encoded.trim()).map_err(|e| e.to_string())?;
+    render_output_bytes(&bytes, &options.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn encode_url_command(text: String) -> String {
-    encode_url(&text)
+pub async fn encode_url_command(
+    text: String,
+    options: Option<TextCodecOptions>,
+    mode: Option<UrlEncodeMode>,
+) -> Result<String, String> {
+    let bytes = read_input_bytes(&text, &options.unwrap_or_default()).map_err(|e| e.to_string())?;
+    Ok(encode_url_mode(&bytes, mode.unwrap_or_default()))
 }
 
 #[tauri::command]
-pub async fn decode_url_command(encoded: String) -> Result<String, String> {
-    decode_url(&encoded).map_err(|e| e.to_string())
+pub async fn decode_url_command(
+    encoded: String,
+    options: Option<TextCodecOptions>,
+    mode: Option<UrlEncodeMode>,
+) -> Result<String, String> {
+    let bytes = decode_url_mode(&encoded, mode.unwrap_or_default());
+    render_output_bytes(&bytes, &options.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn inspect_url_encoding_command(encoded: String, mode: Option<UrlEncodeMode>) -> UrlDecodeReport {
+    decode_url_detailed(&encoded, mode.unwrap_or_default())
 }
 
 // Hex converter commands
 #[tauri::command]
-pub async fn string_to_hex_command(text: String) -> String {
-    string_to_hex(&text)
+pub async fn string_to_hex_command(text: String, options: Option<TextCodecOptions>) -> Result<String, String> {
+    let bytes = read_input_bytes(&text, &options.unwrap_or_default()).map_err(|e| e.to_string())?;
+    Ok(hex::encode(bytes))
+}
+
+#[tauri::command]
+pub async fn hex_to_string_command(hex: String, options: Option<TextCodecOptions>) -> Result<String, String> {
+    let bytes = hex_to_bytes(&hex).map_err(|e| e.to_string())?;
+    render_output_bytes(&bytes, &options.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
+// Text encoding commands
 #[tauri::command]
-pub async fn hex_to_string_command(hex: String) -> Result<String, String> {
-    hex_to_string(&hex).map_err(|e| e.to_string())
+pub async fn decode_text_bytes_command(input: String, options: Option<TextCodecOptions>) -> Result<DecodedText, String> {
+    let options = options.unwrap_or_default();
+    let bytes = read_input_bytes(&input, &options).map_err(|e| e.to_string())?;
+    decode_bytes(&bytes, options.encoding, options.mode).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn encode_text_bytes_command(
+    text: String,
+    options: Option<TextCodecOptions>,
+    output: ByteEncoding,
+) -> Result<(String, Vec<UnmappableChar>), String> {
+    let options = options.unwrap_or_default();
+    let encoding = options.encoding.unwrap_or(TextEncoding::Utf8);
+    let encoded = encode_text(&text, encoding, options.bom, options.mode).map_err(|e| e.to_string())?;
+    Ok((output.encode(&encoded.bytes), encoded.unmappable))
+}
+
+// Hexdump commands
+#[tauri::command]
+pub async fn hexdump_command(
+    input: String,
+    options: Option<HexdumpOptions>,
+    input_options: Option<TextCodecOptions>,
+) -> Result<HexdumpPage, String> {
+    let options = options.unwrap_or_default();
+    let input_options = input_options.unwrap_or_default();
+    if input_options.source == InputSource::File {
+        return hexdump_file(input.trim(), &options).map_err(|e| e.to_string());
+    }
+    let bytes = read_input_bytes(&input, &input_options).map_err(|e| e.to_string())?;
+    hexdump_bytes(&bytes, &options).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reverse_hexdump_command(
+    dump: String,
+    options: Option<HexdumpOptions>,
+    output: ByteEncoding,
+) -> Result<String, String> {
+    let bytes = reverse_hexdump(&dump, &options.unwrap_or_default()).map_err(|e| e.to_string())?;
+    Ok(output.encode(&bytes))
 }
 
 // HTML entities commands
@@ -48,30 +135,79 @@ pub async fn encode_html_command(text: String, format: String) -> String {
 }
 
 #[tauri::command]
-pub async fn decode_html_command(encoded: String) -> Result<String, String> {
-    decode_html(&encoded).map_err(|e| e.to_string())
+pub async fn decode_html_command(encoded: String, in_attribute: Option<bool>) -> String {
+    if in_attribute.unwrap_or(false) {
+        decode_html_attribute(&encoded)
+    } else {
+        decode_html(&encoded)
+    }
+}
+
+#[tauri::command]
+pub async fn escape_html_context_command(text: String, context: HtmlContext) -> String {
+    escape_html_context(&text, context)
 }
 
 // Punycode commands
+// Accept bare domains, URLs and email addresses; only the host part is converted.
+fn idna_output(input: &str, direction: IdnaDirection) -> Result<String, String> {
+    let result = convert_idna(input, direction).map_err(|e| e.to_string())?;
+    if result.valid {
+        Ok(result.output)
+    } else {
+        Err(result.errors.join("; "))
+    }
+}
+
 #[tauri::command]
 pub async fn encode_punycode_command(domain: String) -> Result<String, String> {
-    encode_punycode(&domain).map_err(|e| e.to_string())
+    idna_output(&domain, IdnaDirection::ToAscii)
 }
 
 #[tauri::command]
 pub async fn decode_punycode_command(encoded: String) -> Result<String, String> {
-    decode_punycode(&encoded).map_err(|e| e.to_string())
+    idna_output(&encoded, IdnaDirection::ToUnicode)
+}
+
+#[tauri::command]
+pub async fn inspect_idna_command(input: String, direction: IdnaDirection) -> Result<IdnaResult, String> {
+    convert_idna(&input, direction).map_err(|e| e.to_string())
 }
 
 // Morse code commands
 #[tauri::command]
-pub async fn encode_morse_command(text: String) -> Result<String, String> {
-    encode_morse(&text).map_err(|e| e.to_string())
+pub async fn encode_morse_command(text: String, alphabet: Option<MorseAlphabet>) -> Result<String, String> {
+    encode_morse_with(&text, alphabet.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn decode_morse_command(morse: String, alphabet: Option<MorseAlphabet>) -> Result<String, String> {
+    decode_morse_with(&morse, alphabet.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn decode_morse_command(morse: String) -> Result<String, String> {
-    decode_morse(&morse).map_err(|e| e.to_string())
+pub async fn encode_morse_audio_command(
+    text: String,
+    alphabet: Option<MorseAlphabet>,
+    options: Option<MorseAudioOptions>,
+) -> Result<MorseAudio, String> {
+    morse_to_audio(&text, alphabet.unwrap_or_default(), &options.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Accepts a WAV file path or a `data:audio/wav;base64,` URL
+#[tauri::command]
+pub async fn decode_morse_audio_command(
+    input: String,
+    alphabet: Option<MorseAlphabet>,
+) -> Result<MorseAudioDecoded, String> {
+    let bytes = match input.strip_prefix("data:") {
+        Some(rest) => {
+            let (_, data) = rest.split_once(";base64,").ok_or("Only base64 data URLs are supported")?;
+            decode_base64(data.trim()).map_err(|e| e.to_string())?
+        }
+        None => std::fs::read(&input).map_err(|e| e.to_string())?,
+    };
+    decode_morse_wav(&bytes, alphabet.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 // Image to Base64 commands
@@ -86,3 +222,137 @@ pub async fn decode_image_from_base64_command(data_url: String) -> Result<(Vec<u
     let extension = get_extension_from_mime(&mime_type).to_string();
     Ok((bytes, mime_type, extension))
 }
+
+// Data URI commands
+#[tauri::command]
+pub async fn encode_data_uri_command(
+    input: String,
+    options: Option<DataUriOptions>,
+    input_options: Option<TextCodecOptions>,
+) -> Result<DataUri, String> {
+    let input_options = input_options.unwrap_or_default();
+    let bytes = read_input_bytes(&input, &input_options).map_err(|e| e.to_string())?;
+    let file_name = (input_options.source == InputSource::File).then(|| input.trim());
+    Ok(encode_data_uri(&bytes, file_name, &options.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub async fn parse_data_uri_command(data_uri: String) -> Result<ParsedDataUri, String> {
+    parse_data_uri(&data_uri).map_err(|e| e.to_string())
+}
+
+/// Returns the saved path, which gains an extension when `path` has none
+#[tauri::command]
+pub async fn save_data_uri_command(data_uri: String, path: String) -> Result<String, String> {
+    let saved = save_data_uri(&data_uri, &path).map_err(|e| e.to_string())?;
+    Ok(saved.to_string_lossy().into_owned())
+}
+
+// Base32 / Base58 / Ascii85 / Base64URL / Bech32 commands
+#[tauri::command]
+pub async fn encode_base_n_command(
+    input: String,
+    encoding: BaseEncoding,
+    input_encoding: Option<ByteEncoding>,
+    options: Option<BaseNOptions>,
+) -> Result<String, String> {
+    let bytes = match input_encoding {
+        Some(source) => source.decode(&input)?,
+        None => input.into_bytes(),
+    };
+    encode_base_n(&bytes, encoding, &options.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn decode_base_n_command(input: String, encoding: BaseEncoding) -> Result<BaseNDecoded, String> {
+    decode_base_n(&input, encoding).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn detect_base_n_command(input: String) -> Vec<BaseNDecoded> {
+    detect_base_n(&input)
+}
+
+// Unicode inspector commands
+#[tauri::command]
+pub async fn inspect_unicode_command(text: String) -> UnicodeInspection {
+    inspect_unicode(&text)
+}
+
+#[tauri::command]
+pub async fn normalize_unicode_command(text: String, form: NormalizationForm) -> String {
+    normalize_unicode(&text, form)
+}
+
+// String escape commands
+#[tauri::command]
+pub async fn escape_string_command(text: String, format: EscapeFormat, ascii_only: Option<bool>) -> String {
+    escape_string(&text, format, ascii_only.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn unescape_string_command(text: String, format: EscapeFormat) -> Result<String, String> {
+    unescape_string(&text, format).map_err(|e| e.to_string())
+}
+
+// Classical cipher commands
+#[tauri::command]
+pub async fn rot13_command(text: String) -> String {
+    rot13(&text)
+}
+
+#[tauri::command]
+pub async fn rot47_command(text: String) -> String {
+    rot47(&text)
+}
+
+#[tauri::command]
+pub async fn caesar_command(text: String, shift: i32) -> String {
+    caesar(&text, shift)
+}
+
+#[tauri::command]
+pub async fn caesar_brute_force_command(text: String) -> Vec<CaesarCandidate> {
+    caesar_brute_force(&text)
+}
+
+#[tauri::command]
+pub async fn atbash_command(text: String) -> String {
+    atbash(&text)
+}
+
+#[tauri::command]
+pub async fn encode_vigenere_command(text: String, key: String) -> Result<String, String> {
+    encode_vigenere(&text, &key).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn decode_vigenere_command(text: String, key: String) -> Result<String, String> {
+    decode_vigenere(&text, &key).map_err(|e| e.to_string())
+}
+
+/// `input` and `key` are UTF-8 text unless an encoding is given; output defaults to hex
+#[tauri::command]
+pub async fn xor_command(
+    input: String,
+    key: String,
+    input_encoding: Option<ByteEncoding>,
+    key_encoding: Option<ByteEncoding>,
+    output: Option<ByteEncoding>,
+) -> Result<String, String> {
+    let data = match input_encoding {
+        Some(encoding) => encoding.decode(&input)?,
+        None => input.into_bytes(),
+    };
+    let key = match key_encoding {
+        Some(encoding) => encoding.decode(&key)?,
+        None => key.into_bytes(),
+    };
+    let result = xor_repeating_key(&data, &key).map_err(|e| e.to_string())?;
+    Ok(output.unwrap_or(ByteEncoding::Hex).encode(&result))
+}
+
+#[tauri::command]
+pub async fn frequency_analysis_command(text: String) -> FrequencyReport {
+    frequency_analysis(&text)
+}
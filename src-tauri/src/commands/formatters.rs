@@ -1,9 +1,24 @@
 use crate::tools::formatters::json::{format_json, minify_json, validate_json};
 use crate::tools::formatters::xml::{format_xml, minify_xml, validate_xml};
 use crate::tools::formatters::sql::{format_sql, minify_sql, validate_sql, SqlDialect};
-use crate::tools::formatters::css::{format_css, minify_css, validate_css};
+use crate::tools::formatters::css::{
+    css_stats, format_css, minify_css, transform_css, validate_css, CssStats, CssTransformOptions,
+    CssTransformResult,
+};
+use crate::tools::formatters::graphql::{
+    format_graphql, minify_graphql, validate_graphql, validate_graphql_query, GraphqlValidation,
+};
+use crate::tools::formatters::html::{format_html, minify_html, validate_html, HtmlValidation};
+use crate::tools::formatters::protobuf::{
+    encode_protobuf, format_proto, inspect_protobuf, validate_proto, ProtoValidation,
+    ProtobufInspection,
+};
+use crate::tools::encoders::ByteEncoding;
 use crate::tools::formatters::javascript::{format_js, minify_js, validate_js};
-use crate::tools::formatters::yaml::{format_yaml, minify_yaml, validate_yaml};
+use crate::tools::formatters::yaml::{
+    format_yaml, format_yaml_with_options, minify_yaml, validate_yaml, YamlFormatOptions,
+};
+use crate::tools::formatters::manifest_lint::{lint_manifest, LintReport, ManifestLintOptions};
 use crate::tools::formatters::rustfmt::{format_rust, validate_rust};
 
 #[tauri::command]
@@ -81,6 +96,92 @@ pub async fn validate_css_command(input: String) -> bool {
     validate_css(&input)
 }
 
+#[tauri::command]
+pub async fn transform_css_command(
+    input: String,
+    options: CssTransformOptions,
+) -> Result<CssTransformResult, String> {
+    transform_css(&input, &options).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn css_stats_command(input: String) -> Result<CssStats, String> {
+    css_stats(&input).map_err(|e| e.to_string())
+}
+
+// HTML commands
+#[tauri::command]
+pub async fn format_html_command(input: String, indent: usize) -> Result<String, String> {
+    format_html(&input, indent).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn minify_html_command(input: String) -> Result<String, String> {
+    minify_html(&input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_html_command(input: String) -> HtmlValidation {
+    validate_html(&input)
+}
+
+// GraphQL commands
+#[tauri::command]
+pub async fn format_graphql_command(input: String, indent: usize) -> Result<String, String> {
+    format_graphql(&input, indent).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn minify_graphql_command(input: String) -> Result<String, String> {
+    minify_graphql(&input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_graphql_command(input: String) -> GraphqlValidation {
+    validate_graphql(&input)
+}
+
+#[tauri::command]
+pub async fn validate_graphql_query_command(
+    query: String,
+    schema: String,
+) -> Result<GraphqlValidation, String> {
+    validate_graphql_query(&query, &schema).map_err(|e| e.to_string())
+}
+
+// Protocol Buffers commands
+#[tauri::command]
+pub async fn format_proto_command(input: String, indent: usize) -> Result<String, String> {
+    format_proto(&input, indent).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn validate_proto_command(input: String) -> ProtoValidation {
+    validate_proto(&input)
+}
+
+#[tauri::command]
+pub async fn decode_protobuf_command(
+    input: String,
+    encoding: ByteEncoding,
+    proto: Option<String>,
+    message_type: Option<String>,
+) -> Result<ProtobufInspection, String> {
+    let bytes = encoding.decode(&input)?;
+    inspect_protobuf(&bytes, proto.as_deref(), message_type.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn encode_protobuf_command(
+    json: String,
+    proto: String,
+    message_type: String,
+    encoding: ByteEncoding,
+) -> Result<String, String> {
+    let bytes = encode_protobuf(&json, &proto, &message_type).map_err(|e| e.to_string())?;
+    Ok(encoding.encode(&bytes))
+}
+
 // JavaScript commands
 #[tauri::command]
 pub async fn format_js_command(input: String, indent: usize) -> Result<String, String> {
@@ -103,6 +204,14 @@ pub async fn format_yaml_command(input: String, indent: usize) -> Result<String,
     format_yaml(&input, indent).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn format_yaml_with_options_command(
+    input: String,
+    options: YamlFormatOptions,
+) -> Result<String, String> {
+    format_yaml_with_options(&input, &options).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn minify_yaml_command(input: String) -> Result<String, String> {
     minify_yaml(&input).map_err(|e| e.to_string())
@@ -113,6 +222,15 @@ pub async fn validate_yaml_command(input: String) -> bool {
     validate_yaml(&input)
 }
 
+// Kubernetes / Docker Compose linter commands
+#[tauri::command]
+pub async fn lint_manifest_command(
+    input: String,
+    options: Option<ManifestLintOptions>,
+) -> Result<LintReport, String> {
+    lint_manifest(&input, &options.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
 // Rust formatter commands
 #[tauri::command]
 pub async fn format_rust_command(input: String) -> Result<String, String> {
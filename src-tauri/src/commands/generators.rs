@@ -1,11 +1,15 @@
 use crate::tools::generators::{
-    bcrypt_hash, bcrypt_verify, generate_all_hashes, generate_git_branch_name, generate_gitignore,
+    bcrypt_hash, bcrypt_verify, decrypt_file, decrypt_text, encrypt_file, encrypt_text, inspect_envelope,
+    generate_all_hashes, generate_git_branch_name, generate_gitignore,
     generate_hmac, generate_lorem, generate_password, generate_uuid, generate_multiple_uuids, validate_uuid,
-    generate_qr_code, generate_wifi_qr, generate_rsa_key_pair,
-    BcryptHashOutput, BranchNameOutput, GitignoreTemplate, HmacAlgorithm, LoremOutput,
+    generate_qr_code, generate_wifi_qr, generate_rsa_key_pair, generate_code_from_json,
+    convert_rsa_key, rsa_decrypt, rsa_encrypt, rsa_key_info, rsa_sign, rsa_verify,
+    BcryptHashOutput, BranchNameOutput, CodegenOptions, CodegenOutput, EncryptionOptions, EnvelopeInfo, GitignoreTemplate, HmacAlgorithm, LoremOutput,
     MultiHash, PasswordOutput, QrOutput, RsaKeyPair, RsaKeySize, UuidVersion,
+    ConvertedRsaKey, RsaEncryptionPadding, RsaHash, RsaKeyFormat, RsaKeyInfo, RsaKeyInput, RsaSignatureScheme,
     WifiCredentials,
 };
+use crate::tools::encoders::ByteEncoding;
 
 #[tauri::command]
 pub async fn generate_hash_command(input: String, algorithm: String) -> String {
@@ -171,7 +175,7 @@ pub async fn generate_gitignore_command(templates: Vec<String>) -> String {
 }
 
 #[tauri::command]
-pub async fn generate_rsa_key_pair_command(key_size: String) -> RsaKeyPair {
+pub async fn generate_rsa_key_pair_command(key_size: String) -> Result<RsaKeyPair, String> {
     let size = match key_size.as_str() {
         "2048" => RsaKeySize::Bits2048,
         "4096" => RsaKeySize::Bits4096,
@@ -180,6 +184,136 @@ pub async fn generate_rsa_key_pair_command(key_size: String) -> RsaKeyPair {
     generate_rsa_key_pair(&size)
 }
 
+/// `input` is UTF-8 text unless `input_encoding` is given; ciphertext defaults to base64
+#[tauri::command]
+pub async fn rsa_encrypt_command(
+    key: RsaKeyInput,
+    input: String,
+    input_encoding: Option<ByteEncoding>,
+    padding: Option<RsaEncryptionPadding>,
+    hash: Option<RsaHash>,
+    output: Option<ByteEncoding>,
+) -> Result<String, String> {
+    let data = match input_encoding {
+        Some(encoding) => encoding.decode(&input)?,
+        None => input.into_bytes(),
+    };
+    let ciphertext = rsa_encrypt(&key, &data, padding.unwrap_or_default(), hash.unwrap_or_default())?;
+    Ok(output.unwrap_or(ByteEncoding::Base64).encode(&ciphertext))
+}
+
+/// Returns UTF-8 text unless `output` is given
+#[tauri::command]
+pub async fn rsa_decrypt_command(
+    key: RsaKeyInput,
+    ciphertext: String,
+    ciphertext_encoding: Option<ByteEncoding>,
+    padding: Option<RsaEncryptionPadding>,
+    hash: Option<RsaHash>,
+    output: Option<ByteEncoding>,
+) -> Result<String, String> {
+    let data = ciphertext_encoding.unwrap_or(ByteEncoding::Base64).decode(&ciphertext)?;
+    let plaintext = rsa_decrypt(&key, &data, padding.unwrap_or_default(), hash.unwrap_or_default())?;
+    match output {
+        Some(encoding) => Ok(encoding.encode(&plaintext)),
+        None => String::from_utf8(plaintext)
+            .map_err(|_| "Decrypted data is not valid UTF-8; choose hex or base64 output".to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn rsa_sign_command(
+    key: RsaKeyInput,
+    message: String,
+    message_encoding: Option<ByteEncoding>,
+    scheme: Option<RsaSignatureScheme>,
+    hash: Option<RsaHash>,
+    output: Option<ByteEncoding>,
+) -> Result<String, String> {
+    let data = match message_encoding {
+        Some(encoding) => encoding.decode(&message)?,
+        None => message.into_bytes(),
+    };
+    let signature = rsa_sign(&key, &data, scheme.unwrap_or_default(), hash.unwrap_or_default())?;
+    Ok(output.unwrap_or(ByteEncoding::Base64).encode(&signature))
+}
+
+#[tauri::command]
+pub async fn rsa_verify_command(
+    key: RsaKeyInput,
+    message: String,
+    message_encoding: Option<ByteEncoding>,
+    signature: String,
+    signature_encoding: Option<ByteEncoding>,
+    scheme: Option<RsaSignatureScheme>,
+    hash: Option<RsaHash>,
+) -> Result<bool, String> {
+    let data = match message_encoding {
+        Some(encoding) => encoding.decode(&message)?,
+        None => message.into_bytes(),
+    };
+    let signature = signature_encoding.unwrap_or(ByteEncoding::Base64).decode(&signature)?;
+    rsa_verify(&key, &data, &signature, scheme.unwrap_or_default(), hash.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn rsa_key_info_command(key: RsaKeyInput) -> Result<RsaKeyInfo, String> {
+    rsa_key_info(&key)
+}
+
+#[tauri::command]
+pub async fn convert_rsa_key_command(
+    key: RsaKeyInput,
+    target: RsaKeyFormat,
+    new_password: Option<String>,
+) -> Result<ConvertedRsaKey, String> {
+    convert_rsa_key(&key, target, new_password.as_deref())
+}
+
+#[tauri::command]
+pub async fn encrypt_text_command(
+    text: String,
+    password: String,
+    options: Option<EncryptionOptions>,
+) -> Result<String, String> {
+    encrypt_text(&text, &password, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn decrypt_text_command(encrypted: String, password: String) -> Result<String, String> {
+    decrypt_text(&encrypted, &password)
+}
+
+#[tauri::command]
+pub async fn encrypt_file_command(
+    input_path: String,
+    output_path: String,
+    password: String,
+    options: Option<EncryptionOptions>,
+) -> Result<EnvelopeInfo, String> {
+    encrypt_file(&input_path, &output_path, &password, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn decrypt_file_command(
+    input_path: String,
+    output_path: String,
+    password: String,
+) -> Result<usize, String> {
+    decrypt_file(&input_path, &output_path, &password)
+}
+
+/// `input` is armored text, or a file path when `is_file` is set
+#[tauri::command]
+pub async fn inspect_encrypted_command(input: String, is_file: Option<bool>) -> Result<EnvelopeInfo, String> {
+    if is_file.unwrap_or(false) {
+        let data = std::fs::read(&input).map_err(|e| e.to_string())?;
+        inspect_envelope(&data)
+    } else {
+        inspect_envelope(input.as_bytes())
+    }
+}
+
 #[tauri::command]
 pub async fn bcrypt_hash_command(
     password: String,
@@ -197,3 +331,11 @@ pub async fn bcrypt_verify_command(password: String, hash: String) -> Result<boo
 pub async fn generate_git_branch_name_command(title: String) -> BranchNameOutput {
     generate_git_branch_name(&title)
 }
+
+#[tauri::command]
+pub async fn generate_code_from_json_command(
+    samples: Vec<String>,
+    options: CodegenOptions,
+) -> Result<CodegenOutput, String> {
+    generate_code_from_json(&samples, &options)
+}
@@ -4,6 +4,8 @@ use crate::tools::utilities::compression::{
     compress_gzip, compress_zlib, decompress_gzip, decompress_zlib, CompressionResult
 };
 use crate::tools::utilities::url_parser::{parse_url, update_query_params, ParsedUrl, QueryParam};
+use crate::tools::utilities::table_query::{query_table, QueryResult, TableFormat, DEFAULT_PAGE_SIZE};
+use crate::tools::utilities::asn1::{inspect_asn1, Asn1Document, Asn1InputFormat};
 use jsonwebtoken::Algorithm;
 
 #[tauri::command]
@@ -96,3 +98,31 @@ pub async fn update_query_params_command(
 ) -> Result<String, String> {
     update_query_params(&url, &params).map_err(|e| e.to_string())
 }
+
+// Tabular data viewer commands
+#[tauri::command]
+pub async fn query_table_command(
+    input: String,
+    format: Option<TableFormat>,
+    query: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> Result<QueryResult, String> {
+    query_table(
+        &input,
+        format.unwrap_or(TableFormat::Auto),
+        query.as_deref(),
+        page.unwrap_or(1),
+        page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// ASN.1 / PEM inspector commands
+#[tauri::command]
+pub async fn inspect_asn1_command(
+    input: String,
+    format: Option<Asn1InputFormat>,
+) -> Result<Vec<Asn1Document>, String> {
+    inspect_asn1(&input, format.unwrap_or_default()).map_err(|e| e.to_string())
+}
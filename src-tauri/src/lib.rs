@@ -31,12 +31,27 @@ pub fn run() {
             formatters::format_css_command,
             formatters::minify_css_command,
             formatters::validate_css_command,
+            formatters::transform_css_command,
+            formatters::css_stats_command,
+            formatters::format_html_command,
+            formatters::minify_html_command,
+            formatters::validate_html_command,
+            formatters::format_graphql_command,
+            formatters::minify_graphql_command,
+            formatters::validate_graphql_command,
+            formatters::validate_graphql_query_command,
+            formatters::format_proto_command,
+            formatters::validate_proto_command,
+            formatters::decode_protobuf_command,
+            formatters::encode_protobuf_command,
             formatters::format_js_command,
             formatters::minify_js_command,
             formatters::validate_js_command,
             formatters::format_yaml_command,
+            formatters::format_yaml_with_options_command,
             formatters::minify_yaml_command,
             formatters::validate_yaml_command,
+            formatters::lint_manifest_command,
             formatters::format_rust_command,
             formatters::validate_rust_command,
             // Generator commands
@@ -52,24 +67,64 @@ pub fn run() {
             generators::generate_wifi_qr_command,
             generators::generate_gitignore_command,
             generators::generate_rsa_key_pair_command,
+            generators::rsa_encrypt_command,
+            generators::rsa_decrypt_command,
+            generators::rsa_sign_command,
+            generators::rsa_verify_command,
+            generators::rsa_key_info_command,
+            generators::convert_rsa_key_command,
+            generators::encrypt_text_command,
+            generators::decrypt_text_command,
+            generators::encrypt_file_command,
+            generators::decrypt_file_command,
+            generators::inspect_encrypted_command,
             generators::bcrypt_hash_command,
             generators::bcrypt_verify_command,
             generators::generate_git_branch_name_command,
+            generators::generate_code_from_json_command,
             // Encoder commands
             encoders::encode_base64_command,
             encoders::decode_base64_command,
             encoders::encode_url_command,
             encoders::decode_url_command,
+            encoders::inspect_url_encoding_command,
             encoders::string_to_hex_command,
             encoders::hex_to_string_command,
+            encoders::decode_text_bytes_command,
+            encoders::encode_text_bytes_command,
+            encoders::hexdump_command,
+            encoders::reverse_hexdump_command,
             encoders::encode_html_command,
             encoders::decode_html_command,
+            encoders::escape_html_context_command,
             encoders::encode_punycode_command,
             encoders::decode_punycode_command,
+            encoders::inspect_idna_command,
             encoders::encode_morse_command,
             encoders::decode_morse_command,
+            encoders::encode_morse_audio_command,
+            encoders::decode_morse_audio_command,
             encoders::encode_image_to_base64_command,
             encoders::decode_image_from_base64_command,
+            encoders::encode_data_uri_command,
+            encoders::parse_data_uri_command,
+            encoders::save_data_uri_command,
+            encoders::encode_base_n_command,
+            encoders::decode_base_n_command,
+            encoders::detect_base_n_command,
+            encoders::inspect_unicode_command,
+            encoders::normalize_unicode_command,
+            encoders::escape_string_command,
+            encoders::unescape_string_command,
+            encoders::rot13_command,
+            encoders::rot47_command,
+            encoders::caesar_command,
+            encoders::caesar_brute_force_command,
+            encoders::atbash_command,
+            encoders::encode_vigenere_command,
+            encoders::decode_vigenere_command,
+            encoders::xor_command,
+            encoders::frequency_analysis_command,
             // Utility commands
             utilities::test_regex_command,
             utilities::validate_regex_command,
@@ -82,6 +137,8 @@ pub fn run() {
             utilities::decompress_zlib_command,
             utilities::parse_url_command,
             utilities::update_query_params_command,
+            utilities::query_table_command,
+            utilities::inspect_asn1_command,
             // Diff commands
             diff::diff_text_command,
             // Network commands
@@ -91,7 +148,16 @@ pub fn run() {
             // Converter commands
             converters::json_to_yaml_command,
             converters::yaml_to_json_command,
+            converters::json_to_yaml_with_options_command,
+            converters::yaml_to_json_with_options_command,
             converters::csv_to_json_command,
+            converters::csv_file_to_json_command,
+            converters::json_to_csv_command,
+            converters::detect_csv_delimiter_command,
+            converters::xml_to_json_command,
+            converters::json_to_xml_command,
+            converters::decode_binary_format_command,
+            converters::encode_binary_format_command,
             converters::markdown_to_html_command,
             converters::convert_number_base_command,
             converters::convert_timestamp_command,
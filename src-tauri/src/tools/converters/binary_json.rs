@@ -0,0 +1,641 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use bson::{Bson, Document};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::HashSet;
+
+use crate::tools::encoders::ByteEncoding;
+use crate::tools::utilities::compression::compress_gzip_bytes;
+
+/// Binary serialization formats that can be bridged to JSON
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryFormat {
+    #[serde(alias = "msgpack")]
+    MessagePack,
+    Cbor,
+    Bson,
+}
+
+/// One decoded value with the type it had on the wire
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TypedNode {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Scalar value as JSON; absent for arrays and maps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TypedNode>,
+}
+
+impl TypedNode {
+    fn scalar(type_name: impl Into<String>, value: Value) -> Self {
+        Self {
+            type_name: type_name.into(),
+            key: None,
+            value: Some(value),
+            children: Vec::new(),
+        }
+    }
+
+    fn container(type_name: impl Into<String>, children: Vec<TypedNode>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            key: None,
+            value: None,
+            children,
+        }
+    }
+
+    fn with_key(mut self, key: String) -> Self {
+        self.key = Some(key);
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SizeComparison {
+    pub binary_size: usize,
+    /// Minified JSON
+    pub json_size: usize,
+    pub binary_gzip_size: usize,
+    pub json_gzip_size: usize,
+    /// Binary size relative to JSON, negative when the binary form is smaller
+    pub binary_vs_json_percent: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BinaryDecodeResult {
+    pub json: String,
+    pub tree: TypedNode,
+    pub sizes: SizeComparison,
+    /// Keys repeated within one map; the tree keeps every entry, the JSON only the last
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BinaryEncodeResult {
+    /// Encoded bytes in the requested text encoding
+    pub output: String,
+    pub tree: TypedNode,
+    pub sizes: SizeComparison,
+}
+
+const GZIP_LEVEL: u32 = 9;
+
+fn float_value(f: f64) -> Value {
+    Number::from_f64(f).map_or_else(|| Value::from(f.to_string()), Value::Number)
+}
+
+fn compare_sizes(binary: &[u8], json: &Value) -> Result<SizeComparison, String> {
+    let minified = serde_json::to_vec(json).map_err(|e| e.to_string())?;
+    let gzip = |data: &[u8]| {
+        compress_gzip_bytes(data, GZIP_LEVEL)
+            .map(|c| c.len())
+            .map_err(|e| e.to_string())
+    };
+    let percent = if minified.is_empty() {
+        0.0
+    } else {
+        (binary.len() as f64 / minified.len() as f64 - 1.0) * 100.0
+    };
+    Ok(SizeComparison {
+        binary_size: binary.len(),
+        json_size: minified.len(),
+        binary_gzip_size: gzip(binary)?,
+        json_gzip_size: gzip(&minified)?,
+        binary_vs_json_percent: (percent * 10.0).round() / 10.0,
+    })
+}
+
+/// JSON object keys must be strings; other key types are rendered as JSON text
+fn key_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// MessagePack
+
+/// MessagePack timestamp extension (type -1) as RFC 3339
+fn msgpack_timestamp(data: &[u8]) -> Option<String> {
+    let (seconds, nanos) = match data.len() {
+        4 => (i64::from(u32::from_be_bytes(data.try_into().ok()?)), 0),
+        8 => {
+            let raw = u64::from_be_bytes(data.try_into().ok()?);
+            ((raw & 0x3_ffff_ffff) as i64, (raw >> 34) as u32)
+        }
+        12 => (
+            i64::from_be_bytes(data[4..].try_into().ok()?),
+            u32::from_be_bytes(data[..4].try_into().ok()?),
+        ),
+        _ => return None,
+    };
+    chrono::DateTime::from_timestamp(seconds, nanos)
+        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+}
+
+fn msgpack_to_json(value: &rmpv::Value) -> (Value, TypedNode) {
+    use rmpv::Value as Mp;
+    match value {
+        Mp::Nil => (Value::Null, TypedNode::scalar("nil", Value::Null)),
+        Mp::Boolean(b) => (Value::from(*b), TypedNode::scalar("bool", Value::from(*b))),
+        Mp::Integer(i) => {
+            let (json, type_name) = match (i.as_u64(), i.as_i64()) {
+                (Some(u), _) => (Value::from(u), "uint"),
+                (None, Some(n)) => (Value::from(n), "int"),
+                _ => (Value::Null, "int"),
+            };
+            (json.clone(), TypedNode::scalar(type_name, json))
+        }
+        Mp::F32(f) => (
+            float_value(f64::from(*f)),
+            TypedNode::scalar("float32", float_value(f64::from(*f))),
+        ),
+        Mp::F64(f) => (
+            float_value(*f),
+            TypedNode::scalar("float64", float_value(*f)),
+        ),
+        Mp::String(s) => match s.as_str() {
+            Some(text) => (
+                Value::from(text),
+                TypedNode::scalar("str", Value::from(text)),
+            ),
+            None => {
+                let lossy = String::from_utf8_lossy(s.as_bytes()).into_owned();
+                (
+                    Value::from(lossy.clone()),
+                    TypedNode::scalar("str (invalid UTF-8)", Value::from(lossy)),
+                )
+            }
+        },
+        Mp::Binary(bytes) => {
+            let encoded = Value::from(BASE64.encode(bytes));
+            (
+                encoded.clone(),
+                TypedNode::scalar(format!("bin ({} bytes)", bytes.len()), encoded),
+            )
+        }
+        Mp::Array(items) => {
+            let (values, nodes): (Vec<_>, Vec<_>) = items.iter().map(msgpack_to_json).unzip();
+            (
+                Value::Array(values),
+                TypedNode::container(format!("array ({})", items.len()), nodes),
+            )
+        }
+        Mp::Map(entries) => {
+            let mut map = Map::new();
+            let mut nodes = Vec::new();
+            for (key, value) in entries {
+                let key = key_string(&msgpack_to_json(key).0);
+                let (json, node) = msgpack_to_json(value);
+                map.insert(key.clone(), json);
+                nodes.push(node.with_key(key));
+            }
+            (
+                Value::Object(map),
+                TypedNode::container(format!("map ({})", entries.len()), nodes),
+            )
+        }
+        Mp::Ext(-1, data) => match msgpack_timestamp(data) {
+            Some(timestamp) => (
+                Value::from(timestamp.clone()),
+                TypedNode::scalar("timestamp", Value::from(timestamp)),
+            ),
+            None => msgpack_ext(-1, data),
+        },
+        Mp::Ext(kind, data) => msgpack_ext(*kind, data),
+    }
+}
+
+fn msgpack_ext(kind: i8, data: &[u8]) -> (Value, TypedNode) {
+    let mut json = Map::new();
+    json.insert("type".to_string(), Value::from(kind));
+    json.insert("data".to_string(), Value::from(BASE64.encode(data)));
+    let json = Value::Object(json);
+    (
+        json.clone(),
+        TypedNode::scalar(format!("ext {}", kind), json),
+    )
+}
+
+fn json_to_msgpack(value: &Value) -> rmpv::Value {
+    use rmpv::Value as Mp;
+    match value {
+        Value::Null => Mp::Nil,
+        Value::Bool(b) => Mp::Boolean(*b),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => Mp::from(u),
+            (None, Some(i)) => Mp::from(i),
+            _ => Mp::F64(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => Mp::from(s.as_str()),
+        Value::Array(items) => Mp::Array(items.iter().map(json_to_msgpack).collect()),
+        Value::Object(map) => Mp::Map(
+            map.iter()
+                .map(|(k, v)| (Mp::from(k.as_str()), json_to_msgpack(v)))
+                .collect(),
+        ),
+    }
+}
+
+// CBOR
+
+fn cbor_tag_name(tag: u64) -> Option<&'static str> {
+    Some(match tag {
+        0 => "date/time string",
+        1 => "epoch date/time",
+        2 => "positive bignum",
+        3 => "negative bignum",
+        4 => "decimal fraction",
+        5 => "bigfloat",
+        21 => "expected base64url",
+        22 => "expected base64",
+        23 => "expected base16",
+        24 => "embedded CBOR",
+        32 => "URI",
+        33 => "base64url text",
+        34 => "base64 text",
+        36 => "MIME message",
+        37 => "UUID",
+        55799 => "self-described CBOR",
+        _ => return None,
+    })
+}
+
+fn cbor_to_json(value: &ciborium::Value) -> (Value, TypedNode) {
+    use ciborium::Value as Cb;
+    match value {
+        Cb::Null => (Value::Null, TypedNode::scalar("null", Value::Null)),
+        Cb::Bool(b) => (Value::from(*b), TypedNode::scalar("bool", Value::from(*b))),
+        Cb::Integer(i) => {
+            let n = i128::from(*i);
+            let json = i64::try_from(n)
+                .map(Value::from)
+                .or_else(|_| u64::try_from(n).map(Value::from))
+                .unwrap_or_else(|_| Value::from(n.to_string()));
+            let type_name = if n < 0 {
+                "negative int"
+            } else {
+                "unsigned int"
+            };
+            (json.clone(), TypedNode::scalar(type_name, json))
+        }
+        Cb::Float(f) => (float_value(*f), TypedNode::scalar("float", float_value(*f))),
+        Cb::Text(s) => (
+            Value::from(s.as_str()),
+            TypedNode::scalar("text", Value::from(s.as_str())),
+        ),
+        Cb::Bytes(bytes) => {
+            let encoded = Value::from(BASE64.encode(bytes));
+            (
+                encoded.clone(),
+                TypedNode::scalar(format!("bytes ({} bytes)", bytes.len()), encoded),
+            )
+        }
+        Cb::Array(items) => {
+            let (values, nodes): (Vec<_>, Vec<_>) = items.iter().map(cbor_to_json).unzip();
+            (
+                Value::Array(values),
+                TypedNode::container(format!("array ({})", items.len()), nodes),
+            )
+        }
+        Cb::Map(entries) => {
+            let mut map = Map::new();
+            let mut nodes = Vec::new();
+            for (key, value) in entries {
+                let key = key_string(&cbor_to_json(key).0);
+                let (json, node) = cbor_to_json(value);
+                map.insert(key.clone(), json);
+                nodes.push(node.with_key(key));
+            }
+            (
+                Value::Object(map),
+                TypedNode::container(format!("map ({})", entries.len()), nodes),
+            )
+        }
+        Cb::Tag(tag, inner) => {
+            let type_name = match cbor_tag_name(*tag) {
+                Some(name) => format!("tag {} ({})", tag, name),
+                None => format!("tag {}", tag),
+            };
+            let (inner_json, child) = cbor_to_json(inner);
+            let json = match (tag, inner.as_ref()) {
+                // Bignums become decimal strings
+                (2 | 3, Cb::Bytes(bytes)) if bytes.len() <= 16 => {
+                    let magnitude = bytes
+                        .iter()
+                        .fold(0u128, |acc, b| (acc << 8) | u128::from(*b));
+                    if *tag == 2 {
+                        Value::from(magnitude.to_string())
+                    } else {
+                        Value::from(format!("-{}", magnitude + 1))
+                    }
+                }
+                _ => inner_json,
+            };
+            let mut node = TypedNode::container(type_name, vec![child]);
+            node.value = Some(json.clone());
+            (json, node)
+        }
+        other => {
+            let text = format!("{:?}", other);
+            (
+                Value::from(text.clone()),
+                TypedNode::scalar("simple value", Value::from(text)),
+            )
+        }
+    }
+}
+
+// BSON
+
+fn bson_type_name(value: &Bson) -> String {
+    match value {
+        Bson::Double(_) => "double".to_string(),
+        Bson::String(_) => "string".to_string(),
+        Bson::Array(items) => format!("array ({})", items.len()),
+        Bson::Document(doc) => format!("document ({})", doc.len()),
+        Bson::Boolean(_) => "bool".to_string(),
+        Bson::Null => "null".to_string(),
+        Bson::RegularExpression(_) => "regex".to_string(),
+        Bson::JavaScriptCode(_) => "javascript".to_string(),
+        Bson::JavaScriptCodeWithScope(_) => "javascript with scope".to_string(),
+        Bson::Int32(_) => "int32".to_string(),
+        Bson::Int64(_) => "int64".to_string(),
+        Bson::Timestamp(_) => "timestamp".to_string(),
+        Bson::Binary(binary) => format!(
+            "binary (subtype {:?}, {} bytes)",
+            binary.subtype,
+            binary.bytes.len()
+        ),
+        Bson::ObjectId(_) => "objectId".to_string(),
+        Bson::DateTime(_) => "datetime".to_string(),
+        Bson::Symbol(_) => "symbol".to_string(),
+        Bson::Decimal128(_) => "decimal128".to_string(),
+        Bson::Undefined => "undefined".to_string(),
+        Bson::MaxKey => "maxKey".to_string(),
+        Bson::MinKey => "minKey".to_string(),
+        Bson::DbPointer(_) => "dbPointer".to_string(),
+    }
+}
+
+fn bson_tree(value: &Bson) -> TypedNode {
+    match value {
+        Bson::Document(doc) => TypedNode::container(
+            bson_type_name(value),
+            doc.iter()
+                .map(|(k, v)| bson_tree(v).with_key(k.clone()))
+                .collect(),
+        ),
+        Bson::Array(items) => {
+            TypedNode::container(bson_type_name(value), items.iter().map(bson_tree).collect())
+        }
+        scalar => TypedNode::scalar(
+            bson_type_name(scalar),
+            scalar.clone().into_relaxed_extjson(),
+        ),
+    }
+}
+
+// Public API
+
+/// Report keys that occur more than once in the same map or document
+fn duplicate_keys(node: &TypedNode, path: &str, warnings: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    for (i, child) in node.children.iter().enumerate() {
+        let child_path = match &child.key {
+            Some(key) => {
+                if !seen.insert(key.as_str()) {
+                    warnings.push(format!(
+                        "Duplicate key `{}` in {}; the JSON keeps the last value",
+                        key, path
+                    ));
+                }
+                format!("{}.{}", path, key)
+            }
+            None => format!("{}[{}]", path, i),
+        };
+        duplicate_keys(child, &child_path, warnings);
+    }
+}
+
+fn trailing_error(remaining: usize) -> Result<(), String> {
+    if remaining == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} trailing bytes after the first value",
+            remaining
+        ))
+    }
+}
+
+/// Decode binary data into JSON plus a type-annotated tree
+pub fn decode_binary(bytes: &[u8], format: BinaryFormat) -> Result<(Value, TypedNode), String> {
+    let mut reader = bytes;
+    match format {
+        BinaryFormat::MessagePack => {
+            let value = rmpv::decode::read_value(&mut reader)
+                .map_err(|e| format!("Invalid MessagePack: {}", e))?;
+            trailing_error(reader.len())?;
+            Ok(msgpack_to_json(&value))
+        }
+        BinaryFormat::Cbor => {
+            let value: ciborium::Value = ciborium::de::from_reader(&mut reader)
+                .map_err(|e| format!("Invalid CBOR: {}", e))?;
+            trailing_error(reader.len())?;
+            Ok(cbor_to_json(&value))
+        }
+        BinaryFormat::Bson => {
+            let document =
+                Document::from_reader(&mut reader).map_err(|e| format!("Invalid BSON: {}", e))?;
+            trailing_error(reader.len())?;
+            let value = Bson::Document(document);
+            let tree = bson_tree(&value);
+            Ok((value.into_relaxed_extjson(), tree))
+        }
+    }
+}
+
+/// Encode JSON into a binary format
+///
+/// BSON input may use MongoDB extended JSON (`{"$oid": ...}`, `{"$date": ...}`).
+pub fn encode_binary(value: &Value, format: BinaryFormat) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    match format {
+        BinaryFormat::MessagePack => rmpv::encode::write_value(&mut out, &json_to_msgpack(value))
+            .map_err(|e| e.to_string())?,
+        BinaryFormat::Cbor => {
+            ciborium::ser::into_writer(value, &mut out).map_err(|e| e.to_string())?
+        }
+        BinaryFormat::Bson => {
+            let bson = Bson::try_from(value.clone())
+                .map_err(|e| format!("Invalid extended JSON: {}", e))?;
+            let Bson::Document(document) = bson else {
+                return Err("BSON documents must be JSON objects at the top level".to_string());
+            };
+            document.to_writer(&mut out).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(out)
+}
+
+pub fn decode_binary_format_command(
+    input: String,
+    format: BinaryFormat,
+    encoding: ByteEncoding,
+    indent: usize,
+) -> Result<BinaryDecodeResult, String> {
+    let bytes = encoding.decode(&input)?;
+    let (value, tree) = decode_binary(&bytes, format)?;
+    let json = if indent == 0 {
+        serde_json::to_string(&value)
+    } else {
+        let mut out = Vec::new();
+        let indent = " ".repeat(indent);
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+        value
+            .serialize(&mut serializer)
+            .map(|_| String::from_utf8_lossy(&out).into_owned())
+    }
+    .map_err(|e| e.to_string())?;
+    let mut warnings = Vec::new();
+    duplicate_keys(&tree, "$", &mut warnings);
+    Ok(BinaryDecodeResult {
+        json,
+        tree,
+        sizes: compare_sizes(&bytes, &value)?,
+        warnings,
+    })
+}
+
+pub fn encode_binary_format_command(
+    json: String,
+    format: BinaryFormat,
+    encoding: ByteEncoding,
+) -> Result<BinaryEncodeResult, String> {
+    let value: Value = serde_json::from_str(&json).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let bytes = encode_binary(&value, format)?;
+    let (_, tree) = decode_binary(&bytes, format)?;
+    Ok(BinaryEncodeResult {
+        output: encoding.encode(&bytes),
+        tree,
+        sizes: compare_sizes(&bytes, &value)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_msgpack_round_trip() {
+        let value =
+            json!({"compact": true, "schema": 0, "neg": -5, "pi": 3.5, "list": [1, "a", null]});
+        let bytes = encode_binary(&value, BinaryFormat::MessagePack).unwrap();
+        // fixmap with 5 entries, first key "compact"
+        assert_eq!(&bytes[..2], &[0x85, 0xa7]);
+        let (decoded, tree) = decode_binary(&bytes, BinaryFormat::MessagePack).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(tree.type_name, "map (5)");
+        let types: Vec<&str> = tree.children.iter().map(|c| c.type_name.as_str()).collect();
+        assert_eq!(types, vec!["bool", "uint", "int", "float64", "array (3)"]);
+        assert_eq!(tree.children[2].key.as_deref(), Some("neg"));
+    }
+
+    #[test]
+    fn test_msgpack_binary_and_extensions() {
+        // {"b": bin8 [1,2], "t": timestamp32(0), 1: ext 5}
+        let bytes = hex::decode("83a162c4020102a174d6ff0000000001d40507").unwrap();
+        let (decoded, tree) = decode_binary(&bytes, BinaryFormat::MessagePack).unwrap();
+        assert_eq!(
+            decoded,
+            json!({"b": "AQI=", "t": "1970-01-01T00:00:00Z", "1": {"type": 5, "data": "Bw=="}})
+        );
+        assert_eq!(tree.children[0].type_name, "bin (2 bytes)");
+        assert_eq!(tree.children[1].type_name, "timestamp");
+        assert_eq!(tree.children[2].type_name, "ext 5");
+
+        assert!(decode_binary(&[0x92, 0x01], BinaryFormat::MessagePack).is_err());
+        assert!(decode_binary(&[0x01, 0x02], BinaryFormat::MessagePack).is_err());
+    }
+
+    #[test]
+    fn test_cbor_tags_and_round_trip() {
+        // tag 1 (epoch) 1363896240, tag 2 bignum 2^64
+        let bytes = hex::decode("82c11a514b67b0c249010000000000000000").unwrap();
+        let (decoded, tree) = decode_binary(&bytes, BinaryFormat::Cbor).unwrap();
+        assert_eq!(decoded, json!([1363896240, "18446744073709551616"]));
+        assert_eq!(tree.children[0].type_name, "tag 1 (epoch date/time)");
+        assert_eq!(tree.children[1].type_name, "tag 2 (positive bignum)");
+
+        let value = json!({"a": [1, -2, 2.5, "x"], "b": false});
+        let bytes = encode_binary(&value, BinaryFormat::Cbor).unwrap();
+        assert_eq!(decode_binary(&bytes, BinaryFormat::Cbor).unwrap().0, value);
+
+        // Deeply nested tags are decoded once per level, not once per path
+        let mut nested = vec![0xc6; 64];
+        nested.push(0x01);
+        let (decoded, _) = decode_binary(&nested, BinaryFormat::Cbor).unwrap();
+        assert_eq!(decoded, json!(1));
+    }
+
+    #[test]
+    fn test_duplicate_map_keys_are_reported() {
+        // {"a": 1, "a": 2, "m": {"k": 1, "k": 2}}
+        let hex = "a3616101616102616da2616b01616b02".to_string();
+        let decoded = decode_binary_format_command(hex, BinaryFormat::Cbor, ByteEncoding::Hex, 0).unwrap();
+        assert_eq!(decoded.json, r#"{"a":2,"m":{"k":2}}"#);
+        assert_eq!(decoded.tree.children.len(), 3);
+        assert_eq!(
+            decoded.warnings,
+            vec![
+                "Duplicate key `a` in $; the JSON keeps the last value",
+                "Duplicate key `k` in $.m; the JSON keeps the last value",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bson_extended_json() {
+        let value = json!({"_id": {"$oid": "507f1f77bcf86cd799439011"}, "n": 5, "big": 5_000_000_000i64, "tags": ["a"]});
+        let bytes = encode_binary(&value, BinaryFormat::Bson).unwrap();
+        let (decoded, tree) = decode_binary(&bytes, BinaryFormat::Bson).unwrap();
+        assert_eq!(decoded, value);
+        let types: Vec<&str> = tree.children.iter().map(|c| c.type_name.as_str()).collect();
+        assert_eq!(types, vec!["objectId", "int32", "int64", "array (1)"]);
+
+        assert!(encode_binary(&json!([1, 2]), BinaryFormat::Bson).is_err());
+    }
+
+    #[test]
+    fn test_commands_report_sizes() {
+        let json = r#"{"items": [1, 2, 3, 4, 5, 6, 7, 8], "name": "ferrisbox"}"#.to_string();
+        let encoded =
+            encode_binary_format_command(json, BinaryFormat::MessagePack, ByteEncoding::Hex)
+                .unwrap();
+        assert_eq!(encoded.sizes.json_size, 46);
+        assert_eq!(encoded.sizes.binary_size, encoded.output.len() / 2);
+        assert!(encoded.sizes.binary_size < encoded.sizes.json_size);
+        assert!(encoded.sizes.binary_vs_json_percent < 0.0);
+        assert!(encoded.sizes.json_gzip_size > 0);
+
+        let decoded = decode_binary_format_command(
+            encoded.output,
+            BinaryFormat::MessagePack,
+            ByteEncoding::Hex,
+            2,
+        )
+        .unwrap();
+        assert!(decoded.json.starts_with("{\n  \"items\": [\n    1,"));
+        assert_eq!(decoded.sizes, encoded.sizes);
+    }
+}
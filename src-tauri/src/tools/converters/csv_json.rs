@@ -1,7 +1,42 @@
-use csv::ReaderBuilder;
-use serde_json::{Value, Map};
+use csv::{QuoteStyle, ReaderBuilder, Terminator, WriterBuilder};
+use serde_json::{Value, Map, Number};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CsvJsonError {
+    #[error("Input is empty")]
+    EmptyInput,
+    #[error("Invalid delimiter \"{0}\": must be a single character")]
+    InvalidDelimiter(String),
+    #[error("The {0} character must be ASCII")]
+    NonAsciiCharacter(String),
+    #[error("CSV parse error on line {line}: {message}")]
+    Parse { line: u64, message: String },
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("Expected a JSON object or array of objects, found {0}")]
+    UnsupportedShape(String),
+    #[error("Cannot decode input as {0}")]
+    Encoding(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("CSV write error: {0}")]
+    Write(String),
+    #[error("Column \"{0}\" is produced by more than one key; try another path separator")]
+    ColumnCollision(String),
+}
+
+impl From<csv::Error> for CsvJsonError {
+    fn from(e: csv::Error) -> Self {
+        let line = e.position().map(|p| p.line()).unwrap_or(0);
+        CsvJsonError::Parse {
+            line,
+            message: e.to_string(),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct CsvRow {
@@ -9,46 +44,519 @@ pub struct CsvRow {
     fields: HashMap<String, String>,
 }
 
-pub fn csv_to_json_command(
-    csv: String,
-    delimiter: Option<String>,
+/// Text encoding of CSV files read from disk
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvEncoding {
+    /// UTF-8, also used when a UTF-8 or UTF-16 BOM is present
+    #[default]
+    Utf8,
+    Utf16le,
+    Utf16be,
+    Latin1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CsvToJsonOptions {
+    /// Convert numbers, booleans and empty/`null` fields into typed JSON values
+    #[serde(default)]
+    pub infer_types: bool,
+    #[serde(default = "default_quote")]
+    pub quote: char,
+    /// Escape character for quotes; `None` means quotes are escaped by doubling
+    #[serde(default)]
+    pub escape: Option<char>,
+    #[serde(default)]
+    pub trim: bool,
+    #[serde(default)]
+    pub encoding: CsvEncoding,
+}
+
+fn default_quote() -> char {
+    '"'
+}
+
+impl Default for CsvToJsonOptions {
+    fn default() -> Self {
+        Self {
+            infer_types: false,
+            quote: default_quote(),
+            escape: None,
+            trim: false,
+            encoding: CsvEncoding::Utf8,
+        }
+    }
+}
+
+/// How arrays nested inside JSON objects are written to CSV cells
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayMode {
+    /// Join scalar items with `array_separator`, arrays of objects fall back to JSON
+    #[default]
+    Join,
+    /// One column per item: `tags.0`, `tags.1`, ...
+    Index,
+    /// Keep the array as a JSON string in a single cell
+    Json,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvQuoteStyle {
+    #[default]
+    Necessary,
+    Always,
+    NonNumeric,
+    Never,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JsonToCsvOptions {
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    /// Separator between nested keys in flattened column names
+    #[serde(default = "default_path_separator")]
+    pub path_separator: String,
+    #[serde(default)]
+    pub array_mode: ArrayMode,
+    #[serde(default = "default_array_separator")]
+    pub array_separator: String,
+    #[serde(default)]
+    pub quote_style: CsvQuoteStyle,
+    #[serde(default = "default_quote")]
+    pub quote: char,
+    #[serde(default)]
+    pub escape: Option<char>,
+    #[serde(default = "default_true")]
+    pub include_header: bool,
+    /// Prepend a UTF-8 BOM so spreadsheet applications detect the encoding
+    #[serde(default)]
+    pub include_bom: bool,
+    #[serde(default)]
+    pub crlf: bool,
+}
+
+fn default_path_separator() -> String {
+    ".".to_string()
+}
+
+fn default_array_separator() -> String {
+    "|".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for JsonToCsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            path_separator: default_path_separator(),
+            array_mode: ArrayMode::Join,
+            array_separator: default_array_separator(),
+            quote_style: CsvQuoteStyle::Necessary,
+            quote: default_quote(),
+            escape: None,
+            include_header: true,
+            include_bom: false,
+            crlf: false,
+        }
+    }
+}
+
+/// Parsed CSV content with optionally typed cells
+#[derive(Debug, Clone, Serialize)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub delimiter: char,
+}
+
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// Guess the delimiter from the first lines of the input
+/// Picks the candidate that appears the same (non-zero) number of times on most lines
+pub fn detect_delimiter(csv: &str, quote: u8) -> char {
+    let lines: Vec<&str> = csv
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(10)
+        .collect();
+
+    let mut best = (b',', 0usize, 0usize);
+    for candidate in CANDIDATE_DELIMITERS {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|line| count_unquoted(line, candidate, quote))
+            .collect();
+        let first = counts.first().copied().unwrap_or(0);
+        if first == 0 {
+            continue;
+        }
+        let consistent = counts.iter().filter(|&&c| c == first).count();
+        if (consistent, first) > (best.1, best.2) {
+            best = (candidate, consistent, first);
+        }
+    }
+
+    best.0 as char
+}
+
+fn count_unquoted(line: &str, delimiter: u8, quote: u8) -> usize {
+    let mut in_quotes = false;
+    line.bytes()
+        .filter(|&b| {
+            if b == quote {
+                in_quotes = !in_quotes;
+            }
+            b == delimiter && !in_quotes
+        })
+        .count()
+}
+
+fn parse_delimiter(delimiter: Option<&str>) -> Result<Option<u8>, CsvJsonError> {
+    match delimiter {
+        None | Some("") | Some("auto") => Ok(None),
+        Some("tab") | Some("\\t") => Ok(Some(b'\t')),
+        Some(d) if d.len() == 1 => Ok(Some(d.as_bytes()[0])),
+        Some(d) => Err(CsvJsonError::InvalidDelimiter(d.to_string())),
+    }
+}
+
+fn ascii_byte(c: char, what: &str) -> Result<u8, CsvJsonError> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        Err(CsvJsonError::NonAsciiCharacter(format!("{} '{}'", what, c)))
+    }
+}
+
+/// Turn a CSV field into a typed JSON value
+/// Values with leading zeros ("007") stay strings since they are usually identifiers,
+/// as do integers too large for `i64`/`u64` that would lose precision as floats
+pub fn infer_value(field: &str) -> Value {
+    let trimmed = field.trim();
+    match trimmed {
+        "" => return Value::Null,
+        "null" | "NULL" | "Null" => return Value::Null,
+        _ => {}
+    }
+    if trimmed.eq_ignore_ascii_case("true") {
+        return Value::Bool(true);
+    }
+    if trimmed.eq_ignore_ascii_case("false") {
+        return Value::Bool(false);
+    }
+
+    let digits = trimmed.strip_prefix('-').unwrap_or(trimmed);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    if !leading_zero && !trimmed.starts_with('+') {
+        if let Ok(int) = trimmed.parse::<i64>() {
+            return Value::Number(int.into());
+        }
+        if let Ok(int) = trimmed.parse::<u64>() {
+            return Value::Number(int.into());
+        }
+        let is_plain_float = trimmed
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '.' | 'e' | 'E' | '+'))
+            && trimmed.contains(['.', 'e', 'E']);
+        if is_plain_float {
+            if let Some(num) = trimmed.parse::<f64>().ok().and_then(Number::from_f64) {
+                return Value::Number(num);
+            }
+        }
+    }
+
+    Value::String(field.to_string())
+}
+
+/// Parse CSV text into headers and rows
+pub fn parse_csv(
+    csv: &str,
+    delimiter: Option<&str>,
     has_header: bool,
-) -> Result<String, String> {
-    let delimiter_byte = match delimiter.as_deref() {
-        Some(d) if d.len() == 1 => d.as_bytes()[0],
-        _ => b',',
+    options: &CsvToJsonOptions,
+) -> Result<CsvTable, CsvJsonError> {
+    let csv = csv.strip_prefix('\u{feff}').unwrap_or(csv);
+    if csv.trim().is_empty() {
+        return Err(CsvJsonError::EmptyInput);
+    }
+
+    let quote = ascii_byte(options.quote, "quote")?;
+    let delimiter_byte = match parse_delimiter(delimiter)? {
+        Some(byte) => byte,
+        None => detect_delimiter(csv, quote) as u8,
     };
-    
-    let mut rdr = ReaderBuilder::new()
+
+    let mut builder = ReaderBuilder::new();
+    builder
         .delimiter(delimiter_byte)
         .has_headers(has_header)
         .flexible(true)
-        .from_reader(csv.as_bytes());
-    
+        .quote(quote)
+        .double_quote(options.escape.is_none());
+    if let Some(escape) = options.escape {
+        builder.escape(Some(ascii_byte(escape, "escape")?));
+    }
+    if options.trim {
+        builder.trim(csv::Trim::All);
+    }
+    let mut rdr = builder.from_reader(csv.as_bytes());
+
     let mut headers: Vec<String> = Vec::new();
     if has_header {
-        headers = rdr.headers().map_err(|e| e.to_string())?.iter().map(|s| s.to_string()).collect();
+        headers = rdr.headers()?.iter().map(|s| s.to_string()).collect();
     }
-    
-    let mut result: Vec<Value> = Vec::new();
-    
+
+    let mut rows: Vec<Vec<Value>> = Vec::new();
     for record_result in rdr.records() {
-        let record = record_result.map_err(|e| e.to_string())?;
-        let mut map: Map<String, Value> = Map::new();
-        
-        for (i, field) in record.iter().enumerate() {
-            let key = if has_header && i < headers.len() {
-                headers[i].clone()
-            } else {
-                format!("field_{}", i)
-            };
-            map.insert(key, Value::String(field.to_string()));
+        let record = record_result?;
+        let row = record
+            .iter()
+            .map(|field| {
+                if options.infer_types {
+                    infer_value(field)
+                } else {
+                    Value::String(field.to_string())
+                }
+            })
+            .collect();
+        rows.push(row);
+    }
+
+    Ok(CsvTable {
+        headers,
+        rows,
+        delimiter: delimiter_byte as char,
+    })
+}
+
+impl CsvTable {
+    /// Column name for a field index, falling back to `field_N` past the header
+    pub fn column_name(&self, index: usize) -> String {
+        match self.headers.get(index) {
+            Some(name) => name.clone(),
+            None => format!("field_{}", index),
         }
-        
-        result.push(Value::Object(map));
     }
-    
-    serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+
+    pub fn to_objects(&self) -> Vec<Value> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut map: Map<String, Value> = Map::new();
+                for (i, field) in row.iter().enumerate() {
+                    map.insert(self.column_name(i), field.clone());
+                }
+                Value::Object(map)
+            })
+            .collect()
+    }
+}
+
+pub fn csv_to_json_command(
+    csv: String,
+    delimiter: Option<String>,
+    has_header: bool,
+    options: Option<CsvToJsonOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let table = parse_csv(&csv, delimiter.as_deref(), has_header, &options)
+        .map_err(|e| e.to_string())?;
+
+    serde_json::to_string_pretty(&table.to_objects()).map_err(|e| e.to_string())
+}
+
+/// Read a CSV file from disk using the configured encoding and convert it to JSON
+pub fn csv_file_to_json_command(
+    file_path: String,
+    delimiter: Option<String>,
+    has_header: bool,
+    options: Option<CsvToJsonOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let bytes = std::fs::read(&file_path).map_err(|e| CsvJsonError::from(e).to_string())?;
+    let text = decode_bytes(&bytes, options.encoding).map_err(|e| e.to_string())?;
+
+    csv_to_json_command(text, delimiter, has_header, Some(options))
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> Result<String, CsvJsonError> {
+    let pairs = bytes.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(CsvJsonError::Encoding("UTF-16 (odd number of bytes)".to_string()));
+    }
+    let units = pairs.map(|pair| {
+        if little_endian {
+            u16::from_le_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_be_bytes([pair[0], pair[1]])
+        }
+    });
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| CsvJsonError::Encoding("UTF-16".to_string()))
+}
+
+fn decode_bytes(bytes: &[u8], encoding: CsvEncoding) -> Result<String, CsvJsonError> {
+    // A BOM always wins over the configured encoding
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8(rest.to_vec()).map_err(|_| CsvJsonError::Encoding("UTF-8".to_string()));
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(rest, true);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return decode_utf16(rest, false);
+    }
+
+    match encoding {
+        CsvEncoding::Utf8 => {
+            String::from_utf8(bytes.to_vec()).map_err(|_| CsvJsonError::Encoding("UTF-8".to_string()))
+        }
+        CsvEncoding::Utf16le => decode_utf16(bytes, true),
+        CsvEncoding::Utf16be => decode_utf16(bytes, false),
+        CsvEncoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+    }
+}
+
+/// Flatten a JSON value into `(column, cell)` pairs using dot paths
+fn flatten_value(
+    prefix: &str,
+    value: &Value,
+    options: &JsonToCsvOptions,
+    out: &mut Vec<(String, String)>,
+) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}{}{}", prefix, options.path_separator, key)
+        }
+    };
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_value(&join(key), child, options, out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => match options.array_mode {
+            ArrayMode::Index => {
+                for (i, item) in items.iter().enumerate() {
+                    flatten_value(&join(&i.to_string()), item, options, out);
+                }
+            }
+            ArrayMode::Join if items.iter().all(|item| !item.is_object() && !item.is_array()) => {
+                let joined = items
+                    .iter()
+                    .map(scalar_cell)
+                    .collect::<Vec<_>>()
+                    .join(&options.array_separator);
+                out.push((prefix.to_string(), joined));
+            }
+            _ => out.push((prefix.to_string(), value.to_string())),
+        },
+        Value::Object(_) | Value::Array(_) => out.push((prefix.to_string(), String::new())),
+        scalar => out.push((prefix.to_string(), scalar_cell(scalar))),
+    }
+}
+
+fn scalar_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn json_to_csv(json: &str, options: &JsonToCsvOptions) -> Result<String, CsvJsonError> {
+    if json.trim().is_empty() {
+        return Err(CsvJsonError::EmptyInput);
+    }
+    let value: Value = serde_json::from_str(json)?;
+
+    let records: Vec<&Value> = match &value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![&value],
+        Value::Null => return Err(CsvJsonError::UnsupportedShape("null".to_string())),
+        Value::Bool(_) | Value::Number(_) | Value::String(_) => {
+            return Err(CsvJsonError::UnsupportedShape("a scalar value".to_string()))
+        }
+    };
+
+    // Collect rows first so the header is the union of all columns in first-seen order
+    let mut columns: Vec<String> = Vec::new();
+    let mut rows: Vec<HashMap<String, String>> = Vec::with_capacity(records.len());
+    for record in records {
+        let mut cells = Vec::new();
+        if record.is_object() {
+            flatten_value("", record, options, &mut cells);
+        } else {
+            flatten_value("value", record, options, &mut cells);
+        }
+        let mut row = HashMap::with_capacity(cells.len());
+        for (column, cell) in cells {
+            if row.contains_key(&column) {
+                return Err(CsvJsonError::ColumnCollision(column));
+            }
+            if !columns.contains(&column) {
+                columns.push(column.clone());
+            }
+            row.insert(column, cell);
+        }
+        rows.push(row);
+    }
+
+    let delimiter = parse_delimiter(options.delimiter.as_deref())?.unwrap_or(b',');
+    let quote_style = match options.quote_style {
+        CsvQuoteStyle::Necessary => QuoteStyle::Necessary,
+        CsvQuoteStyle::Always => QuoteStyle::Always,
+        CsvQuoteStyle::NonNumeric => QuoteStyle::NonNumeric,
+        CsvQuoteStyle::Never => QuoteStyle::Never,
+    };
+
+    let mut builder = WriterBuilder::new();
+    builder
+        .delimiter(delimiter)
+        .quote_style(quote_style)
+        .quote(ascii_byte(options.quote, "quote")?)
+        .double_quote(options.escape.is_none())
+        .terminator(if options.crlf { Terminator::CRLF } else { Terminator::Any(b'\n') });
+    if let Some(escape) = options.escape {
+        builder.escape(ascii_byte(escape, "escape")?);
+    }
+    let mut writer = builder.from_writer(Vec::new());
+
+    let write_error = |e: csv::Error| CsvJsonError::Write(e.to_string());
+    if options.include_header {
+        writer.write_record(&columns).map_err(write_error)?;
+    }
+    for row in &rows {
+        let record: Vec<&str> = columns
+            .iter()
+            .map(|column| row.get(column).map(String::as_str).unwrap_or(""))
+            .collect();
+        writer.write_record(&record).map_err(write_error)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| CsvJsonError::Write(e.to_string()))?;
+    let csv = String::from_utf8(bytes).map_err(|e| CsvJsonError::Write(e.to_string()))?;
+
+    if options.include_bom {
+        Ok(format!("\u{feff}{}", csv))
+    } else {
+        Ok(csv)
+    }
+}
+
+pub fn json_to_csv_command(json: String, options: Option<JsonToCsvOptions>) -> Result<String, String> {
+    json_to_csv(&json, &options.unwrap_or_default()).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
@@ -58,7 +566,7 @@ mod tests {
     #[test]
     fn test_simple_csv_to_json() {
         let csv = "name,age\nJohn,30\nJane,25";
-        let result = csv_to_json_command(csv.to_string(), Some(",".to_string()), true);
+        let result = csv_to_json_command(csv.to_string(), Some(",".to_string()), true, None);
         assert!(result.is_ok());
         let json = result.unwrap();
         assert!(json.contains("John"));
@@ -68,7 +576,7 @@ mod tests {
     #[test]
     fn test_csv_with_header() {
         let csv = "name,age\nJohn,30\nJane,25";
-        let result = csv_to_json_command(csv.to_string(), Some(",".to_string()), false);
+        let result = csv_to_json_command(csv.to_string(), Some(",".to_string()), false, None);
         assert!(result.is_ok());
         let json = result.unwrap();
         assert!(json.contains("field_0"));
@@ -78,7 +586,146 @@ mod tests {
     #[test]
     fn test_empty_csv() {
         let csv = "";
-        let result = csv_to_json_command(csv.to_string(), Some(",".to_string()), true);
+        let result = csv_to_json_command(csv.to_string(), Some(",".to_string()), true, None);
         assert!(result.is_err());
+        assert!(matches!(
+            parse_csv(csv, None, true, &CsvToJsonOptions::default()),
+            Err(CsvJsonError::EmptyInput)
+        ));
+    }
+
+    #[test]
+    fn test_type_inference() {
+        let csv = "id,price,active,zip,note\n1,9.5,true,01234,\n2,-3,FALSE,99999,null";
+        let options = CsvToJsonOptions {
+            infer_types: true,
+            ..Default::default()
+        };
+        let json = csv_to_json_command(csv.to_string(), None, true, Some(options)).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["id"], 1);
+        assert_eq!(value[0]["price"], 9.5);
+        assert_eq!(value[0]["active"], true);
+        assert_eq!(value[0]["zip"], "01234");
+        assert_eq!(value[0]["note"], Value::Null);
+        assert_eq!(value[1]["price"], -3);
+        assert_eq!(value[1]["active"], false);
+        assert_eq!(value[1]["note"], Value::Null);
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a;b;c\n1;2;3", b'"'), ';');
+        assert_eq!(detect_delimiter("a\tb\n1\t2", b'"'), '\t');
+        assert_eq!(detect_delimiter("name,desc\nx,\"a;b;c\"", b'"'), ',');
+        assert_eq!(detect_delimiter("name,desc\nx,'a;b;c'", b'\''), ',');
+        assert_eq!(detect_delimiter("single column", b'"'), ',');
+
+        let json = csv_to_json_command("a;b\n1;2".to_string(), None, true, None).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["b"], "2");
+    }
+
+    #[test]
+    fn test_invalid_delimiter() {
+        let result = csv_to_json_command("a,b".to_string(), Some("::".to_string()), true, None);
+        assert!(result.unwrap_err().contains("Invalid delimiter"));
+    }
+
+    #[test]
+    fn test_custom_escape() {
+        let csv = "a,b\n\"say \\\"hi\\\"\",2";
+        let options = CsvToJsonOptions {
+            escape: Some('\\'),
+            ..Default::default()
+        };
+        let table = parse_csv(csv, Some(","), true, &options).unwrap();
+        assert_eq!(table.rows[0][0], "say \"hi\"");
+    }
+
+    #[test]
+    fn test_decode_bytes() {
+        assert_eq!(decode_bytes(&[0xEF, 0xBB, 0xBF, b'a'], CsvEncoding::Utf8).unwrap(), "a");
+        assert_eq!(decode_bytes(&[0xFF, 0xFE, b'a', 0], CsvEncoding::Utf8).unwrap(), "a");
+        assert_eq!(decode_bytes(&[0xE9], CsvEncoding::Latin1).unwrap(), "é");
+        assert!(decode_bytes(&[0xE9], CsvEncoding::Utf8).is_err());
+    }
+
+    #[test]
+    fn test_json_to_csv_flattens_nested_objects() {
+        let json = r#"[
+            {"id": 1, "user": {"name": "Ann", "address": {"city": "Oslo"}}, "tags": ["a", "b"]},
+            {"id": 2, "user": {"name": "Bo, Jr."}, "extra": null}
+        ]"#;
+        let csv = json_to_csv(json, &JsonToCsvOptions::default()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,user.name,user.address.city,tags,extra");
+        assert_eq!(lines[1], "1,Ann,Oslo,a|b,");
+        assert_eq!(lines[2], "2,\"Bo, Jr.\",,,");
+    }
+
+    #[test]
+    fn test_json_to_csv_array_modes() {
+        let json = r#"[{"items": [{"sku": "x"}, {"sku": "y"}]}]"#;
+        let indexed = JsonToCsvOptions {
+            array_mode: ArrayMode::Index,
+            ..Default::default()
+        };
+        let csv = json_to_csv(json, &indexed).unwrap();
+        assert!(csv.starts_with("items.0.sku,items.1.sku\nx,y"));
+
+        let csv = json_to_csv(json, &JsonToCsvOptions::default()).unwrap();
+        assert!(csv.contains(r#""[{""sku"":""x""},{""sku"":""y""}]""#));
+    }
+
+    #[test]
+    fn test_json_to_csv_options() {
+        let options = JsonToCsvOptions {
+            delimiter: Some("tab".to_string()),
+            quote_style: CsvQuoteStyle::Always,
+            include_bom: true,
+            crlf: true,
+            ..Default::default()
+        };
+        let csv = json_to_csv(r#"{"a": 1, "b": "x"}"#, &options).unwrap();
+        assert_eq!(csv, "\u{feff}\"a\"\t\"b\"\r\n\"1\"\t\"x\"\r\n");
+    }
+
+    #[test]
+    fn test_json_to_csv_roundtrip() {
+        let json = r#"[{"name":"Ann","age":31,"admin":true},{"name":"Bo","age":27,"admin":false}]"#;
+        let csv = json_to_csv(json, &JsonToCsvOptions::default()).unwrap();
+        let options = CsvToJsonOptions {
+            infer_types: true,
+            ..Default::default()
+        };
+        let back = csv_to_json_command(csv, None, true, Some(options)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&back).unwrap(),
+            serde_json::from_str::<Value>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_to_csv_errors() {
+        assert!(matches!(json_to_csv("", &JsonToCsvOptions::default()), Err(CsvJsonError::EmptyInput)));
+        assert!(matches!(
+            json_to_csv("42", &JsonToCsvOptions::default()),
+            Err(CsvJsonError::UnsupportedShape(_))
+        ));
+        assert!(json_to_csv("{bad", &JsonToCsvOptions::default()).is_err());
+        assert!(matches!(
+            json_to_csv(r#"{"a.b": 1, "a": {"b": 2}}"#, &JsonToCsvOptions::default()),
+            Err(CsvJsonError::ColumnCollision(column)) if column == "a.b"
+        ));
+    }
+
+    #[test]
+    fn test_large_integers_keep_precision() {
+        assert_eq!(infer_value("9223372036854775807"), Value::Number(i64::MAX.into()));
+        assert_eq!(infer_value("18446744073709551615"), Value::Number(u64::MAX.into()));
+        assert_eq!(infer_value("123456789012345678901234"), "123456789012345678901234");
+        assert_eq!(infer_value("-99999999999999999999"), "-99999999999999999999");
+        assert_eq!(infer_value("1e3"), 1000.0);
     }
 }
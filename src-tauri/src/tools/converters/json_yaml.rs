@@ -1,20 +1,785 @@
-use serde_json::Value as JsonValue;
-use serde_yaml;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use serde_yaml::value::{Tag, TaggedValue};
+use std::collections::HashMap;
+use std::rc::Rc;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser, Tag as EventTag};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
 
-pub fn json_to_yaml_command(json: String, _indent: usize) -> Result<String, String> {
-    let value: JsonValue = serde_json::from_str(&json)
-        .map_err(|e| e.to_string())?;
-    
-    serde_yaml::to_string(&value)
-        .map_err(|e| e.to_string())
+use crate::tools::formatters::yaml::{format_yaml_with_options, YamlFormatOptions};
+
+const CORE_TAG_PREFIX: &str = "tag:yaml.org,2002:";
+
+/// Nodes that alias and merge expansion may produce, so nested aliases
+/// ("billion laughs") fail instead of exhausting memory
+const MAX_ALIAS_EXPANSION: usize = 100_000;
+
+/// YAML 1.1 timestamp, the format behind `!!timestamp`
+static TIMESTAMP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(\d{4})-(\d{1,2})-(\d{1,2})(?:(?:[Tt]|[ \t]+)(\d{1,2}):(\d{2}):(\d{2})(\.\d+)?(?:[ \t]*(Z|[-+]\d{1,2}(?::?\d{2})?))?)?$",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum YamlTagMode {
+    /// Keep the value and drop the tag, with a warning
+    #[default]
+    Strip,
+    /// Represent tagged values as `{"!tag": value}` so they survive a round trip
+    Object,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampMode {
+    /// Keep timestamps exactly as written
+    #[default]
+    String,
+    Rfc3339,
+    /// Seconds since the Unix epoch
+    Epoch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonYamlOptions {
+    pub indent: usize,
+    pub sort_keys: bool,
+    pub tags: YamlTagMode,
+    pub timestamps: TimestampMode,
+}
+
+impl Default for JsonYamlOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            sort_keys: false,
+            tags: YamlTagMode::Strip,
+            timestamps: TimestampMode::String,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConversionWarning {
+    /// JSONPath-style location, e.g. `$.spec.ports[0]`
+    pub path: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonYamlConversion {
+    pub output: String,
+    pub warnings: Vec<ConversionWarning>,
+}
+
+pub fn json_to_yaml_command(json: String, indent: usize) -> Result<String, String> {
+    let options = JsonYamlOptions {
+        indent,
+        ..Default::default()
+    };
+    json_to_yaml(&json, &options).map(|conversion| conversion.output)
+}
+
+pub fn yaml_to_json_command(yaml: String, indent: usize) -> Result<String, String> {
+    let options = JsonYamlOptions {
+        indent,
+        ..Default::default()
+    };
+    yaml_to_json(&yaml, &options).map(|conversion| conversion.output)
+}
+
+pub fn json_to_yaml_with_options_command(
+    json: String,
+    options: Option<JsonYamlOptions>,
+) -> Result<JsonYamlConversion, String> {
+    json_to_yaml(&json, &options.unwrap_or_default())
+}
+
+pub fn yaml_to_json_with_options_command(
+    yaml: String,
+    options: Option<JsonYamlOptions>,
+) -> Result<JsonYamlConversion, String> {
+    yaml_to_json(&yaml, &options.unwrap_or_default())
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    let simple = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if simple {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}[{:?}]", path, key)
+    }
+}
+
+fn warning(
+    warnings: &mut Vec<ConversionWarning>,
+    path: &str,
+    line: Option<usize>,
+    message: impl Into<String>,
+) {
+    warnings.push(ConversionWarning {
+        path: path.to_string(),
+        line,
+        message: message.into(),
+    });
 }
 
-pub fn yaml_to_json_command(yaml: String, _indent: usize) -> Result<String, String> {
-    let value: JsonValue = serde_yaml::from_str(&yaml)
-        .map_err(|e| e.to_string())?;
-    
-    serde_json::to_string_pretty(&value)
-        .map_err(|e| e.to_string())
+// JSON -> YAML
+
+/// Convert JSON to YAML with the requested indentation and key order
+pub fn json_to_yaml(json: &str, options: &JsonYamlOptions) -> Result<JsonYamlConversion, String> {
+    let value: JsonValue = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut warnings = Vec::new();
+    let yaml_value = json_to_yaml_value(&value, "$", options, &mut warnings);
+    let raw = serde_yaml::to_string(&yaml_value).map_err(|e| e.to_string())?;
+
+    let format_options = YamlFormatOptions {
+        indent: options.indent,
+        ..Default::default()
+    };
+    let output = match format_yaml_with_options(&raw, &format_options) {
+        Ok(formatted) => formatted,
+        Err(e) => {
+            warning(
+                &mut warnings,
+                "$",
+                None,
+                format!("Indentation could not be applied: {}", e),
+            );
+            raw
+        }
+    };
+    Ok(JsonYamlConversion { output, warnings })
+}
+
+fn json_to_yaml_value(
+    value: &JsonValue,
+    path: &str,
+    options: &JsonYamlOptions,
+    warnings: &mut Vec<ConversionWarning>,
+) -> serde_yaml::Value {
+    match value {
+        JsonValue::Null => serde_yaml::Value::Null,
+        JsonValue::Bool(b) => serde_yaml::Value::Bool(*b),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                serde_yaml::Value::Number(i.into())
+            } else if let Some(u) = n.as_u64() {
+                serde_yaml::Value::Number(u.into())
+            } else {
+                serde_yaml::Value::Number(n.as_f64().unwrap_or_default().into())
+            }
+        }
+        JsonValue::String(s) => serde_yaml::Value::String(s.clone()),
+        JsonValue::Array(items) => serde_yaml::Value::Sequence(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    json_to_yaml_value(item, &format!("{}[{}]", path, i), options, warnings)
+                })
+                .collect(),
+        ),
+        JsonValue::Object(map) => {
+            if options.tags == YamlTagMode::Object && map.len() == 1 {
+                let (key, inner) = map.iter().next().unwrap();
+                if let Some(tag) = key.strip_prefix('!') {
+                    let inner =
+                        json_to_yaml_value(inner, &child_path(path, key), options, warnings);
+                    if tag.starts_with('!') {
+                        // serde_yaml can only write local tags, so core tags become plain values
+                        warning(
+                            warnings,
+                            path,
+                            None,
+                            format!(
+                                "Core tag `{}` cannot be written back; the value is left untagged",
+                                key
+                            ),
+                        );
+                        return inner;
+                    }
+                    return serde_yaml::Value::Tagged(Box::new(TaggedValue {
+                        tag: Tag::new(key.clone()),
+                        value: inner,
+                    }));
+                }
+            }
+
+            let mut entries: Vec<(&String, &JsonValue)> = map.iter().collect();
+            if options.sort_keys {
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+            }
+            let mut mapping = serde_yaml::Mapping::new();
+            for (key, item) in entries {
+                let item = json_to_yaml_value(item, &child_path(path, key), options, warnings);
+                mapping.insert(serde_yaml::Value::String(key.clone()), item);
+            }
+            serde_yaml::Value::Mapping(mapping)
+        }
+    }
+}
+
+// YAML -> JSON
+
+/// YAML node as read from the parser, before any JSON-specific interpretation
+#[derive(Debug, Clone)]
+enum YamlNode {
+    Scalar {
+        value: String,
+        plain: bool,
+        tag: Option<EventTag>,
+        line: usize,
+    },
+    Sequence {
+        items: Vec<YamlNode>,
+        tag: Option<EventTag>,
+        line: usize,
+    },
+    Mapping {
+        entries: Vec<(YamlNode, YamlNode)>,
+        pending_key: Option<Box<YamlNode>>,
+        tag: Option<EventTag>,
+        line: usize,
+    },
+    Alias {
+        target: Rc<YamlNode>,
+        line: usize,
+    },
+}
+
+impl YamlNode {
+    fn line(&self) -> usize {
+        match self {
+            YamlNode::Scalar { line, .. }
+            | YamlNode::Sequence { line, .. }
+            | YamlNode::Mapping { line, .. }
+            | YamlNode::Alias { line, .. } => *line,
+        }
+    }
+
+    /// Follow aliases to the node they copy
+    fn resolved(&self) -> &YamlNode {
+        match self {
+            YamlNode::Alias { target, .. } => target.resolved(),
+            other => other,
+        }
+    }
+}
+
+/// Builds one tree per document from parser events
+#[derive(Default)]
+struct TreeBuilder {
+    stack: Vec<(YamlNode, usize)>,
+    /// Shared with every alias, which only holds a reference
+    anchors: HashMap<usize, Rc<YamlNode>>,
+    documents: Vec<YamlNode>,
+    error: Option<String>,
+}
+
+impl TreeBuilder {
+    fn push(&mut self, node: YamlNode, anchor: usize) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, Rc::new(node.clone()));
+        }
+        match self.stack.last_mut() {
+            None => self.documents.push(node),
+            Some((YamlNode::Sequence { items, .. }, _)) => items.push(node),
+            Some((
+                YamlNode::Mapping {
+                    entries,
+                    pending_key,
+                    ..
+                },
+                _,
+            )) => match pending_key.take() {
+                Some(key) => entries.push((*key, node)),
+                None => *pending_key = Some(Box::new(node)),
+            },
+            Some(_) => {}
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let line = mark.line();
+        match event {
+            Event::Scalar(value, style, anchor, tag) => {
+                let node = YamlNode::Scalar {
+                    value,
+                    plain: style == TScalarStyle::Plain,
+                    tag,
+                    line,
+                };
+                self.push(node, anchor);
+            }
+            Event::SequenceStart(anchor, tag) => self.stack.push((
+                YamlNode::Sequence {
+                    items: Vec::new(),
+                    tag,
+                    line,
+                },
+                anchor,
+            )),
+            Event::MappingStart(anchor, tag) => self.stack.push((
+                YamlNode::Mapping {
+                    entries: Vec::new(),
+                    pending_key: None,
+                    tag,
+                    line,
+                },
+                anchor,
+            )),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some((node, anchor)) = self.stack.pop() {
+                    self.push(node, anchor);
+                }
+            }
+            Event::Alias(id) => match self.anchors.get(&id).cloned() {
+                Some(target) => self.push(YamlNode::Alias { target, line }, 0),
+                None => self.error = Some(format!("Unknown alias at line {}", line)),
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Resolve a plain scalar following the YAML 1.2 core schema
+fn resolve_plain(value: &str) -> JsonValue {
+    match value {
+        "" | "~" | "null" | "Null" | "NULL" => return JsonValue::Null,
+        "true" | "True" | "TRUE" => return JsonValue::Bool(true),
+        "false" | "False" | "FALSE" => return JsonValue::Bool(false),
+        _ => {}
+    }
+    let digits = value.strip_prefix('+').unwrap_or(value);
+    if let Ok(i) = digits.parse::<i64>() {
+        return JsonValue::from(i);
+    }
+    if let Ok(u) = digits.parse::<u64>() {
+        return JsonValue::from(u);
+    }
+    if let Some(hex) = value.strip_prefix("0x") {
+        if let Ok(i) = i64::from_str_radix(hex, 16) {
+            return JsonValue::from(i);
+        }
+    }
+    if let Some(octal) = value.strip_prefix("0o") {
+        if let Ok(i) = i64::from_str_radix(octal, 8) {
+            return JsonValue::from(i);
+        }
+    }
+    let looks_numeric = value
+        .trim_start_matches(['-', '+'])
+        .starts_with(|c: char| c.is_ascii_digit() || c == '.');
+    if looks_numeric {
+        if let Some(number) = value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+        {
+            return JsonValue::Number(number);
+        }
+    }
+    JsonValue::String(value.to_string())
+}
+
+fn special_float(value: &str) -> Option<&'static str> {
+    match value {
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => Some("positive infinity"),
+        "-.inf" | "-.Inf" | "-.INF" => Some("negative infinity"),
+        ".nan" | ".NaN" | ".NAN" => Some("NaN"),
+        _ => None,
+    }
+}
+
+fn tag_name(tag: &EventTag) -> String {
+    if tag.handle == CORE_TAG_PREFIX {
+        format!("!!{}", tag.suffix)
+    } else {
+        format!("{}{}", tag.handle, tag.suffix)
+    }
+}
+
+/// Parse a YAML 1.1 timestamp; dates and times without an offset are UTC
+fn parse_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    let captures = TIMESTAMP.captures(value.trim())?;
+    let number = |i: usize| captures.get(i).and_then(|m| m.as_str().parse::<u32>().ok());
+    let date = NaiveDate::from_ymd_opt(number(1)? as i32, number(2)?, number(3)?)?;
+    let Some(hour) = number(4) else {
+        return Some(
+            Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?)
+                .fixed_offset(),
+        );
+    };
+    let nanos = captures
+        .get(7)
+        .map(|m| {
+            let digits = &m.as_str()[1..];
+            let padded = format!("{:0<9}", &digits[..digits.len().min(9)]);
+            padded.parse::<u32>().unwrap_or(0)
+        })
+        .unwrap_or(0);
+    let naive = NaiveDateTime::new(
+        date,
+        chrono::NaiveTime::from_hms_nano_opt(hour, number(5)?, number(6)?, nanos)?,
+    );
+
+    let offset_seconds = match captures.get(8).map(|m| m.as_str()) {
+        None | Some("Z") => 0,
+        Some(offset) => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let digits = offset[1..].replace(':', "");
+            let (hours, minutes) = if digits.len() > 2 {
+                digits.split_at(digits.len() - 2)
+            } else {
+                (digits.as_str(), "0")
+            };
+            sign * (hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60)
+        }
+    };
+    FixedOffset::east_opt(offset_seconds)?
+        .from_local_datetime(&naive)
+        .single()
+}
+
+struct YamlToJson<'o> {
+    options: &'o JsonYamlOptions,
+    warnings: Vec<ConversionWarning>,
+    /// Nesting of alias expansions currently being converted
+    alias_depth: usize,
+    /// Nodes produced inside alias expansions so far
+    expanded: usize,
+}
+
+impl<'o> YamlToJson<'o> {
+    fn warn(&mut self, path: &str, node: &YamlNode, message: impl Into<String>) {
+        warning(&mut self.warnings, path, Some(node.line()), message);
+    }
+
+    /// Apply the tag mode to a tagged value that JSON cannot express
+    fn tagged(
+        &mut self,
+        path: &str,
+        node: &YamlNode,
+        tag: &EventTag,
+        value: JsonValue,
+    ) -> JsonValue {
+        let name = tag_name(tag);
+        match self.options.tags {
+            YamlTagMode::Object => {
+                let mut map = Map::new();
+                map.insert(name, value);
+                JsonValue::Object(map)
+            }
+            YamlTagMode::Strip => {
+                self.warn(path, node, format!("Tag `{}` dropped", name));
+                value
+            }
+        }
+    }
+
+    /// Convert a timestamp according to the timestamp mode; `None` keeps it as written
+    fn timestamp(&self, value: &str) -> Option<JsonValue> {
+        if self.options.timestamps == TimestampMode::String {
+            return None;
+        }
+        let timestamp = parse_timestamp(value)?;
+        Some(match self.options.timestamps {
+            TimestampMode::Epoch => JsonValue::from(timestamp.timestamp()),
+            _ => JsonValue::String(timestamp.to_rfc3339()),
+        })
+    }
+
+    /// Resolve a plain scalar or one carrying a core type tag such as `!!int`
+    fn typed(
+        &mut self,
+        path: &str,
+        node: &YamlNode,
+        value: &str,
+        expected: Option<&str>,
+    ) -> Result<JsonValue, String> {
+        if let Some(kind) = special_float(value) {
+            self.warn(
+                path,
+                node,
+                format!("JSON has no {}; written as the string `{}`", kind, value),
+            );
+            return Ok(JsonValue::String(value.to_string()));
+        }
+        let resolved = resolve_plain(value);
+        if let JsonValue::String(s) = &resolved {
+            if let Some(expected) = expected {
+                return Err(format!(
+                    "`{}` is not a valid !!{} at {}",
+                    value, expected, path
+                ));
+            }
+            if let Some(converted) = self.timestamp(s) {
+                return Ok(converted);
+            }
+        }
+        Ok(resolved)
+    }
+
+    fn scalar(&mut self, path: &str, node: &YamlNode) -> Result<JsonValue, String> {
+        let YamlNode::Scalar {
+            value, plain, tag, ..
+        } = node
+        else {
+            unreachable!()
+        };
+        let Some(tag) = tag else {
+            return if *plain {
+                self.typed(path, node, value, None)
+            } else {
+                Ok(JsonValue::String(value.clone()))
+            };
+        };
+
+        let core = (tag.handle == CORE_TAG_PREFIX).then_some(tag.suffix.as_str());
+        match core {
+            Some("str") => Ok(JsonValue::String(value.clone())),
+            Some(expected @ ("int" | "float" | "bool" | "null")) => {
+                self.typed(path, node, value, Some(expected))
+            }
+            Some("binary") => {
+                let compact: String = value.split_whitespace().collect();
+                STANDARD
+                    .decode(&compact)
+                    .map_err(|e| format!("Invalid !!binary data at {}: {}", path, e))?;
+                if self.options.tags == YamlTagMode::Strip {
+                    self.warn(path, node, "Binary data written as a base64 string");
+                    return Ok(JsonValue::String(compact));
+                }
+                Ok(self.tagged(path, node, tag, JsonValue::String(compact)))
+            }
+            Some("timestamp") => {
+                if parse_timestamp(value).is_none() {
+                    return Err(format!(
+                        "`{}` is not a valid !!timestamp at {}",
+                        value, path
+                    ));
+                }
+                match self.timestamp(value) {
+                    Some(converted) => Ok(converted),
+                    None => Ok(self.tagged(path, node, tag, JsonValue::String(value.clone()))),
+                }
+            }
+            _ => {
+                let inner = if *plain {
+                    resolve_plain(value)
+                } else {
+                    JsonValue::String(value.clone())
+                };
+                Ok(self.tagged(path, node, tag, inner))
+            }
+        }
+    }
+
+    fn key(&mut self, path: &str, node: &YamlNode) -> Result<String, String> {
+        match node.resolved() {
+            scalar @ YamlNode::Scalar { value, .. } => match self.scalar(path, scalar)? {
+                JsonValue::String(s) => Ok(s),
+                other => {
+                    self.warn(
+                        path,
+                        node,
+                        format!("Non-string key `{}` converted to a string", value),
+                    );
+                    Ok(other.to_string())
+                }
+            },
+            complex => {
+                let value = self.convert(path, complex)?;
+                let key = value.to_string();
+                self.warn(
+                    path,
+                    node,
+                    format!("Complex key written as the JSON string {}", key),
+                );
+                Ok(key)
+            }
+        }
+    }
+
+    fn expand_alias(&mut self, path: &str, target: &YamlNode) -> Result<JsonValue, String> {
+        self.alias_depth += 1;
+        let result = self.convert(path, target);
+        self.alias_depth -= 1;
+        result
+    }
+
+    fn convert(&mut self, path: &str, node: &YamlNode) -> Result<JsonValue, String> {
+        if self.alias_depth > 0 {
+            self.expanded += 1;
+            if self.expanded > MAX_ALIAS_EXPANSION {
+                return Err(format!(
+                    "Aliases expand to more than {} nodes at {}",
+                    MAX_ALIAS_EXPANSION, path
+                ));
+            }
+        }
+        match node {
+            YamlNode::Alias { target, .. } => {
+                self.warn(
+                    path,
+                    node,
+                    "Alias expanded into a copy of its anchored node",
+                );
+                self.expand_alias(path, target)
+            }
+            YamlNode::Scalar { .. } => self.scalar(path, node),
+            YamlNode::Sequence { items, tag, .. } => {
+                let values = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| self.convert(&format!("{}[{}]", path, i), item))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let array = JsonValue::Array(values);
+                match tag {
+                    Some(tag) if !(tag.handle == CORE_TAG_PREFIX && tag.suffix == "seq") => {
+                        Ok(self.tagged(path, node, tag, array))
+                    }
+                    _ => Ok(array),
+                }
+            }
+            YamlNode::Mapping { entries, tag, .. } => {
+                let mut map = Map::new();
+                let mut merges = Vec::new();
+                for (key_node, value_node) in entries {
+                    let is_merge = matches!(key_node.resolved(), YamlNode::Scalar { value, plain: true, .. } if value == "<<");
+                    if is_merge {
+                        merges.push(value_node);
+                        continue;
+                    }
+                    let key = self.key(path, key_node)?;
+                    let value = self.convert(&child_path(path, &key), value_node)?;
+                    if map.insert(key.clone(), value).is_some() {
+                        return Err(format!("Duplicate key `{}` at {}", key, path));
+                    }
+                }
+
+                for merge in merges {
+                    self.warn(path, merge, "Merge key `<<` expanded into the mapping");
+                    let sources: Vec<&YamlNode> = match merge.resolved() {
+                        YamlNode::Sequence { items, .. } => items.iter().collect(),
+                        _ => vec![merge],
+                    };
+                    for source in sources {
+                        let converted = match source {
+                            YamlNode::Alias { target, .. } => {
+                                self.expand_alias(path, target.resolved())?
+                            }
+                            other => self.convert(path, other)?,
+                        };
+                        let JsonValue::Object(merged) = converted else {
+                            return Err(format!("Merge key at {} must refer to a mapping", path));
+                        };
+                        for (key, value) in merged {
+                            map.entry(key).or_insert(value);
+                        }
+                    }
+                }
+
+                if self.options.sort_keys {
+                    map.sort_keys();
+                }
+
+                let is_set =
+                    matches!(tag, Some(t) if t.handle == CORE_TAG_PREFIX && t.suffix == "set");
+                match tag {
+                    Some(_) if is_set && self.options.tags == YamlTagMode::Strip => {
+                        self.warn(path, node, "!!set written as an array of its members");
+                        Ok(JsonValue::Array(
+                            map.into_iter().map(|(k, _)| JsonValue::String(k)).collect(),
+                        ))
+                    }
+                    Some(tag) if !(tag.handle == CORE_TAG_PREFIX && tag.suffix == "map") => {
+                        Ok(self.tagged(path, node, tag, JsonValue::Object(map)))
+                    }
+                    _ => Ok(JsonValue::Object(map)),
+                }
+            }
+        }
+    }
+}
+
+/// Convert YAML to JSON, reporting everything that JSON cannot represent
+pub fn yaml_to_json(yaml: &str, options: &JsonYamlOptions) -> Result<JsonYamlConversion, String> {
+    let mut builder = TreeBuilder::default();
+    Parser::new_from_str(yaml)
+        .load(&mut builder, true)
+        .map_err(|e| format!("YAML parse error: {}", e))?;
+    if let Some(error) = builder.error {
+        return Err(error);
+    }
+
+    let mut converter = YamlToJson {
+        options,
+        warnings: Vec::new(),
+        alias_depth: 0,
+        expanded: 0,
+    };
+    let mut documents = builder
+        .documents
+        .iter()
+        .map(|document| converter.convert("$", document))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let value = match documents.len() {
+        0 => JsonValue::Null,
+        1 => documents.remove(0),
+        count => {
+            warning(
+                &mut converter.warnings,
+                "$",
+                None,
+                format!("{} YAML documents combined into a JSON array", count),
+            );
+            JsonValue::Array(documents)
+        }
+    };
+
+    if yaml.lines().any(|line| line.trim_start().starts_with('#')) {
+        warning(
+            &mut converter.warnings,
+            "$",
+            None,
+            "Comments are not representable in JSON and were dropped",
+        );
+    }
+
+    let output = if options.indent == 0 {
+        serde_json::to_string(&value)
+    } else {
+        let indent = " ".repeat(options.indent);
+        let mut buffer = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+        value
+            .serialize(&mut serializer)
+            .map(|_| String::from_utf8(buffer).unwrap_or_default())
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(JsonYamlConversion {
+        output,
+        warnings: converter.warnings,
+    })
 }
 
 #[cfg(test)]
@@ -41,4 +806,144 @@ city: New York
         let json = result.unwrap();
         assert!(json.contains("John"));
     }
+
+    #[test]
+    fn test_indent_and_key_order() {
+        let json = r#"{"zeta": {"b": [1, 2], "a": true}, "alpha": null}"#;
+        let yaml = json_to_yaml_command(json.to_string(), 4).unwrap();
+        assert_eq!(
+            yaml,
+            "zeta:\n    b:\n        - 1\n        - 2\n    a: true\nalpha: null\n"
+        );
+
+        let options = JsonYamlOptions {
+            sort_keys: true,
+            ..Default::default()
+        };
+        let sorted = json_to_yaml(json, &options).unwrap().output;
+        assert_eq!(
+            sorted,
+            "alpha: null\nzeta:\n  a: true\n  b:\n    - 1\n    - 2\n"
+        );
+
+        let back = yaml_to_json_command(yaml, 4).unwrap();
+        assert!(back.starts_with("{\n    \"zeta\": {\n        \"b\": ["));
+        assert_eq!(
+            yaml_to_json_command("b: 1\na: 2".to_string(), 0).unwrap(),
+            r#"{"b":1,"a":2}"#
+        );
+    }
+
+    #[test]
+    fn test_yaml_specific_values_warn() {
+        let yaml = "# config\nbase: &base {x: 1}\nderived:\n  <<: *base\n  y: 2\nbin: !!binary |\n  aGVs\n  bG8=\n1: one\ninf: .inf\nref: !Ref bucket\ncopy: *base\n---\nsecond: true\n";
+        let conversion = yaml_to_json(yaml, &JsonYamlOptions::default()).unwrap();
+        let value: JsonValue = serde_json::from_str(&conversion.output).unwrap();
+        assert_eq!(value[0]["derived"], serde_json::json!({"y": 2, "x": 1}));
+        assert_eq!(value[0]["bin"], "aGVsbG8=");
+        assert_eq!(value[0]["1"], "one");
+        assert_eq!(value[0]["inf"], ".inf");
+        assert_eq!(value[0]["ref"], "bucket");
+        assert_eq!(value[0]["copy"], serde_json::json!({"x": 1}));
+        assert_eq!(value[1]["second"], true);
+
+        let messages: Vec<&str> = conversion
+            .warnings
+            .iter()
+            .map(|w| w.message.as_str())
+            .collect();
+        for expected in [
+            "Merge key `<<` expanded into the mapping",
+            "Alias expanded into a copy of its anchored node",
+            "Binary data written as a base64 string",
+            "Non-string key `1` converted to a string",
+            "JSON has no positive infinity; written as the string `.inf`",
+            "Tag `!Ref` dropped",
+            "2 YAML documents combined into a JSON array",
+            "Comments are not representable in JSON and were dropped",
+        ] {
+            assert!(
+                messages.contains(&expected),
+                "missing warning {:?} in {:?}",
+                expected,
+                messages
+            );
+        }
+        let tag_warning = conversion
+            .warnings
+            .iter()
+            .find(|w| w.message.contains("!Ref"))
+            .unwrap();
+        assert_eq!(tag_warning.path, "$.ref");
+        assert_eq!(tag_warning.line, Some(11));
+    }
+
+    #[test]
+    fn test_tags_round_trip_as_objects() {
+        let options = JsonYamlOptions {
+            tags: YamlTagMode::Object,
+            ..Default::default()
+        };
+        let conversion =
+            yaml_to_json("bucket: !Ref MyBucket\nids: !!set {a, b}\n", &options).unwrap();
+        let value: JsonValue = serde_json::from_str(&conversion.output).unwrap();
+        assert_eq!(value["bucket"], serde_json::json!({"!Ref": "MyBucket"}));
+        assert_eq!(
+            value["ids"],
+            serde_json::json!({"!!set": {"a": null, "b": null}})
+        );
+        assert!(conversion.warnings.is_empty());
+
+        let yaml = json_to_yaml(r#"{"bucket": {"!Ref": "MyBucket"}}"#, &options).unwrap();
+        assert_eq!(yaml.output, "bucket: !Ref MyBucket\n");
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let yaml = "plain: 2001-12-14 21:59:43.10 -5\ntagged: !!timestamp 2002-12-14\nquoted: '2001-12-14'\n";
+        let conversion = yaml_to_json(yaml, &JsonYamlOptions::default()).unwrap();
+        let value: JsonValue = serde_json::from_str(&conversion.output).unwrap();
+        assert_eq!(value["plain"], "2001-12-14 21:59:43.10 -5");
+
+        let options = JsonYamlOptions {
+            timestamps: TimestampMode::Rfc3339,
+            ..Default::default()
+        };
+        let value: JsonValue =
+            serde_json::from_str(&yaml_to_json(yaml, &options).unwrap().output).unwrap();
+        assert_eq!(value["plain"], "2001-12-14T21:59:43.100-05:00");
+        assert_eq!(value["tagged"], "2002-12-14T00:00:00+00:00");
+        assert_eq!(value["quoted"], "2001-12-14");
+
+        let options = JsonYamlOptions {
+            timestamps: TimestampMode::Epoch,
+            ..Default::default()
+        };
+        let value: JsonValue =
+            serde_json::from_str(&yaml_to_json(yaml, &options).unwrap().output).unwrap();
+        assert_eq!(value["tagged"], 1039824000);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(yaml_to_json("a: [1, 2", &JsonYamlOptions::default()).is_err());
+        assert!(yaml_to_json("a: 1\na: 2", &JsonYamlOptions::default()).is_err());
+        assert!(yaml_to_json("a: !!binary '%%%'", &JsonYamlOptions::default()).is_err());
+        assert!(json_to_yaml("{", &JsonYamlOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_alias_bomb_is_rejected() {
+        let mut yaml = String::from("a0: &a0 [x, x, x, x, x, x, x, x, x, x]\n");
+        for level in 1..9 {
+            let refs = vec![format!("*a{}", level - 1); 10].join(", ");
+            yaml.push_str(&format!("a{}: &a{} [{}]\n", level, level, refs));
+        }
+        let err = yaml_to_json(&yaml, &JsonYamlOptions::default()).unwrap_err();
+        assert!(err.contains("expand to more than"));
+
+        // Ordinary anchors and merges stay well within the budget
+        let merges = "a: &a {x: 1}\nb: &b {<<: [*a, *a, *a]}\nc: {<<: [*b, *b]}\n";
+        assert!(yaml_to_json(merges, &JsonYamlOptions::default()).is_ok());
+    }
 }
@@ -6,6 +6,8 @@ pub mod timestamp;
 pub mod color;
 pub mod units;
 pub mod cron;
+pub mod xml_json;
+pub mod binary_json;
 
 pub use json_yaml::{json_to_yaml_command, yaml_to_json_command};
 pub use csv_json::csv_to_json_command;
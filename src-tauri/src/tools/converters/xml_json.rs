@@ -0,0 +1,642 @@
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+/// Mapping convention used when bridging XML and JSON
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum XmlJsonConvention {
+    /// Attributes as `@name`, text as `#text` when mixed with attributes/children
+    #[default]
+    Simple,
+    /// Attributes as `@name`, text always under `$`, namespaces under `@xmlns`
+    BadgerFish,
+    /// Attributes dropped, root element omitted, text-only elements become values
+    Parker,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct XmlJsonOptions {
+    #[serde(default)]
+    pub convention: XmlJsonConvention,
+    /// Element names that always become JSON arrays, even with a single occurrence
+    #[serde(default)]
+    pub force_array: Vec<String>,
+    /// Turn every child element into an array
+    #[serde(default)]
+    pub always_array: bool,
+    /// Convert numeric and boolean text into JSON numbers and booleans
+    #[serde(default)]
+    pub infer_types: bool,
+    /// Drop namespace prefixes and `xmlns` declarations
+    #[serde(default)]
+    pub strip_namespaces: bool,
+    /// Indentation for JSON → XML output (0 for a single line)
+    #[serde(default = "default_indent")]
+    pub indent: usize,
+    /// Root element name used when the JSON has no single top-level key
+    #[serde(default)]
+    pub root_name: Option<String>,
+    #[serde(default)]
+    pub xml_declaration: bool,
+}
+
+fn default_indent() -> usize {
+    2
+}
+
+impl Default for XmlJsonOptions {
+    fn default() -> Self {
+        Self {
+            convention: XmlJsonConvention::Simple,
+            force_array: Vec::new(),
+            always_array: false,
+            infer_types: false,
+            strip_namespaces: false,
+            indent: default_indent(),
+            root_name: None,
+            xml_declaration: false,
+        }
+    }
+}
+
+/// Intermediate element tree shared by both directions
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlNode>,
+}
+
+#[derive(Debug)]
+enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+impl XmlElement {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                XmlNode::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("")
+    }
+
+    fn has_child_elements(&self) -> bool {
+        self.children
+            .iter()
+            .any(|child| matches!(child, XmlNode::Element(_)))
+    }
+
+    /// Child elements grouped by name, in order of first appearance
+    fn grouped_children(&self) -> Vec<(&str, Vec<&XmlElement>)> {
+        let mut groups: Vec<(&str, Vec<&XmlElement>)> = Vec::new();
+        for child in &self.children {
+            if let XmlNode::Element(el) = child {
+                match groups.iter_mut().find(|(name, _)| *name == el.name) {
+                    Some((_, items)) => items.push(el),
+                    None => groups.push((el.name.as_str(), vec![el])),
+                }
+            }
+        }
+        groups
+    }
+}
+
+pub fn xml_to_json_command(xml: String, options: Option<XmlJsonOptions>) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let root = parse_xml_tree(&xml, options.strip_namespaces)?;
+
+    let value = match options.convention {
+        XmlJsonConvention::Parker => parker_value(&root, &options),
+        XmlJsonConvention::Simple | XmlJsonConvention::BadgerFish => {
+            let mut map = Map::new();
+            map.insert(root.name.clone(), element_to_value(&root, &options));
+            Value::Object(map)
+        }
+    };
+
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+pub fn json_to_xml_command(json: String, options: Option<XmlJsonOptions>) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let value: Value = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+
+    let root = match (&value, &options.root_name, options.convention) {
+        (Value::Object(map), None, convention)
+            if map.len() == 1 && convention != XmlJsonConvention::Parker =>
+        {
+            let (name, inner) = map.iter().next().unwrap();
+            if inner.is_array() {
+                return Err("Root element cannot be an array; set a root name".to_string());
+            }
+            value_to_element(name, inner, &options)?
+        }
+        (_, root_name, _) => {
+            let name = root_name.as_deref().unwrap_or("root");
+            value_to_element(name, &value, &options)?
+        }
+    };
+
+    write_xml_tree(&root, &options)
+}
+
+/// Deepest element nesting accepted; conversion recurses once per level
+const MAX_DEPTH: usize = 256;
+
+fn parse_xml_tree(xml: &str, strip_namespaces: bool) -> Result<XmlElement, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root: Option<XmlElement> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML parse error at position {}: {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(start) => {
+                if stack.len() >= MAX_DEPTH {
+                    return Err(format!(
+                        "Elements are nested more than {} levels deep",
+                        MAX_DEPTH
+                    ));
+                }
+                stack.push(start_to_element(&start, strip_namespaces)?);
+            }
+            Event::Empty(start) => {
+                let element = start_to_element(&start, strip_namespaces)?;
+                attach_element(&mut stack, &mut root, element)?;
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| "Unexpected closing tag".to_string())?;
+                attach_element(&mut stack, &mut root, element)?;
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| e.to_string())?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(XmlNode::Text(text.into_owned()));
+                }
+            }
+            Event::CData(data) => {
+                let text = String::from_utf8(data.into_inner().into_owned())
+                    .map_err(|e| e.to_string())?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(XmlNode::Text(text));
+                }
+            }
+            Event::Eof => break,
+            // Declarations, comments, processing instructions and doctypes carry no data
+            _ => {}
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(format!("Unclosed element <{}>", stack.last().unwrap().name));
+    }
+
+    root.ok_or_else(|| "No root element found".to_string())
+}
+
+fn start_to_element(start: &BytesStart, strip_namespaces: bool) -> Result<XmlElement, String> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut element = XmlElement::new(&qualified_name(&name, strip_namespaces));
+
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| e.to_string())?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+        if strip_namespaces && is_namespace_declaration(&key) {
+            continue;
+        }
+        let value = attr.unescape_value().map_err(|e| e.to_string())?;
+        element
+            .attributes
+            .push((qualified_name(&key, strip_namespaces), value.into_owned()));
+    }
+
+    Ok(element)
+}
+
+fn attach_element(
+    stack: &mut [XmlElement],
+    root: &mut Option<XmlElement>,
+    element: XmlElement,
+) -> Result<(), String> {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(XmlNode::Element(element)),
+        None if root.is_none() => *root = Some(element),
+        None => return Err("XML document has more than one root element".to_string()),
+    }
+    Ok(())
+}
+
+fn qualified_name(name: &str, strip_namespaces: bool) -> String {
+    if strip_namespaces {
+        name.rsplit(':').next().unwrap_or(name).to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn is_namespace_declaration(key: &str) -> bool {
+    key == "xmlns" || key.starts_with("xmlns:")
+}
+
+fn text_value(text: &str, infer_types: bool) -> Value {
+    if !infer_types {
+        return Value::String(text.to_string());
+    }
+
+    match text {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+
+    // Keep values like "007" or "+1" as strings, they are usually identifiers
+    let looks_like_id =
+        text.starts_with('+') || (text.len() > 1 && text.starts_with('0') && !text.starts_with("0."));
+    if !looks_like_id {
+        if let Ok(int) = text.parse::<i64>() {
+            return Value::Number(int.into());
+        }
+        if let Some(num) = text.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Value::Number(num);
+        }
+    }
+
+    Value::String(text.to_string())
+}
+
+fn wants_array(name: &str, options: &XmlJsonOptions) -> bool {
+    options.always_array || options.force_array.iter().any(|n| n == name)
+}
+
+fn insert_children(
+    map: &mut Map<String, Value>,
+    element: &XmlElement,
+    options: &XmlJsonOptions,
+    convert: fn(&XmlElement, &XmlJsonOptions) -> Value,
+) {
+    for (name, items) in element.grouped_children() {
+        let value = if items.len() > 1 || wants_array(name, options) {
+            Value::Array(items.iter().map(|el| convert(el, options)).collect())
+        } else {
+            convert(items[0], options)
+        };
+        map.insert(name.to_string(), value);
+    }
+}
+
+fn element_to_value(element: &XmlElement, options: &XmlJsonOptions) -> Value {
+    match options.convention {
+        XmlJsonConvention::BadgerFish => badgerfish_value(element, options),
+        XmlJsonConvention::Parker => parker_value(element, options),
+        XmlJsonConvention::Simple => simple_value(element, options),
+    }
+}
+
+fn simple_value(element: &XmlElement, options: &XmlJsonOptions) -> Value {
+    let text = element.text();
+
+    if element.attributes.is_empty() && !element.has_child_elements() {
+        return if text.is_empty() {
+            Value::Null
+        } else {
+            text_value(&text, options.infer_types)
+        };
+    }
+
+    let mut map = Map::new();
+    for (key, value) in &element.attributes {
+        map.insert(format!("@{}", key), text_value(value, options.infer_types));
+    }
+    if !text.is_empty() {
+        map.insert("#text".to_string(), text_value(&text, options.infer_types));
+    }
+    insert_children(&mut map, element, options, simple_value);
+    Value::Object(map)
+}
+
+fn badgerfish_value(element: &XmlElement, options: &XmlJsonOptions) -> Value {
+    let mut map = Map::new();
+    let mut namespaces = Map::new();
+
+    for (key, value) in &element.attributes {
+        if key == "xmlns" {
+            namespaces.insert("$".to_string(), Value::String(value.clone()));
+        } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+            namespaces.insert(prefix.to_string(), Value::String(value.clone()));
+        } else {
+            map.insert(format!("@{}", key), Value::String(value.clone()));
+        }
+    }
+    if !namespaces.is_empty() {
+        map.insert("@xmlns".to_string(), Value::Object(namespaces));
+    }
+
+    let text = element.text();
+    if !text.is_empty() {
+        map.insert("$".to_string(), Value::String(text));
+    }
+    insert_children(&mut map, element, options, badgerfish_value);
+    Value::Object(map)
+}
+
+fn parker_value(element: &XmlElement, options: &XmlJsonOptions) -> Value {
+    if !element.has_child_elements() {
+        let text = element.text();
+        return if text.is_empty() {
+            Value::Null
+        } else {
+            text_value(&text, options.infer_types)
+        };
+    }
+
+    let mut map = Map::new();
+    insert_children(&mut map, element, options, parker_value);
+    Value::Object(map)
+}
+
+fn validate_element_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .map(|c| c.is_alphabetic() || c == '_')
+        .unwrap_or(false);
+    let valid_rest = chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'));
+
+    if valid_start && valid_rest {
+        Ok(())
+    } else {
+        Err(format!("Invalid XML name: \"{}\"", name))
+    }
+}
+
+fn scalar_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn value_to_element(name: &str, value: &Value, options: &XmlJsonOptions) -> Result<XmlElement, String> {
+    validate_element_name(name)?;
+    let mut element = XmlElement::new(name);
+
+    let map = match value {
+        Value::Object(map) => map,
+        Value::Array(_) => {
+            // Nested arrays have no XML equivalent, wrap each item in an <item> element
+            push_child(&mut element, "item", value, options)?;
+            return Ok(element);
+        }
+        Value::Null => return Ok(element),
+        scalar => {
+            element.children.push(XmlNode::Text(scalar_to_string(scalar)));
+            return Ok(element);
+        }
+    };
+
+    for (key, child) in map {
+        match (options.convention, key.as_str()) {
+            (XmlJsonConvention::Simple, "#text") | (XmlJsonConvention::BadgerFish, "$") => {
+                element.children.push(XmlNode::Text(scalar_to_string(child)));
+            }
+            (XmlJsonConvention::BadgerFish, "@xmlns") => {
+                let namespaces = child
+                    .as_object()
+                    .ok_or_else(|| "\"@xmlns\" must be an object".to_string())?;
+                for (prefix, uri) in namespaces {
+                    let attr = if prefix == "$" {
+                        "xmlns".to_string()
+                    } else {
+                        format!("xmlns:{}", prefix)
+                    };
+                    element.attributes.push((attr, scalar_to_string(uri)));
+                }
+            }
+            (convention, key) if convention != XmlJsonConvention::Parker && key.starts_with('@') => {
+                let attr = &key[1..];
+                validate_element_name(attr)?;
+                element.attributes.push((attr.to_string(), scalar_to_string(child)));
+            }
+            (_, key) => push_child(&mut element, key, child, options)?,
+        }
+    }
+
+    Ok(element)
+}
+
+fn push_child(
+    parent: &mut XmlElement,
+    name: &str,
+    value: &Value,
+    options: &XmlJsonOptions,
+) -> Result<(), String> {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                parent
+                    .children
+                    .push(XmlNode::Element(value_to_element(name, item, options)?));
+            }
+        }
+        other => parent
+            .children
+            .push(XmlNode::Element(value_to_element(name, other, options)?)),
+    }
+    Ok(())
+}
+
+fn write_xml_tree(root: &XmlElement, options: &XmlJsonOptions) -> Result<String, String> {
+    let mut writer = if options.indent > 0 {
+        Writer::new_with_indent(Vec::new(), b' ', options.indent)
+    } else {
+        Writer::new(Vec::new())
+    };
+
+    if options.xml_declaration {
+        writer
+            .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+            .map_err(|e| e.to_string())?;
+    }
+
+    write_element(&mut writer, root)?;
+
+    String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+}
+
+fn write_element(writer: &mut Writer<Vec<u8>>, element: &XmlElement) -> Result<(), String> {
+    let mut start = BytesStart::new(element.name.as_str());
+    for (key, value) in &element.attributes {
+        start.push_attribute((key.as_str(), value.as_str()));
+    }
+
+    if element.children.is_empty() {
+        return writer
+            .write_event(Event::Empty(start))
+            .map_err(|e| e.to_string());
+    }
+
+    writer
+        .write_event(Event::Start(start))
+        .map_err(|e| e.to_string())?;
+    for child in &element.children {
+        match child {
+            XmlNode::Element(el) => write_element(writer, el)?,
+            XmlNode::Text(text) => writer
+                .write_event(Event::Text(BytesText::new(text)))
+                .map_err(|e| e.to_string())?,
+        }
+    }
+    writer
+        .write_event(Event::End(BytesEnd::new(element.name.as_str())))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_XML: &str = r#"<?xml version="1.0"?>
+<library xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <book id="1"><dc:title>Dune</dc:title><pages>412</pages></book>
+  <book id="2"><dc:title>Emma</dc:title><pages>474</pages></book>
+  <owner>Ana</owner>
+</library>"#;
+
+    fn parse(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn options(convention: XmlJsonConvention) -> XmlJsonOptions {
+        XmlJsonOptions {
+            convention,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_xml_to_json_simple() {
+        let json = xml_to_json_command(SAMPLE_XML.to_string(), None).unwrap();
+        let value = parse(&json);
+        let books = &value["library"]["book"];
+        assert_eq!(books.as_array().unwrap().len(), 2);
+        assert_eq!(books[0]["@id"], "1");
+        assert_eq!(books[0]["dc:title"], "Dune");
+        assert_eq!(value["library"]["owner"], "Ana");
+        assert_eq!(value["library"]["@xmlns:dc"], "http://purl.org/dc/elements/1.1/");
+    }
+
+    #[test]
+    fn test_xml_to_json_badgerfish() {
+        let json = xml_to_json_command(
+            SAMPLE_XML.to_string(),
+            Some(options(XmlJsonConvention::BadgerFish)),
+        )
+        .unwrap();
+        let value = parse(&json);
+        assert_eq!(value["library"]["@xmlns"]["dc"], "http://purl.org/dc/elements/1.1/");
+        assert_eq!(value["library"]["owner"]["$"], "Ana");
+        assert_eq!(value["library"]["book"][1]["dc:title"]["$"], "Emma");
+    }
+
+    #[test]
+    fn test_xml_to_json_parker_with_types() {
+        let opts = XmlJsonOptions {
+            infer_types: true,
+            ..options(XmlJsonConvention::Parker)
+        };
+        let json = xml_to_json_command(SAMPLE_XML.to_string(), Some(opts)).unwrap();
+        let value = parse(&json);
+        assert_eq!(value["book"][0]["pages"], 412);
+        assert!(value["book"][0].get("@id").is_none());
+        assert_eq!(value["owner"], "Ana");
+    }
+
+    #[test]
+    fn test_force_array_and_strip_namespaces() {
+        let opts = XmlJsonOptions {
+            force_array: vec!["owner".to_string()],
+            strip_namespaces: true,
+            ..Default::default()
+        };
+        let json = xml_to_json_command(SAMPLE_XML.to_string(), Some(opts)).unwrap();
+        let value = parse(&json);
+        assert_eq!(value["library"]["owner"], parse(r#"["Ana"]"#));
+        assert_eq!(value["library"]["book"][0]["title"], "Dune");
+        assert!(value["library"].get("@xmlns:dc").is_none());
+    }
+
+    #[test]
+    fn test_json_to_xml_simple() {
+        let json = r##"{"catalog": {"@version": "2", "item": [{"#text": "A", "@sku": "x1"}, "B"], "empty": null}}"##;
+        let opts = XmlJsonOptions {
+            indent: 0,
+            ..Default::default()
+        };
+        let xml = json_to_xml_command(json.to_string(), Some(opts)).unwrap();
+        assert_eq!(
+            xml,
+            r#"<catalog version="2"><item sku="x1">A</item><item>B</item><empty/></catalog>"#
+        );
+    }
+
+    #[test]
+    fn test_json_to_xml_escapes_text_and_wraps_root() {
+        let opts = XmlJsonOptions {
+            indent: 0,
+            root_name: Some("data".to_string()),
+            xml_declaration: true,
+            ..Default::default()
+        };
+        let xml = json_to_xml_command(r#"{"a": "1 < 2", "b": true}"#.to_string(), Some(opts)).unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<data><a>1 &lt; 2</a><b>true</b></data>"));
+    }
+
+    #[test]
+    fn test_badgerfish_roundtrip() {
+        let opts = options(XmlJsonConvention::BadgerFish);
+        let json = xml_to_json_command(SAMPLE_XML.to_string(), Some(opts.clone())).unwrap();
+        let xml = json_to_xml_command(json.clone(), Some(opts.clone())).unwrap();
+        let json_again = xml_to_json_command(xml, Some(opts)).unwrap();
+        assert_eq!(parse(&json), parse(&json_again));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(xml_to_json_command("<a><b></a>".to_string(), None).is_err());
+        assert!(xml_to_json_command("".to_string(), None).is_err());
+        assert!(json_to_xml_command(r#"{"bad name": 1}"#.to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let deep = format!("{}{}", "<a>".repeat(20_000), "</a>".repeat(20_000));
+        let err = xml_to_json_command(deep, None).unwrap_err();
+        assert!(err.contains("nested"));
+
+        let ok = format!("{}{}", "<a>".repeat(100), "</a>".repeat(100));
+        assert!(xml_to_json_command(ok, None).is_ok());
+    }
+}
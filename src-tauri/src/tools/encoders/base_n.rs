@@ -0,0 +1,676 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BaseNError {
+    #[error("Invalid {encoding} input: {message}")]
+    InvalidInput { encoding: String, message: String },
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+    #[error("Invalid options: {0}")]
+    InvalidOptions(String),
+}
+
+/// Binary-to-text encodings beyond standard Base64 and hex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BaseEncoding {
+    Base32,
+    Base32Hex,
+    Base58,
+    Base58Check,
+    Ascii85,
+    Z85,
+    Base64Url,
+    Bech32,
+    Bech32m,
+}
+
+impl BaseEncoding {
+    fn name(self) -> &'static str {
+        match self {
+            BaseEncoding::Base32 => "Base32",
+            BaseEncoding::Base32Hex => "Base32hex",
+            BaseEncoding::Base58 => "Base58",
+            BaseEncoding::Base58Check => "Base58Check",
+            BaseEncoding::Ascii85 => "Ascii85",
+            BaseEncoding::Z85 => "Z85",
+            BaseEncoding::Base64Url => "Base64URL",
+            BaseEncoding::Bech32 => "Bech32",
+            BaseEncoding::Bech32m => "Bech32m",
+        }
+    }
+
+    fn has_checksum(self) -> bool {
+        matches!(
+            self,
+            BaseEncoding::Base58Check | BaseEncoding::Bech32 | BaseEncoding::Bech32m
+        )
+    }
+
+    fn invalid(self, message: impl Into<String>) -> BaseNError {
+        BaseNError::InvalidInput {
+            encoding: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BaseNOptions {
+    /// Emit `=` padding (Base32 and Base64URL only)
+    pub padding: bool,
+    /// Human-readable part, required for Bech32 and Bech32m
+    pub hrp: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseNDecoded {
+    pub encoding: BaseEncoding,
+    pub length: usize,
+    pub hex: String,
+    pub base64: String,
+    /// Decoded bytes as text when they are valid UTF-8
+    pub text: Option<String>,
+    pub checksum_verified: bool,
+    pub hrp: Option<String>,
+    /// SegWit witness version for `bc`/`tb`/`bcrt` Bech32 addresses
+    pub witness_version: Option<u8>,
+}
+
+impl BaseNDecoded {
+    fn new(encoding: BaseEncoding, bytes: Vec<u8>) -> Self {
+        BaseNDecoded {
+            encoding,
+            length: bytes.len(),
+            hex: hex::encode(&bytes),
+            base64: general_purpose::STANDARD.encode(&bytes),
+            checksum_verified: encoding.has_checksum(),
+            text: String::from_utf8(bytes).ok(),
+            hrp: None,
+            witness_version: None,
+        }
+    }
+}
+
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const SEGWIT_HRPS: [&str; 3] = ["bc", "tb", "bcrt"];
+
+/// Encode raw bytes with the given encoding
+pub fn encode_base_n(
+    data: &[u8],
+    encoding: BaseEncoding,
+    options: &BaseNOptions,
+) -> Result<String, BaseNError> {
+    match encoding {
+        BaseEncoding::Base32 if options.padding => Ok(data_encoding::BASE32.encode(data)),
+        BaseEncoding::Base32 => Ok(data_encoding::BASE32_NOPAD.encode(data)),
+        BaseEncoding::Base32Hex if options.padding => Ok(data_encoding::BASE32HEX.encode(data)),
+        BaseEncoding::Base32Hex => Ok(data_encoding::BASE32HEX_NOPAD.encode(data)),
+        BaseEncoding::Base58 => Ok(bs58::encode(data).into_string()),
+        BaseEncoding::Base58Check => Ok(bs58::encode(data).with_check().into_string()),
+        BaseEncoding::Ascii85 => Ok(encode_ascii85(data)),
+        BaseEncoding::Z85 => encode_z85(data),
+        BaseEncoding::Base64Url if options.padding => Ok(general_purpose::URL_SAFE.encode(data)),
+        BaseEncoding::Base64Url => Ok(general_purpose::URL_SAFE_NO_PAD.encode(data)),
+        BaseEncoding::Bech32 | BaseEncoding::Bech32m => {
+            let hrp = options.hrp.as_deref().unwrap_or("").trim();
+            encode_bech32(hrp, data, encoding)
+        }
+    }
+}
+
+/// Decode a string, validating the checksum where the format has one
+pub fn decode_base_n(input: &str, encoding: BaseEncoding) -> Result<BaseNDecoded, BaseNError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(encoding.invalid("input is empty"));
+    }
+
+    let bytes = match encoding {
+        BaseEncoding::Base32 => decode_base32(input, &data_encoding::BASE32_NOPAD, encoding)?,
+        BaseEncoding::Base32Hex => decode_base32(input, &data_encoding::BASE32HEX_NOPAD, encoding)?,
+        BaseEncoding::Base58 => bs58::decode(input)
+            .into_vec()
+            .map_err(|e| encoding.invalid(e.to_string()))?,
+        BaseEncoding::Base58Check => {
+            bs58::decode(input)
+                .with_check(None)
+                .into_vec()
+                .map_err(|e| match e {
+                    bs58::decode::Error::InvalidChecksum { .. } => {
+                        BaseNError::ChecksumMismatch(e.to_string())
+                    }
+                    e => encoding.invalid(e.to_string()),
+                })?
+        }
+        BaseEncoding::Ascii85 => decode_ascii85(input)?,
+        BaseEncoding::Z85 => decode_z85(input)?,
+        BaseEncoding::Base64Url => {
+            let cleaned: String = input
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != '=')
+                .collect();
+            general_purpose::URL_SAFE_NO_PAD
+                .decode(cleaned)
+                .map_err(|e| encoding.invalid(e.to_string()))?
+        }
+        BaseEncoding::Bech32 | BaseEncoding::Bech32m => return decode_bech32(input, encoding),
+    };
+
+    Ok(BaseNDecoded::new(encoding, bytes))
+}
+
+/// Try every encoding and return the ones the input decodes under,
+/// checksummed formats first
+pub fn detect_base_n(input: &str) -> Vec<BaseNDecoded> {
+    let trimmed = input.trim();
+    let delimited_ascii85 = trimmed.starts_with("<~") && trimmed.ends_with("~>");
+
+    let mut order = vec![
+        BaseEncoding::Bech32,
+        BaseEncoding::Bech32m,
+        BaseEncoding::Base58Check,
+    ];
+    if delimited_ascii85 {
+        order.push(BaseEncoding::Ascii85);
+    }
+    order.extend([
+        BaseEncoding::Base32,
+        BaseEncoding::Base32Hex,
+        BaseEncoding::Base64Url,
+        BaseEncoding::Base58,
+    ]);
+    if !delimited_ascii85 {
+        order.push(BaseEncoding::Ascii85);
+    }
+    order.push(BaseEncoding::Z85);
+
+    order
+        .into_iter()
+        .filter_map(|encoding| decode_base_n(trimmed, encoding).ok())
+        .collect()
+}
+
+fn decode_base32(
+    input: &str,
+    spec: &data_encoding::Encoding,
+    encoding: BaseEncoding,
+) -> Result<Vec<u8>, BaseNError> {
+    // TOTP secrets are often shown lowercase and grouped with spaces or dashes
+    let cleaned: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    spec.decode(cleaned.as_bytes())
+        .map_err(|e| encoding.invalid(e.to_string()))
+}
+
+fn encode_ascii85(data: &[u8]) -> String {
+    let mut out = String::from("<~");
+    for chunk in data.chunks(4) {
+        if chunk == [0, 0, 0, 0] {
+            out.push('z');
+            continue;
+        }
+        let mut block = [0u8; 4];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let digits = base85_digits(u32::from_be_bytes(block));
+        for digit in &digits[..chunk.len() + 1] {
+            out.push((digit + b'!') as char);
+        }
+    }
+    out.push_str("~>");
+    out
+}
+
+fn decode_ascii85(input: &str) -> Result<Vec<u8>, BaseNError> {
+    let encoding = BaseEncoding::Ascii85;
+    let body = input.strip_prefix("<~").unwrap_or(input);
+    let body = body.strip_suffix("~>").unwrap_or(body);
+
+    let mut out = Vec::new();
+    let mut group = Vec::with_capacity(5);
+    for (i, c) in body.chars().filter(|c| !c.is_whitespace()).enumerate() {
+        match c {
+            'z' if group.is_empty() => out.extend_from_slice(&[0, 0, 0, 0]),
+            'z' => return Err(encoding.invalid(format!("'z' inside a group at {}", i))),
+            '!'..='u' => {
+                group.push(c as u8 - b'!');
+                if group.len() == 5 {
+                    out.extend_from_slice(&base85_value(&group, encoding)?);
+                    group.clear();
+                }
+            }
+            _ => return Err(encoding.invalid(format!("invalid character '{}' at {}", c, i))),
+        }
+    }
+
+    match group.len() {
+        0 => {}
+        1 => return Err(encoding.invalid("final group has a single character")),
+        n => {
+            group.resize(5, 84);
+            out.extend_from_slice(&base85_value(&group, encoding)?[..n - 1]);
+        }
+    }
+    Ok(out)
+}
+
+fn encode_z85(data: &[u8]) -> Result<String, BaseNError> {
+    if !data.len().is_multiple_of(4) {
+        return Err(BaseNError::InvalidOptions(format!(
+            "Z85 requires a multiple of 4 bytes, got {}",
+            data.len()
+        )));
+    }
+    let mut out = String::with_capacity(data.len() / 4 * 5);
+    for chunk in data.chunks(4) {
+        let value = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        for digit in base85_digits(value) {
+            out.push(Z85_ALPHABET[digit as usize] as char);
+        }
+    }
+    Ok(out)
+}
+
+fn decode_z85(input: &str) -> Result<Vec<u8>, BaseNError> {
+    let encoding = BaseEncoding::Z85;
+    let digits = input
+        .bytes()
+        .enumerate()
+        .map(|(i, b)| {
+            Z85_ALPHABET
+                .iter()
+                .position(|&a| a == b)
+                .map(|d| d as u8)
+                .ok_or_else(|| {
+                    encoding.invalid(format!("invalid character '{}' at {}", b as char, i))
+                })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if !digits.len().is_multiple_of(5) {
+        return Err(encoding.invalid(format!(
+            "length must be a multiple of 5, got {}",
+            digits.len()
+        )));
+    }
+
+    let mut out = Vec::with_capacity(digits.len() / 5 * 4);
+    for group in digits.chunks(5) {
+        out.extend_from_slice(&base85_value(group, encoding)?);
+    }
+    Ok(out)
+}
+
+fn base85_digits(mut value: u32) -> [u8; 5] {
+    let mut digits = [0u8; 5];
+    for digit in digits.iter_mut().rev() {
+        *digit = (value % 85) as u8;
+        value /= 85;
+    }
+    digits
+}
+
+fn base85_value(group: &[u8], encoding: BaseEncoding) -> Result<[u8; 4], BaseNError> {
+    let value = group.iter().fold(0u64, |acc, &d| acc * 85 + d as u64);
+    u32::try_from(value)
+        .map(u32::to_be_bytes)
+        .map_err(|_| encoding.invalid("group value exceeds 32 bits"))
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut chk: u32 = 1;
+    for &v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x01ff_ffff) << 5) ^ v as u32;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+fn bech32_const(encoding: BaseEncoding) -> u32 {
+    if encoding == BaseEncoding::Bech32m {
+        BECH32M_CONST
+    } else {
+        BECH32_CONST
+    }
+}
+
+/// Regroup bits, e.g. 8-bit bytes into 5-bit Bech32 words
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return None;
+        }
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+fn encode_bech32(hrp: &str, data: &[u8], encoding: BaseEncoding) -> Result<String, BaseNError> {
+    if hrp.is_empty() {
+        return Err(BaseNError::InvalidOptions(format!(
+            "{} requires a human-readable part (hrp)",
+            encoding.name()
+        )));
+    }
+    if hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err(BaseNError::InvalidOptions(
+            "hrp must contain printable ASCII characters only".to_string(),
+        ));
+    }
+    let hrp = hrp.to_ascii_lowercase();
+    // data is always a whole number of bytes, so regrouping cannot fail
+    let words = convert_bits(data, 8, 5, true).unwrap_or_default();
+
+    let mut values = bech32_hrp_expand(&hrp);
+    values.extend_from_slice(&words);
+    values.extend_from_slice(&[0; 6]);
+    let polymod = bech32_polymod(&values) ^ bech32_const(encoding);
+
+    let mut out = hrp;
+    out.push('1');
+    out.extend(words.iter().map(|&w| BECH32_CHARSET[w as usize] as char));
+    out.extend((0..6).map(|i| BECH32_CHARSET[((polymod >> (5 * (5 - i))) & 31) as usize] as char));
+    Ok(out)
+}
+
+fn decode_bech32(input: &str, encoding: BaseEncoding) -> Result<BaseNDecoded, BaseNError> {
+    if input.bytes().any(|b| b.is_ascii_lowercase())
+        && input.bytes().any(|b| b.is_ascii_uppercase())
+    {
+        return Err(encoding.invalid("mixed upper and lower case"));
+    }
+    let lower = input.to_ascii_lowercase();
+    let separator = lower
+        .rfind('1')
+        .ok_or_else(|| encoding.invalid("missing '1' separator"))?;
+    let (hrp, data_part) = (&lower[..separator], &lower[separator + 1..]);
+    if hrp.is_empty() {
+        return Err(encoding.invalid("empty human-readable part"));
+    }
+    if hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err(encoding.invalid("human-readable part has invalid characters"));
+    }
+    if data_part.len() < 6 {
+        return Err(encoding.invalid("data part is shorter than the 6-character checksum"));
+    }
+
+    let words = data_part
+        .bytes()
+        .enumerate()
+        .map(|(i, b)| {
+            BECH32_CHARSET
+                .iter()
+                .position(|&c| c == b)
+                .map(|w| w as u8)
+                .ok_or_else(|| {
+                    encoding.invalid(format!(
+                        "invalid character '{}' at {}",
+                        b as char,
+                        separator + 1 + i
+                    ))
+                })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend_from_slice(&words);
+    let polymod = bech32_polymod(&values);
+    if polymod != bech32_const(encoding) {
+        let other = if encoding == BaseEncoding::Bech32 {
+            BaseEncoding::Bech32m
+        } else {
+            BaseEncoding::Bech32
+        };
+        let message = if polymod == bech32_const(other) {
+            format!(
+                "checksum is valid {} but not {}",
+                other.name(),
+                encoding.name()
+            )
+        } else {
+            format!("invalid {} checksum", encoding.name())
+        };
+        return Err(BaseNError::ChecksumMismatch(message));
+    }
+
+    let words = &words[..words.len() - 6];
+    let segwit = SEGWIT_HRPS.contains(&hrp) && !words.is_empty() && words[0] <= 16;
+    let (witness_version, payload) = if segwit {
+        (Some(words[0]), &words[1..])
+    } else {
+        (None, words)
+    };
+    let bytes = convert_bits(payload, 5, 8, false)
+        .ok_or_else(|| encoding.invalid("data part has invalid padding"))?;
+
+    let mut decoded = BaseNDecoded::new(encoding, bytes);
+    decoded.hrp = Some(hrp.to_string());
+    decoded.witness_version = witness_version;
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts() -> BaseNOptions {
+        BaseNOptions::default()
+    }
+
+    #[test]
+    fn test_base32_totp_secret() {
+        let encoded =
+            encode_base_n(b"Hello!\xde\xad\xbe\xef", BaseEncoding::Base32, &opts()).unwrap();
+        assert_eq!(encoded, "JBSWY3DPEHPK3PXP");
+
+        let padded = BaseNOptions {
+            padding: true,
+            ..opts()
+        };
+        assert_eq!(
+            encode_base_n(b"f", BaseEncoding::Base32, &padded).unwrap(),
+            "MY======"
+        );
+
+        // Lowercase, grouped and padded input is accepted
+        let decoded = decode_base_n("jbsw y3dp ehpk 3pxp", BaseEncoding::Base32).unwrap();
+        assert_eq!(decoded.hex, "48656c6c6f21deadbeef");
+        assert_eq!(
+            decode_base_n("MY======", BaseEncoding::Base32)
+                .unwrap()
+                .text
+                .as_deref(),
+            Some("f")
+        );
+    }
+
+    #[test]
+    fn test_base58_and_check() {
+        assert_eq!(
+            encode_base_n(b"hello world", BaseEncoding::Base58, &opts()).unwrap(),
+            "StV1DL6CwTryKyV"
+        );
+        assert_eq!(
+            encode_base_n(&[0, 0, 1], BaseEncoding::Base58, &opts()).unwrap(),
+            "112"
+        );
+
+        // Genesis block coinbase address
+        let address = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+        let decoded = decode_base_n(address, BaseEncoding::Base58Check).unwrap();
+        assert!(decoded.checksum_verified);
+        assert_eq!(decoded.hex, "0062e907b15cbf27d5425399ebf6f0fb50ebb88f18");
+
+        let bytes = hex::decode(&decoded.hex).unwrap();
+        assert_eq!(
+            encode_base_n(&bytes, BaseEncoding::Base58Check, &opts()).unwrap(),
+            address
+        );
+
+        let tampered = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb";
+        assert!(matches!(
+            decode_base_n(tampered, BaseEncoding::Base58Check),
+            Err(BaseNError::ChecksumMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_ascii85_and_z85() {
+        assert_eq!(
+            encode_base_n(b"Man ", BaseEncoding::Ascii85, &opts()).unwrap(),
+            "<~9jqo^~>"
+        );
+        assert_eq!(
+            encode_base_n(&[0, 0, 0, 0, 1], BaseEncoding::Ascii85, &opts()).unwrap(),
+            "<~z!<~>"
+        );
+
+        let decoded = decode_base_n("<~87cURD]i,\"Ebo80~>", BaseEncoding::Ascii85).unwrap();
+        assert_eq!(decoded.text.as_deref(), Some("Hello World!"));
+
+        // Reference vector from the Z85 specification
+        let bytes = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+        assert_eq!(
+            encode_base_n(&bytes, BaseEncoding::Z85, &opts()).unwrap(),
+            "HelloWorld"
+        );
+        assert_eq!(
+            decode_base_n("HelloWorld", BaseEncoding::Z85).unwrap().hex,
+            hex::encode(bytes)
+        );
+        assert!(encode_base_n(b"abc", BaseEncoding::Z85, &opts()).is_err());
+    }
+
+    #[test]
+    fn test_base64url_without_padding() {
+        let bytes = [0xfb, 0xff, 0xfe];
+        assert_eq!(
+            encode_base_n(&bytes, BaseEncoding::Base64Url, &opts()).unwrap(),
+            "-__-"
+        );
+        assert_eq!(
+            encode_base_n(b"ab", BaseEncoding::Base64Url, &opts()).unwrap(),
+            "YWI"
+        );
+
+        let padded = BaseNOptions {
+            padding: true,
+            ..opts()
+        };
+        assert_eq!(
+            encode_base_n(b"ab", BaseEncoding::Base64Url, &padded).unwrap(),
+            "YWI="
+        );
+        assert_eq!(
+            decode_base_n("YWI=", BaseEncoding::Base64Url)
+                .unwrap()
+                .text
+                .as_deref(),
+            Some("ab")
+        );
+    }
+
+    #[test]
+    fn test_bech32_segwit_addresses() {
+        let v0 = decode_base_n(
+            "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+            BaseEncoding::Bech32,
+        )
+        .unwrap();
+        assert_eq!(v0.hrp.as_deref(), Some("bc"));
+        assert_eq!(v0.witness_version, Some(0));
+        assert_eq!(v0.hex, "751e76e8199196d454941c45d1b3a323f1433bd6");
+
+        let taproot = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+        let v1 = decode_base_n(taproot, BaseEncoding::Bech32m).unwrap();
+        assert_eq!(v1.witness_version, Some(1));
+        assert_eq!(v1.length, 32);
+
+        // A Bech32m string checked as Bech32 names the right variant
+        let err = decode_base_n(taproot, BaseEncoding::Bech32).unwrap_err();
+        assert!(err.to_string().contains("valid Bech32m"));
+        assert!(decode_base_n(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+            BaseEncoding::Bech32
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_bech32_round_trip() {
+        let options = BaseNOptions {
+            hrp: Some("test".to_string()),
+            ..opts()
+        };
+        let encoded = encode_base_n(b"ferris", BaseEncoding::Bech32m, &options).unwrap();
+        assert!(encoded.starts_with("test1"));
+
+        let decoded = decode_base_n(&encoded, BaseEncoding::Bech32m).unwrap();
+        assert_eq!(decoded.text.as_deref(), Some("ferris"));
+        assert!(encode_base_n(b"x", BaseEncoding::Bech32, &opts()).is_err());
+    }
+
+    #[test]
+    fn test_detect_base_n() {
+        let candidates = detect_base_n("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+        assert_eq!(candidates[0].encoding, BaseEncoding::Base58Check);
+
+        let candidates = detect_base_n("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        assert_eq!(candidates[0].encoding, BaseEncoding::Bech32);
+
+        let candidates = detect_base_n("<~87cURD]i,\"Ebo80~>");
+        assert_eq!(candidates[0].encoding, BaseEncoding::Ascii85);
+
+        let candidates = detect_base_n("eyJhbGciOiJIUzI1NiJ9");
+        assert!(candidates
+            .iter()
+            .any(|c| c.encoding == BaseEncoding::Base64Url
+                && c.text.as_deref() == Some("{\"alg\":\"HS256\"}")));
+    }
+}
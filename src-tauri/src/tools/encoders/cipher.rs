@@ -0,0 +1,310 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CipherError {
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaesarCandidate {
+    pub shift: u8,
+    pub text: String,
+    /// Chi-squared distance from English letter frequencies; lower is more likely
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LetterFrequency {
+    pub letter: char,
+    pub count: usize,
+    pub percent: f64,
+    pub english_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrequencyReport {
+    pub total_letters: usize,
+    /// A to Z, including letters that do not occur
+    pub letters: Vec<LetterFrequency>,
+    /// Most common letter pairs, highest first
+    pub top_bigrams: Vec<(String, usize)>,
+    /// About 0.066 for English and other monoalphabetic text, 0.038 for random letters
+    pub index_of_coincidence: f64,
+    pub chi_squared: f64,
+    /// Caesar shift that best decrypts the text into English
+    pub likely_caesar_shift: u8,
+}
+
+/// Letter frequencies of English text in percent, A to Z
+const ENGLISH_FREQUENCIES: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// Shift ASCII letters by `shift` places, keeping case and leaving anything else as is
+fn shift_letter(c: char, shift: u8) -> char {
+    let base = match c {
+        'a'..='z' => b'a',
+        'A'..='Z' => b'A',
+        _ => return c,
+    };
+    (((c as u8 - base + shift % 26) % 26) + base) as char
+}
+
+pub fn caesar(text: &str, shift: i32) -> String {
+    let shift = shift.rem_euclid(26) as u8;
+    text.chars().map(|c| shift_letter(c, shift)).collect()
+}
+
+pub fn rot13(text: &str) -> String {
+    caesar(text, 13)
+}
+
+/// Rotate the printable ASCII range `!`..`~` by 47
+pub fn rot47(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '!'..='~' => (((c as u8 - b'!' + 47) % 94) + b'!') as char,
+            _ => c,
+        })
+        .collect()
+}
+
+pub fn atbash(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' => (b'z' - (c as u8 - b'a')) as char,
+            'A'..='Z' => (b'Z' - (c as u8 - b'A')) as char,
+            _ => c,
+        })
+        .collect()
+}
+
+fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for c in text.chars().filter(char::is_ascii_alphabetic) {
+        counts[(c.to_ascii_uppercase() as u8 - b'A') as usize] += 1;
+    }
+    counts
+}
+
+fn chi_squared(counts: &[usize; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    counts
+        .iter()
+        .zip(ENGLISH_FREQUENCIES)
+        .map(|(&observed, percent)| {
+            let expected = total as f64 * percent / 100.0;
+            (observed as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Decrypt with every shift from 1 to 25, most English-like first
+pub fn caesar_brute_force(text: &str) -> Vec<CaesarCandidate> {
+    let mut candidates: Vec<CaesarCandidate> = (1..26)
+        .map(|shift| {
+            // Decrypting with `shift` means shifting forward by 26 - shift
+            let decrypted = caesar(text, -shift);
+            CaesarCandidate {
+                shift: shift as u8,
+                score: chi_squared(&letter_counts(&decrypted)),
+                text: decrypted,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+    candidates
+}
+
+fn vigenere_shifts(key: &str) -> Result<Vec<u8>, CipherError> {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(CipherError::InvalidKey(
+            "Vigenère keys must be non-empty and contain only letters A-Z".to_string(),
+        ));
+    }
+    Ok(key.bytes().map(|b| b.to_ascii_uppercase() - b'A').collect())
+}
+
+/// Only letters consume key characters; spacing and punctuation pass through
+fn vigenere(text: &str, shifts: &[u8]) -> String {
+    let mut key = shifts.iter().cycle();
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                shift_letter(c, *key.next().unwrap_or(&0))
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+pub fn encode_vigenere(text: &str, key: &str) -> Result<String, CipherError> {
+    Ok(vigenere(text, &vigenere_shifts(key)?))
+}
+
+pub fn decode_vigenere(text: &str, key: &str) -> Result<String, CipherError> {
+    let shifts: Vec<u8> = vigenere_shifts(key)?
+        .iter()
+        .map(|s| (26 - s) % 26)
+        .collect();
+    Ok(vigenere(text, &shifts))
+}
+
+/// XOR `data` with `key` repeated to its length; applying it twice restores the input
+pub fn xor_repeating_key(data: &[u8], key: &[u8]) -> Result<Vec<u8>, CipherError> {
+    if key.is_empty() {
+        return Err(CipherError::InvalidKey(
+            "XOR key cannot be empty".to_string(),
+        ));
+    }
+    Ok(data
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(d, k)| d ^ k)
+        .collect())
+}
+
+pub fn frequency_analysis(text: &str) -> FrequencyReport {
+    let counts = letter_counts(text);
+    let total: usize = counts.iter().sum();
+    let percent = |count: usize| match total {
+        0 => 0.0,
+        _ => count as f64 * 100.0 / total as f64,
+    };
+
+    let letters = counts
+        .iter()
+        .zip(ENGLISH_FREQUENCIES)
+        .enumerate()
+        .map(|(i, (&count, english_percent))| LetterFrequency {
+            letter: (b'A' + i as u8) as char,
+            count,
+            percent: percent(count),
+            english_percent,
+        })
+        .collect();
+
+    let mut bigrams: HashMap<String, usize> = HashMap::new();
+    for word in text.split(|c: char| !c.is_ascii_alphabetic()) {
+        let upper = word.to_ascii_uppercase();
+        for pair in upper.as_bytes().windows(2) {
+            *bigrams
+                .entry(String::from_utf8_lossy(pair).into_owned())
+                .or_default() += 1;
+        }
+    }
+    let mut top_bigrams: Vec<(String, usize)> = bigrams.into_iter().collect();
+    top_bigrams.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_bigrams.truncate(10);
+
+    let index_of_coincidence = match total {
+        0 | 1 => 0.0,
+        _ => {
+            counts
+                .iter()
+                .map(|&n| n * n.saturating_sub(1))
+                .sum::<usize>() as f64
+                / (total * (total - 1)) as f64
+        }
+    };
+    let chi_squared = chi_squared(&counts);
+    let likely_caesar_shift = caesar_brute_force(text)
+        .first()
+        .filter(|best| best.score < chi_squared)
+        .map_or(0, |best| best.shift);
+
+    FrequencyReport {
+        total_letters: total,
+        letters,
+        top_bigrams,
+        index_of_coincidence,
+        chi_squared,
+        likely_caesar_shift,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rot_ciphers() {
+        assert_eq!(rot13("Hello, World!"), "Uryyb, Jbeyq!");
+        assert_eq!(rot13(&rot13("Round trip")), "Round trip");
+        assert_eq!(rot47("Hello, World!"), "w6==@[ (@C=5P");
+        assert_eq!(rot47(&rot47("p@ss w0rd~")), "p@ss w0rd~");
+        assert_eq!(caesar("xyz ABC", 3), "abc DEF");
+        assert_eq!(caesar("abc", -1), "zab");
+        assert_eq!(atbash("Attack at dawn"), "Zggzxp zg wzdm");
+    }
+
+    #[test]
+    fn test_caesar_brute_force() {
+        let ciphertext = caesar("The quick brown fox jumps over the lazy dog", 7);
+        let candidates = caesar_brute_force(&ciphertext);
+        assert_eq!(candidates.len(), 25);
+        assert_eq!(candidates[0].shift, 7);
+        assert_eq!(
+            candidates[0].text,
+            "The quick brown fox jumps over the lazy dog"
+        );
+    }
+
+    #[test]
+    fn test_vigenere() {
+        // Classic textbook example
+        let encrypted = encode_vigenere("ATTACK AT DAWN", "LEMON").unwrap();
+        assert_eq!(encrypted, "LXFOPV EF RNHR");
+        assert_eq!(
+            decode_vigenere(&encrypted, "lemon").unwrap(),
+            "ATTACK AT DAWN"
+        );
+        assert_eq!(encode_vigenere("attack!", "Lemon").unwrap(), "lxfopv!");
+        assert!(encode_vigenere("text", "").is_err());
+        assert!(encode_vigenere("text", "k3y").is_err());
+    }
+
+    #[test]
+    fn test_xor_repeating_key() {
+        // Cryptopals set 1 challenge 5
+        let plain = b"Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+        let encrypted = xor_repeating_key(plain, b"ICE").unwrap();
+        assert_eq!(
+            hex::encode(&encrypted),
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272\
+             a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
+        );
+        assert_eq!(xor_repeating_key(&encrypted, b"ICE").unwrap(), plain);
+        assert!(xor_repeating_key(plain, b"").is_err());
+    }
+
+    #[test]
+    fn test_frequency_analysis() {
+        let text = "Frequency analysis counts how often each letter appears in a message";
+        let report = frequency_analysis(text);
+        assert_eq!(report.letters.len(), 26);
+        assert_eq!(report.total_letters, 58);
+        let e = &report.letters[4];
+        assert_eq!((e.letter, e.count), ('E', 9));
+        assert!(report.index_of_coincidence > 0.05);
+        assert_eq!(report.likely_caesar_shift, 0);
+
+        let shifted = frequency_analysis(&caesar(text, 10));
+        assert_eq!(shifted.likely_caesar_shift, 10);
+        assert!(frequency_analysis("")
+            .letters
+            .iter()
+            .all(|l| l.percent == 0.0));
+    }
+}
@@ -0,0 +1,437 @@
+use super::hexdump::detect_file_type;
+use super::image::get_extension_from_mime;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, CONTROLS};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DataUriError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Base64 decode error: {0}")]
+    Base64Error(#[from] base64::DecodeError),
+    #[error("Invalid data URI: {0}")]
+    InvalidDataUri(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataUriEncoding {
+    /// Percent-encoding for text types when it is smaller, base64 otherwise
+    #[default]
+    Auto,
+    Base64,
+    Percent,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DataUriOptions {
+    pub encoding: DataUriEncoding,
+    /// Overrides the sniffed MIME type
+    pub mime: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataUri {
+    pub data_uri: String,
+    pub mime: String,
+    /// Base64 or percent, never auto
+    pub encoding: DataUriEncoding,
+    pub original_size: usize,
+    pub encoded_size: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedDataUri {
+    pub mime: String,
+    /// Parameters such as `charset`, in order
+    pub parameters: Vec<(String, String)>,
+    pub base64: bool,
+    pub bytes: Vec<u8>,
+}
+
+/// Characters escaped in percent-encoded data URIs. Single quotes are kept, so
+/// the result belongs in a double-quoted attribute or CSS `url("...")`.
+const DATA_URI: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Above this size a data URI usually costs more than a separate request
+const INLINE_WARNING_SIZE: usize = 4 * 1024;
+/// IE8 and some email clients reject longer data URIs
+const LEGACY_LIMIT: usize = 32 * 1024;
+/// Chromium refuses to navigate to longer URLs
+const BROWSER_LIMIT: usize = 2 * 1024 * 1024;
+
+fn is_text_mime(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.ends_with("+xml")
+        || mime.ends_with("+json")
+        || matches!(
+            mime,
+            "application/json" | "application/xml" | "application/javascript"
+        )
+}
+
+fn mime_from_extension(file_name: &str) -> Option<&'static str> {
+    let extension = Path::new(file_name).extension()?.to_str()?.to_lowercase();
+    Some(match extension.as_str() {
+        "svg" => "image/svg+xml",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "txt" => "text/plain",
+        _ => return None,
+    })
+}
+
+/// Guess a MIME type from magic bytes, then text content, then the file name
+pub fn sniff_mime(bytes: &[u8], file_name: Option<&str>) -> String {
+    if let Some(file_type) = detect_file_type(bytes) {
+        if file_type.mime != "text/plain" {
+            return file_type.mime.to_string();
+        }
+    }
+    let Ok(text) = std::str::from_utf8(bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes)) else {
+        return "application/octet-stream".to_string();
+    };
+    let head = text.trim_start().get(..512).unwrap_or(text.trim_start());
+    let lower = head.to_ascii_lowercase();
+    let sniffed =
+        if lower.starts_with("<svg") || (lower.starts_with("<?xml") && lower.contains("<svg")) {
+            Some("image/svg+xml")
+        } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+            Some("text/html")
+        } else if lower.starts_with("<?xml") {
+            Some("application/xml")
+        } else if (lower.starts_with('{') || lower.starts_with('['))
+            && serde_json::from_str::<serde_json::Value>(text).is_ok()
+        {
+            Some("application/json")
+        } else {
+            None
+        };
+    sniffed
+        .or_else(|| file_name.and_then(mime_from_extension))
+        .unwrap_or("text/plain")
+        .to_string()
+}
+
+/// Switch double-quoted attribute values in markup to single quotes. Text,
+/// comments and CDATA are left alone, as are values containing an apostrophe.
+fn swap_attribute_quotes(markup: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(markup.len());
+    let mut i = 0;
+    while i < markup.len() {
+        let rest = &markup[i..];
+        let verbatim_end = if rest.starts_with(b"<!--") {
+            Some(b"-->".as_slice())
+        } else if rest.starts_with(b"<![CDATA[") {
+            Some(b"]]>".as_slice())
+        } else {
+            None
+        };
+        if let Some(end) = verbatim_end {
+            let len = rest
+                .windows(end.len())
+                .position(|w| w == end)
+                .map_or(rest.len(), |p| p + end.len());
+            out.extend_from_slice(&rest[..len]);
+            i += len;
+        } else if rest[0] == b'<' {
+            // Copy the tag, rewriting each quoted value inside it
+            let (mut j, mut copied) = (1, 0);
+            while j < rest.len() && rest[j] != b'>' {
+                let quote = rest[j];
+                if quote != b'"' && quote != b'\'' {
+                    j += 1;
+                    continue;
+                }
+                let len = rest[j + 1..].iter().position(|&b| b == quote);
+                let Some(len) = len else {
+                    j = rest.len();
+                    break;
+                };
+                let value = &rest[j + 1..j + 1 + len];
+                if quote == b'"' && !value.contains(&b'\'') {
+                    out.extend_from_slice(&rest[copied..j]);
+                    out.push(b'\'');
+                    out.extend_from_slice(value);
+                    out.push(b'\'');
+                    copied = j + len + 2;
+                }
+                j += len + 2;
+            }
+            let end = (j + 1).min(rest.len());
+            out.extend_from_slice(&rest[copied..end]);
+            i += end;
+        } else {
+            let len = rest.iter().position(|&b| b == b'<').unwrap_or(rest.len());
+            out.extend_from_slice(&rest[..len]);
+            i += len;
+        }
+    }
+    out
+}
+
+/// Build a data URI for arbitrary bytes
+pub fn encode_data_uri(bytes: &[u8], file_name: Option<&str>, options: &DataUriOptions) -> DataUri {
+    let mime = options
+        .mime
+        .clone()
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| sniff_mime(bytes, file_name));
+    let text = is_text_mime(&mime);
+    let header = if text && !bytes.is_ascii() && std::str::from_utf8(bytes).is_ok() {
+        format!("{};charset=utf-8", mime)
+    } else {
+        mime.clone()
+    };
+
+    let base64_uri = || format!("data:{};base64,{}", header, STANDARD.encode(bytes));
+    let percent_uri = || {
+        // SVG attributes can use single quotes, which need no escaping
+        let swapped = (mime == "image/svg+xml").then(|| swap_attribute_quotes(bytes));
+        let data = swapped.as_deref().unwrap_or(bytes);
+        format!("data:{},{}", header, percent_encode(data, DATA_URI))
+    };
+    let (data_uri, encoding) = match options.encoding {
+        DataUriEncoding::Base64 => (base64_uri(), DataUriEncoding::Base64),
+        DataUriEncoding::Percent => (percent_uri(), DataUriEncoding::Percent),
+        DataUriEncoding::Auto if text => {
+            let (percent, base64) = (percent_uri(), base64_uri());
+            if percent.len() <= base64.len() {
+                (percent, DataUriEncoding::Percent)
+            } else {
+                (base64, DataUriEncoding::Base64)
+            }
+        }
+        DataUriEncoding::Auto => (base64_uri(), DataUriEncoding::Base64),
+    };
+
+    let mut warnings = Vec::new();
+    if data_uri.len() > BROWSER_LIMIT {
+        warnings
+            .push("Over 2 MB: Chromium-based browsers refuse to open URLs this long".to_string());
+    } else if data_uri.len() > LEGACY_LIMIT {
+        warnings.push("Over 32 KB: rejected by IE8 and some email clients".to_string());
+    }
+    if data_uri.len() > INLINE_WARNING_SIZE {
+        warnings.push(
+            "Over 4 KB: inlined data is not cached separately; a regular file is usually better"
+                .to_string(),
+        );
+    }
+    if encoding == DataUriEncoding::Percent && !text {
+        warnings.push("Percent-encoding binary data is usually larger than base64".to_string());
+    }
+
+    DataUri {
+        encoded_size: data_uri.len(),
+        data_uri,
+        mime,
+        encoding,
+        original_size: bytes.len(),
+        warnings,
+    }
+}
+
+/// Parse a data URI (RFC 2397); a missing MIME type means `text/plain`
+pub fn parse_data_uri(data_uri: &str) -> Result<ParsedDataUri, DataUriError> {
+    let rest = data_uri
+        .trim()
+        .get(..5)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+        .map(|_| &data_uri.trim()[5..])
+        .ok_or_else(|| DataUriError::InvalidDataUri("missing data: scheme".to_string()))?;
+    let (header, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| DataUriError::InvalidDataUri("missing comma before the data".to_string()))?;
+
+    let mut parts = header.split(';').map(str::trim);
+    let mime = match parts.next() {
+        Some(m) if !m.is_empty() => m.to_ascii_lowercase(),
+        _ => "text/plain".to_string(),
+    };
+    let mut base64 = false;
+    let mut parameters = Vec::new();
+    for part in parts {
+        match part.split_once('=') {
+            Some((key, value)) => parameters.push((key.to_ascii_lowercase(), value.to_string())),
+            None if part.eq_ignore_ascii_case("base64") => base64 = true,
+            None => {}
+        }
+    }
+
+    let decoded: Vec<u8> = percent_decode_str(payload).collect();
+    let bytes = if base64 {
+        let cleaned: Vec<u8> = decoded
+            .into_iter()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        STANDARD.decode(&cleaned)?
+    } else {
+        decoded
+    };
+
+    Ok(ParsedDataUri {
+        mime,
+        parameters,
+        base64,
+        bytes,
+    })
+}
+
+/// Write the data of a data URI to `path`, adding an extension from the MIME
+/// type (or the sniffed content) when `path` has none. Returns the final path.
+pub fn save_data_uri(data_uri: &str, path: &str) -> Result<PathBuf, DataUriError> {
+    let parsed = parse_data_uri(data_uri)?;
+    let mut target = PathBuf::from(path);
+    if target.extension().is_none() {
+        let mut extension = get_extension_from_mime(&parsed.mime);
+        if extension == "bin" {
+            if let Some(file_type) = detect_file_type(&parsed.bytes) {
+                extension = file_type.extension;
+            }
+        }
+        target.set_extension(extension);
+    }
+    std::fs::write(&target, &parsed.bytes)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><circle cx="5" cy="5" r="4" fill="#f00"/></svg>"##;
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(b"%PDF-1.7\n", None), "application/pdf");
+        assert_eq!(sniff_mime(b"wOF2\x00\x01", Some("font.bin")), "font/woff2");
+        assert_eq!(sniff_mime(SVG.as_bytes(), None), "image/svg+xml");
+        assert_eq!(sniff_mime(br#"{"a": [1, 2]}"#, None), "application/json");
+        assert_eq!(
+            sniff_mime(b"body { color: red }", Some("site.css")),
+            "text/css"
+        );
+        assert_eq!(
+            sniff_mime(b"\xff\x00\x13", None),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_svg_prefers_percent_encoding() {
+        let uri = encode_data_uri(SVG.as_bytes(), None, &DataUriOptions::default());
+        assert_eq!(uri.encoding, DataUriEncoding::Percent);
+        assert!(uri
+            .data_uri
+            .starts_with("data:image/svg+xml,%3Csvg%20xmlns="));
+        assert!(uri.data_uri.contains("fill='%23f00'/%3E"));
+
+        let base64 = encode_data_uri(
+            SVG.as_bytes(),
+            None,
+            &DataUriOptions {
+                encoding: DataUriEncoding::Base64,
+                mime: None,
+            },
+        );
+        assert!(uri.encoded_size < base64.encoded_size);
+        assert_eq!(
+            parse_data_uri(&uri.data_uri).unwrap().bytes,
+            SVG.replace('"', "'").as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_svg_quote_swap_skips_text() {
+        let svg = r#"<svg a="x"><text title="it's">say "hi"</text><![CDATA[ "c" ]]><!-- "d" --></svg>"#;
+        let options = DataUriOptions {
+            encoding: DataUriEncoding::Percent,
+            mime: None,
+        };
+        let uri = encode_data_uri(svg.as_bytes(), None, &options);
+        assert_eq!(
+            String::from_utf8(parse_data_uri(&uri.data_uri).unwrap().bytes).unwrap(),
+            r#"<svg a='x'><text title="it's">say "hi"</text><![CDATA[ "c" ]]><!-- "d" --></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_binary_and_warnings() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00";
+        let uri = encode_data_uri(png, None, &DataUriOptions::default());
+        assert_eq!(uri.data_uri, "data:image/png;base64,iVBORw0KGgoAAA==");
+        assert!(uri.warnings.is_empty());
+
+        let large = encode_data_uri(&vec![0u8; 40 * 1024], None, &DataUriOptions::default());
+        assert_eq!(large.encoding, DataUriEncoding::Base64);
+        assert!(large.warnings.iter().any(|w| w.contains("32 KB")));
+    }
+
+    #[test]
+    fn test_parse_data_uri() {
+        let parsed = parse_data_uri("data:text/plain;charset=utf-8,caf%C3%A9").unwrap();
+        assert_eq!(parsed.mime, "text/plain");
+        assert_eq!(
+            parsed.parameters,
+            vec![("charset".to_string(), "utf-8".to_string())]
+        );
+        assert_eq!(parsed.bytes, "café".as_bytes());
+
+        let defaulted = parse_data_uri("data:;base64,SGk=").unwrap();
+        assert_eq!(defaulted.mime, "text/plain");
+        assert_eq!(defaulted.bytes, b"Hi");
+
+        assert!(parse_data_uri("data:text/plain").is_err());
+        assert!(parse_data_uri("http://example.com").is_err());
+    }
+
+    #[test]
+    fn test_save_data_uri_adds_extension() {
+        let dir = std::env::temp_dir().join("ferrisbox_data_uri_test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("report");
+        let saved = save_data_uri(
+            "data:application/pdf;base64,JVBERi0=",
+            path.to_str().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(saved.extension().unwrap(), "pdf");
+        assert_eq!(std::fs::read(&saved).unwrap(), b"%PDF-");
+
+        // Unknown MIME types fall back to sniffing the content
+        let sniffed = save_data_uri(
+            "data:application/x-custom;base64,d09GMgAB",
+            dir.join("font").to_str().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(sniffed.extension().unwrap(), "woff2");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,459 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EscapeError {
+    #[error("Invalid escape sequence '{sequence}' at position {position}")]
+    InvalidEscape { sequence: String, position: usize },
+    #[error("Unterminated {0}")]
+    Unterminated(&'static str),
+    #[error("Escaped bytes are not valid UTF-8")]
+    InvalidUtf8,
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// String literal or quoting syntax to escape for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EscapeFormat {
+    Json,
+    Rust,
+    C,
+    Java,
+    Python,
+    Shell,
+    Csv,
+}
+
+/// Escape text as the body of a string literal (without surrounding quotes),
+/// a POSIX shell word or a CSV field
+///
+/// With `ascii_only`, non-ASCII characters are escaped too where the syntax allows it.
+pub fn escape_string(text: &str, format: EscapeFormat, ascii_only: bool) -> String {
+    match format {
+        EscapeFormat::Shell => escape_shell(text),
+        EscapeFormat::Csv => escape_csv(text),
+        _ => {
+            let mut out = String::with_capacity(text.len() + 8);
+            for c in text.chars() {
+                escape_char(c, format, ascii_only, &mut out);
+            }
+            out
+        }
+    }
+}
+
+fn escape_char(c: char, format: EscapeFormat, ascii_only: bool, out: &mut String) {
+    let simple = match c {
+        '\\' => Some("\\\\"),
+        '"' => Some("\\\""),
+        '\n' => Some("\\n"),
+        '\r' => Some("\\r"),
+        '\t' => Some("\\t"),
+        '\u{8}' if format != EscapeFormat::Rust => Some("\\b"),
+        '\u{c}' if format != EscapeFormat::Rust => Some("\\f"),
+        '\0' if format == EscapeFormat::Rust => Some("\\0"),
+        '\u{7}' if matches!(format, EscapeFormat::C | EscapeFormat::Python) => Some("\\a"),
+        '\u{b}' if matches!(format, EscapeFormat::C | EscapeFormat::Python) => Some("\\v"),
+        _ => None,
+    };
+    if let Some(escaped) = simple {
+        out.push_str(escaped);
+        return;
+    }
+
+    let printable = !c.is_control() && (c.is_ascii() || !ascii_only);
+    if printable {
+        out.push(c);
+        return;
+    }
+
+    match format {
+        EscapeFormat::Rust if c.is_ascii() => out.push_str(&format!("\\x{:02x}", c as u32)),
+        EscapeFormat::Rust => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+        // Octal escapes stop after three digits, unlike greedy \x escapes
+        EscapeFormat::C => {
+            let mut buf = [0u8; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("\\{:03o}", b));
+            }
+        }
+        EscapeFormat::Python if (c as u32) < 0x100 => out.push_str(&format!("\\x{:02x}", c as u32)),
+        EscapeFormat::Python if (c as u32) > 0xFFFF => {
+            out.push_str(&format!("\\U{:08x}", c as u32))
+        }
+        _ => {
+            let mut buf = [0u16; 2];
+            for unit in c.encode_utf16(&mut buf) {
+                out.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+}
+
+fn escape_shell(text: &str) -> String {
+    let safe = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@%+=:,./-_".contains(c));
+    if safe {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+fn escape_csv(text: &str) -> String {
+    let needs_quotes =
+        text.contains([',', '"', '\n', '\r']) || text.starts_with(' ') || text.ends_with(' ');
+    if needs_quotes {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Reverse `escape_string`; surrounding quotes are accepted and removed
+pub fn unescape_string(text: &str, format: EscapeFormat) -> Result<String, EscapeError> {
+    match format {
+        EscapeFormat::Json => {
+            let quoted = if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+                text.to_string()
+            } else {
+                format!("\"{}\"", text)
+            };
+            Ok(serde_json::from_str(&quoted)?)
+        }
+        EscapeFormat::Shell => unescape_shell(text),
+        EscapeFormat::Csv => unescape_csv(text),
+        _ => unescape_literal(strip_quotes(text, format), format),
+    }
+}
+
+fn strip_quotes(text: &str, format: EscapeFormat) -> &str {
+    let quotes: &[char] = if format == EscapeFormat::Python {
+        &['"', '\'']
+    } else {
+        &['"']
+    };
+    for &q in quotes {
+        if text.len() >= 2 && text.starts_with(q) && text.ends_with(q) {
+            return &text[1..text.len() - 1];
+        }
+    }
+    text
+}
+
+fn unescape_literal(text: &str, format: EscapeFormat) -> Result<String, EscapeError> {
+    // Bytes rather than chars: C octal and \x escapes produce raw UTF-8 bytes
+    let mut out: Vec<u8> = Vec::with_capacity(text.len());
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+
+    let push_char = |out: &mut Vec<u8>, c: char| {
+        let mut buf = [0u8; 4];
+        out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    };
+    let invalid = |start: usize, end: usize| EscapeError::InvalidEscape {
+        sequence: chars[start..end.min(chars.len())].iter().collect(),
+        position: start,
+    };
+    let read_hex = |from: usize, min: usize, max: usize| -> (u32, usize) {
+        let digits: String = chars[from.min(chars.len())..]
+            .iter()
+            .take(max)
+            .take_while(|c| c.is_ascii_hexdigit())
+            .collect();
+        if digits.len() < min {
+            return (u32::MAX, digits.len());
+        }
+        (
+            u32::from_str_radix(&digits, 16).unwrap_or(u32::MAX),
+            digits.len(),
+        )
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c != '\\' {
+            push_char(&mut out, c);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let Some(&e) = chars.get(i + 1) else {
+            return Err(EscapeError::Unterminated("escape sequence"));
+        };
+        i += 2;
+
+        match e {
+            '\\' => out.push(b'\\'),
+            '"' => out.push(b'"'),
+            '\'' => out.push(b'\''),
+            'n' => out.push(b'\n'),
+            'r' => out.push(b'\r'),
+            't' => out.push(b'\t'),
+            'b' if format != EscapeFormat::Rust => out.push(0x08),
+            'f' if format != EscapeFormat::Rust => out.push(0x0c),
+            'a' | 'v' if matches!(format, EscapeFormat::C | EscapeFormat::Python) => {
+                out.push(if e == 'a' { 0x07 } else { 0x0b })
+            }
+            '\n' => {
+                // Line continuation; Rust also skips the next line's indentation
+                if format == EscapeFormat::Rust {
+                    while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                        i += 1;
+                    }
+                }
+            }
+            '0'..='7' if format != EscapeFormat::Rust => {
+                let digits: String = chars[i - 1..]
+                    .iter()
+                    .take(3)
+                    .take_while(|c| ('0'..='7').contains(*c))
+                    .collect();
+                i += digits.len() - 1;
+                let value = u32::from_str_radix(&digits, 8).unwrap_or(0);
+                if format == EscapeFormat::C {
+                    out.push(u8::try_from(value).map_err(|_| invalid(start, i))?);
+                } else {
+                    push_char(
+                        &mut out,
+                        char::from_u32(value).ok_or_else(|| invalid(start, i))?,
+                    );
+                }
+            }
+            '0' => out.push(0),
+            'x' if format != EscapeFormat::Java => {
+                let max = if format == EscapeFormat::C { 8 } else { 2 };
+                let min = if format == EscapeFormat::C { 1 } else { 2 };
+                let (value, len) = read_hex(i, min, max);
+                i += len;
+                match format {
+                    EscapeFormat::C => {
+                        out.push(u8::try_from(value).map_err(|_| invalid(start, i))?)
+                    }
+                    EscapeFormat::Rust if value > 0x7f => return Err(invalid(start, i)),
+                    _ => push_char(
+                        &mut out,
+                        char::from_u32(value).ok_or_else(|| invalid(start, i))?,
+                    ),
+                }
+            }
+            'u' if format == EscapeFormat::Rust => {
+                if chars.get(i) != Some(&'{') {
+                    return Err(invalid(start, i));
+                }
+                let (value, len) = read_hex(i + 1, 1, 6);
+                if chars.get(i + 1 + len) != Some(&'}') {
+                    return Err(invalid(start, i + 1 + len));
+                }
+                i += len + 2;
+                push_char(
+                    &mut out,
+                    char::from_u32(value).ok_or_else(|| invalid(start, i))?,
+                );
+            }
+            'u' | 'U' if e == 'u' || matches!(format, EscapeFormat::C | EscapeFormat::Python) => {
+                let width = if e == 'u' { 4 } else { 8 };
+                let (mut value, len) = read_hex(i, width, width);
+                i += len;
+                if value == u32::MAX {
+                    return Err(invalid(start, i));
+                }
+                // Combine a UTF-16 surrogate pair written as two \u escapes
+                if (0xD800..0xDC00).contains(&value)
+                    && chars.get(i) == Some(&'\\')
+                    && chars.get(i + 1) == Some(&'u')
+                {
+                    let (low, len) = read_hex(i + 2, 4, 4);
+                    if (0xDC00..0xE000).contains(&low) {
+                        value = 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00);
+                        i += 2 + len;
+                    }
+                }
+                push_char(
+                    &mut out,
+                    char::from_u32(value).ok_or_else(|| invalid(start, i))?,
+                );
+            }
+            // Python and C compilers keep unknown escapes verbatim
+            _ if matches!(format, EscapeFormat::Python | EscapeFormat::C) => {
+                out.push(b'\\');
+                push_char(&mut out, e);
+            }
+            _ => return Err(invalid(start, i)),
+        }
+    }
+
+    String::from_utf8(out).map_err(|_| EscapeError::InvalidUtf8)
+}
+
+fn unescape_shell(text: &str) -> Result<String, EscapeError> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(c) => out.push(c),
+                    None => return Err(EscapeError::Unterminated("single quote")),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('$' | '`' | '"' | '\\')) => out.push(c),
+                        Some('\n') => {}
+                        Some(c) => {
+                            out.push('\\');
+                            out.push(c);
+                        }
+                        None => return Err(EscapeError::Unterminated("double quote")),
+                    },
+                    Some(c) => out.push(c),
+                    None => return Err(EscapeError::Unterminated("double quote")),
+                }
+            },
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => out.push(c),
+                None => return Err(EscapeError::Unterminated("escape sequence")),
+            },
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+fn unescape_csv(text: &str) -> Result<String, EscapeError> {
+    let Some(inner) = text.strip_prefix('"') else {
+        return Ok(text.to_string());
+    };
+    let inner = inner
+        .strip_suffix('"')
+        .ok_or(EscapeError::Unterminated("quoted field"))?;
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        if c == '"' && chars.next_if(|&(_, n)| n == '"').is_none() {
+            return Err(EscapeError::InvalidEscape {
+                sequence: "\"".to_string(),
+                position: position + 1,
+            });
+        }
+        out.push(c);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Tab\there \"quoted\" \\ new\nline é 😀\u{1}";
+
+    #[test]
+    fn test_escape_c_family() {
+        assert_eq!(
+            escape_string(SAMPLE, EscapeFormat::Rust, true),
+            "Tab\\there \\\"quoted\\\" \\\\ new\\nline \\u{e9} \\u{1f600}\\x01"
+        );
+        assert_eq!(
+            escape_string(SAMPLE, EscapeFormat::Java, true),
+            "Tab\\there \\\"quoted\\\" \\\\ new\\nline \\u00e9 \\ud83d\\ude00\\u0001"
+        );
+        assert_eq!(
+            escape_string(SAMPLE, EscapeFormat::Python, true),
+            "Tab\\there \\\"quoted\\\" \\\\ new\\nline \\xe9 \\U0001f600\\x01"
+        );
+        assert_eq!(
+            escape_string("é\u{7}", EscapeFormat::C, true),
+            "\\303\\251\\a"
+        );
+        assert_eq!(escape_string("é", EscapeFormat::Json, false), "é");
+    }
+
+    #[test]
+    fn test_round_trip_all_literal_formats() {
+        for format in [
+            EscapeFormat::Json,
+            EscapeFormat::Rust,
+            EscapeFormat::C,
+            EscapeFormat::Java,
+            EscapeFormat::Python,
+            EscapeFormat::Shell,
+            EscapeFormat::Csv,
+        ] {
+            for ascii_only in [false, true] {
+                let escaped = escape_string(SAMPLE, format, ascii_only);
+                assert_eq!(
+                    unescape_string(&escaped, format).unwrap(),
+                    SAMPLE,
+                    "{:?}",
+                    format
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unescape_literals() {
+        assert_eq!(
+            unescape_string("\"a\\u00e9\\ud83d\\ude00\"", EscapeFormat::Json).unwrap(),
+            "aé😀"
+        );
+        assert_eq!(
+            unescape_string("\\x41\\101\\xe2\\x82\\xac", EscapeFormat::C).unwrap(),
+            "AA€"
+        );
+        assert_eq!(
+            unescape_string("'\\N'", EscapeFormat::Python).unwrap(),
+            "\\N"
+        );
+        assert_eq!(
+            unescape_string("a\\\n    b", EscapeFormat::Rust).unwrap(),
+            "ab"
+        );
+        assert!(unescape_string("\\xff", EscapeFormat::Rust).is_err());
+        assert!(unescape_string("\\q", EscapeFormat::Java).is_err());
+        assert!(unescape_string("\\u12", EscapeFormat::Java).is_err());
+    }
+
+    #[test]
+    fn test_shell_quoting() {
+        assert_eq!(
+            escape_string("safe-name_1.txt", EscapeFormat::Shell, false),
+            "safe-name_1.txt"
+        );
+        assert_eq!(
+            escape_string("it's $HOME", EscapeFormat::Shell, false),
+            "'it'\\''s $HOME'"
+        );
+        assert_eq!(escape_string("", EscapeFormat::Shell, false), "''");
+        assert_eq!(
+            unescape_string("\"a \\$b\"' c'\\ d", EscapeFormat::Shell).unwrap(),
+            "a $b c d"
+        );
+        assert!(unescape_string("'open", EscapeFormat::Shell).is_err());
+    }
+
+    #[test]
+    fn test_csv_fields() {
+        assert_eq!(escape_string("plain", EscapeFormat::Csv, false), "plain");
+        assert_eq!(
+            escape_string("a,\"b\"", EscapeFormat::Csv, false),
+            "\"a,\"\"b\"\"\""
+        );
+        assert_eq!(
+            unescape_string("\"x\"\"y\"", EscapeFormat::Csv).unwrap(),
+            "x\"y"
+        );
+        assert!(unescape_string("\"x\"y\"", EscapeFormat::Csv).is_err());
+    }
+}
@@ -4,40 +4,20 @@ use thiserror::Error;
 pub enum HexError {
     #[error("Invalid hex string: {0}")]
     InvalidHex(String),
-    #[error("UTF-8 conversion error: {0}")]
-    Utf8Error(#[from] std::string::FromUtf8Error),
 }
 
-/// Convert string to hexadecimal
-/// 
-/// # Examples
-/// ```
-/// use ferrisbox_lib::tools::encoders::hex::string_to_hex;
-/// assert_eq!(string_to_hex("Hello"), "48656c6c6f");
-/// ```
-pub fn string_to_hex(text: &str) -> String {
-    hex::encode(text.as_bytes())
-}
-
-/// Convert hexadecimal to string
-/// Supports formats: "48656c6c6f", "48 65 6c 6c 6f", "0x48656c6c6f"
-/// 
-/// # Examples
-/// ```
-/// use ferrisbox_lib::tools::encoders::hex::hex_to_string;
-/// assert_eq!(hex_to_string("48656c6c6f").unwrap(), "Hello");
-/// ```
-pub fn hex_to_string(hex_str: &str) -> Result<String, HexError> {
-    // Clean input: remove spaces and 0x prefix
-    let cleaned = hex_str
-        .replace(" ", "")
+/// Convert hexadecimal to raw bytes
+/// Supports formats: "48656c6c6f", "48 65 6c 6c 6f", "0x48656c6c6f", including newlines and tabs
+pub fn hex_to_bytes(hex_str: &str) -> Result<Vec<u8>, HexError> {
+    // Clean input: remove whitespace and 0x prefix
+    let cleaned: String = hex_str
         .replace("0x", "")
-        .replace("0X", "");
-    
-    let decoded = hex::decode(&cleaned)
-        .map_err(|e| HexError::InvalidHex(e.to_string()))?;
-    
-    Ok(String::from_utf8(decoded)?)
+        .replace("0X", "")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    hex::decode(&cleaned).map_err(|e| HexError::InvalidHex(e.to_string()))
 }
 
 #[cfg(test)]
@@ -45,51 +25,50 @@ mod tests {
     use super::*;
     
     #[test]
-    fn test_string_to_hex() {
-        assert_eq!(string_to_hex("Hello"), "48656c6c6f");
-    }
-    
-    #[test]
-    fn test_hex_to_string() {
-        assert_eq!(hex_to_string("48656c6c6f").unwrap(), "Hello");
+    fn test_hex_to_bytes_text() {
+        assert_eq!(hex_to_bytes("48656c6c6f").unwrap(), b"Hello");
     }
     
     #[test]
     fn test_hex_with_spaces() {
-        assert_eq!(hex_to_string("48 65 6c 6c 6f").unwrap(), "Hello");
+        assert_eq!(hex_to_bytes("48 65 6c 6c 6f").unwrap(), b"Hello");
     }
     
     #[test]
     fn test_hex_with_prefix() {
-        assert_eq!(hex_to_string("0x48656c6c6f").unwrap(), "Hello");
-        assert_eq!(hex_to_string("0X48656c6c6f").unwrap(), "Hello");
+        assert_eq!(hex_to_bytes("0x48656c6c6f").unwrap(), b"Hello");
+        assert_eq!(hex_to_bytes("0X48656c6c6f").unwrap(), b"Hello");
     }
     
     #[test]
     fn test_invalid_hex() {
-        assert!(hex_to_string("ZZZ").is_err());
-        assert!(hex_to_string("GHIJ").is_err());
+        assert!(hex_to_bytes("ZZZ").is_err());
+        assert!(hex_to_bytes("GHIJ").is_err());
     }
     
     #[test]
     fn test_unicode() {
         let original = "Hola 你好 مرحبا";
-        let hex = string_to_hex(original);
-        let decoded = hex_to_string(&hex).unwrap();
-        assert_eq!(original, decoded);
+        let hex = hex::encode(original);
+        assert_eq!(hex_to_bytes(&hex).unwrap(), original.as_bytes());
     }
     
     #[test]
     fn test_empty_string() {
-        assert_eq!(string_to_hex(""), "");
-        assert_eq!(hex_to_string("").unwrap(), "");
+        assert_eq!(hex::encode(""), "");
+        assert!(hex_to_bytes("").unwrap().is_empty());
     }
     
+    #[test]
+    fn test_hex_to_bytes() {
+        assert_eq!(hex_to_bytes("0a ff\n00").unwrap(), vec![0x0a, 0xff, 0x00]);
+        assert!(hex_to_bytes("abc").is_err());
+    }
+
     #[test]
     fn test_special_characters() {
         let original = "!@#$%^&*()_+-=[]{}|;':\",./<>?";
-        let hex = string_to_hex(original);
-        let decoded = hex_to_string(&hex).unwrap();
-        assert_eq!(original, decoded);
+        let hex = hex::encode(original);
+        assert_eq!(hex_to_bytes(&hex).unwrap(), original.as_bytes());
     }
 }
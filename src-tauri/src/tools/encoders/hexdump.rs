@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HexdumpError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid options: {0}")]
+    InvalidOptions(String),
+    #[error("Line {line}: {message}")]
+    InvalidDump { line: usize, message: String },
+}
+
+/// Bytes shown per page when no length is given
+pub const DEFAULT_PAGE_SIZE: usize = 64 * 1024;
+
+/// Largest output `reverse_hexdump` will zero-fill up to when offsets skip ahead
+pub const MAX_REVERSED_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HexdumpOptions {
+    /// Bytes per line
+    pub width: usize,
+    /// Bytes per group; 0 prints the whole line as one group
+    pub group: usize,
+    /// Show each group as a little-endian word, like `xxd -e`
+    pub little_endian: bool,
+    pub uppercase: bool,
+    pub show_ascii: bool,
+    /// First byte of the page
+    pub offset: u64,
+    /// Bytes in the page, `DEFAULT_PAGE_SIZE` when absent
+    pub length: Option<usize>,
+}
+
+impl Default for HexdumpOptions {
+    fn default() -> Self {
+        HexdumpOptions {
+            width: 16,
+            group: 2,
+            little_endian: false,
+            uppercase: false,
+            show_ascii: true,
+            offset: 0,
+            length: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileType {
+    pub name: &'static str,
+    pub mime: &'static str,
+    pub extension: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexdumpPage {
+    pub dump: String,
+    pub offset: u64,
+    pub length: usize,
+    pub total_size: u64,
+    pub has_more: bool,
+    pub file_type: Option<FileType>,
+}
+
+struct Magic {
+    parts: &'static [(usize, &'static [u8])],
+    file_type: FileType,
+}
+
+macro_rules! magic {
+    ($parts:expr, $name:expr, $mime:expr, $ext:expr) => {
+        Magic {
+            parts: $parts,
+            file_type: FileType {
+                name: $name,
+                mime: $mime,
+                extension: $ext,
+            },
+        }
+    };
+}
+
+// More specific signatures come first, e.g. WebP before a generic RIFF container
+#[rustfmt::skip]
+const MAGIC_TABLE: &[Magic] = &[
+    magic!(&[(0, b"\x89PNG\r\n\x1a\n")], "PNG image", "image/png", "png"),
+    magic!(&[(0, b"\xff\xd8\xff")], "JPEG image", "image/jpeg", "jpg"),
+    magic!(&[(0, b"GIF87a")], "GIF image", "image/gif", "gif"),
+    magic!(&[(0, b"GIF89a")], "GIF image", "image/gif", "gif"),
+    magic!(&[(0, b"RIFF"), (8, b"WEBP")], "WebP image", "image/webp", "webp"),
+    magic!(&[(0, b"RIFF"), (8, b"WAVE")], "WAV audio", "audio/wav", "wav"),
+    magic!(&[(0, b"RIFF"), (8, b"AVI ")], "AVI video", "video/x-msvideo", "avi"),
+    magic!(&[(0, b"BM")], "BMP image", "image/bmp", "bmp"),
+    magic!(&[(0, b"\x00\x00\x01\x00")], "ICO icon", "image/x-icon", "ico"),
+    magic!(&[(0, b"II*\x00")], "TIFF image", "image/tiff", "tif"),
+    magic!(&[(0, b"MM\x00*")], "TIFF image", "image/tiff", "tif"),
+    magic!(&[(4, b"ftypavif")], "AVIF image", "image/avif", "avif"),
+    magic!(&[(4, b"ftypheic")], "HEIC image", "image/heic", "heic"),
+    magic!(&[(4, b"ftypqt")], "QuickTime video", "video/quicktime", "mov"),
+    magic!(&[(4, b"ftyp")], "MP4 media", "video/mp4", "mp4"),
+    magic!(&[(0, b"%PDF-")], "PDF document", "application/pdf", "pdf"),
+    magic!(&[(0, b"PK\x03\x04")], "ZIP archive", "application/zip", "zip"),
+    magic!(&[(0, b"PK\x05\x06")], "ZIP archive (empty)", "application/zip", "zip"),
+    magic!(&[(0, b"\x1f\x8b")], "gzip compressed data", "application/gzip", "gz"),
+    magic!(&[(0, b"BZh")], "bzip2 compressed data", "application/x-bzip2", "bz2"),
+    magic!(&[(0, b"\xfd7zXZ\x00")], "xz compressed data", "application/x-xz", "xz"),
+    magic!(&[(0, b"\x28\xb5\x2f\xfd")], "Zstandard compressed data", "application/zstd", "zst"),
+    magic!(&[(0, b"7z\xbc\xaf\x27\x1c")], "7-Zip archive", "application/x-7z-compressed", "7z"),
+    magic!(&[(0, b"Rar!\x1a\x07")], "RAR archive", "application/vnd.rar", "rar"),
+    magic!(&[(257, b"ustar")], "tar archive", "application/x-tar", "tar"),
+    magic!(&[(0, b"\x7fELF")], "ELF executable", "application/x-elf", "elf"),
+    magic!(&[(0, b"MZ")], "DOS/Windows executable", "application/vnd.microsoft.portable-executable", "exe"),
+    magic!(&[(0, b"\xcf\xfa\xed\xfe")], "Mach-O executable (64-bit)", "application/x-mach-binary", "macho"),
+    magic!(&[(0, b"\xce\xfa\xed\xfe")], "Mach-O executable (32-bit)", "application/x-mach-binary", "macho"),
+    magic!(&[(0, b"\xca\xfe\xba\xbe")], "Java class or Mach-O universal binary", "application/java-vm", "class"),
+    magic!(&[(0, b"\x00asm")], "WebAssembly module", "application/wasm", "wasm"),
+    magic!(&[(0, b"SQLite format 3\x00")], "SQLite database", "application/vnd.sqlite3", "sqlite"),
+    magic!(&[(0, b"ID3")], "MP3 audio", "audio/mpeg", "mp3"),
+    magic!(&[(0, b"OggS")], "Ogg media", "audio/ogg", "ogg"),
+    magic!(&[(0, b"fLaC")], "FLAC audio", "audio/flac", "flac"),
+    magic!(&[(0, b"wOFF")], "WOFF font", "font/woff", "woff"),
+    magic!(&[(0, b"wOF2")], "WOFF2 font", "font/woff2", "woff2"),
+    magic!(&[(0, b"\x00\x01\x00\x00\x00")], "TrueType font", "font/ttf", "ttf"),
+    magic!(&[(0, b"OTTO")], "OpenType font", "font/otf", "otf"),
+    magic!(&[(0, b"-----BEGIN ")], "PEM encoded data", "application/x-pem-file", "pem"),
+    magic!(&[(0, b"\xef\xbb\xbf")], "UTF-8 text with BOM", "text/plain", "txt"),
+    magic!(&[(0, b"\xff\xfe")], "UTF-16LE text with BOM", "text/plain", "txt"),
+    magic!(&[(0, b"\xfe\xff")], "UTF-16BE text with BOM", "text/plain", "txt"),
+];
+
+/// Identify a file type from its leading magic bytes
+pub fn detect_file_type(bytes: &[u8]) -> Option<FileType> {
+    MAGIC_TABLE
+        .iter()
+        .find(|magic| {
+            magic
+                .parts
+                .iter()
+                .all(|(offset, sig)| bytes.get(*offset..offset + sig.len()) == Some(*sig))
+        })
+        .map(|magic| magic.file_type.clone())
+}
+
+fn validate_options(options: &HexdumpOptions) -> Result<(), HexdumpError> {
+    if options.width == 0 || options.width > 256 {
+        return Err(HexdumpError::InvalidOptions(
+            "width must be between 1 and 256 bytes".to_string(),
+        ));
+    }
+    if options.little_endian && options.group > 0 && !options.width.is_multiple_of(options.group) {
+        return Err(HexdumpError::InvalidOptions(
+            "width must be a multiple of the group size in little-endian mode".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Format bytes as an `xxd`-style dump; `start` is the offset of the first byte
+pub fn format_hexdump(bytes: &[u8], start: u64, options: &HexdumpOptions) -> String {
+    let group = if options.group == 0 {
+        options.width
+    } else {
+        options.group.min(options.width)
+    };
+    let groups_per_line = options.width.div_ceil(group);
+    let hex_width = options.width * 2 + groups_per_line - 1;
+
+    let mut out = String::with_capacity(bytes.len() * 4);
+    for (i, line) in bytes.chunks(options.width).enumerate() {
+        let offset = start + (i * options.width) as u64;
+        let hex = line
+            .chunks(group)
+            .map(|chunk| {
+                let mut chunk = chunk.to_vec();
+                if options.little_endian {
+                    chunk.reverse();
+                }
+                if options.uppercase {
+                    hex::encode_upper(chunk)
+                } else {
+                    hex::encode(chunk)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        out.push_str(&format!("{:08x}: ", offset));
+        if options.show_ascii {
+            let ascii: String = line
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            out.push_str(&format!("{:<width$}  {}", hex, ascii, width = hex_width));
+        } else {
+            out.push_str(&hex);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Dump one page of in-memory bytes
+pub fn hexdump_bytes(bytes: &[u8], options: &HexdumpOptions) -> Result<HexdumpPage, HexdumpError> {
+    validate_options(options)?;
+    let total = bytes.len();
+    let start = usize::try_from(options.offset)
+        .unwrap_or(usize::MAX)
+        .min(total);
+    let length = options
+        .length
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .min(total - start);
+    let page = &bytes[start..start + length];
+
+    Ok(HexdumpPage {
+        dump: format_hexdump(page, start as u64, options),
+        offset: start as u64,
+        length,
+        total_size: total as u64,
+        has_more: start + length < total,
+        file_type: detect_file_type(bytes),
+    })
+}
+
+/// Dump one page of a file, reading only the requested range
+pub fn hexdump_file(path: &str, options: &HexdumpOptions) -> Result<HexdumpPage, HexdumpError> {
+    validate_options(options)?;
+    let mut file = File::open(path)?;
+    let total = file.metadata()?.len();
+
+    let mut header = Vec::with_capacity(512);
+    (&mut file).take(512).read_to_end(&mut header)?;
+
+    let start = options.offset.min(total);
+    let length = options.length.unwrap_or(DEFAULT_PAGE_SIZE) as u64;
+    file.seek(SeekFrom::Start(start))?;
+    let mut page = Vec::new();
+    file.take(length).read_to_end(&mut page)?;
+
+    Ok(HexdumpPage {
+        dump: format_hexdump(&page, start, options),
+        offset: start,
+        length: page.len(),
+        total_size: total,
+        has_more: start + (page.len() as u64) < total,
+        file_type: detect_file_type(&header),
+    })
+}
+
+/// Turn a hexdump back into bytes
+///
+/// Accepts `xxd` output, `hexdump -C` output and plain hex. Line offsets are
+/// honoured relative to the first line, so gaps are filled with zeros, up to
+/// `MAX_REVERSED_SIZE` bytes of output. Set `little_endian` and `group` to undo an `xxd -e` dump.
+pub fn reverse_hexdump(dump: &str, options: &HexdumpOptions) -> Result<Vec<u8>, HexdumpError> {
+    let mut out = Vec::new();
+    let mut base_offset: Option<u64> = None;
+
+    for (index, raw_line) in dump.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw_line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (offset, hex_part) = split_dump_line(line);
+
+        let mut line_bytes = Vec::new();
+        for token in hex_part.split_whitespace() {
+            let token = token.trim_start_matches("0x").trim_start_matches("0X");
+            let mut chunk = hex::decode(token).map_err(|e| HexdumpError::InvalidDump {
+                line: line_no,
+                message: format!("'{}': {}", token, e),
+            })?;
+            if options.little_endian {
+                chunk.reverse();
+            }
+            line_bytes.extend_from_slice(&chunk);
+        }
+
+        match offset {
+            Some(offset) => {
+                let base = *base_offset.get_or_insert(offset);
+                let position = offset.checked_sub(base).ok_or(HexdumpError::InvalidDump {
+                    line: line_no,
+                    message: "offset is lower than the first line".to_string(),
+                })?;
+                let end = usize::try_from(position)
+                    .ok()
+                    .and_then(|position| position.checked_add(line_bytes.len()))
+                    .filter(|&end| end <= MAX_REVERSED_SIZE)
+                    .ok_or_else(|| HexdumpError::InvalidDump {
+                        line: line_no,
+                        message: format!(
+                            "offset {:#x} is too far from the first line (output limited to {} MiB)",
+                            offset,
+                            MAX_REVERSED_SIZE / (1024 * 1024)
+                        ),
+                    })?;
+                let start = end - line_bytes.len();
+                if out.len() < end {
+                    out.resize(end, 0);
+                }
+                out[start..end].copy_from_slice(&line_bytes);
+            }
+            None => out.extend_from_slice(&line_bytes),
+        }
+    }
+    Ok(out)
+}
+
+/// Split a dump line into its offset and hex column, dropping the ASCII column
+fn split_dump_line(line: &str) -> (Option<u64>, &str) {
+    let trimmed = line.trim_start();
+
+    // xxd: "00000010: 4865 6c6c ...  Hell..."
+    if let Some((prefix, rest)) = trimmed.split_once(':') {
+        if let Ok(offset) = u64::from_str_radix(prefix.trim(), 16) {
+            let rest = rest.trim_start();
+            let hex = rest.find("  ").map_or(rest, |end| &rest[..end]);
+            return (Some(offset), hex);
+        }
+    }
+    // hexdump -C: "00000010  48 65 6c 6c ...  |Hell...|"
+    if let Some((prefix, rest)) = trimmed.split_once("  ") {
+        if prefix.len() >= 7 && !prefix.contains(' ') {
+            if let Ok(offset) = u64::from_str_radix(prefix, 16) {
+                let hex = rest.find('|').map_or(rest, |bar| &rest[..bar]);
+                return (Some(offset), hex);
+            }
+        }
+    }
+    (None, trimmed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xxd_style_dump() {
+        let dump = format_hexdump(b"Hello, ferris!\n\x00\xff", 0, &HexdumpOptions::default());
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            lines[0],
+            "00000000: 4865 6c6c 6f2c 2066 6572 7269 7321 0a00  Hello, ferris!.."
+        );
+        assert_eq!(
+            lines[1],
+            "00000010: ff                                       ."
+        );
+    }
+
+    #[test]
+    fn test_width_group_and_endianness() {
+        let options = HexdumpOptions {
+            width: 8,
+            group: 4,
+            little_endian: true,
+            uppercase: true,
+            show_ascii: false,
+            ..Default::default()
+        };
+        let dump = format_hexdump(
+            &[0x01, 0x02, 0x03, 0x04, 0xaa, 0xbb, 0xcc, 0xdd],
+            0x20,
+            &options,
+        );
+        assert_eq!(dump, "00000020: 04030201 DDCCBBAA\n");
+
+        let bad = HexdumpOptions {
+            width: 10,
+            group: 4,
+            little_endian: true,
+            ..Default::default()
+        };
+        assert!(hexdump_bytes(b"x", &bad).is_err());
+    }
+
+    #[test]
+    fn test_paging() {
+        let data: Vec<u8> = (0..100).collect();
+        let options = HexdumpOptions {
+            offset: 32,
+            length: Some(32),
+            ..Default::default()
+        };
+        let page = hexdump_bytes(&data, &options).unwrap();
+        assert_eq!(page.length, 32);
+        assert!(page.has_more);
+        assert!(page.dump.starts_with("00000020: 2021"));
+        assert_eq!(page.dump.lines().count(), 2);
+
+        let last = HexdumpOptions {
+            offset: 96,
+            length: Some(32),
+            ..Default::default()
+        };
+        let page = hexdump_bytes(&data, &last).unwrap();
+        assert_eq!(page.length, 4);
+        assert!(!page.has_more);
+    }
+
+    #[test]
+    fn test_file_paging_and_type() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut data = b"%PDF-1.7\n".to_vec();
+        data.resize(1000, b'x');
+        std::fs::write(file.path(), &data).unwrap();
+
+        let options = HexdumpOptions {
+            offset: 992,
+            ..Default::default()
+        };
+        let page = hexdump_file(&file.path().to_string_lossy(), &options).unwrap();
+        assert_eq!(page.total_size, 1000);
+        assert_eq!(page.length, 8);
+        assert_eq!(page.file_type.unwrap().mime, "application/pdf");
+    }
+
+    #[test]
+    fn test_reverse_round_trip() {
+        let data: Vec<u8> = (0..=255u8).rev().collect();
+        let options = HexdumpOptions {
+            width: 12,
+            group: 4,
+            little_endian: true,
+            ..Default::default()
+        };
+        let dump = format_hexdump(&data[..240], 0, &options);
+        assert_eq!(reverse_hexdump(&dump, &options).unwrap(), &data[..240]);
+
+        let plain = format_hexdump(&data, 0, &HexdumpOptions::default());
+        assert_eq!(
+            reverse_hexdump(&plain, &HexdumpOptions::default()).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_reverse_other_formats() {
+        let hexdump_c = "00000000  48 65 6c 6c 6f 20 20 77  |Hello  w|\n00000010  21                       |!|\n";
+        let bytes = reverse_hexdump(hexdump_c, &HexdumpOptions::default()).unwrap();
+        assert_eq!(&bytes[..8], b"Hello  w");
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(bytes[16], b'!');
+
+        assert_eq!(
+            reverse_hexdump("de ad\nbeef", &HexdumpOptions::default()).unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert!(reverse_hexdump("00000000: zz", &HexdumpOptions::default()).is_err());
+
+        // Offsets that would zero-fill gigabytes are rejected
+        let gap = reverse_hexdump("00000000: 00\nffffffff: 00", &HexdumpOptions::default());
+        assert!(matches!(gap, Err(HexdumpError::InvalidDump { line: 2, .. })));
+        let small_gap = reverse_hexdump("00000000: 01\n00000010: 02", &HexdumpOptions::default()).unwrap();
+        assert_eq!(small_gap.len(), 17);
+    }
+
+    #[test]
+    fn test_detect_file_type() {
+        let png = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00];
+        assert_eq!(detect_file_type(&png).unwrap().extension, "png");
+        assert_eq!(
+            detect_file_type(b"RIFF\0\0\0\0WEBPVP8 ").unwrap().mime,
+            "image/webp"
+        );
+        assert_eq!(
+            detect_file_type(b"\x7fELF\x02\x01").unwrap().name,
+            "ELF executable"
+        );
+        assert_eq!(
+            detect_file_type(&[0x1f, 0x8b, 0x08]).unwrap().extension,
+            "gz"
+        );
+        assert!(detect_file_type(b"plain text").is_none());
+    }
+}
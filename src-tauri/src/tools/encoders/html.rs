@@ -1,21 +1,49 @@
-use html_escape::{encode_text, decode_html_entities};
-use thiserror::Error;
+use entities::ENTITIES;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 
-#[derive(Error, Debug)]
-pub enum HtmlError {
-    #[error("Decoding error: {0}")]
-    DecodeError(String),
+/// Where escaped text will be placed in a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HtmlContext {
+    /// Element content
+    Body,
+    /// Attribute value inside single or double quotes
+    Attribute,
+    /// Attribute value without quotes
+    UnquotedAttribute,
+    /// JSON placed inside a `<script>` element
+    ScriptJson,
+    /// Value inside a quoted CSS string
+    CssString,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub enum HtmlEntityFormat {
-    Named,     // &lt; &gt; &amp;
-    Numeric,   // &#60; &#62; &#38;
-    Hex,       // &#x3C; &#x3E; &#x26;
-}
+/// WHATWG named references ("&amp;", and legacy forms like "&amp") to their text
+static ENTITY_TO_TEXT: Lazy<HashMap<&'static str, &'static str>> =
+    Lazy::new(|| ENTITIES.iter().map(|e| (e.entity, e.characters)).collect());
+
+/// Preferred reference for each text: the shortest name with a semicolon,
+/// lowercase first ("&amp;" over "&AMP;")
+static TEXT_TO_ENTITY: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let mut map: HashMap<&'static str, &'static str> = HashMap::new();
+    let rank = |name: &str| (name.len(), name.chars().any(|c| c.is_ascii_uppercase()));
+    for e in ENTITIES.iter().filter(|e| e.entity.ends_with(';')) {
+        let current = map.entry(e.characters).or_insert(e.entity);
+        if rank(e.entity) < rank(current)
+            || (rank(e.entity) == rank(current) && e.entity < *current)
+        {
+            *current = e.entity;
+        }
+    }
+    map
+});
+
+/// Longest legacy/named reference, "&CounterClockwiseContourIntegral;" included
+const MAX_ENTITY_LEN: usize = 33;
 
 /// Encode text to HTML entities (named format)
-/// Converts special characters like <, >, &, ", ' to named entities
+/// Converts special characters like <, >, &, ", ' and any non-ASCII character
+/// with an HTML5 named reference (é → &eacute;)
 /// 
 /// # Examples
 /// ```
@@ -23,7 +51,31 @@ pub enum HtmlEntityFormat {
 /// assert_eq!(encode_html_named("<div>"), "&lt;div&gt;");
 /// ```
 pub fn encode_html_named(text: &str) -> String {
-    encode_text(text).to_string()
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '&' | '<' | '>' | '"' | '\'' => {}
+            c if c.is_ascii() => {
+                result.push(c);
+                continue;
+            }
+            _ => {}
+        }
+        // A few references stand for two code points, e.g. &nvlt; for "<\u{20D2}"
+        if let Some(&(j, next)) = chars.peek() {
+            if let Some(entity) = TEXT_TO_ENTITY.get(&text[i..j + next.len_utf8()]) {
+                result.push_str(entity);
+                chars.next();
+                continue;
+            }
+        }
+        match TEXT_TO_ENTITY.get(&text[i..i + c.len_utf8()]) {
+            Some(entity) => result.push_str(entity),
+            None => result.push(c),
+        }
+    }
+    result
 }
 
 /// Encode text to HTML entities (numeric format)
@@ -74,11 +126,145 @@ pub fn encode_html_hex(text: &str) -> String {
 /// # Examples
 /// ```
 /// use ferrisbox_lib::tools::encoders::html::decode_html;
-/// assert_eq!(decode_html("&lt;div&gt;").unwrap(), "<div>");
+/// assert_eq!(decode_html("&lt;div&gt;"), "<div>");
 /// ```
-pub fn decode_html(encoded: &str) -> Result<String, HtmlError> {
-    let decoded = decode_html_entities(encoded);
-    Ok(decoded.to_string())
+pub fn decode_html(encoded: &str) -> String {
+    decode_references(encoded, false)
+}
+
+/// Decode an attribute value. Legacy references without a semicolon are kept
+/// when followed by "=" or an alphanumeric, so "?a=1&copy=2" survives.
+pub fn decode_html_attribute(encoded: &str) -> String {
+    decode_references(encoded, true)
+}
+
+/// Character reference decoding as in the WHATWG tokenizer
+fn decode_references(text: &str, in_attribute: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let consumed = if rest[1..].starts_with('#') {
+            decode_numeric(rest, &mut result)
+        } else {
+            decode_named(rest, in_attribute, &mut result)
+        };
+        if consumed == 0 {
+            result.push('&');
+            rest = &rest[1..];
+        } else {
+            rest = &rest[consumed..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Decode "&#...;" at the start of `text`; returns the bytes consumed, or 0
+fn decode_numeric(text: &str, out: &mut String) -> usize {
+    let bytes = text.as_bytes();
+    let (radix, start) = match bytes.get(2) {
+        Some(b'x' | b'X') => (16, 3),
+        _ => (10, 2),
+    };
+    let digits = bytes[start..]
+        .iter()
+        .take_while(|b| (**b as char).is_digit(radix))
+        .count();
+    if digits == 0 {
+        return 0;
+    }
+    let mut end = start + digits;
+    let value = u32::from_str_radix(&text[start..end], radix).unwrap_or(u32::MAX);
+    if bytes.get(end) == Some(&b';') {
+        end += 1;
+    }
+    match value {
+        // C1 controls are read as windows-1252, as browsers do
+        0x80..=0x9F => {
+            let byte = [value as u8];
+            let (decoded, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(&byte);
+            out.push_str(&decoded);
+        }
+        0 => out.push(char::REPLACEMENT_CHARACTER),
+        _ => out.push(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER)),
+    }
+    end
+}
+
+/// Decode the longest named reference at the start of `text`; returns the bytes consumed, or 0
+fn decode_named(text: &str, in_attribute: bool, out: &mut String) -> usize {
+    let name_len = text[1..]
+        .bytes()
+        .take(MAX_ENTITY_LEN)
+        .take_while(u8::is_ascii_alphanumeric)
+        .count();
+    let candidate_end = 1 + name_len + usize::from(text[1 + name_len..].starts_with(';'));
+    for end in (2..=candidate_end).rev() {
+        let Some(decoded) = ENTITY_TO_TEXT.get(&text[..end]) else {
+            continue;
+        };
+        let next = text[end..].bytes().next();
+        if in_attribute
+            && !text[..end].ends_with(';')
+            && next.is_some_and(|b| b == b'=' || b.is_ascii_alphanumeric())
+        {
+            return 0;
+        }
+        out.push_str(decoded);
+        return end;
+    }
+    0
+}
+
+/// Escape text for the given context, following the OWASP XSS prevention rules
+pub fn escape_html_context(text: &str, context: HtmlContext) -> String {
+    match context {
+        HtmlContext::Body | HtmlContext::Attribute => {
+            let mut result = String::with_capacity(text.len());
+            for c in text.chars() {
+                match c {
+                    '&' => result.push_str("&amp;"),
+                    '<' => result.push_str("&lt;"),
+                    '>' => result.push_str("&gt;"),
+                    '"' => result.push_str("&quot;"),
+                    '\'' => result.push_str("&#x27;"),
+                    '`' if context == HtmlContext::Attribute => result.push_str("&#x60;"),
+                    c => result.push(c),
+                }
+            }
+            result
+        }
+        HtmlContext::UnquotedAttribute => text
+            .chars()
+            .map(|c| match c as u32 {
+                0..=0xFF if !c.is_ascii_alphanumeric() => format!("&#x{:02X};", c as u32),
+                _ => c.to_string(),
+            })
+            .collect(),
+        HtmlContext::ScriptJson => {
+            // Valid JSON is embedded as-is; anything else becomes a JSON string
+            let json = match serde_json::from_str::<serde_json::Value>(text) {
+                Ok(_) => text.to_string(),
+                Err(_) => serde_json::Value::String(text.to_string()).to_string(),
+            };
+            // Outside strings these characters cannot appear in valid JSON
+            json.replace('<', "\\u003c")
+                .replace('>', "\\u003e")
+                .replace('&', "\\u0026")
+                .replace('\u{2028}', "\\u2028")
+                .replace('\u{2029}', "\\u2029")
+        }
+        HtmlContext::CssString => text
+            .chars()
+            .map(|c| match c as u32 {
+                // The trailing space ends the escape so a following hex digit is not absorbed
+                0..=0xFF if !c.is_ascii_alphanumeric() => format!("\\{:X} ", c as u32),
+                _ => c.to_string(),
+            })
+            .collect(),
+    }
 }
 
 #[cfg(test)]
@@ -89,16 +275,13 @@ mod tests {
     fn test_encode_named_basic() {
         assert_eq!(encode_html_named("<div>"), "&lt;div&gt;");
         assert_eq!(encode_html_named("A & B"), "A &amp; B");
-        // Note: html-escape doesn't escape quotes by default, only in attributes
-        let encoded = encode_html_named("Quote \"text\"");
-        assert!(encoded.contains("Quote"));
+        // Quotes are escaped so the output is also safe inside attribute values
+        assert_eq!(encode_html_named("Quote \"text\""), "Quote &quot;text&quot;");
     }
     
     #[test]
     fn test_encode_named_apostrophe() {
-        // html-escape keeps apostrophes as-is in text content
-        let encoded = encode_html_named("It's");
-        assert!(encoded.contains("It"));
+        assert_eq!(encode_html_named("It's"), "It&apos;s");
     }
     
     #[test]
@@ -117,28 +300,28 @@ mod tests {
     
     #[test]
     fn test_decode_named() {
-        assert_eq!(decode_html("&lt;div&gt;").unwrap(), "<div>");
-        assert_eq!(decode_html("A &amp; B").unwrap(), "A & B");
-        assert_eq!(decode_html("&quot;text&quot;").unwrap(), "\"text\"");
+        assert_eq!(decode_html("&lt;div&gt;"), "<div>");
+        assert_eq!(decode_html("A &amp; B"), "A & B");
+        assert_eq!(decode_html("&quot;text&quot;"), "\"text\"");
     }
     
     #[test]
     fn test_decode_numeric() {
-        assert_eq!(decode_html("&#60;div&#62;").unwrap(), "<div>");
-        assert_eq!(decode_html("&#38;").unwrap(), "&");
+        assert_eq!(decode_html("&#60;div&#62;"), "<div>");
+        assert_eq!(decode_html("&#38;"), "&");
     }
     
     #[test]
     fn test_decode_hex() {
-        assert_eq!(decode_html("&#x3C;div&#x3E;").unwrap(), "<div>");
-        assert_eq!(decode_html("&#x26;").unwrap(), "&");
+        assert_eq!(decode_html("&#x3C;div&#x3E;"), "<div>");
+        assert_eq!(decode_html("&#x26;"), "&");
     }
     
     #[test]
     fn test_roundtrip_named() {
         let original = "<script>alert('XSS')</script>";
         let encoded = encode_html_named(original);
-        let decoded = decode_html(&encoded).unwrap();
+        let decoded = decode_html(&encoded);
         assert_eq!(original, decoded);
     }
     
@@ -146,7 +329,7 @@ mod tests {
     fn test_roundtrip_numeric() {
         let original = "A < B & C > D";
         let encoded = encode_html_numeric(original);
-        let decoded = decode_html(&encoded).unwrap();
+        let decoded = decode_html(&encoded);
         assert_eq!(original, decoded);
     }
     
@@ -154,7 +337,7 @@ mod tests {
     fn test_unicode() {
         let original = "Emoji: 😀 Arabic: مرحبا";
         let encoded = encode_html_numeric(original);
-        let decoded = decode_html(&encoded).unwrap();
+        let decoded = decode_html(&encoded);
         assert_eq!(original, decoded);
     }
     
@@ -162,7 +345,80 @@ mod tests {
     fn test_mixed_entities() {
         // Test decoding mixed format
         let mixed = "&lt;div&#62; &#x26; test";
-        let decoded = decode_html(mixed).unwrap();
+        let decoded = decode_html(mixed);
         assert_eq!(decoded, "<div> & test");
     }
+
+    #[test]
+    fn test_full_entity_table() {
+        assert_eq!(
+            decode_html("&eacute;&hearts;&NotEqualTilde;"),
+            "é♥≂\u{338}"
+        );
+        assert_eq!(
+            decode_html("&CounterClockwiseContourIntegral;"),
+            "∳"
+        );
+        // Legacy references work without a semicolon, longest match first
+        assert_eq!(
+            decode_html("&copy 2024 &notin; &notit;"),
+            "© 2024 ∉ ¬it;"
+        );
+        assert_eq!(decode_html("&bogus; & &#;"), "&bogus; & &#;");
+
+        assert_eq!(
+            encode_html_named("café © ≂\u{338}"),
+            "caf&eacute; &copy; &nesim;"
+        );
+        assert_eq!(encode_html_named("\"'&"), "&quot;&apos;&amp;");
+        assert_eq!(
+            decode_html(&encode_html_named("Ωmega ∑ ½")),
+            "Ωmega ∑ ½"
+        );
+    }
+
+    #[test]
+    fn test_numeric_edge_cases() {
+        assert_eq!(decode_html("&#128;&#x99;"), "€™");
+        assert_eq!(
+            decode_html("&#0;&#xD800;&#x110000;"),
+            "\u{FFFD}\u{FFFD}\u{FFFD}"
+        );
+        assert_eq!(decode_html("&#65&#x42"), "AB");
+    }
+
+    #[test]
+    fn test_attribute_decoding() {
+        assert_eq!(
+            decode_html_attribute("?a=1&copy=2&amp;b"),
+            "?a=1&copy=2&b"
+        );
+        assert_eq!(decode_html("?a=1&copy=2"), "?a=1©=2");
+        assert_eq!(decode_html_attribute("&copy 2024"), "© 2024");
+    }
+
+    #[test]
+    fn test_context_escaping() {
+        let payload = "\"><img src=x onerror=alert(1)>";
+        assert_eq!(
+            escape_html_context(payload, HtmlContext::Attribute),
+            "&quot;&gt;&lt;img src=x onerror=alert(1)&gt;"
+        );
+        assert_eq!(
+            escape_html_context("a b=c", HtmlContext::UnquotedAttribute),
+            "a&#x20;b&#x3D;c"
+        );
+        assert_eq!(
+            escape_html_context(r#"{"html":"</script><b>"}"#, HtmlContext::ScriptJson),
+            r#"{"html":"\u003c/script\u003e\u003cb\u003e"}"#
+        );
+        assert_eq!(
+            escape_html_context("</script>", HtmlContext::ScriptJson),
+            r#""\u003c/script\u003e""#
+        );
+        assert_eq!(
+            escape_html_context("a';}b", HtmlContext::CssString),
+            "a\\27 \\3B \\7D b"
+        );
+    }
 }
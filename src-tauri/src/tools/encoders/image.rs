@@ -88,15 +88,45 @@ fn detect_mime_type(file_path: &str) -> Result<&'static str, ImageError> {
 }
 
 /// Get file extension from MIME type
+/// Parameters such as `;charset=utf-8` are ignored
 pub fn get_extension_from_mime(mime_type: &str) -> &'static str {
-    match mime_type {
+    let essence = mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match essence.as_str() {
         "image/png" => "png",
         "image/jpeg" => "jpg",
         "image/gif" => "gif",
         "image/webp" => "webp",
         "image/svg+xml" => "svg",
         "image/bmp" => "bmp",
-        "image/x-icon" => "ico",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "image/avif" => "avif",
+        "image/tiff" => "tif",
+        "font/woff" => "woff",
+        "font/woff2" => "woff2",
+        "font/ttf" => "ttf",
+        "font/otf" => "otf",
+        "application/pdf" => "pdf",
+        "application/json" => "json",
+        "application/xml" | "text/xml" => "xml",
+        "application/zip" => "zip",
+        "application/gzip" => "gz",
+        "application/wasm" => "wasm",
+        "text/plain" => "txt",
+        "text/html" => "html",
+        "text/css" => "css",
+        "text/csv" => "csv",
+        "text/markdown" => "md",
+        "text/javascript" | "application/javascript" => "js",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "audio/mpeg" => "mp3",
+        "audio/ogg" => "ogg",
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
         _ => "bin", // fallback
     }
 }
@@ -157,6 +187,8 @@ mod tests {
         assert_eq!(get_extension_from_mime("image/jpeg"), "jpg");
         assert_eq!(get_extension_from_mime("image/gif"), "gif");
         assert_eq!(get_extension_from_mime("unknown"), "bin");
+        assert_eq!(get_extension_from_mime("font/woff2"), "woff2");
+        assert_eq!(get_extension_from_mime("text/plain;charset=utf-8"), "txt");
     }
 
     #[test]
@@ -4,4 +4,54 @@ pub mod hex;
 pub mod html;
 pub mod punycode;
 pub mod morse;
+pub mod morse_audio;
 pub mod image;
+pub mod data_uri;
+pub mod base_n;
+pub mod text_encoding;
+pub mod hexdump;
+pub mod unicode;
+pub mod escape;
+pub mod cipher;
+
+use serde::{Deserialize, Serialize};
+
+/// Text representation used when binary data is passed around as a string
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteEncoding {
+    Hex,
+    Base64,
+}
+
+impl ByteEncoding {
+    /// Decode hex or base64 text into raw bytes
+    ///
+    /// Base64 input may be URL-safe, unpadded or wrapped across lines.
+    pub fn decode(self, input: &str) -> Result<Vec<u8>, String> {
+        match self {
+            ByteEncoding::Hex => hex::hex_to_bytes(input).map_err(|e| e.to_string()),
+            ByteEncoding::Base64 => {
+                let mut cleaned: String = input
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .map(|c| match c {
+                        '-' => '+',
+                        '_' => '/',
+                        c => c,
+                    })
+                    .collect();
+                let padding = (4 - cleaned.len() % 4) % 4;
+                cleaned.push_str(&"=".repeat(padding));
+                base64::decode_base64(&cleaned).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            ByteEncoding::Hex => ::hex::encode(bytes),
+            ByteEncoding::Base64 => base64::encode_base64(bytes),
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Error, Debug)]
 pub enum MorseError {
@@ -7,8 +9,60 @@ pub enum MorseError {
     UnknownCharacter(char),
     #[error("Invalid morse code sequence: {0}")]
     InvalidMorse(String),
+    #[error("Unknown prosign: <{0}>")]
+    UnknownProsign(String),
 }
 
+/// Letter table used besides digits and punctuation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MorseAlphabet {
+    #[default]
+    Latin,
+    /// Russian Morse code
+    Cyrillic,
+    /// Japanese Wabun code (katakana; hiragana is accepted when encoding)
+    Wabun,
+}
+
+/// Procedural signals, written as `<AR>` and sent without letter gaps
+const PROSIGNS: &[(&str, &str)] = &[
+    ("AR", ".-.-."),
+    ("AS", ".-..."),
+    ("BK", "-...-.-"),
+    ("BT", "-...-"),
+    ("CT", "-.-.-"),
+    ("HH", "........"),
+    ("KN", "-.--."),
+    ("SK", "...-.-"),
+    ("SN", "...-."),
+    ("SOS", "...---..."),
+];
+
+#[rustfmt::skip]
+const CYRILLIC: &[(char, &str)] = &[
+    ('А', ".-"), ('Б', "-..."), ('В', ".--"), ('Г', "--."), ('Д', "-.."), ('Е', "."),
+    ('Ж', "...-"), ('З', "--.."), ('И', ".."), ('Й', ".---"), ('К', "-.-"), ('Л', ".-.."),
+    ('М', "--"), ('Н', "-."), ('О', "---"), ('П', ".--."), ('Р', ".-."), ('С', "..."),
+    ('Т', "-"), ('У', "..-"), ('Ф', "..-."), ('Х', "...."), ('Ц', "-.-."), ('Ч', "---."),
+    ('Ш', "----"), ('Щ', "--.-"), ('Ъ', "--.--"), ('Ы', "-.--"), ('Ь', "-..-"), ('Э', "..-.."),
+    ('Ю', "..--"), ('Я', ".-.-"),
+];
+
+#[rustfmt::skip]
+const WABUN: &[(char, &str)] = &[
+    ('イ', ".-"), ('ロ', ".-.-"), ('ハ', "-..."), ('ニ', "-.-."), ('ホ', "-.."), ('ヘ', "."),
+    ('ト', "..-.."), ('チ', "..-."), ('リ', "--."), ('ヌ', "...."), ('ル', "-.--."), ('ヲ', ".---"),
+    ('ワ', "-.-"), ('カ', ".-.."), ('ヨ', "--"), ('タ', "-."), ('レ', "---"), ('ソ', "---."),
+    ('ツ', ".--."), ('ネ', "--.-"), ('ナ', ".-."), ('ラ', "..."), ('ム', "-"), ('ウ', "..-"),
+    ('ヰ', ".-..-"), ('ノ', "..--"), ('オ', ".-..."), ('ク', "...-"), ('ヤ', ".--"), ('マ', "-..-"),
+    ('ケ', "-.--"), ('フ', "--.."), ('コ', "----"), ('エ', "-.---"), ('テ', ".-.--"), ('ア', "--.--"),
+    ('サ', "-.-.-"), ('キ', "-.-.."), ('ユ', "-..--"), ('メ', "-...-"), ('ミ', "..-.-"), ('シ', "--.-."),
+    ('ヱ', ".--.."), ('ヒ', "--..-"), ('モ', "-..-."), ('セ', ".---."), ('ス', "---.-"), ('ン', ".-.-."),
+    ('\u{3099}', ".."), ('\u{309A}', "..--."), ('ー', ".--.-"), ('、', ".-.-.-"), ('」', ".-.-.."),
+    ('（', "-.--.-"), ('）', ".-..-."),
+];
+
 /// Get the morse code mapping (character -> morse)
 fn get_morse_map() -> HashMap<char, &'static str> {
     let mut map = HashMap::new();
@@ -83,61 +137,145 @@ fn get_reverse_morse_map() -> HashMap<&'static str, char> {
         .collect()
 }
 
-/// Encode text to morse code
-/// Words are separated by " / " (slash with spaces)
-/// Letters/numbers are separated by single space
-pub fn encode_morse(text: &str) -> Result<String, MorseError> {
-    let morse_map = get_morse_map();
+/// Encoding table for an alphabet; Latin letters are always accepted
+fn alphabet_encode_map(alphabet: MorseAlphabet) -> HashMap<char, &'static str> {
+    let mut map = get_morse_map();
+    match alphabet {
+        MorseAlphabet::Latin => {}
+        MorseAlphabet::Cyrillic => {
+            map.extend(CYRILLIC.iter().copied());
+            map.insert('Ё', ".");
+        }
+        MorseAlphabet::Wabun => map.extend(WABUN.iter().copied()),
+    }
+    map
+}
+
+/// Decoding table: the alphabet's letters win over Latin ones with the same code
+fn alphabet_decode_map(alphabet: MorseAlphabet) -> HashMap<&'static str, char> {
+    let mut map = get_reverse_morse_map();
+    match alphabet {
+        MorseAlphabet::Latin => {}
+        MorseAlphabet::Cyrillic => map.extend(CYRILLIC.iter().map(|&(c, m)| (m, c))),
+        MorseAlphabet::Wabun => {
+            // Wabun shares only digits with the international table
+            map.retain(|_, c| c.is_ascii_digit());
+            map.extend(WABUN.iter().map(|&(c, m)| (m, c)));
+        }
+    }
+    map
+}
+
+/// Fold kana variants onto the Wabun table: hiragana to katakana, small kana to
+/// full size, voiced kana to base + combining (han)dakuten
+fn normalize_kana(text: &str) -> String {
+    text.nfd()
+        .map(|c| match c {
+            '\u{3041}'..='\u{3096}' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .map(|c| match c {
+            'ァ' => 'ア',
+            'ィ' => 'イ',
+            'ゥ' => 'ウ',
+            'ェ' => 'エ',
+            'ォ' => 'オ',
+            'ッ' => 'ツ',
+            'ャ' => 'ヤ',
+            'ュ' => 'ユ',
+            'ョ' => 'ヨ',
+            'ヮ' => 'ワ',
+            '\u{309B}' => '\u{3099}',
+            '\u{309C}' => '\u{309A}',
+            c => c,
+        })
+        .collect()
+}
+
+fn prosign_code(name: &str) -> Result<&'static str, MorseError> {
+    PROSIGNS
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+        .ok_or_else(|| MorseError::UnknownProsign(name.to_string()))
+}
+
+/// Encode text using the given alphabet; prosigns are written as `<SK>`
+/// Words are separated by " / " and letters by a single space
+pub fn encode_morse_with(text: &str, alphabet: MorseAlphabet) -> Result<String, MorseError> {
+    let morse_map = alphabet_encode_map(alphabet);
+    let text = if alphabet == MorseAlphabet::Wabun {
+        normalize_kana(text)
+    } else {
+        text.to_string()
+    };
     let mut result = Vec::new();
-    
+
     for word in text.split_whitespace() {
-        let mut morse_word = Vec::new();
-        
-        for ch in word.chars() {
-            let upper = ch.to_ascii_uppercase();
-            
-            if let Some(morse) = morse_map.get(&upper) {
-                morse_word.push(*morse);
-            } else {
-                return Err(MorseError::UnknownCharacter(ch));
+        let mut morse_word: Vec<&str> = Vec::new();
+        let mut chars = word.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '<' {
+                let name: String = chars.by_ref().take_while(|&c| c != '>').collect();
+                morse_word.push(prosign_code(&name)?);
+                continue;
+            }
+            let mut upper = ch.to_uppercase();
+            let upper = match upper.len() {
+                1 => upper.next().unwrap_or(ch),
+                _ => ch,
+            };
+            match morse_map.get(&upper) {
+                Some(morse) => morse_word.push(morse),
+                None => return Err(MorseError::UnknownCharacter(ch)),
             }
         }
-        
         result.push(morse_word.join(" "));
     }
-    
+
     Ok(result.join(" / "))
 }
 
-/// Decode morse code to text
-/// Expects words separated by " / " and letters by space
-pub fn decode_morse(morse: &str) -> Result<String, MorseError> {
-    let reverse_map = get_reverse_morse_map();
+/// Decode morse using the given alphabet; codes that are only prosigns decode as `<SK>`
+pub fn decode_morse_with(morse: &str, alphabet: MorseAlphabet) -> Result<String, MorseError> {
+    let reverse_map = alphabet_decode_map(alphabet);
     let mut result = Vec::new();
-    
-    // Split by word separator " / "
-    for morse_word in morse.split(" / ") {
+
+    for morse_word in morse.split('/') {
         let mut decoded_word = String::new();
-        
-        // Split by letter separator (single space)
         for morse_char in morse_word.split_whitespace() {
             if let Some(ch) = reverse_map.get(morse_char) {
                 decoded_word.push(*ch);
+            } else if let Some((name, _)) = PROSIGNS.iter().find(|(_, code)| *code == morse_char) {
+                decoded_word.push_str(&format!("<{}>", name));
             } else {
                 return Err(MorseError::InvalidMorse(morse_char.to_string()));
             }
         }
-        
         result.push(decoded_word);
     }
-    
-    Ok(result.join(" "))
+
+    let text = result.join(" ");
+    // Recombine kana with their dakuten marks
+    Ok(if alphabet == MorseAlphabet::Wabun {
+        text.nfc().collect()
+    } else {
+        text
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn encode_morse(text: &str) -> Result<String, MorseError> {
+        encode_morse_with(text, MorseAlphabet::Latin)
+    }
+
+    fn decode_morse(morse: &str) -> Result<String, MorseError> {
+        decode_morse_with(morse, MorseAlphabet::Latin)
+    }
+
     #[test]
     fn test_encode_simple() {
         let result = encode_morse("HELLO").unwrap();
@@ -231,4 +369,29 @@ mod tests {
         let result = decode_morse(".... .... ....... ...."); // Invalid morse sequence
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_prosigns() {
+        assert_eq!(encode_morse("<SOS> <AR>").unwrap(), "...---... / .-.-.");
+        assert_eq!(encode_morse("CQ<sk>").unwrap(), "-.-. --.- ...-.-");
+        // Codes shared with punctuation decode as the character
+        assert_eq!(decode_morse("...-.- / .-.-.").unwrap(), "<SK> +");
+        assert!(matches!(encode_morse("<XYZ>"), Err(MorseError::UnknownProsign(_))));
+    }
+
+    #[test]
+    fn test_cyrillic_alphabet() {
+        let morse = encode_morse_with("Привет 73", MorseAlphabet::Cyrillic).unwrap();
+        assert_eq!(morse, ".--. .-. .. .-- . - / --... ...--");
+        assert_eq!(decode_morse_with(&morse, MorseAlphabet::Cyrillic).unwrap(), "ПРИВЕТ 73");
+        assert_eq!(decode_morse(&morse).unwrap(), "PRIWET 73");
+    }
+
+    #[test]
+    fn test_wabun_alphabet() {
+        // Hiragana and voiced kana are folded onto the katakana table
+        let morse = encode_morse_with("さくら ガ", MorseAlphabet::Wabun).unwrap();
+        assert_eq!(morse, "-.-.- ...- ... / .-.. ..");
+        assert_eq!(decode_morse_with(&morse, MorseAlphabet::Wabun).unwrap(), "サクラ ガ");
+    }
 }
@@ -0,0 +1,510 @@
+use super::morse::{decode_morse_with, encode_morse_with, MorseAlphabet, MorseError};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MorseAudioError {
+    #[error(transparent)]
+    Morse(#[from] MorseError),
+    #[error("Invalid option: {0}")]
+    InvalidOptions(String),
+    #[error("Invalid WAV file: {0}")]
+    InvalidWav(String),
+    #[error("No morse signal found in audio")]
+    NoSignal,
+}
+
+/// Keying parameters for generated audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MorseAudioOptions {
+    /// Character speed in words per minute (PARIS timing)
+    pub wpm: f32,
+    /// Effective speed for Farnsworth spacing; gaps are stretched to reach it
+    pub farnsworth_wpm: Option<f32>,
+    /// Tone frequency in Hz
+    pub frequency: f32,
+    pub sample_rate: u32,
+    /// Amplitude between 0 and 1
+    pub volume: f32,
+}
+
+impl Default for MorseAudioOptions {
+    fn default() -> Self {
+        Self {
+            wpm: 20.0,
+            farnsworth_wpm: None,
+            frequency: 600.0,
+            sample_rate: 8000,
+            volume: 0.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MorseAudio {
+    pub morse: String,
+    /// `data:audio/wav;base64,...`
+    pub data_url: String,
+    pub duration_seconds: f32,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MorseAudioDecoded {
+    pub morse: String,
+    /// Decoded text; unknown sequences are shown as `?`
+    pub text: String,
+    pub estimated_wpm: f32,
+    pub estimated_frequency: f32,
+}
+
+/// Ramp applied to each tone edge to avoid key clicks
+const RAMP_SECONDS: f32 = 0.005;
+/// Envelope window used when decoding
+const WINDOW_SECONDS: f32 = 0.002;
+
+impl MorseAudioOptions {
+    fn validate(&self) -> Result<(), MorseAudioError> {
+        if !(5.0..=60.0).contains(&self.wpm) {
+            return Err(MorseAudioError::InvalidOptions(
+                "wpm must be between 5 and 60".to_string(),
+            ));
+        }
+        if let Some(effective) = self.farnsworth_wpm {
+            if !(1.0..=self.wpm).contains(&effective) {
+                return Err(MorseAudioError::InvalidOptions(
+                    "Farnsworth speed must be between 1 and the character speed".to_string(),
+                ));
+            }
+        }
+        if !(8000..=96000).contains(&self.sample_rate) {
+            return Err(MorseAudioError::InvalidOptions(
+                "sample rate must be between 8000 and 96000 Hz".to_string(),
+            ));
+        }
+        if self.frequency < 100.0 || self.frequency * 2.0 >= self.sample_rate as f32 {
+            return Err(MorseAudioError::InvalidOptions(
+                "frequency must be at least 100 Hz and below the Nyquist limit".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.volume) {
+            return Err(MorseAudioError::InvalidOptions(
+                "volume must be between 0 and 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Dot length, gap between characters and gap between words, in seconds
+    fn timing(&self) -> (f32, f32, f32) {
+        let dot = 1.2 / self.wpm;
+        match self.farnsworth_wpm {
+            Some(effective) if effective < self.wpm => {
+                // ARRL Farnsworth formula: total extra delay spread over 19 units
+                let delay = (60.0 * self.wpm - 37.2 * effective) / (self.wpm * effective);
+                (dot, 3.0 * delay / 19.0, 7.0 * delay / 19.0)
+            }
+            _ => (dot, 3.0 * dot, 7.0 * dot),
+        }
+    }
+}
+
+/// Render a morse string (`.`, `-`, spaces and `/`) as 16-bit mono PCM samples
+fn render_samples(morse: &str, options: &MorseAudioOptions) -> Vec<i16> {
+    let (dot, char_gap, word_gap) = options.timing();
+    let rate = options.sample_rate as f32;
+    let amplitude = options.volume * i16::MAX as f32;
+    let mut samples = Vec::new();
+
+    let tone = |samples: &mut Vec<i16>, seconds: f32| {
+        let count = (seconds * rate) as usize;
+        let ramp = ((RAMP_SECONDS * rate) as usize).min(count / 2).max(1);
+        for i in 0..count {
+            let edge = i.min(count - 1 - i);
+            let gain = if edge < ramp {
+                0.5 - 0.5 * (PI * edge as f32 / ramp as f32).cos()
+            } else {
+                1.0
+            };
+            let phase = 2.0 * PI * options.frequency * i as f32 / rate;
+            samples.push((phase.sin() * gain * amplitude) as i16);
+        }
+    };
+    let silence = |samples: &mut Vec<i16>, seconds: f32| {
+        samples.resize(samples.len() + (seconds * rate) as usize, 0);
+    };
+
+    for (w, word) in morse
+        .split('/')
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .enumerate()
+    {
+        if w > 0 {
+            silence(&mut samples, word_gap);
+        }
+        for (c, code) in word.split_whitespace().enumerate() {
+            if c > 0 {
+                silence(&mut samples, char_gap);
+            }
+            for (e, element) in code.chars().enumerate() {
+                if e > 0 {
+                    silence(&mut samples, dot);
+                }
+                tone(&mut samples, if element == '-' { 3.0 * dot } else { dot });
+            }
+        }
+    }
+    samples
+}
+
+fn write_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Encode text as morse and render it to a WAV file
+pub fn morse_to_wav(
+    text: &str,
+    alphabet: MorseAlphabet,
+    options: &MorseAudioOptions,
+) -> Result<(String, Vec<u8>), MorseAudioError> {
+    options.validate()?;
+    let morse = encode_morse_with(text, alphabet)?;
+    let samples = render_samples(&morse, options);
+    Ok((morse, write_wav(&samples, options.sample_rate)))
+}
+
+/// Same as [`morse_to_wav`], returned as a playable data URL
+pub fn morse_to_audio(
+    text: &str,
+    alphabet: MorseAlphabet,
+    options: &MorseAudioOptions,
+) -> Result<MorseAudio, MorseAudioError> {
+    let (morse, wav) = morse_to_wav(text, alphabet, options)?;
+    let duration_seconds = (wav.len() - 44) as f32 / 2.0 / options.sample_rate as f32;
+    Ok(MorseAudio {
+        morse,
+        data_url: format!(
+            "data:audio/wav;base64,{}",
+            general_purpose::STANDARD.encode(&wav)
+        ),
+        duration_seconds,
+        size: wav.len(),
+    })
+}
+
+/// Read a RIFF/WAVE file (8/16/24/32-bit PCM or 32-bit float) into mono samples
+fn read_wav(bytes: &[u8]) -> Result<(Vec<f32>, u32), MorseAudioError> {
+    let invalid = |msg: &str| MorseAudioError::InvalidWav(msg.to_string());
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF/WAVE header"));
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32::from_le_bytes([
+            bytes[pos + 4],
+            bytes[pos + 5],
+            bytes[pos + 6],
+            bytes[pos + 7],
+        ]) as usize;
+        let body = &bytes[pos + 8..(pos + 8 + len).min(bytes.len())];
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let u16_at = |i: usize| u16::from_le_bytes([body[i], body[i + 1]]);
+                let rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                format = Some((u16_at(0), u16_at(2), rate, u16_at(14)));
+            }
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        pos += 8 + len + len % 2;
+    }
+
+    let (tag, channels, rate, bits) = format.ok_or_else(|| invalid("missing fmt chunk"))?;
+    let data = data.ok_or_else(|| invalid("missing data chunk"))?;
+    if channels == 0 || rate == 0 {
+        return Err(invalid("bad channel count or sample rate"));
+    }
+    // 0xFFFE is WAVE_FORMAT_EXTENSIBLE; assume its subformat matches the bit depth
+    let float = match (tag, bits) {
+        (3, 32) => true,
+        (1 | 0xFFFE, 8 | 16 | 24 | 32) => false,
+        (0xFFFE, _) | (1, _) => return Err(invalid("unsupported bit depth")),
+        _ => return Err(invalid("only PCM and float WAV files are supported")),
+    };
+
+    let width = bits as usize / 8;
+    let frame = width * channels as usize;
+    let samples = data
+        .chunks_exact(frame)
+        .map(|f| {
+            f.chunks_exact(width)
+                .map(|s| match (float, width) {
+                    (true, _) => f32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+                    (_, 1) => (s[0] as f32 - 128.0) / 128.0,
+                    (_, 2) => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+                    (_, 3) => i32::from_le_bytes([0, s[0], s[1], s[2]]) as f32 / 2_147_483_648.0,
+                    _ => i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2_147_483_648.0,
+                })
+                .sum::<f32>()
+                / channels as f32
+        })
+        .collect();
+    Ok((samples, rate))
+}
+
+/// Two-cluster 1-D k-means; returns (low, high) centres
+fn two_means(values: &[f32]) -> (f32, f32) {
+    let mut low = values.iter().copied().fold(f32::MAX, f32::min);
+    let mut high = values.iter().copied().fold(f32::MIN, f32::max);
+    for _ in 0..16 {
+        let split = (low + high) / 2.0;
+        let (lo, hi): (Vec<f32>, Vec<f32>) = values.iter().partition(|&&v| v < split);
+        if lo.is_empty() || hi.is_empty() {
+            break;
+        }
+        low = lo.iter().sum::<f32>() / lo.len() as f32;
+        high = hi.iter().sum::<f32>() / hi.len() as f32;
+    }
+    (low, high)
+}
+
+/// Decode morse from a WAV recording using envelope detection
+pub fn decode_morse_wav(
+    bytes: &[u8],
+    alphabet: MorseAlphabet,
+) -> Result<MorseAudioDecoded, MorseAudioError> {
+    let (samples, rate) = read_wav(bytes)?;
+    let window = ((WINDOW_SECONDS * rate as f32) as usize).max(1);
+    let envelope: Vec<f32> = samples
+        .chunks(window)
+        .map(|w| (w.iter().map(|s| s * s).sum::<f32>() / w.len() as f32).sqrt())
+        .collect();
+    let peak = envelope.iter().copied().fold(0.0, f32::max);
+    if peak < 0.01 {
+        return Err(MorseAudioError::NoSignal);
+    }
+    let threshold = peak * 0.4;
+
+    // Run lengths of key-down/key-up, in windows
+    let mut runs: Vec<(bool, usize)> = Vec::new();
+    for on in envelope.iter().map(|&e| e > threshold) {
+        match runs.last_mut() {
+            Some((state, len)) if *state == on => *len += 1,
+            _ => runs.push((on, 1)),
+        }
+    }
+    // Trim leading/trailing silence and merge glitches shorter than the ramp
+    while runs.first().is_some_and(|r| !r.0) {
+        runs.remove(0);
+    }
+    while runs.last().is_some_and(|r| !r.0) {
+        runs.pop();
+    }
+    let min_run = ((RAMP_SECONDS / WINDOW_SECONDS) as usize).max(1);
+    let mut merged: Vec<(bool, usize)> = Vec::new();
+    for (on, len) in runs {
+        match merged.last_mut() {
+            Some(last) if last.0 == on => last.1 += len,
+            Some(last) if len < min_run => last.1 += len,
+            _ => merged.push((on, len)),
+        }
+    }
+    let marks: Vec<f32> = merged.iter().filter(|r| r.0).map(|r| r.1 as f32).collect();
+    if marks.is_empty() {
+        return Err(MorseAudioError::NoSignal);
+    }
+
+    let (short, long) = two_means(&marks);
+    // A single mark length is ambiguous; treat it as dots unless gaps say otherwise
+    let unit = if long >= short * 2.0 {
+        short.min(long / 3.0)
+    } else {
+        let shortest_gap = merged
+            .iter()
+            .filter(|r| !r.0)
+            .map(|r| r.1 as f32)
+            .fold(f32::MAX, f32::min);
+        if shortest_gap < short / 2.0 {
+            short / 3.0
+        } else {
+            short
+        }
+    };
+
+    let gaps: Vec<f32> = merged
+        .iter()
+        .filter(|r| !r.0 && r.1 as f32 >= unit * 2.0)
+        .map(|r| r.1 as f32)
+        .collect();
+    let word_threshold = if gaps.is_empty() {
+        f32::MAX
+    } else {
+        let (lo, hi) = two_means(&gaps);
+        if hi >= lo * 1.8 {
+            (lo + hi) / 2.0
+        } else if lo >= unit * 5.5 {
+            0.0
+        } else {
+            f32::MAX
+        }
+    };
+
+    let mut morse = String::new();
+    for (on, len) in merged {
+        let len = len as f32;
+        if on {
+            morse.push(if len >= unit * 2.0 { '-' } else { '.' });
+        } else if len >= word_threshold.max(unit * 2.0) {
+            morse.push_str(" / ");
+        } else if len >= unit * 2.0 {
+            morse.push(' ');
+        }
+    }
+
+    // Decode letter by letter so a single bad sequence does not lose the rest
+    let text = morse
+        .split(" / ")
+        .map(|word| {
+            word.split(' ')
+                .map(|code| decode_morse_with(code, alphabet).unwrap_or_else(|_| "?".to_string()))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Zero crossings while key-down give the tone frequency
+    let keyed_pairs: Vec<&[f32]> = samples
+        .windows(2)
+        .enumerate()
+        .filter(|(i, _)| envelope[i / window] > threshold)
+        .map(|(_, pair)| pair)
+        .collect();
+    let crossings = keyed_pairs
+        .iter()
+        .filter(|p| (p[0] < 0.0) != (p[1] < 0.0))
+        .count();
+    let keyed = keyed_pairs.len();
+
+    Ok(MorseAudioDecoded {
+        morse,
+        text,
+        estimated_wpm: 1.2 / (unit * window as f32 / rate as f32),
+        estimated_frequency: crossings as f32 / 2.0 / (keyed as f32 / rate as f32),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_header() {
+        let (morse, wav) =
+            morse_to_wav("E", MorseAlphabet::Latin, &MorseAudioOptions::default()).unwrap();
+        assert_eq!(morse, ".");
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 8000);
+        // One dot at 20 wpm is 60 ms
+        assert_eq!(wav.len(), 44 + 480 * 2);
+    }
+
+    #[test]
+    fn test_farnsworth_timing() {
+        let standard = MorseAudioOptions::default();
+        let farnsworth = MorseAudioOptions {
+            farnsworth_wpm: Some(10.0),
+            ..Default::default()
+        };
+        let (dot, char_gap, word_gap) = standard.timing();
+        assert!((char_gap - 3.0 * dot).abs() < 1e-6);
+        let (f_dot, f_char, f_word) = farnsworth.timing();
+        assert_eq!(dot, f_dot);
+        assert!(f_char > char_gap && f_word > word_gap);
+
+        let short = morse_to_audio("PARIS PARIS", MorseAlphabet::Latin, &standard).unwrap();
+        let long = morse_to_audio("PARIS PARIS", MorseAlphabet::Latin, &farnsworth).unwrap();
+        assert!(long.duration_seconds > short.duration_seconds * 1.5);
+        assert!(short.data_url.starts_with("data:audio/wav;base64,UklGR"));
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        for options in [
+            MorseAudioOptions::default(),
+            MorseAudioOptions {
+                wpm: 25.0,
+                farnsworth_wpm: Some(12.0),
+                frequency: 800.0,
+                sample_rate: 44100,
+                volume: 0.5,
+            },
+        ] {
+            let (morse, wav) = morse_to_wav("SOS HELP 73", MorseAlphabet::Latin, &options).unwrap();
+            let decoded = decode_morse_wav(&wav, MorseAlphabet::Latin).unwrap();
+            assert_eq!(decoded.morse, morse);
+            assert_eq!(decoded.text, "SOS HELP 73");
+            assert!((decoded.estimated_wpm - options.wpm).abs() < options.wpm * 0.15);
+            assert!((decoded.estimated_frequency - options.frequency).abs() < 20.0);
+        }
+    }
+
+    #[test]
+    fn test_decode_uniform_marks() {
+        let options = MorseAudioOptions::default();
+        let (_, dashes) = morse_to_wav("MOM", MorseAlphabet::Latin, &options).unwrap();
+        assert_eq!(
+            decode_morse_wav(&dashes, MorseAlphabet::Latin)
+                .unwrap()
+                .text,
+            "MOM"
+        );
+        let (_, dots) = morse_to_wav("EEE", MorseAlphabet::Latin, &options).unwrap();
+        assert_eq!(
+            decode_morse_wav(&dots, MorseAlphabet::Latin).unwrap().text,
+            "EEE"
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let bad_speed = MorseAudioOptions {
+            wpm: 100.0,
+            ..Default::default()
+        };
+        assert!(morse_to_wav("E", MorseAlphabet::Latin, &bad_speed).is_err());
+        assert!(decode_morse_wav(b"not a wav", MorseAlphabet::Latin).is_err());
+        let silent = write_wav(&[0; 8000], 8000);
+        assert!(matches!(
+            decode_morse_wav(&silent, MorseAlphabet::Latin),
+            Err(MorseAudioError::NoSignal)
+        ));
+    }
+}
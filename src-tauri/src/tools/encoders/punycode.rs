@@ -1,5 +1,10 @@
-use idna::{domain_to_ascii, domain_to_unicode};
+use idna::{domain_to_ascii, domain_to_unicode, Config};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_script::{Script, UnicodeScript};
+use unicode_security::confusable_detection::skeleton;
+use unicode_security::MixedScript;
 
 #[derive(Error, Debug)]
 pub enum PunycodeError {
@@ -7,81 +12,407 @@ pub enum PunycodeError {
     InvalidDomain(String),
 }
 
-/// Encode internationalized domain to ASCII (Punycode)
-/// 
-/// # Examples
-/// ```
-/// use ferrisbox_lib::tools::encoders::punycode::encode_punycode;
-/// assert_eq!(encode_punycode("münchen.de").unwrap(), "xn--mnchen-3ya.de");
-/// ```
-pub fn encode_punycode(domain: &str) -> Result<String, PunycodeError> {
-    domain_to_ascii(domain)
-        .map_err(|e| PunycodeError::InvalidDomain(e.to_string()))
+/// Conversion direction for `convert_idna`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdnaDirection {
+    ToAscii,
+    ToUnicode,
 }
 
-/// Decode Punycode to Unicode domain
-/// 
-/// # Examples
-/// ```
-/// use ferrisbox_lib::tools::encoders::punycode::decode_punycode;
-/// assert_eq!(decode_punycode("xn--mnchen-3ya.de").unwrap(), "münchen.de");
-/// ```
-pub fn decode_punycode(encoded: &str) -> Result<String, PunycodeError> {
-    let (result, _) = domain_to_unicode(encoded);
-    Ok(result)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdnaInputKind {
+    Domain,
+    Url,
+    Email,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdnaLabel {
+    pub unicode: String,
+    pub ascii: String,
+    pub scripts: Vec<&'static str>,
+    pub mixed_script: bool,
+    /// ASCII label this one imitates, for whole-script confusables like "аррӏе"
+    pub confusable_with: Option<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdnaResult {
+    pub kind: IdnaInputKind,
+    /// The input with only its host or domain converted
+    pub output: String,
+    pub host_ascii: String,
+    pub host_unicode: String,
+    pub labels: Vec<IdnaLabel>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub valid: bool,
+}
+
+const MAX_LABEL_LENGTH: usize = 63;
+const MAX_DOMAIN_LENGTH: usize = 253;
+
+/// UTS-46 processing with the STD3 and DNS length rules used for host names
+fn strict_config() -> Config {
+    Config::default()
+        .use_std3_ascii_rules(true)
+        .check_hyphens(true)
+        .verify_dns_length(true)
+}
+
+/// Explain why `label` fails strict UTS-46 processing. idna's error flags are
+/// private, so each rule is isolated by rerunning with one check relaxed.
+fn describe_uts46_errors(label: &str, has_disallowed: bool) -> Vec<String> {
+    let passes_without = |relax: fn(Config) -> Config| relax(strict_config()).to_ascii(label).is_ok();
+    let mut errors = Vec::new();
+    if passes_without(|config| config.check_hyphens(false)) {
+        errors.push("hyphen at the start or end".to_string());
+    }
+    if passes_without(|config| config.use_std3_ascii_rules(false)) {
+        errors.push("contains ASCII characters not allowed in host names".to_string());
+    }
+    if passes_without(|config| config.verify_dns_length(false)) {
+        errors.push("is empty or exceeds DNS length limits".to_string());
+    }
+    if !errors.is_empty() || has_disallowed {
+        return errors;
+    }
+
+    let first = label.chars().next();
+    let message = if label.get(..4).is_some_and(|p| p.eq_ignore_ascii_case("xn--")) {
+        "invalid punycode"
+    } else if first.is_some_and(|c| {
+        matches!(
+            get_general_category(c),
+            GeneralCategory::NonspacingMark
+                | GeneralCategory::SpacingMark
+                | GeneralCategory::EnclosingMark
+        )
+    }) {
+        "starts with a combining mark"
+    } else if label.chars().any(is_right_to_left) {
+        "violates the bidi rule for right-to-left labels (RFC 5893)"
+    } else {
+        "is not a valid label under UTS-46"
+    };
+    vec![message.to_string()]
+}
+
+fn is_right_to_left(c: char) -> bool {
+    matches!(
+        c.script(),
+        Script::Hebrew
+            | Script::Arabic
+            | Script::Syriac
+            | Script::Thaana
+            | Script::Nko
+            | Script::Samaritan
+            | Script::Mandaic
+            | Script::Adlam
+    )
+}
+
+/// Code points that fail on their own and after a base letter, which rules out
+/// failures that only come from position (combining marks) or bidi context
+fn disallowed_code_points(label: &str) -> Vec<String> {
+    label
+        .chars()
+        .filter(|c| !c.is_ascii_alphanumeric() && *c != '-')
+        .filter(|c| {
+            strict_config().to_ascii(&c.to_string()).is_err()
+                && strict_config().to_ascii(&format!("a{}", c)).is_err()
+        })
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect()
+}
+
+fn inspect_label(unicode: &str, ascii: &str) -> IdnaLabel {
+    let disallowed = disallowed_code_points(unicode);
+    let mut errors = match strict_config().to_ascii(unicode) {
+        Ok(_) => Vec::new(),
+        Err(_) => describe_uts46_errors(unicode, !disallowed.is_empty()),
+    };
+    if !disallowed.is_empty() {
+        errors.push(format!("disallowed code points: {}", disallowed.join(", ")));
+    }
+    if ascii.len() > MAX_LABEL_LENGTH {
+        errors.push(format!(
+            "label is {} octets long, the limit is {}",
+            ascii.len(),
+            MAX_LABEL_LENGTH
+        ));
+    }
+
+    let mut scripts: Vec<&'static str> = unicode
+        .chars()
+        .map(|c| c.script())
+        .filter(|s| !matches!(s, Script::Common | Script::Inherited | Script::Unknown))
+        .map(Script::full_name)
+        .collect();
+    scripts.sort_unstable();
+    scripts.dedup();
+
+    // Map only the non-ASCII characters: skeletons also fold ASCII ("m" -> "rn")
+    let confusable_with = if unicode.is_ascii() {
+        None
+    } else {
+        unicode
+            .chars()
+            .map(|c| {
+                if c.is_ascii() {
+                    return Some(c.to_string());
+                }
+                let prototype: String = skeleton(&c.to_string()).collect();
+                (prototype.is_ascii() && !prototype.is_empty()).then_some(prototype)
+            })
+            .collect::<Option<String>>()
+            .map(|s| s.to_lowercase())
+    };
+
+    IdnaLabel {
+        unicode: unicode.to_string(),
+        ascii: ascii.to_string(),
+        scripts,
+        mixed_script: !unicode.is_single_script(),
+        confusable_with,
+        errors,
+    }
+}
+
+/// Split input into prefix, host and suffix so only the host gets converted
+fn split_host(input: &str) -> (IdnaInputKind, &str, &str, &str) {
+    if let Some(scheme_end) = input.find("://") {
+        let start = scheme_end + 3;
+        let end = input[start..]
+            .find(['/', '?', '#'])
+            .map_or(input.len(), |p| start + p);
+        let authority = &input[start..end];
+        let host_start = authority.rfind('@').map_or(0, |at| at + 1);
+        let host_port = &authority[host_start..];
+        let host_len = if host_port.starts_with('[') {
+            host_port.len()
+        } else {
+            host_port.rfind(':').unwrap_or(host_port.len())
+        };
+        let host_start = start + host_start;
+        return (
+            IdnaInputKind::Url,
+            &input[..host_start],
+            &input[host_start..host_start + host_len],
+            &input[host_start + host_len..],
+        );
+    }
+    if let Some(at) = input.rfind('@') {
+        return (IdnaInputKind::Email, &input[..at + 1], &input[at + 1..], "");
+    }
+    let end = input.find(['/', '?', '#', ':']).unwrap_or(input.len());
+    (IdnaInputKind::Domain, "", &input[..end], &input[end..])
+}
+
+/// Convert the host of a domain, URL or email address with UTS-46 and report problems
+pub fn convert_idna(input: &str, direction: IdnaDirection) -> Result<IdnaResult, PunycodeError> {
+    let input = input.trim();
+    let (kind, prefix, host, suffix) = split_host(input);
+    if host.is_empty() {
+        return Err(PunycodeError::InvalidDomain(format!(
+            "no host name found in '{}'",
+            input
+        )));
+    }
+
+    // Lenient conversion for output, strict per-label checks for the report
+    let (host_unicode, _) = domain_to_unicode(host);
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let host_ascii = domain_to_ascii(host).unwrap_or_else(|e| {
+        errors.push(format!("UTS-46 processing failed: {}", e));
+        host.to_string()
+    });
+    let labels: Vec<IdnaLabel> = host_unicode
+        .trim_end_matches('.')
+        .split('.')
+        .zip(host_ascii.trim_end_matches('.').split('.'))
+        .map(|(unicode, ascii)| inspect_label(unicode, ascii))
+        .collect();
+
+    if host_ascii.trim_end_matches('.').len() > MAX_DOMAIN_LENGTH {
+        errors.push(format!(
+            "domain is {} octets long, the limit is {}",
+            host_ascii.len(),
+            MAX_DOMAIN_LENGTH
+        ));
+    }
+    for label in &labels {
+        errors.extend(
+            label
+                .errors
+                .iter()
+                .map(|e| format!("{}: {}", label.unicode, e)),
+        );
+        if label.mixed_script {
+            warnings.push(format!(
+                "{}: mixes scripts ({}), possible spoofing",
+                label.unicode,
+                label.scripts.join(", ")
+            ));
+        }
+        if let Some(target) = &label.confusable_with {
+            if *target != label.unicode.to_lowercase() {
+                warnings.push(format!("{}: looks like '{}'", label.unicode, target));
+            }
+        }
+    }
+    if kind == IdnaInputKind::Email && !prefix.is_ascii() {
+        warnings.push("The local part is not ASCII and needs SMTPUTF8 support".to_string());
+    }
+
+    let converted_host = match direction {
+        IdnaDirection::ToAscii => host_ascii.clone(),
+        IdnaDirection::ToUnicode => host_unicode.clone(),
+    };
+
+    Ok(IdnaResult {
+        kind,
+        output: format!("{}{}{}", prefix, converted_host, suffix),
+        host_ascii,
+        host_unicode,
+        labels,
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    fn to_ascii(domain: &str) -> String {
+        convert_idna(domain, IdnaDirection::ToAscii).unwrap().output
+    }
+
+    fn to_unicode(domain: &str) -> String {
+        convert_idna(domain, IdnaDirection::ToUnicode).unwrap().output
+    }
+    
     #[test]
     fn test_encode_german() {
-        assert_eq!(encode_punycode("münchen.de").unwrap(), "xn--mnchen-3ya.de");
+        assert_eq!(to_ascii("münchen.de"), "xn--mnchen-3ya.de");
     }
     
     #[test]
     fn test_encode_spanish() {
-        let result = encode_punycode("español.com").unwrap();
-        assert!(result.starts_with("xn--"));
+        assert!(to_ascii("español.com").starts_with("xn--"));
     }
     
     #[test]
     fn test_encode_chinese() {
-        let result = encode_punycode("中国.cn");
-        assert!(result.is_ok());
-        assert!(result.unwrap().starts_with("xn--"));
+        assert!(to_ascii("中国.cn").starts_with("xn--"));
     }
     
     #[test]
     fn test_encode_arabic() {
-        let result = encode_punycode("مصر.eg");
-        assert!(result.is_ok());
+        let result = convert_idna("مصر.eg", IdnaDirection::ToAscii).unwrap();
+        assert!(result.valid);
+        assert!(result.output.starts_with("xn--"));
     }
     
     #[test]
     fn test_decode() {
-        assert_eq!(decode_punycode("xn--mnchen-3ya.de").unwrap(), "münchen.de");
+        assert_eq!(to_unicode("xn--mnchen-3ya.de"), "münchen.de");
     }
     
     #[test]
     fn test_ascii_passthrough() {
         // ASCII domains should pass through unchanged
-        assert_eq!(encode_punycode("example.com").unwrap(), "example.com");
+        assert_eq!(to_ascii("example.com"), "example.com");
     }
     
     #[test]
     fn test_subdomain() {
-        let result = encode_punycode("sub.münchen.de");
-        assert!(result.is_ok());
-        assert!(result.unwrap().contains("xn--"));
+        assert_eq!(to_ascii("sub.münchen.de"), "sub.xn--mnchen-3ya.de");
     }
     
     #[test]
     fn test_roundtrip() {
         let original = "münchen.de";
-        let encoded = encode_punycode(original).unwrap();
-        let decoded = decode_punycode(&encoded).unwrap();
-        assert_eq!(original, decoded);
+        assert_eq!(to_unicode(&to_ascii(original)), original);
+    }
+
+    #[test]
+    fn test_convert_url_and_email() {
+        let url = convert_idna(
+            "https://user@bücher.de:8443/straße?q=ü#ä",
+            IdnaDirection::ToAscii,
+        )
+        .unwrap();
+        assert_eq!(url.kind, IdnaInputKind::Url);
+        assert_eq!(
+            url.output,
+            "https://user@xn--bcher-kva.de:8443/straße?q=ü#ä"
+        );
+        assert!(url.valid);
+
+        let email = convert_idna("jörg@münchen.de", IdnaDirection::ToAscii).unwrap();
+        assert_eq!(email.kind, IdnaInputKind::Email);
+        assert_eq!(email.output, "jörg@xn--mnchen-3ya.de");
+        assert!(email.warnings.iter().any(|w| w.contains("SMTPUTF8")));
+
+        let back = convert_idna("mailto:info@xn--mnchen-3ya.de", IdnaDirection::ToUnicode).unwrap();
+        assert_eq!(back.output, "mailto:info@münchen.de");
+    }
+
+    #[test]
+    fn test_uts46_errors() {
+        let long_label = format!("{}.com", "a".repeat(64));
+        let result = convert_idna(&long_label, IdnaDirection::ToAscii).unwrap();
+        assert!(!result.valid);
+        assert!(result.errors.iter().any(|e| e.contains("64 octets")));
+
+        let hyphen = convert_idna("-bad-.com", IdnaDirection::ToAscii).unwrap();
+        assert!(hyphen.errors.iter().any(|e| e.contains("hyphen")));
+
+        let disallowed = convert_idna("exa mple.com", IdnaDirection::ToAscii).unwrap();
+        assert!(disallowed.errors.iter().any(|e| e.contains("U+0020")));
+
+        // Right-to-left label mixed with a left-to-right digit prefix breaks the bidi rule
+        let bidi = convert_idna("1\u{05D0}.com", IdnaDirection::ToAscii).unwrap();
+        assert!(bidi.errors.iter().any(|e| e.contains("bidi")));
+
+        let mark = convert_idna("\u{0301}abc.com", IdnaDirection::ToAscii).unwrap();
+        assert!(mark.errors.iter().any(|e| e.contains("combining mark")));
+        assert!(!mark.errors.iter().any(|e| e.contains("U+0301")));
+    }
+
+    #[test]
+    fn test_spoofing_detection() {
+        // Latin "p" with Cyrillic "аypal"
+        let mixed = convert_idna("p\u{0430}ypal.com", IdnaDirection::ToAscii).unwrap();
+        assert!(mixed.labels[0].mixed_script);
+        assert!(mixed.warnings.iter().any(|w| w.contains("mixes scripts")));
+
+        // All-Cyrillic "сосоа" is single-script but imitates "cocoa"
+        let whole = convert_idna(
+            "\u{0441}\u{043E}\u{0441}\u{043E}\u{0430}.com",
+            IdnaDirection::ToAscii,
+        )
+        .unwrap();
+        assert!(!whole.labels[0].mixed_script);
+        assert_eq!(whole.labels[0].confusable_with.as_deref(), Some("cocoa"));
+        assert!(whole
+            .warnings
+            .iter()
+            .any(|w| w.contains("looks like 'cocoa'")));
+        assert!(whole.host_ascii.starts_with("xn--"));
+
+        assert!(convert_idna("münchen.de", IdnaDirection::ToAscii)
+            .unwrap()
+            .warnings
+            .is_empty());
     }
 }
@@ -0,0 +1,445 @@
+use encoding_rs::{DecoderResult, EncoderResult, Encoding};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::ByteEncoding;
+
+#[derive(Error, Debug)]
+pub enum TextEncodingError {
+    #[error("Failed to read file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Invalid {encoding} sequence {bytes} at byte {offset}")]
+    InvalidSequence {
+        encoding: String,
+        offset: usize,
+        bytes: String,
+    },
+    #[error("Character '{character}' at position {position} cannot be encoded as {encoding}")]
+    Unmappable {
+        encoding: String,
+        character: char,
+        position: usize,
+    },
+}
+
+/// Character encoding used to turn text into bytes and back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+    Windows1252,
+    ShiftJis,
+}
+
+impl TextEncoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Latin1 => "ISO-8859-1",
+            TextEncoding::Windows1252 => "Windows-1252",
+            TextEncoding::ShiftJis => "Shift_JIS",
+        }
+    }
+
+    fn bom(self) -> &'static [u8] {
+        match self {
+            TextEncoding::Utf8 => &[0xEF, 0xBB, 0xBF],
+            TextEncoding::Utf16Le => &[0xFF, 0xFE],
+            TextEncoding::Utf16Be => &[0xFE, 0xFF],
+            _ => &[],
+        }
+    }
+
+    /// Latin-1 is handled by hand: encoding_rs follows WHATWG and maps it to Windows-1252
+    fn codec(self) -> Option<&'static Encoding> {
+        match self {
+            TextEncoding::Utf8 => Some(encoding_rs::UTF_8),
+            TextEncoding::Utf16Le => Some(encoding_rs::UTF_16LE),
+            TextEncoding::Utf16Be => Some(encoding_rs::UTF_16BE),
+            TextEncoding::Latin1 => None,
+            TextEncoding::Windows1252 => Some(encoding_rs::WINDOWS_1252),
+            TextEncoding::ShiftJis => Some(encoding_rs::SHIFT_JIS),
+        }
+    }
+}
+
+/// How invalid or unmappable input is handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecodeMode {
+    /// Fail on the first problem
+    #[default]
+    Strict,
+    /// Substitute U+FFFD when decoding and `?` when encoding, and report each one
+    Lossy,
+}
+
+/// Where the bytes for an encoder come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputSource {
+    /// Text converted to bytes with the selected encoding
+    #[default]
+    Text,
+    /// Path of a file read as-is
+    File,
+    Hex,
+    Base64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextCodecOptions {
+    pub source: InputSource,
+    /// Text encoding; UTF-8 when encoding, auto-detected when decoding
+    pub encoding: Option<TextEncoding>,
+    /// Prepend a byte order mark when encoding UTF-8/UTF-16 text
+    pub bom: bool,
+    pub mode: DecodeMode,
+    /// Emit decoded bytes as hex/base64 instead of text
+    pub output: Option<ByteEncoding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidSequence {
+    pub offset: usize,
+    pub bytes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappableChar {
+    pub position: usize,
+    pub character: char,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: TextEncoding,
+    pub bom: bool,
+    pub byte_length: usize,
+    pub invalid_sequences: Vec<InvalidSequence>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EncodedText {
+    pub bytes: Vec<u8>,
+    pub unmappable: Vec<UnmappableChar>,
+}
+
+/// Encode text into bytes, optionally prefixed with a BOM
+pub fn encode_text(
+    text: &str,
+    encoding: TextEncoding,
+    bom: bool,
+    mode: DecodeMode,
+) -> Result<EncodedText, TextEncodingError> {
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    if bom {
+        bytes.extend_from_slice(encoding.bom());
+    }
+    let mut unmappable = Vec::new();
+
+    match encoding {
+        TextEncoding::Utf8 => bytes.extend_from_slice(text.as_bytes()),
+        TextEncoding::Utf16Le => text
+            .encode_utf16()
+            .for_each(|unit| bytes.extend_from_slice(&unit.to_le_bytes())),
+        TextEncoding::Utf16Be => text
+            .encode_utf16()
+            .for_each(|unit| bytes.extend_from_slice(&unit.to_be_bytes())),
+        TextEncoding::Latin1 => {
+            for (position, c) in text.chars().enumerate() {
+                match u8::try_from(c as u32) {
+                    Ok(b) => bytes.push(b),
+                    Err(_) => {
+                        unmappable.push(UnmappableChar {
+                            position,
+                            character: c,
+                        });
+                        bytes.push(b'?');
+                    }
+                }
+            }
+        }
+        TextEncoding::Windows1252 | TextEncoding::ShiftJis => {
+            let codec = encoding.codec().unwrap_or(encoding_rs::UTF_8);
+            let mut encoder = codec.new_encoder();
+            let mut remaining = text;
+            let mut consumed = 0;
+            loop {
+                let needed = encoder
+                    .max_buffer_length_from_utf8_without_replacement(remaining.len())
+                    .unwrap_or(remaining.len() * 2);
+                bytes.reserve(needed);
+                let (result, read) = encoder
+                    .encode_from_utf8_to_vec_without_replacement(remaining, &mut bytes, true);
+                remaining = &remaining[read..];
+                consumed += read;
+                match result {
+                    EncoderResult::InputEmpty => break,
+                    EncoderResult::OutputFull => continue,
+                    EncoderResult::Unmappable(c) => {
+                        let position = text[..consumed].chars().count() - 1;
+                        unmappable.push(UnmappableChar {
+                            position,
+                            character: c,
+                        });
+                        bytes.push(b'?');
+                    }
+                }
+            }
+        }
+    }
+
+    if mode == DecodeMode::Strict {
+        if let Some(first) = unmappable.first() {
+            return Err(TextEncodingError::Unmappable {
+                encoding: encoding.name().to_string(),
+                character: first.character,
+                position: first.position,
+            });
+        }
+    }
+    Ok(EncodedText { bytes, unmappable })
+}
+
+/// Guess the encoding from a BOM, falling back to UTF-8 or Windows-1252
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    [
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+    ]
+    .into_iter()
+    .find(|e| bytes.starts_with(e.bom()))
+    .unwrap_or_else(|| {
+        if std::str::from_utf8(bytes).is_ok() {
+            TextEncoding::Utf8
+        } else {
+            TextEncoding::Windows1252
+        }
+    })
+}
+
+/// Decode bytes into text, reporting every invalid sequence
+///
+/// With no encoding given, the encoding is detected with `detect_encoding`.
+/// A matching BOM is stripped and flagged in the result.
+pub fn decode_bytes(
+    bytes: &[u8],
+    encoding: Option<TextEncoding>,
+    mode: DecodeMode,
+) -> Result<DecodedText, TextEncodingError> {
+    let encoding = encoding.unwrap_or_else(|| detect_encoding(bytes));
+    let bom = encoding.bom();
+    let has_bom = !bom.is_empty() && bytes.starts_with(bom);
+    let body = if has_bom { &bytes[bom.len()..] } else { bytes };
+
+    let mut invalid_sequences = Vec::new();
+    let text = match encoding.codec() {
+        None => body.iter().map(|&b| b as char).collect(),
+        Some(codec) => {
+            let mut decoder = codec.new_decoder_without_bom_handling();
+            let mut text = String::with_capacity(body.len());
+            let mut position = 0;
+            loop {
+                let needed = decoder
+                    .max_utf8_buffer_length_without_replacement(body.len() - position)
+                    .unwrap_or(body.len() * 3);
+                text.reserve(needed);
+                let (result, read) = decoder.decode_to_string_without_replacement(
+                    &body[position..],
+                    &mut text,
+                    true,
+                );
+                position += read;
+                match result {
+                    DecoderResult::InputEmpty => break,
+                    DecoderResult::OutputFull => continue,
+                    DecoderResult::Malformed(bad, extra) => {
+                        let start = position - extra as usize - bad as usize;
+                        let offset = start + if has_bom { bom.len() } else { 0 };
+                        let sequence = hex::encode(&body[start..start + bad as usize]);
+                        if mode == DecodeMode::Strict {
+                            return Err(TextEncodingError::InvalidSequence {
+                                encoding: encoding.name().to_string(),
+                                offset,
+                                bytes: sequence,
+                            });
+                        }
+                        invalid_sequences.push(InvalidSequence {
+                            offset,
+                            bytes: sequence,
+                        });
+                        text.push(char::REPLACEMENT_CHARACTER);
+                    }
+                }
+            }
+            text
+        }
+    };
+
+    Ok(DecodedText {
+        text,
+        encoding,
+        bom: has_bom,
+        byte_length: bytes.len(),
+        invalid_sequences,
+    })
+}
+
+/// Resolve encoder input into raw bytes according to `options.source`
+pub fn read_input_bytes(
+    input: &str,
+    options: &TextCodecOptions,
+) -> Result<Vec<u8>, TextEncodingError> {
+    match options.source {
+        InputSource::Text => {
+            let encoding = options.encoding.unwrap_or(TextEncoding::Utf8);
+            Ok(encode_text(input, encoding, options.bom, options.mode)?.bytes)
+        }
+        InputSource::File => Ok(std::fs::read(input.trim())?),
+        InputSource::Hex => ByteEncoding::Hex
+            .decode(input)
+            .map_err(TextEncodingError::InvalidInput),
+        InputSource::Base64 => ByteEncoding::Base64
+            .decode(input)
+            .map_err(TextEncodingError::InvalidInput),
+    }
+}
+
+/// Render decoder output as text, or as hex/base64 when `options.output` is set
+pub fn render_output_bytes(
+    bytes: &[u8],
+    options: &TextCodecOptions,
+) -> Result<String, TextEncodingError> {
+    match options.output {
+        Some(output) => Ok(output.encode(bytes)),
+        None => Ok(decode_bytes(bytes, options.encoding, options.mode)?.text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_utf16_with_bom() {
+        let le = encode_text("Hi€", TextEncoding::Utf16Le, true, DecodeMode::Strict).unwrap();
+        assert_eq!(
+            le.bytes,
+            vec![0xFF, 0xFE, 0x48, 0x00, 0x69, 0x00, 0xAC, 0x20]
+        );
+
+        let be = encode_text("Hi", TextEncoding::Utf16Be, false, DecodeMode::Strict).unwrap();
+        assert_eq!(be.bytes, vec![0x00, 0x48, 0x00, 0x69]);
+
+        let decoded = decode_bytes(&le.bytes, None, DecodeMode::Strict).unwrap();
+        assert_eq!(decoded.encoding, TextEncoding::Utf16Le);
+        assert!(decoded.bom);
+        assert_eq!(decoded.text, "Hi€");
+    }
+
+    #[test]
+    fn test_latin1_and_windows1252_differ() {
+        // 0x80 is € in Windows-1252 but a C1 control in Latin-1
+        let w = decode_bytes(
+            &[0x80, 0xE9],
+            Some(TextEncoding::Windows1252),
+            DecodeMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(w.text, "€é");
+        let l = decode_bytes(
+            &[0x80, 0xE9],
+            Some(TextEncoding::Latin1),
+            DecodeMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(l.text, "\u{80}é");
+
+        let encoded = encode_text("café", TextEncoding::Latin1, false, DecodeMode::Strict).unwrap();
+        assert_eq!(encoded.bytes, b"caf\xe9");
+    }
+
+    #[test]
+    fn test_shift_jis_round_trip() {
+        let encoded =
+            encode_text("日本語", TextEncoding::ShiftJis, false, DecodeMode::Strict).unwrap();
+        assert_eq!(hex::encode(&encoded.bytes), "93fa967b8cea");
+        let decoded = decode_bytes(
+            &encoded.bytes,
+            Some(TextEncoding::ShiftJis),
+            DecodeMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(decoded.text, "日本語");
+    }
+
+    #[test]
+    fn test_strict_and_lossy_decoding() {
+        let bytes = b"ok\xff\xfeok\xc3";
+        let err = decode_bytes(bytes, Some(TextEncoding::Utf8), DecodeMode::Strict).unwrap_err();
+        assert!(err.to_string().contains("at byte 2"));
+
+        let lossy = decode_bytes(bytes, Some(TextEncoding::Utf8), DecodeMode::Lossy).unwrap();
+        assert_eq!(lossy.text, "ok\u{FFFD}\u{FFFD}ok\u{FFFD}");
+        let offsets: Vec<usize> = lossy.invalid_sequences.iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, vec![2, 3, 6]);
+        assert_eq!(lossy.invalid_sequences[2].bytes, "c3");
+    }
+
+    #[test]
+    fn test_unmappable_characters() {
+        assert!(encode_text("naïve ☃", TextEncoding::Latin1, false, DecodeMode::Strict).is_err());
+
+        let lossy =
+            encode_text("a☃b日", TextEncoding::Windows1252, false, DecodeMode::Lossy).unwrap();
+        assert_eq!(lossy.bytes, b"a?b?");
+        let positions: Vec<usize> = lossy.unmappable.iter().map(|u| u.position).collect();
+        assert_eq!(positions, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_read_input_bytes_and_render() {
+        let hex = TextCodecOptions {
+            source: InputSource::Hex,
+            ..Default::default()
+        };
+        assert_eq!(read_input_bytes("ff 00", &hex).unwrap(), vec![0xFF, 0x00]);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), [0xDE, 0xAD]).unwrap();
+        let from_file = TextCodecOptions {
+            source: InputSource::File,
+            ..Default::default()
+        };
+        let path = file.path().to_string_lossy();
+        assert_eq!(
+            read_input_bytes(&path, &from_file).unwrap(),
+            vec![0xDE, 0xAD]
+        );
+
+        // Non-UTF-8 bytes fall back to Windows-1252 or can be emitted as hex
+        assert_eq!(
+            render_output_bytes(&[0x93, 0x94], &TextCodecOptions::default()).unwrap(),
+            "“”"
+        );
+        let as_hex = TextCodecOptions {
+            output: Some(ByteEncoding::Hex),
+            ..Default::default()
+        };
+        assert_eq!(render_output_bytes(&[0x93, 0x94], &as_hex).unwrap(), "9394");
+    }
+}
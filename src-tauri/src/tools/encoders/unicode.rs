@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_normalization::UnicodeNormalization;
+use unicode_script::{Script, UnicodeScript};
+use unicode_security::confusable_detection::skeleton;
+use unicode_security::MixedScript;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+/// Reasons a character deserves a second look
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CharFlag {
+    /// Renders as nothing: zero-width characters, fillers, variation selectors, tags
+    Invisible,
+    /// Whitespace other than a plain space, tab or newline
+    UnusualWhitespace,
+    /// Reorders text visually (Trojan Source)
+    BidiControl,
+    /// Looks like a different ASCII character
+    Confusable,
+    Control,
+    Combining,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePointInfo {
+    pub code_point: String,
+    pub character: String,
+    pub name: Option<String>,
+    pub category: &'static str,
+    pub category_name: String,
+    pub script: &'static str,
+    pub utf8: String,
+    pub utf16: String,
+    pub flags: Vec<CharFlag>,
+    /// ASCII look-alike for confusable characters
+    pub confusable_with: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphemeInfo {
+    pub text: String,
+    pub byte_offset: usize,
+    pub code_points: Vec<CodePointInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizationForms {
+    pub nfc: String,
+    pub nfd: String,
+    pub nfkc: String,
+    pub nfkd: String,
+    pub is_nfc: bool,
+    pub is_nfd: bool,
+    pub is_nfkc: bool,
+    pub is_nfkd: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnicodeInspection {
+    pub grapheme_count: usize,
+    pub code_point_count: usize,
+    pub utf8_length: usize,
+    pub utf16_length: usize,
+    pub graphemes: Vec<GraphemeInfo>,
+    pub scripts: Vec<&'static str>,
+    pub mixed_script: bool,
+    /// UTS #39 skeleton; two strings with the same skeleton are confusable
+    pub skeleton: String,
+    pub normalization: NormalizationForms,
+    pub warnings: Vec<String>,
+}
+
+const BIDI_CONTROLS: &[char] = &[
+    '\u{061C}', '\u{200E}', '\u{200F}', '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}',
+];
+
+fn is_invisible(c: char, category: GeneralCategory) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{034F}' | '\u{115F}' | '\u{1160}' | '\u{17B4}' | '\u{17B5}' | '\u{180E}'
+            | '\u{3164}' | '\u{FFA0}' | '\u{FE00}'..='\u{FE0F}' | '\u{E0000}'..='\u{E007F}'
+            | '\u{E0100}'..='\u{E01EF}'
+    ) || (category == GeneralCategory::Format && !BIDI_CONTROLS.contains(&c))
+}
+
+fn char_flags(c: char, category: GeneralCategory) -> (Vec<CharFlag>, Option<String>) {
+    let mut flags = Vec::new();
+    if BIDI_CONTROLS.contains(&c) {
+        flags.push(CharFlag::BidiControl);
+    }
+    if is_invisible(c, category) {
+        flags.push(CharFlag::Invisible);
+    }
+    if matches!(
+        category,
+        GeneralCategory::SpaceSeparator
+            | GeneralCategory::LineSeparator
+            | GeneralCategory::ParagraphSeparator
+    ) && c != ' '
+    {
+        flags.push(CharFlag::UnusualWhitespace);
+    }
+    if category == GeneralCategory::Control && !matches!(c, '\n' | '\r' | '\t') {
+        flags.push(CharFlag::Control);
+    }
+    if matches!(
+        category,
+        GeneralCategory::NonspacingMark
+            | GeneralCategory::SpacingMark
+            | GeneralCategory::EnclosingMark
+    ) {
+        flags.push(CharFlag::Combining);
+    }
+
+    let mut confusable_with = None;
+    if !c.is_ascii() && !c.is_whitespace() {
+        let buf = c.to_string();
+        let prototype: String = skeleton(&buf).collect();
+        if !prototype.is_empty()
+            && prototype.is_ascii()
+            && prototype.chars().all(|p| p.is_ascii_graphic())
+        {
+            flags.push(CharFlag::Confusable);
+            confusable_with = Some(prototype);
+        }
+    }
+    (flags, confusable_with)
+}
+
+/// Describe a single code point
+pub fn inspect_char(c: char) -> CodePointInfo {
+    let category = get_general_category(c);
+    let (flags, confusable_with) = char_flags(c, category);
+    let mut utf8 = [0u8; 4];
+    let mut utf16 = [0u16; 2];
+
+    CodePointInfo {
+        code_point: format!("U+{:04X}", c as u32),
+        character: c.to_string(),
+        name: unicode_names2::name(c).map(|n| n.to_string()),
+        category: category.abbreviation(),
+        category_name: format!("{:?}", category),
+        script: c.script().full_name(),
+        utf8: hex::encode_upper(c.encode_utf8(&mut utf8).as_bytes()),
+        utf16: c
+            .encode_utf16(&mut utf16)
+            .iter()
+            .map(|unit| format!("{:04X}", unit))
+            .collect::<Vec<_>>()
+            .join(" "),
+        flags,
+        confusable_with,
+    }
+}
+
+pub fn normalize_unicode(text: &str, form: NormalizationForm) -> String {
+    match form {
+        NormalizationForm::Nfc => text.nfc().collect(),
+        NormalizationForm::Nfd => text.nfd().collect(),
+        NormalizationForm::Nfkc => text.nfkc().collect(),
+        NormalizationForm::Nfkd => text.nfkd().collect(),
+    }
+}
+
+/// Break text into grapheme clusters and code points and flag spoofing risks
+pub fn inspect_unicode(text: &str) -> UnicodeInspection {
+    let graphemes: Vec<GraphemeInfo> = text
+        .grapheme_indices(true)
+        .map(|(byte_offset, g)| GraphemeInfo {
+            text: g.to_string(),
+            byte_offset,
+            code_points: g.chars().map(inspect_char).collect(),
+        })
+        .collect();
+
+    let scripts: BTreeSet<&'static str> = text
+        .chars()
+        .map(|c| c.script())
+        .filter(|s| !matches!(s, Script::Common | Script::Inherited | Script::Unknown))
+        .map(Script::full_name)
+        .collect();
+    let mixed_script = !text.is_single_script();
+
+    let flagged = |flag: CharFlag| {
+        graphemes
+            .iter()
+            .flat_map(|g| &g.code_points)
+            .filter(|cp| cp.flags.contains(&flag))
+            .map(|cp| cp.code_point.clone())
+            .collect::<BTreeSet<_>>()
+    };
+    let mut warnings = Vec::new();
+    let bidi = flagged(CharFlag::BidiControl);
+    if !bidi.is_empty() {
+        warnings.push(format!(
+            "Bidirectional control characters can reorder how text is displayed: {}",
+            bidi.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    let invisible = flagged(CharFlag::Invisible);
+    if !invisible.is_empty() {
+        warnings.push(format!(
+            "Invisible characters: {}",
+            invisible.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    let confusable = flagged(CharFlag::Confusable);
+    if !confusable.is_empty() {
+        warnings.push(format!(
+            "Characters that look like ASCII: {}",
+            confusable.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    if mixed_script {
+        warnings.push(format!(
+            "Mixes scripts ({}), a common homoglyph spoofing pattern",
+            scripts.iter().copied().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    UnicodeInspection {
+        grapheme_count: graphemes.len(),
+        code_point_count: text.chars().count(),
+        utf8_length: text.len(),
+        utf16_length: text.encode_utf16().count(),
+        graphemes,
+        scripts: scripts.into_iter().collect(),
+        mixed_script,
+        skeleton: skeleton(text).collect(),
+        normalization: NormalizationForms {
+            nfc: normalize_unicode(text, NormalizationForm::Nfc),
+            nfd: normalize_unicode(text, NormalizationForm::Nfd),
+            nfkc: normalize_unicode(text, NormalizationForm::Nfkc),
+            nfkd: normalize_unicode(text, NormalizationForm::Nfkd),
+            is_nfc: unicode_normalization::is_nfc(text),
+            is_nfd: unicode_normalization::is_nfd(text),
+            is_nfkc: unicode_normalization::is_nfkc(text),
+            is_nfkd: unicode_normalization::is_nfkd(text),
+        },
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_char() {
+        let info = inspect_char('€');
+        assert_eq!(info.code_point, "U+20AC");
+        assert_eq!(info.name.as_deref(), Some("EURO SIGN"));
+        assert_eq!(info.category, "Sc");
+        assert_eq!(info.utf8, "E282AC");
+        assert_eq!(info.utf16, "20AC");
+
+        let emoji = inspect_char('😀');
+        assert_eq!(emoji.utf16, "D83D DE00");
+        assert_eq!(emoji.utf8, "F09F9880");
+    }
+
+    #[test]
+    fn test_grapheme_clusters() {
+        // Family emoji joined with ZWJ and a flag are one grapheme each
+        let result = inspect_unicode("👨‍👩‍👧🇪🇸e\u{301}");
+        assert_eq!(result.grapheme_count, 3);
+        assert_eq!(result.code_point_count, 9);
+        assert_eq!(result.graphemes[0].code_points.len(), 5);
+        assert_eq!(result.graphemes[2].byte_offset, 26);
+        assert!(result.graphemes[2].code_points[1]
+            .flags
+            .contains(&CharFlag::Combining));
+    }
+
+    #[test]
+    fn test_homoglyph_detection() {
+        // "pаypal" with a Cyrillic а
+        let result = inspect_unicode("p\u{0430}ypal");
+        assert!(result.mixed_script);
+        assert_eq!(result.scripts, vec!["Cyrillic", "Latin"]);
+        assert_eq!(result.skeleton, "paypal");
+        let cyrillic = &result.graphemes[1].code_points[0];
+        assert!(cyrillic.flags.contains(&CharFlag::Confusable));
+        assert_eq!(cyrillic.confusable_with.as_deref(), Some("a"));
+        assert!(result.warnings.iter().any(|w| w.contains("Mixes scripts")));
+
+        assert!(!inspect_unicode("paypal").mixed_script);
+    }
+
+    #[test]
+    fn test_invisible_and_bidi_characters() {
+        let result = inspect_unicode("admin\u{200B}\u{202E}txt.exe\u{00A0}");
+        let flags: Vec<&Vec<CharFlag>> = result
+            .graphemes
+            .iter()
+            .flat_map(|g| &g.code_points)
+            .map(|cp| &cp.flags)
+            .filter(|f| !f.is_empty())
+            .collect();
+        assert_eq!(flags.len(), 3);
+        assert!(result.warnings.iter().any(|w| w.contains("U+202E")));
+        assert!(result.warnings.iter().any(|w| w.contains("U+200B")));
+    }
+
+    #[test]
+    fn test_normalization_forms() {
+        let composed = "é";
+        let decomposed = "e\u{301}";
+        assert_eq!(
+            normalize_unicode(decomposed, NormalizationForm::Nfc),
+            composed
+        );
+        assert_eq!(
+            normalize_unicode(composed, NormalizationForm::Nfd),
+            decomposed
+        );
+        assert_eq!(normalize_unicode("ﬁ²", NormalizationForm::Nfkc), "fi2");
+
+        let forms = inspect_unicode(decomposed).normalization;
+        assert!(!forms.is_nfc);
+        assert!(forms.is_nfd);
+        assert_eq!(forms.nfc, composed);
+    }
+}
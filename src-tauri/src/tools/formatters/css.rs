@@ -1,21 +1,38 @@
 use lightningcss::{
+    css_modules::{Config as CssModulesConfig, CssModuleReference, Pattern},
+    declaration::DeclarationBlock,
     printer::PrinterOptions,
+    properties::Property,
+    rules::{CssRule, CssRuleList},
     stylesheet::{MinifyOptions, ParserOptions, StyleSheet},
+    targets::{Browsers, Targets},
+    traits::ToCss,
 };
+use once_cell::sync::Lazy;
+use parcel_sourcemap::SourceMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use thiserror::Error;
 
+static VAR_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"var\(\s*(--[\w-]+)").unwrap());
+
 #[derive(Error, Debug)]
 pub enum CssError {
     #[error("CSS parse error: {0}")]
-    ParseError(String),
+    Parse(String),
     #[error("CSS print error: {0}")]
-    PrintError(String),
+    Print(String),
+    #[error("Invalid browser targets: {0}")]
+    Targets(String),
+    #[error("Invalid CSS Modules pattern: {0}")]
+    Pattern(String),
 }
 
 /// Format CSS with indentation
 pub fn format_css(css: &str, indent_size: usize) -> Result<String, CssError> {
     let stylesheet = StyleSheet::parse(css, ParserOptions::default())
-        .map_err(|e| CssError::ParseError(format!("{:?}", e)))?;
+        .map_err(|e| CssError::Parse(format!("{:?}", e)))?;
 
     let printer_options = PrinterOptions {
         minify: false,
@@ -24,7 +41,7 @@ pub fn format_css(css: &str, indent_size: usize) -> Result<String, CssError> {
 
     let result = stylesheet
         .to_css(printer_options)
-        .map_err(|e| CssError::PrintError(format!("{:?}", e)))?;
+        .map_err(|e| CssError::Print(format!("{:?}", e)))?;
 
     // Add indentation manually since lightningcss doesn't support custom indentation
     let indented = add_indentation(&result.code, indent_size);
@@ -35,18 +52,18 @@ pub fn format_css(css: &str, indent_size: usize) -> Result<String, CssError> {
 /// Minify CSS
 pub fn minify_css(css: &str) -> Result<String, CssError> {
     let mut stylesheet = StyleSheet::parse(css, ParserOptions::default())
-        .map_err(|e| CssError::ParseError(format!("{:?}", e)))?;
+        .map_err(|e| CssError::Parse(format!("{:?}", e)))?;
 
     stylesheet
         .minify(MinifyOptions::default())
-        .map_err(|e| CssError::PrintError(format!("{:?}", e)))?;
+        .map_err(|e| CssError::Print(format!("{:?}", e)))?;
 
     let result = stylesheet
         .to_css(PrinterOptions {
             minify: true,
             ..PrinterOptions::default()
         })
-        .map_err(|e| CssError::PrintError(format!("{:?}", e)))?;
+        .map_err(|e| CssError::Print(format!("{:?}", e)))?;
 
     Ok(result.code)
 }
@@ -56,6 +73,285 @@ pub fn validate_css(css: &str) -> bool {
     StyleSheet::parse(css, ParserOptions::default()).is_ok()
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CssTransformOptions {
+    /// Browser targets such as `chrome >= 90, safari 14, firefox > 100`
+    pub targets: Option<String>,
+    pub minify: bool,
+    pub source_map: bool,
+    pub css_modules: bool,
+    /// CSS Modules naming pattern, e.g. `[name]_[local]_[hash]`
+    pub css_modules_pattern: Option<String>,
+    /// File name used in the source map and for CSS Modules hashes
+    pub filename: String,
+}
+
+impl Default for CssTransformOptions {
+    fn default() -> Self {
+        Self {
+            targets: None,
+            minify: false,
+            source_map: false,
+            css_modules: false,
+            css_modules_pattern: None,
+            filename: "input.css".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CssModuleClass {
+    /// Name as written in the source
+    pub name: String,
+    /// Generated name to use from JavaScript
+    pub local: String,
+    /// Names added through `composes`
+    pub composes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CssTransformResult {
+    pub code: String,
+    pub source_map: Option<String>,
+    pub exports: Vec<CssModuleClass>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Specificity {
+    pub ids: u32,
+    pub classes: u32,
+    pub elements: u32,
+}
+
+impl From<u32> for Specificity {
+    fn from(packed: u32) -> Self {
+        Self {
+            ids: packed >> 20,
+            classes: (packed >> 10) & 0x3ff,
+            elements: packed & 0x3ff,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct SpecificityCount {
+    pub specificity: Specificity,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateDeclaration {
+    pub selector: String,
+    pub property: String,
+    pub count: usize,
+    pub line: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CssStats {
+    pub rule_count: usize,
+    pub selector_count: usize,
+    pub declaration_count: usize,
+    pub important_count: usize,
+    pub max_specificity: Option<Specificity>,
+    /// Number of selectors per specificity, most specific first
+    pub specificity_distribution: Vec<SpecificityCount>,
+    pub custom_properties: Vec<String>,
+    pub unused_custom_properties: Vec<String>,
+    /// Custom properties read with `var()` but never declared
+    pub undefined_custom_properties: Vec<String>,
+    pub duplicate_declarations: Vec<DuplicateDeclaration>,
+}
+
+/// Parse browser targets from a browserslist query such as `defaults`,
+/// `last 2 versions, not dead` or `chrome >= 90 or safari 14.1`
+pub fn parse_browser_targets(query: &str) -> Result<Browsers, CssError> {
+    Browsers::from_browserslist([query])
+        .map_err(|e| CssError::Targets(e.to_string()))?
+        .ok_or_else(|| CssError::Targets(format!("`{}` matches no supported browsers", query)))
+}
+
+/// Compile CSS for browser targets, optionally minifying, with a source map and CSS Modules
+pub fn transform_css(css: &str, options: &CssTransformOptions) -> Result<CssTransformResult, CssError> {
+    let targets: Targets = match options.targets.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => parse_browser_targets(query)?.into(),
+        _ => Targets::default(),
+    };
+    let css_modules = if options.css_modules {
+        let pattern = match options.css_modules_pattern.as_deref() {
+            Some(pattern) => Pattern::parse(pattern).map_err(|e| CssError::Pattern(e.to_string()))?,
+            None => Pattern::default(),
+        };
+        Some(CssModulesConfig {
+            pattern,
+            ..CssModulesConfig::default()
+        })
+    } else {
+        None
+    };
+
+    let parser_options = ParserOptions {
+        filename: options.filename.clone(),
+        css_modules,
+        ..ParserOptions::default()
+    };
+    let mut stylesheet =
+        StyleSheet::parse(css, parser_options).map_err(|e| CssError::Parse(e.to_string()))?;
+    stylesheet
+        .minify(MinifyOptions {
+            targets,
+            ..MinifyOptions::default()
+        })
+        .map_err(|e| CssError::Print(e.to_string()))?;
+
+    let mut source_map = options.source_map.then(|| {
+        let mut map = SourceMap::new("/");
+        map.add_source(&options.filename);
+        let _ = map.set_source_content(0, css);
+        map
+    });
+    let result = stylesheet
+        .to_css(PrinterOptions {
+            minify: options.minify,
+            source_map: source_map.as_mut(),
+            targets,
+            ..PrinterOptions::default()
+        })
+        .map_err(|e| CssError::Print(e.to_string()))?;
+
+    let source_map = match source_map.as_mut() {
+        Some(map) => Some(map.to_json(None).map_err(|e| CssError::Print(format!("{:?}", e)))?),
+        None => None,
+    };
+
+    let mut exports: Vec<CssModuleClass> = result
+        .exports
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, export)| CssModuleClass {
+            name,
+            local: export.name,
+            composes: export
+                .composes
+                .into_iter()
+                .map(|reference| match reference {
+                    CssModuleReference::Local { name }
+                    | CssModuleReference::Global { name }
+                    | CssModuleReference::Dependency { name, .. } => name,
+                })
+                .collect(),
+        })
+        .collect();
+    exports.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(CssTransformResult {
+        code: result.code,
+        source_map,
+        exports,
+    })
+}
+
+#[derive(Default)]
+struct StatsCollector {
+    stats: CssStats,
+    specificities: BTreeMap<Specificity, usize>,
+    declared: BTreeSet<String>,
+    used: BTreeSet<String>,
+}
+
+impl StatsCollector {
+    fn declarations(&mut self, block: &DeclarationBlock, selector: &str, line: u32) {
+        let all = block.declarations.iter().chain(&block.important_declarations);
+        self.stats.declaration_count += block.declarations.len() + block.important_declarations.len();
+        self.stats.important_count += block.important_declarations.len();
+
+        let mut seen: Vec<(String, usize)> = Vec::new();
+        for property in all {
+            let id = property.property_id();
+            let name = id.to_css_string(PrinterOptions::default()).unwrap_or_else(|_| id.name().to_string());
+            if let Property::Custom(_) = property {
+                self.declared.insert(name.clone());
+            }
+            if let Ok(value) = property.value_to_css_string(PrinterOptions::default()) {
+                for reference in VAR_REFERENCE.captures_iter(&value) {
+                    self.used.insert(reference[1].to_string());
+                }
+            }
+            match seen.iter_mut().find(|(seen_name, _)| *seen_name == name) {
+                Some((_, count)) => *count += 1,
+                None => seen.push((name, 1)),
+            }
+        }
+
+        for (property, count) in seen.into_iter().filter(|(_, count)| *count > 1) {
+            self.stats.duplicate_declarations.push(DuplicateDeclaration {
+                selector: selector.to_string(),
+                property,
+                count,
+                line,
+            });
+        }
+    }
+
+    fn rules<R>(&mut self, rules: &CssRuleList<R>) {
+        for rule in &rules.0 {
+            match rule {
+                CssRule::Style(style) => {
+                    self.stats.rule_count += 1;
+                    self.stats.selector_count += style.selectors.0.len();
+                    for selector in &style.selectors.0 {
+                        *self.specificities.entry(selector.specificity().into()).or_insert(0) += 1;
+                    }
+                    let selector = style
+                        .selectors
+                        .to_css_string(PrinterOptions::default())
+                        .unwrap_or_default();
+                    self.declarations(&style.declarations, &selector, style.loc.line + 1);
+                    self.rules(&style.rules);
+                }
+                CssRule::Media(media) => self.rules(&media.rules),
+                CssRule::Supports(supports) => self.rules(&supports.rules),
+                CssRule::LayerBlock(layer) => self.rules(&layer.rules),
+                CssRule::Container(container) => self.rules(&container.rules),
+                CssRule::Scope(scope) => self.rules(&scope.rules),
+                CssRule::StartingStyle(starting) => self.rules(&starting.rules),
+                CssRule::MozDocument(document) => self.rules(&document.rules),
+                CssRule::NestedDeclarations(nested) => {
+                    self.declarations(&nested.declarations, "&", nested.loc.line + 1)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Collect selector, specificity and declaration statistics for a stylesheet
+pub fn css_stats(css: &str) -> Result<CssStats, CssError> {
+    let stylesheet = StyleSheet::parse(css, ParserOptions::default())
+        .map_err(|e| CssError::Parse(e.to_string()))?;
+
+    let mut collector = StatsCollector::default();
+    collector.rules(&stylesheet.rules);
+
+    let StatsCollector {
+        mut stats,
+        specificities,
+        declared,
+        used,
+    } = collector;
+    stats.max_specificity = specificities.keys().next_back().copied();
+    stats.specificity_distribution = specificities
+        .into_iter()
+        .rev()
+        .map(|(specificity, count)| SpecificityCount { specificity, count })
+        .collect();
+    stats.unused_custom_properties = declared.difference(&used).cloned().collect();
+    stats.undefined_custom_properties = used.difference(&declared).cloned().collect();
+    stats.custom_properties = declared.into_iter().collect();
+    Ok(stats)
+}
+
 /// Add indentation to CSS (simple formatting)
 fn add_indentation(css: &str, indent_size: usize) -> String {
     let indent = " ".repeat(indent_size);
@@ -161,6 +457,107 @@ h1, h2, h3 {
         assert!(result.contains("body"));
     }
 
+    #[test]
+    fn test_parse_browser_targets() {
+        let browsers = parse_browser_targets("chrome >= 90, safari 14.1 or chrome 80, firefox > 100").unwrap();
+        assert_eq!(browsers.chrome, Some(80 << 16));
+        assert_eq!(browsers.safari, Some(14 << 16 | 1 << 8));
+        assert_eq!(browsers.firefox, Some(101 << 16));
+        assert!(parse_browser_targets("netscape 4").is_err());
+
+        let defaults = parse_browser_targets("defaults").unwrap();
+        assert!(defaults.chrome.is_some() && defaults.firefox.is_some() && defaults.safari.is_some());
+        let recent = parse_browser_targets("last 2 versions").unwrap();
+        assert!(recent.chrome.unwrap() > 100 << 16);
+        // Minor versions: `> 15.4` starts at 15.5, not at the next major
+        let safari = parse_browser_targets("safari > 15.4").unwrap();
+        assert_eq!(safari.safari, Some(15 << 16 | 5 << 8));
+    }
+
+    #[test]
+    fn test_transform_with_targets() {
+        let css = ".a { user-select: none; color: rgb(255 0 0 / 50%); }";
+        let options = CssTransformOptions {
+            targets: Some("safari 10, ie 11".to_string()),
+            minify: true,
+            ..Default::default()
+        };
+        let result = transform_css(css, &options).unwrap();
+        assert!(result.code.contains("-webkit-user-select:none"));
+        assert!(result.code.contains("rgba(255,0,0,.5)"));
+        assert!(result.source_map.is_none());
+
+        let modern = transform_css(css, &CssTransformOptions {
+            targets: Some("chrome 120".to_string()),
+            ..options
+        })
+        .unwrap();
+        assert!(!modern.code.contains("-webkit-"));
+    }
+
+    #[test]
+    fn test_transform_source_map_and_modules() {
+        let css = ".button { color: red; }\n.primary { composes: button; background: blue; }";
+        let options = CssTransformOptions {
+            minify: true,
+            source_map: true,
+            css_modules: true,
+            css_modules_pattern: Some("[local]_[hash]".to_string()),
+            filename: "button.module.css".to_string(),
+            ..Default::default()
+        };
+        let result = transform_css(css, &options).unwrap();
+
+        let map: serde_json::Value = serde_json::from_str(result.source_map.as_ref().unwrap()).unwrap();
+        assert_eq!(map["sources"][0], "button.module.css");
+        assert!(!map["mappings"].as_str().unwrap().is_empty());
+
+        let names: Vec<&str> = result.exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["button", "primary"]);
+        let button = &result.exports[0];
+        assert!(button.local.starts_with("button_"));
+        assert!(result.code.contains(&format!(".{}", button.local)));
+        assert_eq!(result.exports[1].composes, vec![button.local.clone()]);
+    }
+
+    #[test]
+    fn test_css_stats() {
+        let css = r#"
+:root { --brand: red; --unused: 4px; }
+#main .item a { color: var(--brand); color: blue; }
+.item, ul li { margin: 0 !important; padding: var(--gap, 0); }
+@media (min-width: 600px) { .item { margin: 1px; } }
+"#;
+        let stats = css_stats(css).unwrap();
+        assert_eq!(stats.rule_count, 4);
+        assert_eq!(stats.selector_count, 5);
+        assert_eq!(stats.declaration_count, 7);
+        assert_eq!(stats.important_count, 1);
+        assert_eq!(
+            stats.max_specificity,
+            Some(Specificity { ids: 1, classes: 1, elements: 1 })
+        );
+        assert_eq!(stats.specificity_distribution[0].count, 1);
+        let item = stats
+            .specificity_distribution
+            .iter()
+            .find(|s| s.specificity == Specificity { ids: 0, classes: 1, elements: 0 })
+            .unwrap();
+        assert_eq!(item.count, 3);
+        assert_eq!(stats.custom_properties, vec!["--brand", "--unused"]);
+        assert_eq!(stats.unused_custom_properties, vec!["--unused"]);
+        assert_eq!(stats.undefined_custom_properties, vec!["--gap"]);
+        assert_eq!(
+            stats.duplicate_declarations,
+            vec![DuplicateDeclaration {
+                selector: "#main .item a".to_string(),
+                property: "color".to_string(),
+                count: 2,
+                line: 3,
+            }]
+        );
+    }
+
     #[test]
     fn test_roundtrip() {
         let formatted = format_css(SAMPLE_CSS, 2).unwrap();
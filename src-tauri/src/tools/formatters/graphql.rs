@@ -0,0 +1,2847 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GraphqlError {
+    #[error("GraphQL syntax error at {line}:{column}: {message}")]
+    SyntaxError {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("Invalid schema: {0}")]
+    SchemaError(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GraphqlIssue {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphqlValidation {
+    pub valid: bool,
+    pub issues: Vec<GraphqlIssue>,
+}
+
+impl GraphqlValidation {
+    fn from_issues(mut issues: Vec<GraphqlIssue>) -> Self {
+        let mut seen = HashSet::new();
+        issues.retain(|i| seen.insert((i.line, i.column, i.message.clone())));
+        issues.sort_by_key(|i| (i.line, i.column));
+        Self {
+            valid: issues.is_empty(),
+            issues,
+        }
+    }
+}
+
+impl From<GraphqlError> for GraphqlIssue {
+    fn from(error: GraphqlError) -> Self {
+        match error {
+            GraphqlError::SyntaxError {
+                line,
+                column,
+                message,
+            } => GraphqlIssue {
+                line,
+                column,
+                message,
+            },
+            GraphqlError::SchemaError(message) => GraphqlIssue {
+                line: 0,
+                column: 0,
+                message,
+            },
+        }
+    }
+}
+
+const BUILTIN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+
+// Lexer
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Pos {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    Punct,
+    Name,
+    Int,
+    Float,
+    String,
+    BlockString,
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    pos: Pos,
+    /// `#` comments found before this token
+    comments: Vec<String>,
+}
+
+fn syntax_error(pos: Pos, message: impl Into<String>) -> GraphqlError {
+    GraphqlError::SyntaxError {
+        line: pos.line,
+        column: pos.column,
+        message: message.into(),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, GraphqlError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+
+    while i <= bytes.len() {
+        let pos = Pos {
+            line,
+            column: source[line_start..i].chars().count() + 1,
+        };
+        if i == bytes.len() {
+            tokens.push(Token {
+                kind: TokenKind::Eof,
+                text: "",
+                pos,
+                comments: std::mem::take(&mut comments),
+            });
+            break;
+        }
+
+        let c = bytes[i];
+        let start = i;
+        let kind = match c {
+            b'\n' => {
+                i += 1;
+                line += 1;
+                line_start = i;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' | b',' => {
+                i += 1;
+                continue;
+            }
+            b'#' => {
+                let end = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                comments.push(source[i..end].trim_end().to_string());
+                i = end;
+                continue;
+            }
+            b'!' | b'$' | b'&' | b'(' | b')' | b':' | b'=' | b'@' | b'[' | b']' | b'{' | b'|'
+            | b'}' => {
+                i += 1;
+                TokenKind::Punct
+            }
+            b'.' if source[i..].starts_with("...") => {
+                i += 3;
+                TokenKind::Punct
+            }
+            b'_' | b'a'..=b'z' | b'A'..=b'Z' => {
+                while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                TokenKind::Name
+            }
+            b'-' | b'0'..=b'9' => {
+                if c == b'-' {
+                    i += 1;
+                }
+                let digits = i;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                if i == digits {
+                    return Err(syntax_error(pos, "expected digit after `-`"));
+                }
+                if bytes[digits] == b'0' && i - digits > 1 {
+                    return Err(syntax_error(pos, "numbers cannot have leading zeros"));
+                }
+                let mut kind = TokenKind::Int;
+                if i < bytes.len() && bytes[i] == b'.' {
+                    i += 1;
+                    let fraction = i;
+                    while i < bytes.len() && bytes[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                    if i == fraction {
+                        return Err(syntax_error(pos, "expected digit after `.`"));
+                    }
+                    kind = TokenKind::Float;
+                }
+                if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+                    i += 1;
+                    if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+                        i += 1;
+                    }
+                    let exponent = i;
+                    while i < bytes.len() && bytes[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                    if i == exponent {
+                        return Err(syntax_error(pos, "expected digit in exponent"));
+                    }
+                    kind = TokenKind::Float;
+                }
+                if i < bytes.len()
+                    && (bytes[i] == b'_' || bytes[i].is_ascii_alphabetic() || bytes[i] == b'.')
+                {
+                    return Err(syntax_error(pos, "invalid number"));
+                }
+                kind
+            }
+            b'"' if bytes[i..].starts_with(b"\"\"\"") => {
+                i += 3;
+                loop {
+                    if i >= bytes.len() {
+                        return Err(syntax_error(pos, "unterminated block string"));
+                    }
+                    // Compare bytes: `i` steps through multi-byte characters one byte at a time
+                    if bytes[i..].starts_with(b"\\\"\"\"") {
+                        i += 4;
+                    } else if bytes[i..].starts_with(b"\"\"\"") {
+                        i += 3;
+                        break;
+                    } else {
+                        if bytes[i] == b'\n' {
+                            line += 1;
+                            line_start = i + 1;
+                        }
+                        i += 1;
+                    }
+                }
+                TokenKind::BlockString
+            }
+            b'"' => {
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        None | Some(b'\n') => return Err(syntax_error(pos, "unterminated string")),
+                        Some(b'\\') => i += 2,
+                        Some(b'"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(_) => i += 1,
+                    }
+                }
+                TokenKind::String
+            }
+            _ => {
+                let ch = source[i..].chars().next().unwrap_or('?');
+                return Err(syntax_error(pos, format!("unexpected character `{}`", ch)));
+            }
+        };
+        tokens.push(Token {
+            kind,
+            text: &source[start..i],
+            pos,
+            comments: std::mem::take(&mut comments),
+        });
+    }
+    Ok(tokens)
+}
+
+// AST
+
+#[derive(Debug, Clone, PartialEq)]
+enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl TypeRef {
+    fn named(&self) -> &str {
+        match self {
+            TypeRef::Named(name) => name,
+            TypeRef::List(inner) | TypeRef::NonNull(inner) => inner.named(),
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            TypeRef::Named(name) => name.clone(),
+            TypeRef::List(inner) => format!("[{}]", inner.render()),
+            TypeRef::NonNull(inner) => format!("{}!", inner.render()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Variable(String),
+    Int(String),
+    Float(String),
+    /// Raw source text including quotes
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(String),
+    List(Vec<(Pos, Value)>),
+    Object(Vec<Argument>),
+}
+
+#[derive(Debug, Clone)]
+struct Argument {
+    name: String,
+    value: Value,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone)]
+struct Directive {
+    name: String,
+    arguments: Vec<Argument>,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone)]
+struct VariableDefinition {
+    name: String,
+    ty: TypeRef,
+    default: Option<Value>,
+    directives: Vec<Directive>,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone)]
+enum Selection {
+    Field {
+        comments: Vec<String>,
+        alias: Option<String>,
+        name: String,
+        arguments: Vec<Argument>,
+        directives: Vec<Directive>,
+        selection_set: Option<SelectionSet>,
+        pos: Pos,
+    },
+    FragmentSpread {
+        comments: Vec<String>,
+        name: String,
+        directives: Vec<Directive>,
+        pos: Pos,
+    },
+    InlineFragment {
+        comments: Vec<String>,
+        type_condition: Option<String>,
+        directives: Vec<Directive>,
+        selection_set: SelectionSet,
+        pos: Pos,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+struct SelectionSet {
+    selections: Vec<Selection>,
+    /// Comments before the closing brace
+    trailing_comments: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct InputValueDefinition {
+    comments: Vec<String>,
+    description: Option<String>,
+    name: String,
+    ty: TypeRef,
+    default: Option<Value>,
+    directives: Vec<Directive>,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone)]
+struct FieldDefinition {
+    comments: Vec<String>,
+    description: Option<String>,
+    name: String,
+    arguments: Vec<InputValueDefinition>,
+    ty: TypeRef,
+    directives: Vec<Directive>,
+    pos: Pos,
+}
+
+#[derive(Debug, Clone)]
+struct EnumValueDefinition {
+    comments: Vec<String>,
+    description: Option<String>,
+    name: String,
+    directives: Vec<Directive>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+}
+
+impl TypeKind {
+    fn keyword(self) -> &'static str {
+        match self {
+            TypeKind::Scalar => "scalar",
+            TypeKind::Object => "type",
+            TypeKind::Interface => "interface",
+            TypeKind::Union => "union",
+            TypeKind::Enum => "enum",
+            TypeKind::InputObject => "input",
+        }
+    }
+}
+
+/// Members of a type definition; which ones are used depends on the kind
+#[derive(Debug, Clone, Default)]
+struct TypeBody {
+    interfaces: Vec<String>,
+    fields: Vec<FieldDefinition>,
+    input_fields: Vec<InputValueDefinition>,
+    values: Vec<EnumValueDefinition>,
+    members: Vec<String>,
+    trailing_comments: Vec<String>,
+    has_block: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Definition {
+    Operation {
+        comments: Vec<String>,
+        kind: Option<String>,
+        name: Option<String>,
+        variables: Vec<VariableDefinition>,
+        directives: Vec<Directive>,
+        selection_set: SelectionSet,
+        pos: Pos,
+    },
+    Fragment {
+        comments: Vec<String>,
+        name: String,
+        type_condition: String,
+        directives: Vec<Directive>,
+        selection_set: SelectionSet,
+        pos: Pos,
+    },
+    Schema {
+        comments: Vec<String>,
+        description: Option<String>,
+        extend: bool,
+        directives: Vec<Directive>,
+        operations: Vec<(String, String)>,
+        pos: Pos,
+    },
+    Type {
+        comments: Vec<String>,
+        description: Option<String>,
+        extend: bool,
+        kind: TypeKind,
+        name: String,
+        directives: Vec<Directive>,
+        body: TypeBody,
+        pos: Pos,
+    },
+    Directive {
+        comments: Vec<String>,
+        description: Option<String>,
+        name: String,
+        arguments: Vec<InputValueDefinition>,
+        repeatable: bool,
+        locations: Vec<String>,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Document {
+    definitions: Vec<Definition>,
+    trailing_comments: Vec<String>,
+}
+
+// Parser
+
+/// Deepest nesting of selections, list values and list types; parsing,
+/// printing and validation all recurse once per level
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+    /// Comments from tokens that cannot carry them, waiting for the next item
+    pending: Vec<String>,
+    depth: usize,
+}
+
+type ParseResult<T> = Result<T, GraphqlError>;
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> ParseResult<Self> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            index: 0,
+            pending: Vec::new(),
+            depth: 0,
+        })
+    }
+
+    fn peek(&self) -> &Token<'a> {
+        &self.tokens[self.index]
+    }
+
+    fn is(&self, text: &str) -> bool {
+        let token = self.peek();
+        token.kind != TokenKind::String
+            && token.kind != TokenKind::BlockString
+            && token.text == text
+    }
+
+    fn is_description(&self) -> bool {
+        matches!(self.peek().kind, TokenKind::String | TokenKind::BlockString)
+    }
+
+    fn advance(&mut self) -> Token<'a> {
+        let mut token = self.tokens[self.index].clone();
+        self.pending.append(&mut token.comments);
+        self.tokens[self.index].comments.clear();
+        if token.kind != TokenKind::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    /// Comments that belong to the item starting at the current token
+    fn take_comments(&mut self) -> Vec<String> {
+        let mut comments = std::mem::take(&mut self.pending);
+        comments.append(&mut self.tokens[self.index].comments);
+        comments
+    }
+
+    fn unexpected(&self, expected: &str) -> GraphqlError {
+        let token = self.peek();
+        let found = match token.kind {
+            TokenKind::Eof => "end of input".to_string(),
+            _ => format!("`{}`", token.text),
+        };
+        syntax_error(token.pos, format!("expected {}, found {}", expected, found))
+    }
+
+    fn expect(&mut self, text: &str) -> ParseResult<Token<'a>> {
+        if self.is(text) {
+            Ok(self.advance())
+        } else {
+            Err(self.unexpected(&format!("`{}`", text)))
+        }
+    }
+
+    /// Parse one level deeper, failing past `MAX_DEPTH`
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(syntax_error(
+                self.peek().pos,
+                format!("nesting exceeds the limit of {} levels", MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        if self.is(text) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn name(&mut self) -> ParseResult<(String, Pos)> {
+        if self.peek().kind == TokenKind::Name {
+            let token = self.advance();
+            Ok((token.text.to_string(), token.pos))
+        } else {
+            Err(self.unexpected("a name"))
+        }
+    }
+
+    fn description(&mut self) -> Option<String> {
+        self.is_description()
+            .then(|| self.advance().text.to_string())
+    }
+
+    fn document(&mut self) -> ParseResult<Document> {
+        let mut document = Document::default();
+        while self.peek().kind != TokenKind::Eof {
+            document.definitions.push(self.definition()?);
+        }
+        document.trailing_comments = self.take_comments();
+        Ok(document)
+    }
+
+    fn definition(&mut self) -> ParseResult<Definition> {
+        let comments = self.take_comments();
+        let pos = self.peek().pos;
+        if self.is("{") {
+            return Ok(Definition::Operation {
+                comments,
+                kind: None,
+                name: None,
+                variables: Vec::new(),
+                directives: Vec::new(),
+                selection_set: self.selection_set()?,
+                pos,
+            });
+        }
+
+        let description = self.description();
+        let extend = description.is_none() && self.eat("extend");
+        let keyword = self.peek().text;
+        if description.is_none() && !extend {
+            match keyword {
+                "query" | "mutation" | "subscription" => return self.operation(comments),
+                "fragment" => return self.fragment(comments),
+                _ => {}
+            }
+        }
+
+        let kind = match keyword {
+            "schema" => return self.schema(comments, description, extend),
+            "directive" if !extend => return self.directive_definition(comments, description),
+            "scalar" => TypeKind::Scalar,
+            "type" => TypeKind::Object,
+            "interface" => TypeKind::Interface,
+            "union" => TypeKind::Union,
+            "enum" => TypeKind::Enum,
+            "input" => TypeKind::InputObject,
+            _ => return Err(self.unexpected("a definition")),
+        };
+        self.advance();
+        let (name, _) = self.name()?;
+        let mut body = TypeBody::default();
+
+        if matches!(kind, TypeKind::Object | TypeKind::Interface) && self.eat("implements") {
+            self.eat("&");
+            body.interfaces.push(self.name()?.0);
+            while self.eat("&") {
+                body.interfaces.push(self.name()?.0);
+            }
+        }
+        let directives = self.directives()?;
+
+        match kind {
+            TypeKind::Object | TypeKind::Interface if self.is("{") => {
+                body.has_block = true;
+                self.advance();
+                while !self.is("}") {
+                    body.fields.push(self.field_definition()?);
+                }
+                body.trailing_comments = self.take_comments();
+                self.advance();
+            }
+            TypeKind::InputObject if self.is("{") => {
+                body.has_block = true;
+                self.advance();
+                while !self.is("}") {
+                    body.input_fields.push(self.input_value_definition()?);
+                }
+                body.trailing_comments = self.take_comments();
+                self.advance();
+            }
+            TypeKind::Enum if self.is("{") => {
+                body.has_block = true;
+                self.advance();
+                while !self.is("}") {
+                    let comments = self.take_comments();
+                    let description = self.description();
+                    let (name, pos) = self.name()?;
+                    if matches!(name.as_str(), "true" | "false" | "null") {
+                        return Err(syntax_error(
+                            pos,
+                            format!("`{}` cannot be an enum value", name),
+                        ));
+                    }
+                    body.values.push(EnumValueDefinition {
+                        comments,
+                        description,
+                        name,
+                        directives: self.directives()?,
+                    });
+                }
+                body.trailing_comments = self.take_comments();
+                self.advance();
+            }
+            TypeKind::Union if self.eat("=") => {
+                self.eat("|");
+                body.members.push(self.name()?.0);
+                while self.eat("|") {
+                    body.members.push(self.name()?.0);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(Definition::Type {
+            comments,
+            description,
+            extend,
+            kind,
+            name,
+            directives,
+            body,
+            pos,
+        })
+    }
+
+    fn operation(&mut self, comments: Vec<String>) -> ParseResult<Definition> {
+        let token = self.advance();
+        let name = if self.peek().kind == TokenKind::Name {
+            Some(self.name()?.0)
+        } else {
+            None
+        };
+        let mut variables = Vec::new();
+        if self.eat("(") {
+            while !self.is(")") {
+                let pos = self.expect("$")?.pos;
+                let (name, _) = self.name()?;
+                self.expect(":")?;
+                let ty = self.type_ref()?;
+                let default = if self.eat("=") {
+                    Some(self.value(true)?)
+                } else {
+                    None
+                };
+                variables.push(VariableDefinition {
+                    name,
+                    ty,
+                    default,
+                    directives: self.directives()?,
+                    pos,
+                });
+            }
+            self.advance();
+        }
+        Ok(Definition::Operation {
+            comments,
+            kind: Some(token.text.to_string()),
+            name,
+            variables,
+            directives: self.directives()?,
+            selection_set: self.selection_set()?,
+            pos: token.pos,
+        })
+    }
+
+    fn fragment(&mut self, comments: Vec<String>) -> ParseResult<Definition> {
+        let pos = self.advance().pos;
+        let (name, name_pos) = self.name()?;
+        if name == "on" {
+            return Err(syntax_error(name_pos, "a fragment cannot be named `on`"));
+        }
+        self.expect("on")?;
+        let (type_condition, _) = self.name()?;
+        Ok(Definition::Fragment {
+            comments,
+            name,
+            type_condition,
+            directives: self.directives()?,
+            selection_set: self.selection_set()?,
+            pos,
+        })
+    }
+
+    fn schema(
+        &mut self,
+        comments: Vec<String>,
+        description: Option<String>,
+        extend: bool,
+    ) -> ParseResult<Definition> {
+        let pos = self.advance().pos;
+        let directives = self.directives()?;
+        let mut operations = Vec::new();
+        if self.eat("{") {
+            while !self.is("}") {
+                let (operation, operation_pos) = self.name()?;
+                if !matches!(operation.as_str(), "query" | "mutation" | "subscription") {
+                    return Err(syntax_error(
+                        operation_pos,
+                        format!("unknown operation type `{}`", operation),
+                    ));
+                }
+                self.expect(":")?;
+                operations.push((operation, self.name()?.0));
+            }
+            self.advance();
+        } else if !extend {
+            return Err(self.unexpected("`{`"));
+        }
+        Ok(Definition::Schema {
+            comments,
+            description,
+            extend,
+            directives,
+            operations,
+            pos,
+        })
+    }
+
+    fn directive_definition(
+        &mut self,
+        comments: Vec<String>,
+        description: Option<String>,
+    ) -> ParseResult<Definition> {
+        self.advance();
+        self.expect("@")?;
+        let (name, _) = self.name()?;
+        let arguments = self.argument_definitions()?;
+        let repeatable = self.eat("repeatable");
+        self.expect("on")?;
+        self.eat("|");
+        let mut locations = vec![self.name()?.0];
+        while self.eat("|") {
+            locations.push(self.name()?.0);
+        }
+        Ok(Definition::Directive {
+            comments,
+            description,
+            name,
+            arguments,
+            repeatable,
+            locations,
+        })
+    }
+
+    fn field_definition(&mut self) -> ParseResult<FieldDefinition> {
+        let comments = self.take_comments();
+        let description = self.description();
+        let (name, pos) = self.name()?;
+        let arguments = self.argument_definitions()?;
+        self.expect(":")?;
+        Ok(FieldDefinition {
+            comments,
+            description,
+            name,
+            arguments,
+            ty: self.type_ref()?,
+            directives: self.directives()?,
+            pos,
+        })
+    }
+
+    fn argument_definitions(&mut self) -> ParseResult<Vec<InputValueDefinition>> {
+        let mut arguments = Vec::new();
+        if self.eat("(") {
+            while !self.is(")") {
+                arguments.push(self.input_value_definition()?);
+            }
+            self.advance();
+        }
+        Ok(arguments)
+    }
+
+    fn input_value_definition(&mut self) -> ParseResult<InputValueDefinition> {
+        let comments = self.take_comments();
+        let description = self.description();
+        let (name, pos) = self.name()?;
+        self.expect(":")?;
+        let ty = self.type_ref()?;
+        let default = if self.eat("=") {
+            Some(self.value(true)?)
+        } else {
+            None
+        };
+        Ok(InputValueDefinition {
+            comments,
+            description,
+            name,
+            ty,
+            default,
+            directives: self.directives()?,
+            pos,
+        })
+    }
+
+    fn type_ref(&mut self) -> ParseResult<TypeRef> {
+        let ty = if self.eat("[") {
+            let inner = self.nested(Self::type_ref)?;
+            self.expect("]")?;
+            TypeRef::List(Box::new(inner))
+        } else {
+            TypeRef::Named(self.name()?.0)
+        };
+        if self.eat("!") {
+            Ok(TypeRef::NonNull(Box::new(ty)))
+        } else {
+            Ok(ty)
+        }
+    }
+
+    fn directives(&mut self) -> ParseResult<Vec<Directive>> {
+        let mut directives = Vec::new();
+        while self.is("@") {
+            let pos = self.advance().pos;
+            let (name, _) = self.name()?;
+            directives.push(Directive {
+                name,
+                arguments: self.arguments(false)?,
+                pos,
+            });
+        }
+        Ok(directives)
+    }
+
+    fn arguments(&mut self, constant: bool) -> ParseResult<Vec<Argument>> {
+        let mut arguments = Vec::new();
+        if self.eat("(") {
+            if self.is(")") {
+                return Err(self.unexpected("an argument"));
+            }
+            while !self.is(")") {
+                let (name, pos) = self.name()?;
+                self.expect(":")?;
+                arguments.push(Argument {
+                    name,
+                    value: self.value(constant)?,
+                    pos,
+                });
+            }
+            self.advance();
+        }
+        Ok(arguments)
+    }
+
+    fn value(&mut self, constant: bool) -> ParseResult<Value> {
+        let token = self.peek().clone();
+        let value = match token.kind {
+            TokenKind::Int => Value::Int(token.text.to_string()),
+            TokenKind::Float => Value::Float(token.text.to_string()),
+            TokenKind::String | TokenKind::BlockString => Value::String(token.text.to_string()),
+            TokenKind::Name => match token.text {
+                "true" => Value::Boolean(true),
+                "false" => Value::Boolean(false),
+                "null" => Value::Null,
+                name => Value::Enum(name.to_string()),
+            },
+            TokenKind::Punct if token.text == "$" && !constant => {
+                self.advance();
+                return Ok(Value::Variable(self.name()?.0));
+            }
+            TokenKind::Punct if token.text == "[" => {
+                self.advance();
+                let mut items = Vec::new();
+                while !self.is("]") {
+                    let pos = self.peek().pos;
+                    items.push((pos, self.nested(|parser| parser.value(constant))?));
+                }
+                self.advance();
+                return Ok(Value::List(items));
+            }
+            TokenKind::Punct if token.text == "{" => {
+                self.advance();
+                let mut fields = Vec::new();
+                while !self.is("}") {
+                    let (name, pos) = self.name()?;
+                    self.expect(":")?;
+                    fields.push(Argument {
+                        name,
+                        value: self.nested(|parser| parser.value(constant))?,
+                        pos,
+                    });
+                }
+                self.advance();
+                return Ok(Value::Object(fields));
+            }
+            _ => return Err(self.unexpected("a value")),
+        };
+        self.advance();
+        Ok(value)
+    }
+
+    fn selection_set(&mut self) -> ParseResult<SelectionSet> {
+        self.expect("{")?;
+        let mut set = SelectionSet::default();
+        if self.is("}") {
+            return Err(self.unexpected("a selection"));
+        }
+        while !self.is("}") {
+            set.selections.push(self.selection()?);
+        }
+        set.trailing_comments = self.take_comments();
+        self.advance();
+        Ok(set)
+    }
+
+    fn selection(&mut self) -> ParseResult<Selection> {
+        let comments = self.take_comments();
+        let pos = self.peek().pos;
+        if self.eat("...") {
+            let spread_name = self.peek().kind == TokenKind::Name && self.peek().text != "on";
+            if spread_name {
+                let (name, _) = self.name()?;
+                return Ok(Selection::FragmentSpread {
+                    comments,
+                    name,
+                    directives: self.directives()?,
+                    pos,
+                });
+            }
+            let type_condition = if self.eat("on") {
+                Some(self.name()?.0)
+            } else {
+                None
+            };
+            return Ok(Selection::InlineFragment {
+                comments,
+                type_condition,
+                directives: self.directives()?,
+                selection_set: self.nested(Self::selection_set)?,
+                pos,
+            });
+        }
+
+        let (first, pos) = self.name()?;
+        let (alias, name, pos) = if self.eat(":") {
+            let (name, name_pos) = self.name()?;
+            (Some(first), name, name_pos)
+        } else {
+            (None, first, pos)
+        };
+        let arguments = self.arguments(false)?;
+        let directives = self.directives()?;
+        let selection_set = if self.is("{") {
+            Some(self.nested(Self::selection_set)?)
+        } else {
+            None
+        };
+        Ok(Selection::Field {
+            comments,
+            alias,
+            name,
+            arguments,
+            directives,
+            selection_set,
+            pos,
+        })
+    }
+}
+
+fn parse(source: &str) -> ParseResult<Document> {
+    let mut parser = Parser::new(source)?;
+    let document = parser.document()?;
+    if document.definitions.is_empty() {
+        return Err(syntax_error(
+            Pos { line: 1, column: 1 },
+            "document has no definitions",
+        ));
+    }
+    Ok(document)
+}
+
+// Printer
+
+const MAX_LINE_WIDTH: usize = 80;
+
+fn print_value(value: &Value) -> String {
+    match value {
+        Value::Variable(name) => format!("${}", name),
+        Value::Int(text) | Value::Float(text) | Value::String(text) | Value::Enum(text) => {
+            text.clone()
+        }
+        Value::Boolean(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        Value::List(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|(_, v)| print_value(v))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Object(fields) if fields.is_empty() => "{}".to_string(),
+        Value::Object(fields) => format!("{{ {} }}", print_arguments_inner(fields)),
+    }
+}
+
+fn print_arguments_inner(arguments: &[Argument]) -> String {
+    arguments
+        .iter()
+        .map(|a| format!("{}: {}", a.name, print_value(&a.value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_arguments(arguments: &[Argument]) -> String {
+    if arguments.is_empty() {
+        String::new()
+    } else {
+        format!("({})", print_arguments_inner(arguments))
+    }
+}
+
+fn print_directives(directives: &[Directive]) -> String {
+    directives
+        .iter()
+        .map(|d| format!(" @{}{}", d.name, print_arguments(&d.arguments)))
+        .collect()
+}
+
+fn print_input_value(input: &InputValueDefinition) -> String {
+    let mut text = format!("{}: {}", input.name, input.ty.render());
+    if let Some(default) = &input.default {
+        text.push_str(" = ");
+        text.push_str(&print_value(default));
+    }
+    text.push_str(&print_directives(&input.directives));
+    text
+}
+
+/// Strip the common indentation of a block string's lines, per the GraphQL spec
+fn block_string_lines(raw: &str) -> Vec<String> {
+    let content = &raw[3..raw.len() - 3];
+    let lines: Vec<&str> = content.lines().collect();
+    let common = lines
+        .iter()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut result: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            if i == 0 {
+                l.trim().to_string()
+            } else {
+                l.get(common..).unwrap_or("").trim_end().to_string()
+            }
+        })
+        .collect();
+    while result.first().is_some_and(|l| l.is_empty()) {
+        result.remove(0);
+    }
+    while result.last().is_some_and(|l| l.is_empty()) {
+        result.pop();
+    }
+    result
+}
+
+struct Printer {
+    indent: String,
+    out: String,
+}
+
+impl Printer {
+    fn line(&mut self, level: usize, text: &str) {
+        self.out.push_str(&self.indent.repeat(level));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn comments(&mut self, comments: &[String], level: usize) {
+        for comment in comments {
+            self.line(level, comment);
+        }
+    }
+
+    fn description(&mut self, description: &Option<String>, level: usize) {
+        let Some(raw) = description else {
+            return;
+        };
+        if !raw.starts_with("\"\"\"") {
+            self.line(level, raw);
+            return;
+        }
+        let lines = block_string_lines(raw);
+        if lines.len() <= 1 {
+            self.line(
+                level,
+                &format!("\"\"\"{}\"\"\"", lines.first().map_or("", |l| l.as_str())),
+            );
+            return;
+        }
+        self.line(level, "\"\"\"");
+        for line in &lines {
+            if line.is_empty() {
+                self.out.push('\n');
+            } else {
+                self.line(level, line);
+            }
+        }
+        self.line(level, "\"\"\"");
+    }
+
+    fn document(&mut self, document: &Document) {
+        for (i, definition) in document.definitions.iter().enumerate() {
+            if i > 0 {
+                self.out.push('\n');
+            }
+            self.definition(definition);
+        }
+        if !document.trailing_comments.is_empty() {
+            self.out.push('\n');
+            self.comments(&document.trailing_comments, 0);
+        }
+    }
+
+    fn definition(&mut self, definition: &Definition) {
+        match definition {
+            Definition::Operation {
+                comments,
+                kind,
+                name,
+                variables,
+                directives,
+                selection_set,
+                ..
+            } => {
+                self.comments(comments, 0);
+                let mut head = String::new();
+                if let Some(kind) = kind {
+                    head.push_str(kind);
+                    if let Some(name) = name {
+                        head.push(' ');
+                        head.push_str(name);
+                    }
+                    if !variables.is_empty() {
+                        let vars: Vec<String> = variables
+                            .iter()
+                            .map(|v| {
+                                let mut text = format!("${}: {}", v.name, v.ty.render());
+                                if let Some(default) = &v.default {
+                                    text.push_str(" = ");
+                                    text.push_str(&print_value(default));
+                                }
+                                text.push_str(&print_directives(&v.directives));
+                                text
+                            })
+                            .collect();
+                        head.push_str(&format!("({})", vars.join(", ")));
+                    }
+                    head.push_str(&print_directives(directives));
+                    head.push(' ');
+                }
+                self.selection_set(&head, selection_set, 0);
+            }
+            Definition::Fragment {
+                comments,
+                name,
+                type_condition,
+                directives,
+                selection_set,
+                ..
+            } => {
+                self.comments(comments, 0);
+                let head = format!(
+                    "fragment {} on {}{} ",
+                    name,
+                    type_condition,
+                    print_directives(directives)
+                );
+                self.selection_set(&head, selection_set, 0);
+            }
+            Definition::Schema {
+                comments,
+                description,
+                extend,
+                directives,
+                operations,
+                ..
+            } => {
+                self.comments(comments, 0);
+                self.description(description, 0);
+                let prefix = if *extend { "extend " } else { "" };
+                let head = format!("{}schema{}", prefix, print_directives(directives));
+                if operations.is_empty() {
+                    self.line(0, &head);
+                    return;
+                }
+                self.line(0, &format!("{} {{", head));
+                for (operation, ty) in operations {
+                    self.line(1, &format!("{}: {}", operation, ty));
+                }
+                self.line(0, "}");
+            }
+            Definition::Type {
+                comments,
+                description,
+                extend,
+                kind,
+                name,
+                directives,
+                body,
+                ..
+            } => {
+                self.comments(comments, 0);
+                self.description(description, 0);
+                let mut head = format!(
+                    "{}{} {}",
+                    if *extend { "extend " } else { "" },
+                    kind.keyword(),
+                    name
+                );
+                if !body.interfaces.is_empty() {
+                    head.push_str(" implements ");
+                    head.push_str(&body.interfaces.join(" & "));
+                }
+                head.push_str(&print_directives(directives));
+                if !body.members.is_empty() {
+                    head.push_str(" = ");
+                    head.push_str(&body.members.join(" | "));
+                }
+                if !body.has_block {
+                    self.line(0, &head);
+                    return;
+                }
+                self.line(0, &format!("{} {{", head));
+                for field in &body.fields {
+                    self.field_definition(field, 1);
+                }
+                for input in &body.input_fields {
+                    self.comments(&input.comments, 1);
+                    self.description(&input.description, 1);
+                    self.line(1, &print_input_value(input));
+                }
+                for value in &body.values {
+                    self.comments(&value.comments, 1);
+                    self.description(&value.description, 1);
+                    self.line(
+                        1,
+                        &format!("{}{}", value.name, print_directives(&value.directives)),
+                    );
+                }
+                self.comments(&body.trailing_comments, 1);
+                self.line(0, "}");
+            }
+            Definition::Directive {
+                comments,
+                description,
+                name,
+                arguments,
+                repeatable,
+                locations,
+                ..
+            } => {
+                self.comments(comments, 0);
+                self.description(description, 0);
+                let tail = format!(
+                    "{} on {}",
+                    if *repeatable { " repeatable" } else { "" },
+                    locations.join(" | ")
+                );
+                self.with_arguments(&format!("directive @{}", name), arguments, &tail, 0);
+            }
+        }
+    }
+
+    fn field_definition(&mut self, field: &FieldDefinition, level: usize) {
+        self.comments(&field.comments, level);
+        self.description(&field.description, level);
+        let tail = format!(
+            ": {}{}",
+            field.ty.render(),
+            print_directives(&field.directives)
+        );
+        self.with_arguments(&field.name, &field.arguments, &tail, level);
+    }
+
+    /// Print `head(args)tail`, breaking arguments onto their own lines when needed
+    fn with_arguments(
+        &mut self,
+        head: &str,
+        arguments: &[InputValueDefinition],
+        tail: &str,
+        level: usize,
+    ) {
+        if arguments.is_empty() {
+            self.line(level, &format!("{}{}", head, tail));
+            return;
+        }
+        let inline = format!(
+            "{}({}){}",
+            head,
+            arguments
+                .iter()
+                .map(print_input_value)
+                .collect::<Vec<_>>()
+                .join(", "),
+            tail
+        );
+        let annotated = arguments
+            .iter()
+            .any(|a| a.description.is_some() || !a.comments.is_empty());
+        if !annotated && self.indent.len() * level + inline.len() <= MAX_LINE_WIDTH {
+            self.line(level, &inline);
+            return;
+        }
+        self.line(level, &format!("{}(", head));
+        for argument in arguments {
+            self.comments(&argument.comments, level + 1);
+            self.description(&argument.description, level + 1);
+            self.line(level + 1, &print_input_value(argument));
+        }
+        self.line(level, &format!("){}", tail));
+    }
+
+    fn selection_set(&mut self, head: &str, set: &SelectionSet, level: usize) {
+        self.line(level, &format!("{}{{", head));
+        for selection in &set.selections {
+            self.selection(selection, level + 1);
+        }
+        self.comments(&set.trailing_comments, level + 1);
+        self.line(level, "}");
+    }
+
+    fn selection(&mut self, selection: &Selection, level: usize) {
+        match selection {
+            Selection::Field {
+                comments,
+                alias,
+                name,
+                arguments,
+                directives,
+                selection_set,
+                ..
+            } => {
+                self.comments(comments, level);
+                let mut text = String::new();
+                if let Some(alias) = alias {
+                    text.push_str(alias);
+                    text.push_str(": ");
+                }
+                text.push_str(name);
+                text.push_str(&print_arguments(arguments));
+                text.push_str(&print_directives(directives));
+                match selection_set {
+                    Some(set) => self.selection_set(&format!("{} ", text), set, level),
+                    None => self.line(level, &text),
+                }
+            }
+            Selection::FragmentSpread {
+                comments,
+                name,
+                directives,
+                ..
+            } => {
+                self.comments(comments, level);
+                self.line(
+                    level,
+                    &format!("...{}{}", name, print_directives(directives)),
+                );
+            }
+            Selection::InlineFragment {
+                comments,
+                type_condition,
+                directives,
+                selection_set,
+                ..
+            } => {
+                self.comments(comments, level);
+                let condition = type_condition
+                    .as_ref()
+                    .map(|t| format!(" on {}", t))
+                    .unwrap_or_default();
+                let head = format!("...{}{} ", condition, print_directives(directives));
+                self.selection_set(&head, selection_set, level);
+            }
+        }
+    }
+}
+
+/// Format a GraphQL query or SDL document
+pub fn format_graphql(input: &str, indent_size: usize) -> Result<String, GraphqlError> {
+    let document = parse(input)?;
+    let mut printer = Printer {
+        indent: " ".repeat(indent_size),
+        out: String::new(),
+    };
+    printer.document(&document);
+    Ok(printer.out)
+}
+
+/// Minify a GraphQL document: drop comments, commas and insignificant whitespace
+pub fn minify_graphql(input: &str) -> Result<String, GraphqlError> {
+    // Parse first so invalid documents are rejected rather than mangled
+    parse(input)?;
+    let mut out = String::new();
+    let mut previous: Option<Token> = None;
+    for token in tokenize(input)? {
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+        if let Some(previous) = &previous {
+            let word =
+                |t: &Token| matches!(t.kind, TokenKind::Name | TokenKind::Int | TokenKind::Float);
+            let quotes = previous.text.ends_with('"') && token.text.starts_with('"');
+            if (word(previous) && word(&token)) || quotes {
+                out.push(' ');
+            }
+        }
+        out.push_str(token.text);
+        previous = Some(token);
+    }
+    Ok(out)
+}
+
+// Validation
+
+fn issue(issues: &mut Vec<GraphqlIssue>, pos: Pos, message: impl Into<String>) {
+    issues.push(GraphqlIssue {
+        line: pos.line,
+        column: pos.column,
+        message: message.into(),
+    });
+}
+
+#[derive(Debug, Clone)]
+struct InputInfo {
+    name: String,
+    ty: TypeRef,
+    has_default: bool,
+}
+
+impl From<&InputValueDefinition> for InputInfo {
+    fn from(input: &InputValueDefinition) -> Self {
+        Self {
+            name: input.name.clone(),
+            ty: input.ty.clone(),
+            has_default: input.default.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FieldInfo {
+    name: String,
+    arguments: Vec<InputInfo>,
+    ty: TypeRef,
+}
+
+#[derive(Debug, Clone)]
+struct TypeInfo {
+    kind: TypeKind,
+    interfaces: Vec<String>,
+    fields: Vec<FieldInfo>,
+    input_fields: Vec<InputInfo>,
+    values: Vec<String>,
+    members: Vec<String>,
+}
+
+impl TypeInfo {
+    fn new(kind: TypeKind) -> Self {
+        Self {
+            kind,
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            input_fields: Vec::new(),
+            values: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    fn is_composite(&self) -> bool {
+        matches!(
+            self.kind,
+            TypeKind::Object | TypeKind::Interface | TypeKind::Union
+        )
+    }
+
+    fn is_input(&self) -> bool {
+        matches!(
+            self.kind,
+            TypeKind::Scalar | TypeKind::Enum | TypeKind::InputObject
+        )
+    }
+}
+
+struct Schema {
+    types: HashMap<String, TypeInfo>,
+    directives: HashMap<String, Vec<InputInfo>>,
+    roots: HashMap<String, String>,
+}
+
+impl Schema {
+    fn build(document: &Document, issues: &mut Vec<GraphqlIssue>) -> Self {
+        let mut schema = Schema {
+            types: BUILTIN_SCALARS
+                .iter()
+                .map(|name| (name.to_string(), TypeInfo::new(TypeKind::Scalar)))
+                .collect(),
+            directives: HashMap::new(),
+            roots: HashMap::new(),
+        };
+        let boolean = |name: &str| InputInfo {
+            name: name.to_string(),
+            ty: TypeRef::NonNull(Box::new(TypeRef::Named("Boolean".to_string()))),
+            has_default: false,
+        };
+        let optional = |name: &str| InputInfo {
+            name: name.to_string(),
+            ty: TypeRef::Named("String".to_string()),
+            has_default: true,
+        };
+        schema
+            .directives
+            .insert("skip".to_string(), vec![boolean("if")]);
+        schema
+            .directives
+            .insert("include".to_string(), vec![boolean("if")]);
+        schema
+            .directives
+            .insert("deprecated".to_string(), vec![optional("reason")]);
+        schema
+            .directives
+            .insert("specifiedBy".to_string(), vec![optional("url")]);
+        schema.directives.insert("oneOf".to_string(), Vec::new());
+
+        let mut defined = HashSet::new();
+        for definition in &document.definitions {
+            match definition {
+                Definition::Type {
+                    extend,
+                    kind,
+                    name,
+                    body,
+                    pos,
+                    ..
+                } => {
+                    if !extend && !defined.insert(name.clone()) {
+                        issue(
+                            issues,
+                            *pos,
+                            format!("Type `{}` is defined more than once", name),
+                        );
+                    }
+                    let info = schema
+                        .types
+                        .entry(name.clone())
+                        .or_insert_with(|| TypeInfo::new(*kind));
+                    if info.kind != *kind {
+                        issue(
+                            issues,
+                            *pos,
+                            format!("`{}` is extended as a different kind of type", name),
+                        );
+                        continue;
+                    }
+                    info.interfaces.extend(body.interfaces.iter().cloned());
+                    info.members.extend(body.members.iter().cloned());
+                    info.values
+                        .extend(body.values.iter().map(|v| v.name.clone()));
+                    info.input_fields
+                        .extend(body.input_fields.iter().map(InputInfo::from));
+                    for field in &body.fields {
+                        if info.fields.iter().any(|f| f.name == field.name) {
+                            issue(
+                                issues,
+                                field.pos,
+                                format!(
+                                    "Field `{}.{}` is defined more than once",
+                                    name, field.name
+                                ),
+                            );
+                        }
+                        info.fields.push(FieldInfo {
+                            name: field.name.clone(),
+                            arguments: field.arguments.iter().map(InputInfo::from).collect(),
+                            ty: field.ty.clone(),
+                        });
+                    }
+                }
+                Definition::Directive {
+                    name, arguments, ..
+                } => {
+                    schema.directives.insert(
+                        name.clone(),
+                        arguments.iter().map(InputInfo::from).collect(),
+                    );
+                }
+                Definition::Schema { operations, .. } => {
+                    for (operation, ty) in operations {
+                        schema.roots.insert(operation.clone(), ty.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (operation, default) in [
+            ("query", "Query"),
+            ("mutation", "Mutation"),
+            ("subscription", "Subscription"),
+        ] {
+            if !schema.roots.contains_key(operation) && schema.types.contains_key(default) {
+                schema
+                    .roots
+                    .insert(operation.to_string(), default.to_string());
+            }
+        }
+        schema
+    }
+
+    /// Check that every type referenced by the SDL exists and has the right kind
+    fn check_references(&self, document: &Document, issues: &mut Vec<GraphqlIssue>) {
+        let output = |name: &str| {
+            self.types
+                .get(name)
+                .map(|t| !matches!(t.kind, TypeKind::InputObject))
+        };
+        let input = |name: &str| self.types.get(name).map(|t| t.is_input());
+        let check_input = |issues: &mut Vec<GraphqlIssue>, value: &InputValueDefinition| match input(
+            value.ty.named(),
+        ) {
+            None => issue(
+                issues,
+                value.pos,
+                format!("Unknown type `{}`", value.ty.named()),
+            ),
+            Some(false) => issue(
+                issues,
+                value.pos,
+                format!("`{}` is not an input type", value.ty.named()),
+            ),
+            Some(true) => {}
+        };
+
+        for definition in &document.definitions {
+            match definition {
+                Definition::Type {
+                    name, body, pos, ..
+                } => {
+                    for field in &body.fields {
+                        match output(field.ty.named()) {
+                            None => issue(
+                                issues,
+                                field.pos,
+                                format!("Unknown type `{}`", field.ty.named()),
+                            ),
+                            Some(false) => issue(
+                                issues,
+                                field.pos,
+                                format!(
+                                    "`{}` is an input type and cannot be a field type",
+                                    field.ty.named()
+                                ),
+                            ),
+                            Some(true) => {}
+                        }
+                        for argument in &field.arguments {
+                            check_input(issues, argument);
+                        }
+                    }
+                    for value in &body.input_fields {
+                        check_input(issues, value);
+                    }
+                    for interface in &body.interfaces {
+                        match self.types.get(interface) {
+                            Some(t) if t.kind == TypeKind::Interface => {
+                                let own = &self.types[name];
+                                for required in &t.fields {
+                                    if !own.fields.iter().any(|f| f.name == required.name) {
+                                        issue(issues, *pos, format!("`{}` does not define field `{}` required by interface `{}`", name, required.name, interface));
+                                    }
+                                }
+                            }
+                            Some(_) => {
+                                issue(issues, *pos, format!("`{}` is not an interface", interface))
+                            }
+                            None => {
+                                issue(issues, *pos, format!("Unknown interface `{}`", interface))
+                            }
+                        }
+                    }
+                    for member in &body.members {
+                        match self.types.get(member) {
+                            Some(t) if t.kind == TypeKind::Object => {}
+                            Some(_) => issue(
+                                issues,
+                                *pos,
+                                format!("Union member `{}` is not an object type", member),
+                            ),
+                            None => issue(
+                                issues,
+                                *pos,
+                                format!("Unknown type `{}` in union `{}`", member, name),
+                            ),
+                        }
+                    }
+                }
+                Definition::Directive { arguments, .. } => {
+                    for argument in arguments {
+                        check_input(issues, argument);
+                    }
+                }
+                Definition::Schema {
+                    operations, pos, ..
+                } => {
+                    for (operation, ty) in operations {
+                        if !matches!(self.types.get(ty), Some(t) if t.kind == TypeKind::Object) {
+                            issue(
+                                issues,
+                                *pos,
+                                format!(
+                                    "Root {} type `{}` is not a defined object type",
+                                    operation, ty
+                                ),
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn possible_types(&self, name: &str) -> HashSet<String> {
+        match self.types.get(name) {
+            Some(t) if t.kind == TypeKind::Object => HashSet::from([name.to_string()]),
+            Some(t) if t.kind == TypeKind::Union => t.members.iter().cloned().collect(),
+            Some(t) if t.kind == TypeKind::Interface => self
+                .types
+                .iter()
+                .filter(|(_, candidate)| {
+                    candidate.kind == TypeKind::Object
+                        && candidate.interfaces.iter().any(|i| i == name)
+                })
+                .map(|(n, _)| n.clone())
+                .collect(),
+            _ => HashSet::new(),
+        }
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for j in 0..b.len() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == b[j] {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(row[j + 1])
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn suggestion<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    let best = candidates
+        .map(|c| (edit_distance(&name.to_lowercase(), &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= 2.max(name.len() / 3))
+        .min();
+    best.map(|(_, c)| format!(" Did you mean `{}`?", c))
+        .unwrap_or_default()
+}
+
+/// Validates operations against a schema
+struct QueryValidator<'s, 'd> {
+    schema: &'s Schema,
+    fragments: HashMap<&'d str, &'d Definition>,
+    issues: Vec<GraphqlIssue>,
+}
+
+/// Per-operation state: declared variables and those actually used
+struct OperationScope<'d> {
+    variables: HashMap<&'d str, &'d VariableDefinition>,
+    used: HashSet<String>,
+    visited_fragments: HashSet<String>,
+}
+
+impl<'s, 'd> QueryValidator<'s, 'd> {
+    /// Variables may be used where their type is at least as strict as the location
+    fn variable_fits(variable: &TypeRef, location: &TypeRef, has_default: bool) -> bool {
+        match (variable, location) {
+            (TypeRef::NonNull(v), TypeRef::NonNull(l)) => Self::variable_fits(v, l, false),
+            (TypeRef::NonNull(v), l) => Self::variable_fits(v, l, false),
+            (v, TypeRef::NonNull(l)) => has_default && Self::variable_fits(v, l, false),
+            (TypeRef::List(v), TypeRef::List(l)) => Self::variable_fits(v, l, false),
+            (TypeRef::Named(v), TypeRef::Named(l)) => v == l,
+            _ => false,
+        }
+    }
+
+    fn check_value(
+        &mut self,
+        value: &Value,
+        ty: &TypeRef,
+        pos: Pos,
+        scope: &mut OperationScope<'d>,
+        location_default: bool,
+    ) {
+        match (value, ty) {
+            (Value::Variable(name), _) => {
+                scope.used.insert(name.clone());
+                match scope.variables.get(name.as_str()) {
+                    None => issue(
+                        &mut self.issues,
+                        pos,
+                        format!("Variable `${}` is not defined", name),
+                    ),
+                    Some(definition) => {
+                        let has_default = location_default || definition.default.is_some();
+                        if !Self::variable_fits(&definition.ty, ty, has_default) {
+                            issue(
+                                &mut self.issues,
+                                pos,
+                                format!(
+                                    "Variable `${}` of type `{}` cannot be used where `{}` is expected",
+                                    name,
+                                    definition.ty.render(),
+                                    ty.render()
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+            (Value::Null, TypeRef::NonNull(_)) => issue(
+                &mut self.issues,
+                pos,
+                format!("Expected a value of type `{}`, found null", ty.render()),
+            ),
+            (_, TypeRef::NonNull(inner)) => self.check_value(value, inner, pos, scope, false),
+            (Value::Null, _) => {}
+            (Value::List(items), TypeRef::List(inner)) => {
+                for (item_pos, item) in items {
+                    self.check_value(item, inner, *item_pos, scope, false);
+                }
+            }
+            (_, TypeRef::List(inner)) => self.check_value(value, inner, pos, scope, false),
+            (_, TypeRef::Named(name)) => self.check_named_value(value, name, pos, scope),
+        }
+    }
+
+    fn check_named_value(
+        &mut self,
+        value: &Value,
+        name: &str,
+        pos: Pos,
+        scope: &mut OperationScope<'d>,
+    ) {
+        let Some(info) = self.schema.types.get(name) else {
+            return;
+        };
+        let mismatch = |found: &str| format!("`{}` cannot represent {}", name, found);
+        let describe = |value: &Value| match value {
+            Value::Int(t) | Value::Float(t) | Value::String(t) | Value::Enum(t) => {
+                format!("the value {}", t)
+            }
+            Value::Boolean(b) => format!("the value {}", b),
+            Value::List(_) => "a list".to_string(),
+            Value::Object(_) => "an object".to_string(),
+            Value::Variable(_) | Value::Null => "this value".to_string(),
+        };
+        match info.kind {
+            TypeKind::Scalar => {
+                let ok = match (name, value) {
+                    ("Int", Value::Int(t)) => t.parse::<i32>().is_ok(),
+                    ("Float", Value::Int(_) | Value::Float(_)) => true,
+                    ("String", Value::String(_)) => true,
+                    ("Boolean", Value::Boolean(_)) => true,
+                    ("ID", Value::String(_) | Value::Int(_)) => true,
+                    ("Int" | "Float" | "String" | "Boolean" | "ID", _) => false,
+                    // Custom scalars accept any literal
+                    _ => true,
+                };
+                if !ok {
+                    issue(&mut self.issues, pos, mismatch(&describe(value)));
+                }
+            }
+            TypeKind::Enum => match value {
+                Value::Enum(variant) if info.values.contains(variant) => {}
+                Value::Enum(variant) => {
+                    let hint = suggestion(variant, info.values.iter().map(String::as_str));
+                    issue(
+                        &mut self.issues,
+                        pos,
+                        format!("`{}` is not a value of enum `{}`.{}", variant, name, hint),
+                    );
+                }
+                other => issue(&mut self.issues, pos, mismatch(&describe(other))),
+            },
+            TypeKind::InputObject => match value {
+                Value::Object(fields) => {
+                    let expected = info.input_fields.clone();
+                    for field in fields {
+                        match expected.iter().find(|f| f.name == field.name) {
+                            Some(definition) => self.check_value(
+                                &field.value,
+                                &definition.ty,
+                                field.pos,
+                                scope,
+                                definition.has_default,
+                            ),
+                            None => {
+                                let hint = suggestion(
+                                    &field.name,
+                                    expected.iter().map(|f| f.name.as_str()),
+                                );
+                                issue(
+                                    &mut self.issues,
+                                    field.pos,
+                                    format!(
+                                        "Field `{}` is not defined by input type `{}`.{}",
+                                        field.name, name, hint
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                    for required in expected
+                        .iter()
+                        .filter(|f| matches!(f.ty, TypeRef::NonNull(_)) && !f.has_default)
+                    {
+                        if !fields.iter().any(|f| f.name == required.name) {
+                            let message = format!(
+                                "Field `{}.{}` of type `{}` is required but not provided",
+                                name,
+                                required.name,
+                                required.ty.render()
+                            );
+                            issue(&mut self.issues, pos, message);
+                        }
+                    }
+                }
+                other => issue(&mut self.issues, pos, mismatch(&describe(other))),
+            },
+            _ => {}
+        }
+    }
+
+    fn check_arguments(
+        &mut self,
+        owner: &str,
+        arguments: &[Argument],
+        definitions: &[InputInfo],
+        pos: Pos,
+        scope: &mut OperationScope<'d>,
+    ) {
+        for argument in arguments {
+            match definitions.iter().find(|d| d.name == argument.name) {
+                Some(definition) => {
+                    let ty = definition.ty.clone();
+                    self.check_value(
+                        &argument.value,
+                        &ty,
+                        argument.pos,
+                        scope,
+                        definition.has_default,
+                    );
+                }
+                None => {
+                    let hint =
+                        suggestion(&argument.name, definitions.iter().map(|d| d.name.as_str()));
+                    issue(
+                        &mut self.issues,
+                        argument.pos,
+                        format!("Unknown argument `{}` on {}.{}", argument.name, owner, hint),
+                    );
+                }
+            }
+        }
+        for required in definitions
+            .iter()
+            .filter(|d| matches!(d.ty, TypeRef::NonNull(_)) && !d.has_default)
+        {
+            if !arguments.iter().any(|a| a.name == required.name) {
+                issue(
+                    &mut self.issues,
+                    pos,
+                    format!(
+                        "{} requires argument `{}` of type `{}`",
+                        owner,
+                        required.name,
+                        required.ty.render()
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_directives(&mut self, directives: &[Directive], scope: &mut OperationScope<'d>) {
+        for directive in directives {
+            match self.schema.directives.get(&directive.name) {
+                Some(definitions) => {
+                    let definitions = definitions.clone();
+                    let owner = format!("directive `@{}`", directive.name);
+                    self.check_arguments(
+                        &owner,
+                        &directive.arguments,
+                        &definitions,
+                        directive.pos,
+                        scope,
+                    );
+                }
+                None => issue(
+                    &mut self.issues,
+                    directive.pos,
+                    format!("Unknown directive `@{}`", directive.name),
+                ),
+            }
+        }
+    }
+
+    fn check_type_condition(&mut self, parent: &str, condition: &str, pos: Pos) -> bool {
+        match self.schema.types.get(condition) {
+            None => {
+                issue(
+                    &mut self.issues,
+                    pos,
+                    format!("Unknown type `{}`", condition),
+                );
+                false
+            }
+            Some(info) if !info.is_composite() => {
+                issue(
+                    &mut self.issues,
+                    pos,
+                    format!(
+                        "Fragment cannot condition on non-composite type `{}`",
+                        condition
+                    ),
+                );
+                false
+            }
+            Some(_) => {
+                let parent_types = self.schema.possible_types(parent);
+                let fragment_types = self.schema.possible_types(condition);
+                if parent_types.is_disjoint(&fragment_types) {
+                    issue(
+                        &mut self.issues,
+                        pos,
+                        format!(
+                            "Fragment on `{}` can never match inside `{}`",
+                            condition, parent
+                        ),
+                    );
+                }
+                true
+            }
+        }
+    }
+
+    fn check_selection_set(
+        &mut self,
+        set: &'d SelectionSet,
+        parent: &str,
+        scope: &mut OperationScope<'d>,
+    ) {
+        let Some(parent_info) = self.schema.types.get(parent).cloned() else {
+            return;
+        };
+        for selection in &set.selections {
+            match selection {
+                Selection::Field {
+                    name,
+                    arguments,
+                    directives,
+                    selection_set,
+                    pos,
+                    ..
+                } => {
+                    self.check_directives(directives, scope);
+                    if name == "__typename" {
+                        for argument in arguments {
+                            scope_variables(&argument.value, scope);
+                        }
+                        continue;
+                    }
+                    let Some(field) = parent_info.fields.iter().find(|f| &f.name == name) else {
+                        let message = if parent_info.kind == TypeKind::Union {
+                            format!(
+                                "Cannot query field `{}` on union `{}`; use an inline fragment",
+                                name, parent
+                            )
+                        } else {
+                            let hint = suggestion(
+                                name,
+                                parent_info.fields.iter().map(|f| f.name.as_str()),
+                            );
+                            format!(
+                                "Cannot query field `{}` on type `{}`.{}",
+                                name, parent, hint
+                            )
+                        };
+                        issue(&mut self.issues, *pos, message);
+                        if let Some(set) = selection_set {
+                            for argument in arguments {
+                                scope_variables(&argument.value, scope);
+                            }
+                            collect_set_variables(set, scope);
+                        }
+                        continue;
+                    };
+                    let field = field.clone();
+                    self.check_arguments(
+                        &format!("field `{}.{}`", parent, name),
+                        arguments,
+                        &field.arguments,
+                        *pos,
+                        scope,
+                    );
+
+                    let field_type = field.ty.named();
+                    let composite = self
+                        .schema
+                        .types
+                        .get(field_type)
+                        .is_some_and(|t| t.is_composite());
+                    match (selection_set, composite) {
+                        (Some(set), true) => self.check_selection_set(set, field_type, scope),
+                        (None, true) => issue(
+                            &mut self.issues,
+                            *pos,
+                            format!(
+                                "Field `{}` of type `{}` must have a selection of subfields",
+                                name,
+                                field.ty.render()
+                            ),
+                        ),
+                        (Some(set), false) => {
+                            issue(
+                                &mut self.issues,
+                                *pos,
+                                format!(
+                                    "Field `{}` of type `{}` cannot have a selection of subfields",
+                                    name,
+                                    field.ty.render()
+                                ),
+                            );
+                            collect_set_variables(set, scope);
+                        }
+                        (None, false) => {}
+                    }
+                }
+                Selection::FragmentSpread {
+                    name,
+                    directives,
+                    pos,
+                    ..
+                } => {
+                    self.check_directives(directives, scope);
+                    let Some(fragment) = self.fragments.get(name.as_str()).copied() else {
+                        issue(
+                            &mut self.issues,
+                            *pos,
+                            format!("Unknown fragment `{}`", name),
+                        );
+                        continue;
+                    };
+                    let Definition::Fragment {
+                        type_condition,
+                        selection_set,
+                        ..
+                    } = fragment
+                    else {
+                        continue;
+                    };
+                    if !self.check_type_condition(parent, type_condition, *pos) {
+                        continue;
+                    }
+                    if scope.visited_fragments.insert(name.clone()) {
+                        self.check_selection_set(selection_set, type_condition, scope);
+                        scope.visited_fragments.remove(name);
+                    } else {
+                        issue(
+                            &mut self.issues,
+                            *pos,
+                            format!("Fragment `{}` spreads itself", name),
+                        );
+                    }
+                }
+                Selection::InlineFragment {
+                    type_condition,
+                    directives,
+                    selection_set,
+                    pos,
+                    ..
+                } => {
+                    self.check_directives(directives, scope);
+                    let target = match type_condition {
+                        Some(condition) => {
+                            if !self.check_type_condition(parent, condition, *pos) {
+                                continue;
+                            }
+                            condition.as_str()
+                        }
+                        None => parent,
+                    };
+                    self.check_selection_set(selection_set, target, scope);
+                }
+            }
+        }
+    }
+}
+
+fn scope_variables(value: &Value, scope: &mut OperationScope) {
+    match value {
+        Value::Variable(name) => {
+            scope.used.insert(name.clone());
+        }
+        Value::List(items) => items.iter().for_each(|(_, v)| scope_variables(v, scope)),
+        Value::Object(fields) => fields.iter().for_each(|f| scope_variables(&f.value, scope)),
+        _ => {}
+    }
+}
+
+/// Mark variables used under a selection that could not be validated
+fn collect_set_variables(set: &SelectionSet, scope: &mut OperationScope) {
+    for selection in &set.selections {
+        if let Selection::Field {
+            arguments,
+            selection_set: Some(inner),
+            ..
+        } = selection
+        {
+            arguments
+                .iter()
+                .for_each(|a| scope_variables(&a.value, scope));
+            collect_set_variables(inner, scope);
+        } else if let Selection::Field { arguments, .. } = selection {
+            arguments
+                .iter()
+                .for_each(|a| scope_variables(&a.value, scope));
+        }
+    }
+}
+
+/// Checks on executable documents that do not need a schema
+fn check_executable(document: &Document, issues: &mut Vec<GraphqlIssue>) {
+    let mut operation_names = HashSet::new();
+    let mut fragment_names = HashSet::new();
+    let operations = document
+        .definitions
+        .iter()
+        .filter(|d| matches!(d, Definition::Operation { .. }))
+        .count();
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation {
+                name,
+                variables,
+                pos,
+                ..
+            } => {
+                match name {
+                    Some(name) if !operation_names.insert(name.clone()) => issue(
+                        issues,
+                        *pos,
+                        format!("There can be only one operation named `{}`", name),
+                    ),
+                    None if operations > 1 => issue(
+                        issues,
+                        *pos,
+                        "An anonymous operation must be the only operation in the document",
+                    ),
+                    _ => {}
+                }
+                let mut seen = HashSet::new();
+                for variable in variables {
+                    if !seen.insert(&variable.name) {
+                        issue(
+                            issues,
+                            variable.pos,
+                            format!("Variable `${}` is declared more than once", variable.name),
+                        );
+                    }
+                }
+            }
+            Definition::Fragment { name, pos, .. } if !fragment_names.insert(name.clone()) => {
+                issue(
+                    issues,
+                    *pos,
+                    format!("There can be only one fragment named `{}`", name),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let mut spreads = HashSet::new();
+    fn collect_spreads(set: &SelectionSet, spreads: &mut HashSet<String>) {
+        for selection in &set.selections {
+            match selection {
+                Selection::FragmentSpread { name, .. } => {
+                    spreads.insert(name.clone());
+                }
+                Selection::Field {
+                    selection_set: Some(inner),
+                    ..
+                }
+                | Selection::InlineFragment {
+                    selection_set: inner,
+                    ..
+                } => collect_spreads(inner, spreads),
+                _ => {}
+            }
+        }
+    }
+    for definition in &document.definitions {
+        if let Definition::Operation { selection_set, .. }
+        | Definition::Fragment { selection_set, .. } = definition
+        {
+            collect_spreads(selection_set, &mut spreads);
+        }
+    }
+    for definition in &document.definitions {
+        if let Definition::Fragment { name, pos, .. } = definition {
+            if !spreads.contains(name) {
+                issue(issues, *pos, format!("Fragment `{}` is never used", name));
+            }
+        }
+    }
+    for name in spreads.difference(&fragment_names) {
+        let pos = find_spread(document, name).unwrap_or_default();
+        issue(issues, pos, format!("Unknown fragment `{}`", name));
+    }
+}
+
+fn find_spread(document: &Document, target: &str) -> Option<Pos> {
+    fn search(set: &SelectionSet, target: &str) -> Option<Pos> {
+        set.selections.iter().find_map(|selection| match selection {
+            Selection::FragmentSpread { name, pos, .. } if name == target => Some(*pos),
+            Selection::Field {
+                selection_set: Some(inner),
+                ..
+            }
+            | Selection::InlineFragment {
+                selection_set: inner,
+                ..
+            } => search(inner, target),
+            _ => None,
+        })
+    }
+    document.definitions.iter().find_map(|d| match d {
+        Definition::Operation { selection_set, .. }
+        | Definition::Fragment { selection_set, .. } => search(selection_set, target),
+        _ => None,
+    })
+}
+
+/// Validate GraphQL syntax, plus the checks that need no schema.
+///
+/// SDL documents are checked for unknown and misused type references.
+pub fn validate_graphql(input: &str) -> GraphqlValidation {
+    let document = match parse(input) {
+        Ok(document) => document,
+        Err(e) => return GraphqlValidation::from_issues(vec![e.into()]),
+    };
+    let mut issues = Vec::new();
+    check_executable(&document, &mut issues);
+    let has_types = document.definitions.iter().any(|d| {
+        matches!(
+            d,
+            Definition::Type { .. } | Definition::Schema { .. } | Definition::Directive { .. }
+        )
+    });
+    if has_types {
+        let schema = Schema::build(&document, &mut issues);
+        schema.check_references(&document, &mut issues);
+    }
+    GraphqlValidation::from_issues(issues)
+}
+
+/// Validate a query document against an SDL schema
+pub fn validate_graphql_query(
+    query: &str,
+    schema_sdl: &str,
+) -> Result<GraphqlValidation, GraphqlError> {
+    let schema_document =
+        parse(schema_sdl).map_err(|e| GraphqlError::SchemaError(e.to_string()))?;
+    let mut schema_issues = Vec::new();
+    let schema = Schema::build(&schema_document, &mut schema_issues);
+    schema.check_references(&schema_document, &mut schema_issues);
+    if let Some(first) = schema_issues.first() {
+        return Err(GraphqlError::SchemaError(format!(
+            "{} (line {})",
+            first.message, first.line
+        )));
+    }
+
+    let document = match parse(query) {
+        Ok(document) => document,
+        Err(e) => return Ok(GraphqlValidation::from_issues(vec![e.into()])),
+    };
+    let mut issues = Vec::new();
+    check_executable(&document, &mut issues);
+
+    let fragments = document
+        .definitions
+        .iter()
+        .filter_map(|d| match d {
+            Definition::Fragment { name, .. } => Some((name.as_str(), d)),
+            _ => None,
+        })
+        .collect();
+    let mut validator = QueryValidator {
+        schema: &schema,
+        fragments,
+        issues,
+    };
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation {
+                kind,
+                variables,
+                directives,
+                selection_set,
+                pos,
+                ..
+            } => {
+                let kind = kind.as_deref().unwrap_or("query");
+                let mut scope = OperationScope {
+                    variables: variables.iter().map(|v| (v.name.as_str(), v)).collect(),
+                    used: HashSet::new(),
+                    visited_fragments: HashSet::new(),
+                };
+                for variable in variables {
+                    match schema.types.get(variable.ty.named()) {
+                        None => issue(
+                            &mut validator.issues,
+                            variable.pos,
+                            format!("Unknown type `{}`", variable.ty.named()),
+                        ),
+                        Some(info) if !info.is_input() => issue(
+                            &mut validator.issues,
+                            variable.pos,
+                            format!(
+                                "Variable `${}` cannot be of output type `{}`",
+                                variable.name,
+                                variable.ty.render()
+                            ),
+                        ),
+                        Some(_) => {
+                            if let Some(default) = &variable.default {
+                                validator.check_value(
+                                    default,
+                                    &variable.ty,
+                                    variable.pos,
+                                    &mut scope,
+                                    true,
+                                );
+                            }
+                        }
+                    }
+                }
+                validator.check_directives(directives, &mut scope);
+                match schema.roots.get(kind) {
+                    Some(root) => validator.check_selection_set(selection_set, root, &mut scope),
+                    None => issue(
+                        &mut validator.issues,
+                        *pos,
+                        format!("Schema does not define a {} type", kind),
+                    ),
+                }
+                for variable in variables {
+                    if !scope.used.contains(&variable.name) {
+                        issue(
+                            &mut validator.issues,
+                            variable.pos,
+                            format!("Variable `${}` is never used", variable.name),
+                        );
+                    }
+                }
+            }
+            Definition::Fragment {
+                type_condition,
+                pos,
+                ..
+            } => {
+                if !schema
+                    .types
+                    .get(type_condition)
+                    .is_some_and(|t| t.is_composite())
+                {
+                    issue(
+                        &mut validator.issues,
+                        *pos,
+                        format!(
+                            "Fragment on unknown or non-composite type `{}`",
+                            type_condition
+                        ),
+                    );
+                }
+            }
+            _ => issue(
+                &mut validator.issues,
+                Pos::default(),
+                "Query documents cannot contain type definitions",
+            ),
+        }
+    }
+
+    Ok(GraphqlValidation::from_issues(validator.issues))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+"""
+Root query
+"""
+type Query {
+  user(id: ID!): User
+  users(first: Int = 10, role: Role, filter: UserFilter): [User!]!
+  search(term: String!): [SearchResult!]!
+}
+
+type Mutation {
+  createUser(input: CreateUserInput!): User!
+}
+
+interface Node { id: ID! }
+
+type User implements Node {
+  id: ID!
+  name: String!
+  role: Role!
+  friends(first: Int): [User!]!
+}
+
+type Post implements Node {
+  id: ID!
+  title: String!
+}
+
+union SearchResult = User | Post
+
+enum Role { ADMIN EDITOR VIEWER }
+
+input UserFilter { nameContains: String, roles: [Role!] }
+
+input CreateUserInput {
+  name: String!
+  role: Role = VIEWER
+}
+"#;
+
+    #[test]
+    fn test_format_query() {
+        let query = r#"query GetUser($id: ID!, $first: Int = 5) { user(id: $id) { id, name # display name
+ friends(first: $first) @include(if: true) { ...UserFields } } }
+fragment UserFields on User { id name role }"#;
+        let expected = r#"query GetUser($id: ID!, $first: Int = 5) {
+  user(id: $id) {
+    id
+    name
+    # display name
+    friends(first: $first) @include(if: true) {
+      ...UserFields
+    }
+  }
+}
+
+fragment UserFields on User {
+  id
+  name
+  role
+}
+"#;
+        assert_eq!(format_graphql(query, 2).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_sdl() {
+        let sdl = r#"   """
+    A person
+      with details
+    """
+ type User implements Node&Entity @key(fields: "id") { id: ID!  "The display name" name(format: NameFormat = FULL): String @deprecated(reason: "use fullName") }
+union Result=User|Post
+enum Role{ADMIN VIEWER}
+input Filter { roles: [Role!] = [ADMIN], limit: Int }"#;
+        let expected = r#""""
+A person
+  with details
+"""
+type User implements Node & Entity @key(fields: "id") {
+  id: ID!
+  "The display name"
+  name(format: NameFormat = FULL): String @deprecated(reason: "use fullName")
+}
+
+union Result = User | Post
+
+enum Role {
+  ADMIN
+  VIEWER
+}
+
+input Filter {
+  roles: [Role!] = [ADMIN]
+  limit: Int
+}
+"#;
+        assert_eq!(format_graphql(sdl, 2).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_utf8_block_string_description() {
+        let sdl = "\"\"\"Descripción del usuario — ñandú \\\"\"\" ok\"\"\" type Usuario { \"año\" anio: Int }";
+        let formatted = format_graphql(sdl, 2).unwrap();
+        assert!(formatted.starts_with("\"\"\"Descripción del usuario — ñandú \\\"\"\" ok\"\"\"\ntype Usuario {"));
+        assert!(minify_graphql(sdl).unwrap().contains("Descripción"));
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let selections = format!("{}{}", "{a".repeat(2000), "}".repeat(2000));
+        assert!(matches!(
+            format_graphql(&selections, 2),
+            Err(GraphqlError::SyntaxError { .. })
+        ));
+        let list = format!("{{ a(x: {}) }}", "[".repeat(5000));
+        assert!(format_graphql(&list, 2).is_err());
+        let ty = format!("type T {{ a: {}Int{} }}", "[".repeat(5000), "]".repeat(5000));
+        assert!(!validate_graphql(&ty).valid);
+
+        let ok = format!("{}{}", "{a".repeat(MAX_DEPTH), "}".repeat(MAX_DEPTH));
+        assert!(format_graphql(&ok, 2).is_ok());
+        assert!(minify_graphql(&ok).is_ok());
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let formatted = format_graphql(SCHEMA, 4).unwrap();
+        assert_eq!(format_graphql(&formatted, 4).unwrap(), formatted);
+        assert!(formatted
+            .contains("    users(first: Int = 10, role: Role, filter: UserFilter): [User!]!\n"));
+    }
+
+    #[test]
+    fn test_minify_graphql() {
+        let query = "query Q($id: ID!) {\n  user(id: $id, tag: \"a\") { # comment\n    id\n    ... on User { name }\n  }\n}";
+        assert_eq!(
+            minify_graphql(query).unwrap(),
+            r#"query Q($id:ID!){user(id:$id tag:"a"){id...on User{name}}}"#
+        );
+        assert!(minify_graphql("{ user(").is_err());
+    }
+
+    #[test]
+    fn test_validate_graphql_syntax() {
+        assert!(validate_graphql("{ user { id } }").valid);
+        assert!(validate_graphql(SCHEMA).valid);
+
+        let report = validate_graphql("query {\n  user(id: ) { id }\n}");
+        assert!(!report.valid);
+        assert_eq!(report.issues[0].line, 2);
+        assert_eq!(report.issues[0].message, "expected a value, found `)`");
+
+        let report =
+            validate_graphql("type A { b: Missing, c: In }\ninput In { x: A }\nunion U = In");
+        let messages: Vec<&str> = report.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown type `Missing`",
+                "`In` is an input type and cannot be a field type",
+                "`A` is not an input type",
+                "Union member `In` is not an object type",
+            ]
+        );
+
+        let report = validate_graphql("{ a { ...Missing } }\nfragment Unused on A { b }");
+        let messages: Vec<&str> = report.issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown fragment `Missing`",
+                "Fragment `Unused` is never used"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_query_against_schema() {
+        let query = r#"query Q($id: ID!, $role: Role, $unused: Int) {
+  user(id: $id) { id nmae friends { id } }
+  users(first: "ten", role: SUPERUSER, filter: { nameContains: "a", roles: [$role] }) { name }
+  search { ... on Post { title } ... on User { name } }
+  create: user { id }
+  plain: user(id: 1) { role { x } name @skip(if: $missing) }
+}"#;
+        let report = validate_graphql_query(query, SCHEMA).unwrap();
+        assert!(!report.valid);
+        let messages: Vec<(usize, &str)> = report
+            .issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect();
+        for expected in [
+            (1, "Variable `$unused` is never used"),
+            (
+                2,
+                "Cannot query field `nmae` on type `User`. Did you mean `name`?",
+            ),
+            (3, "`Int` cannot represent the value \"ten\""),
+            (3, "`SUPERUSER` is not a value of enum `Role`."),
+            (
+                3,
+                "Variable `$role` of type `Role` cannot be used where `Role!` is expected",
+            ),
+            (
+                4,
+                "field `Query.search` requires argument `term` of type `String!`",
+            ),
+            (5, "field `Query.user` requires argument `id` of type `ID!`"),
+            (
+                6,
+                "Field `role` of type `Role!` cannot have a selection of subfields",
+            ),
+            (6, "Variable `$missing` is not defined"),
+        ] {
+            assert!(
+                messages.contains(&expected),
+                "missing {:?} in {:?}",
+                expected,
+                messages
+            );
+        }
+        assert_eq!(report.issues.len(), 9, "{:?}", messages);
+    }
+
+    #[test]
+    fn test_validate_query_valid_and_schema_errors() {
+        let query = r#"mutation Create($name: String!) {
+  createUser(input: { name: $name, role: ADMIN }) { id __typename }
+}
+query Search { search(term: "x") { __typename ...Named ... on Post { title } } }
+fragment Named on User { name friends(first: 2) { id } }"#;
+        let report = validate_graphql_query(query, SCHEMA).unwrap();
+        assert!(report.valid, "{:?}", report.issues);
+
+        let report = validate_graphql_query("{ search(term: \"x\") { title } }", SCHEMA).unwrap();
+        assert_eq!(
+            report.issues[0].message,
+            "Cannot query field `title` on union `SearchResult`; use an inline fragment"
+        );
+        let report = validate_graphql_query("subscription { x }", SCHEMA).unwrap();
+        assert_eq!(
+            report.issues[0].message,
+            "Schema does not define a subscription type"
+        );
+
+        assert!(validate_graphql_query("{ a }", "type Query { a: Nope }").is_err());
+        assert!(validate_graphql_query("{ a }", "type Query {").is_err());
+    }
+}
@@ -0,0 +1,1145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::css::{format_css, minify_css};
+use super::javascript::format_js;
+use super::json::{format_json, minify_json};
+
+#[derive(Error, Debug)]
+pub enum HtmlError {
+    #[error("HTML parse error at line {line}: {message}")]
+    ParseError { line: usize, message: String },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HtmlIssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HtmlIssue {
+    pub line: usize,
+    pub severity: HtmlIssueSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HtmlValidation {
+    /// True when there are no errors; warnings alone keep a document valid
+    pub valid: bool,
+    pub issues: Vec<HtmlIssue>,
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param",
+    "source", "track", "wbr",
+];
+
+/// Elements whose content is not parsed as markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements whose whitespace is significant
+const PRESERVE_ELEMENTS: &[&str] = &["pre", "textarea"];
+
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "del", "dfn", "em",
+    "i", "img", "input", "ins", "kbd", "label", "mark", "meter", "output", "picture", "progress",
+    "q", "ruby", "rp", "rt", "s", "samp", "select", "small", "span", "strong", "sub", "sup",
+    "time", "u", "var", "wbr",
+];
+
+/// Flow content that is not phrasing content
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Elements that may only contain phrasing content
+const PHRASING_PARENTS: &[&str] = &[
+    "abbr", "b", "bdi", "bdo", "button", "cite", "code", "dfn", "em", "h1", "h2", "h3", "h4", "h5",
+    "h6", "i", "kbd", "label", "mark", "p", "pre", "q", "s", "samp", "small", "span", "strong",
+    "sub", "sup", "u", "var",
+];
+
+/// Elements whose end tag may be omitted
+const OPTIONAL_END_TAGS: &[&str] = &[
+    "body", "caption", "colgroup", "dd", "dt", "head", "html", "li", "optgroup", "option", "p",
+    "rp", "rt", "tbody", "td", "tfoot", "th", "thead", "tr",
+];
+
+/// Elements that may not contain another element of the same kind
+const NO_SELF_NESTING: &[&str] = &["a", "button", "form", "label"];
+
+/// Required parents for elements that only make sense in a specific container
+const REQUIRED_PARENTS: &[(&str, &[&str])] = &[
+    ("li", &["ul", "ol", "menu"]),
+    ("dt", &["dl", "div"]),
+    ("dd", &["dl", "div"]),
+    ("tr", &["table", "thead", "tbody", "tfoot"]),
+    ("td", &["tr"]),
+    ("th", &["tr"]),
+    ("thead", &["table"]),
+    ("tbody", &["table"]),
+    ("tfoot", &["table"]),
+    ("caption", &["table"]),
+    ("colgroup", &["table"]),
+    ("option", &["select", "optgroup", "datalist"]),
+    ("optgroup", &["select"]),
+    ("figcaption", &["figure"]),
+    ("summary", &["details"]),
+];
+
+/// Start tags that implicitly close an open element of the listed kinds
+const IMPLIED_END: &[(&str, &[&str])] = &[
+    ("li", &["li"]),
+    ("dt", &["dt", "dd"]),
+    ("dd", &["dt", "dd"]),
+    ("tr", &["tr", "td", "th"]),
+    ("td", &["td", "th"]),
+    ("th", &["td", "th"]),
+    ("option", &["option"]),
+    ("optgroup", &["optgroup", "option"]),
+    ("thead", &["tbody", "tfoot", "tr", "td", "th"]),
+    ("tbody", &["thead", "tbody", "tr", "td", "th"]),
+    ("tfoot", &["thead", "tbody", "tr", "td", "th"]),
+];
+
+#[derive(Debug, Clone)]
+struct Attribute {
+    name: String,
+    /// Raw value as written, entities left untouched
+    value: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    /// Lowercase name used for all structural rules
+    name: String,
+    /// Name as written, so SVG and MathML keep their camel case
+    tag: String,
+    self_closing: bool,
+    attrs: Vec<Attribute>,
+    children: Vec<Node>,
+    line: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Element(Element),
+    Text(String),
+    Comment(String),
+    /// `<!DOCTYPE ...>` and other `<!...>` / `<?...>` declarations, stored verbatim
+    Declaration(String),
+}
+
+#[derive(Debug)]
+enum Token {
+    StartTag {
+        name: String,
+        tag: String,
+        attrs: Vec<Attribute>,
+        self_closing: bool,
+        line: usize,
+    },
+    EndTag {
+        name: String,
+        line: usize,
+    },
+    Text(String),
+    Comment(String),
+    Declaration(String),
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    pos: usize,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            input,
+            pos: 0,
+            line_starts,
+        }
+    }
+
+    fn line_at(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        }
+    }
+
+    fn error(&self, offset: usize, message: impl Into<String>) -> HtmlError {
+        HtmlError::ParseError {
+            line: self.line_at(offset),
+            message: message.into(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, HtmlError> {
+        let mut tokens = Vec::new();
+        while self.pos < self.input.len() {
+            let rest = self.rest();
+            let start = self.pos;
+
+            if let Some(after) = rest.strip_prefix("<!--") {
+                let end = after
+                    .find("-->")
+                    .ok_or_else(|| self.error(start, "unterminated comment"))?;
+                tokens.push(Token::Comment(after[..end].to_string()));
+                self.pos += 4 + end + 3;
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                let end = rest
+                    .find('>')
+                    .ok_or_else(|| self.error(start, "unterminated declaration"))?;
+                tokens.push(Token::Declaration(rest[..=end].to_string()));
+                self.pos += end + 1;
+            } else if rest.starts_with("</")
+                && rest[2..].starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                let end = rest
+                    .find('>')
+                    .ok_or_else(|| self.error(start, "unterminated end tag"))?;
+                let name = rest[2..end].trim().to_ascii_lowercase();
+                tokens.push(Token::EndTag {
+                    name,
+                    line: self.line_at(start),
+                });
+                self.pos += end + 1;
+            } else if rest.starts_with('<')
+                && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                let token = self.start_tag()?;
+                if let Token::StartTag {
+                    name, self_closing, ..
+                } = &token
+                {
+                    let raw_name = name.clone();
+                    let raw_text = !self_closing && RAW_TEXT_ELEMENTS.contains(&raw_name.as_str());
+                    tokens.push(token);
+                    if raw_text {
+                        let content = self.raw_text(&raw_name);
+                        if !content.is_empty() {
+                            tokens.push(Token::Text(content.to_string()));
+                        }
+                    }
+                }
+            } else {
+                // Text, or a lone `<` that does not open a tag; skip a whole character
+                let first = rest.chars().next().map_or(1, char::len_utf8);
+                let end = rest[first..].find('<').map(|i| i + first).unwrap_or(rest.len());
+                tokens.push(Token::Text(rest[..end].to_string()));
+                self.pos += end;
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn start_tag(&mut self) -> Result<Token, HtmlError> {
+        let start = self.pos;
+        let line = self.line_at(start);
+        let bytes = self.input.as_bytes();
+        let mut i = self.pos + 1;
+        let name_end = self.input[i..]
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .map(|n| i + n)
+            .unwrap_or(self.input.len());
+        let tag = self.input[i..name_end].to_string();
+        let name = tag.to_ascii_lowercase();
+        i = name_end;
+
+        let mut attrs = Vec::new();
+        let mut self_closing = false;
+        loop {
+            while i < bytes.len() && (bytes[i] as char).is_whitespace() {
+                i += 1;
+            }
+            if i >= bytes.len() {
+                return Err(self.error(start, format!("unterminated <{}> tag", name)));
+            }
+            match bytes[i] {
+                b'>' => {
+                    i += 1;
+                    break;
+                }
+                b'/' => {
+                    i += 1;
+                    self_closing = i < bytes.len() && bytes[i] == b'>';
+                    continue;
+                }
+                _ => {}
+            }
+
+            let attr_end = self.input[i..]
+                .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+                .map(|n| i + n)
+                .unwrap_or(self.input.len());
+            let attr_name = self.input[i..attr_end].to_string();
+            i = attr_end;
+            while i < bytes.len() && (bytes[i] as char).is_whitespace() {
+                i += 1;
+            }
+
+            let mut value = None;
+            if i < bytes.len() && bytes[i] == b'=' {
+                i += 1;
+                while i < bytes.len() && (bytes[i] as char).is_whitespace() {
+                    i += 1;
+                }
+                if i < bytes.len() && (bytes[i] == b'"' || bytes[i] == b'\'') {
+                    let quote = bytes[i] as char;
+                    let close = self.input[i + 1..].find(quote).ok_or_else(|| {
+                        self.error(start, format!("unterminated attribute value in <{}>", name))
+                    })?;
+                    value = Some(self.input[i + 1..i + 1 + close].to_string());
+                    i += close + 2;
+                } else {
+                    let end = self.input[i..]
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .map(|n| i + n)
+                        .unwrap_or(self.input.len());
+                    value = Some(self.input[i..end].to_string());
+                    i = end;
+                }
+            }
+            attrs.push(Attribute {
+                name: attr_name,
+                value,
+            });
+        }
+
+        self.pos = i;
+        Ok(Token::StartTag {
+            name,
+            tag,
+            attrs,
+            self_closing,
+            line,
+        })
+    }
+
+    /// Consume the content of a raw text element up to its end tag
+    fn raw_text(&mut self, name: &str) -> &'a str {
+        let rest = self.rest();
+        let lower = rest.to_ascii_lowercase();
+        let closing = format!("</{}", name);
+        let end = lower.find(&closing).unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+}
+
+fn is(list: &[&str], name: &str) -> bool {
+    list.contains(&name)
+}
+
+/// Builds the element tree, recording structural problems along the way
+struct TreeBuilder {
+    stack: Vec<Element>,
+    roots: Vec<Node>,
+    issues: Vec<HtmlIssue>,
+    ids: HashMap<String, usize>,
+}
+
+impl TreeBuilder {
+    fn issue(&mut self, line: usize, severity: HtmlIssueSeverity, message: String) {
+        self.issues.push(HtmlIssue {
+            line,
+            severity,
+            message,
+        });
+    }
+
+    fn append(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.roots.push(node),
+        }
+    }
+
+    fn close_top(&mut self) {
+        if let Some(element) = self.stack.pop() {
+            self.append(Node::Element(element));
+        }
+    }
+
+    fn current(&self) -> Option<&str> {
+        self.stack.last().map(|e| e.name.as_str())
+    }
+
+    fn start_tag(
+        &mut self,
+        name: String,
+        tag: String,
+        attrs: Vec<Attribute>,
+        self_closing: bool,
+        line: usize,
+    ) {
+        if let Some((_, closes)) = IMPLIED_END.iter().find(|(tag, _)| *tag == name) {
+            while self
+                .current()
+                .is_some_and(|current| closes.contains(&current))
+            {
+                self.close_top();
+            }
+        }
+
+        if is(BLOCK_ELEMENTS, &name) {
+            if let Some(parent) = self.current().map(str::to_string) {
+                if parent == "p" {
+                    self.issue(
+                        line,
+                        HtmlIssueSeverity::Warning,
+                        format!("<{}> implicitly closes the open <p>", name),
+                    );
+                    self.close_top();
+                } else if is(PHRASING_PARENTS, &parent) {
+                    self.issue(
+                        line,
+                        HtmlIssueSeverity::Error,
+                        format!("<{}> is not allowed inside <{}>", name, parent),
+                    );
+                }
+            }
+        }
+
+        if is(NO_SELF_NESTING, &name) && self.stack.iter().any(|e| e.name == name) {
+            self.issue(
+                line,
+                HtmlIssueSeverity::Error,
+                format!("<{}> cannot be nested inside another <{}>", name, name),
+            );
+        }
+
+        if let Some((_, parents)) = REQUIRED_PARENTS.iter().find(|(tag, _)| *tag == name) {
+            let parent = self.current().unwrap_or("");
+            if !parents.contains(&parent) {
+                let parent = if parent.is_empty() {
+                    "the document root".to_string()
+                } else {
+                    format!("<{}>", parent)
+                };
+                self.issue(
+                    line,
+                    HtmlIssueSeverity::Error,
+                    format!(
+                        "<{}> must be inside <{}>, found in {}",
+                        name,
+                        parents.join("> or <"),
+                        parent
+                    ),
+                );
+            }
+        }
+
+        let mut seen = Vec::new();
+        for attr in &attrs {
+            let attr_name = attr.name.to_ascii_lowercase();
+            if seen.contains(&attr_name) {
+                self.issue(
+                    line,
+                    HtmlIssueSeverity::Error,
+                    format!("Duplicate attribute `{}` on <{}>", attr.name, name),
+                );
+            }
+            if attr_name == "id" {
+                let id = attr.value.clone().unwrap_or_default();
+                if id.is_empty() {
+                    self.issue(
+                        line,
+                        HtmlIssueSeverity::Error,
+                        format!("Empty id on <{}>", name),
+                    );
+                } else if let Some(first) = self.ids.get(&id) {
+                    let message = format!("Duplicate id `{}` (first used on line {})", id, first);
+                    self.issue(line, HtmlIssueSeverity::Error, message);
+                } else {
+                    self.ids.insert(id, line);
+                }
+            }
+            seen.push(attr_name);
+        }
+
+        let element = Element {
+            name,
+            tag,
+            self_closing,
+            attrs,
+            children: Vec::new(),
+            line,
+        };
+        // `/>` only closes foreign elements such as SVG children; void elements never have content
+        if self_closing || is(VOID_ELEMENTS, &element.name) {
+            self.append(Node::Element(element));
+        } else {
+            self.stack.push(element);
+        }
+    }
+
+    fn end_tag(&mut self, name: &str, line: usize) {
+        if is(VOID_ELEMENTS, name) {
+            self.issue(
+                line,
+                HtmlIssueSeverity::Warning,
+                format!("</{}> is not needed; <{}> is a void element", name, name),
+            );
+            return;
+        }
+        let Some(index) = self.stack.iter().rposition(|e| e.name == name) else {
+            self.issue(
+                line,
+                HtmlIssueSeverity::Error,
+                format!("Closing tag </{}> has no matching opening tag", name),
+            );
+            return;
+        };
+        while self.stack.len() > index + 1 {
+            let open = self.stack.last().unwrap();
+            if !is(OPTIONAL_END_TAGS, &open.name) {
+                let message = format!(
+                    "<{}> opened on line {} is not closed before </{}>",
+                    open.name, open.line, name
+                );
+                self.issue(line, HtmlIssueSeverity::Error, message);
+            }
+            self.close_top();
+        }
+        self.close_top();
+    }
+
+    fn finish(mut self) -> (Vec<Node>, Vec<HtmlIssue>) {
+        while let Some(open) = self.stack.last() {
+            if !is(OPTIONAL_END_TAGS, &open.name) {
+                let message = format!(
+                    "<{}> opened on line {} is never closed",
+                    open.name, open.line
+                );
+                let line = open.line;
+                self.issue(line, HtmlIssueSeverity::Error, message);
+            }
+            self.close_top();
+        }
+        (self.roots, self.issues)
+    }
+}
+
+/// Deepest element nesting accepted; printing and minifying recurse once per level
+const MAX_DEPTH: usize = 512;
+
+fn parse(html: &str) -> Result<(Vec<Node>, Vec<HtmlIssue>), HtmlError> {
+    let tokens = Tokenizer::new(html).tokenize()?;
+    let mut builder = TreeBuilder {
+        stack: Vec::new(),
+        roots: Vec::new(),
+        issues: Vec::new(),
+        ids: HashMap::new(),
+    };
+    for token in tokens {
+        match token {
+            Token::StartTag { line, .. } if builder.stack.len() >= MAX_DEPTH => {
+                return Err(HtmlError::ParseError {
+                    line,
+                    message: format!("Elements are nested more than {} levels deep", MAX_DEPTH),
+                });
+            }
+            Token::StartTag {
+                name,
+                tag,
+                attrs,
+                self_closing,
+                line,
+            } => builder.start_tag(name, tag, attrs, self_closing, line),
+            Token::EndTag { name, line } => builder.end_tag(&name, line),
+            Token::Text(text) => builder.append(Node::Text(text)),
+            Token::Comment(text) => builder.append(Node::Comment(text)),
+            Token::Declaration(text) => builder.append(Node::Declaration(text)),
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_space = false;
+    for ch in text.chars() {
+        if ch.is_ascii_whitespace() {
+            if !in_space {
+                result.push(' ');
+            }
+            in_space = true;
+        } else {
+            result.push(ch);
+            in_space = false;
+        }
+    }
+    result
+}
+
+/// Attribute values can drop their quotes when they contain none of these characters
+fn can_unquote(value: &str) -> bool {
+    !value.is_empty()
+        && !value.ends_with('/')
+        && !value
+            .chars()
+            .any(|c| c.is_ascii_whitespace() || matches!(c, '"' | '\'' | '=' | '<' | '>' | '`'))
+}
+
+fn write_open_tag(out: &mut String, element: &Element, minify: bool) {
+    out.push('<');
+    out.push_str(&element.tag);
+    for attr in &element.attrs {
+        out.push(' ');
+        out.push_str(&attr.name);
+        if let Some(value) = &attr.value {
+            out.push('=');
+            if minify && can_unquote(value) {
+                out.push_str(value);
+            } else if value.contains('"') {
+                out.push('\'');
+                out.push_str(value);
+                out.push('\'');
+            } else {
+                out.push('"');
+                out.push_str(value);
+                out.push('"');
+            }
+        }
+    }
+    if element.self_closing && !is(VOID_ELEMENTS, &element.name) {
+        out.push_str(" /");
+    }
+    out.push('>');
+}
+
+fn write_close_tag(out: &mut String, element: &Element) {
+    if !element.self_closing && !is(VOID_ELEMENTS, &element.name) {
+        out.push_str("</");
+        out.push_str(&element.tag);
+        out.push('>');
+    }
+}
+
+/// Serialize nodes exactly as parsed, used for whitespace-sensitive content
+fn write_verbatim(out: &mut String, nodes: &[Node]) {
+    for node in nodes {
+        match node {
+            Node::Element(element) => {
+                write_open_tag(out, element, false);
+                write_verbatim(out, &element.children);
+                write_close_tag(out, element);
+            }
+            Node::Text(text) => out.push_str(text),
+            Node::Comment(text) => {
+                out.push_str("<!--");
+                out.push_str(text);
+                out.push_str("-->");
+            }
+            Node::Declaration(text) => out.push_str(text),
+        }
+    }
+}
+
+fn raw_content(element: &Element) -> String {
+    let mut content = String::new();
+    write_verbatim(&mut content, &element.children);
+    content
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EmbeddedKind {
+    Script,
+    Json,
+    Style,
+    Other,
+}
+
+fn embedded_kind(element: &Element) -> EmbeddedKind {
+    if element.name == "style" {
+        return EmbeddedKind::Style;
+    }
+    let script_type = element
+        .attrs
+        .iter()
+        .find(|a| a.name.eq_ignore_ascii_case("type"))
+        .and_then(|a| a.value.as_deref())
+        .map(|t| t.trim().to_ascii_lowercase());
+    match script_type.as_deref() {
+        None
+        | Some("")
+        | Some("module")
+        | Some("text/javascript")
+        | Some("application/javascript") => EmbeddedKind::Script,
+        Some("application/json") | Some("application/ld+json") | Some("importmap") => {
+            EmbeddedKind::Json
+        }
+        _ => EmbeddedKind::Other,
+    }
+}
+
+/// Remove the common leading indentation from a block of text
+fn dedent(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let common = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let start = lines
+        .iter()
+        .position(|l| !l.trim().is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end]
+        .iter()
+        .map(|l| l.get(common..).unwrap_or("").trim_end().to_string())
+        .collect()
+}
+
+struct Formatter {
+    indent: String,
+    indent_size: usize,
+    out: String,
+}
+
+impl Formatter {
+    fn line(&mut self, level: usize, text: &str) {
+        if text.is_empty() {
+            self.out.push('\n');
+            return;
+        }
+        self.out.push_str(&self.indent.repeat(level));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn is_inline(node: &Node) -> bool {
+        match node {
+            Node::Text(_) => true,
+            Node::Element(element) => {
+                is(INLINE_ELEMENTS, &element.name) && element.children.iter().all(Self::is_inline)
+            }
+            Node::Comment(_) | Node::Declaration(_) => false,
+        }
+    }
+
+    fn inline(out: &mut String, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(&collapse_whitespace(text)),
+                Node::Element(element) => {
+                    write_open_tag(out, element, false);
+                    Self::inline(out, &element.children);
+                    write_close_tag(out, element);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn nodes(&mut self, nodes: &[Node], level: usize) {
+        let mut run: Vec<&Node> = Vec::new();
+        for node in nodes {
+            if Self::is_inline(node) {
+                run.push(node);
+                continue;
+            }
+            self.flush_inline(&mut run, level);
+            match node {
+                Node::Element(element) => self.element(element, level),
+                Node::Comment(text) => self.line(level, &format!("<!--{}-->", text)),
+                Node::Declaration(text) => self.line(level, text),
+                Node::Text(_) => unreachable!(),
+            }
+        }
+        self.flush_inline(&mut run, level);
+    }
+
+    fn flush_inline(&mut self, run: &mut Vec<&Node>, level: usize) {
+        if run.is_empty() {
+            return;
+        }
+        let mut text = String::new();
+        let nodes: Vec<Node> = run.drain(..).cloned().collect();
+        Self::inline(&mut text, &nodes);
+        let text = text.trim();
+        if !text.is_empty() {
+            self.line(level, text);
+        }
+    }
+
+    fn element(&mut self, element: &Element, level: usize) {
+        let mut open = String::new();
+        write_open_tag(&mut open, element, false);
+        let mut close = String::new();
+        write_close_tag(&mut close, element);
+
+        if element.self_closing || is(VOID_ELEMENTS, &element.name) {
+            self.line(level, &open);
+            return;
+        }
+
+        if is(PRESERVE_ELEMENTS, &element.name) {
+            let mut text = open;
+            text.push_str(&raw_content(element));
+            text.push_str(&close);
+            self.line(level, &text);
+            return;
+        }
+
+        if element.name == "script" || element.name == "style" {
+            let content = raw_content(element);
+            if content.trim().is_empty() {
+                self.line(level, &format!("{}{}", open, close));
+                return;
+            }
+            self.line(level, &open);
+            for line in self.embedded(element, &content) {
+                self.line(level + 1, &line);
+            }
+            self.line(level, &close);
+            return;
+        }
+
+        if element.children.iter().all(Self::is_inline) {
+            let mut text = open;
+            let mut inner = String::new();
+            Self::inline(&mut inner, &element.children);
+            text.push_str(inner.trim());
+            text.push_str(&close);
+            self.line(level, &text);
+            return;
+        }
+
+        self.line(level, &open);
+        self.nodes(&element.children, level + 1);
+        self.line(level, &close);
+    }
+
+    /// Format script and style content with the matching formatter, falling back to dedenting
+    fn embedded(&self, element: &Element, content: &str) -> Vec<String> {
+        let formatted = match embedded_kind(element) {
+            EmbeddedKind::Script => format_js(&dedent(content).join("\n"), self.indent_size).ok(),
+            EmbeddedKind::Style => format_css(content, self.indent_size).ok(),
+            EmbeddedKind::Json => format_json(content, self.indent_size).ok(),
+            EmbeddedKind::Other => None,
+        };
+        dedent(formatted.as_deref().unwrap_or(content))
+    }
+}
+
+/// Format HTML with indentation, keeping whitespace-sensitive content intact
+pub fn format_html(html: &str, indent_size: usize) -> Result<String, HtmlError> {
+    let (nodes, _) = parse(html)?;
+    let mut formatter = Formatter {
+        indent: " ".repeat(indent_size),
+        indent_size,
+        out: String::new(),
+    };
+    formatter.nodes(&nodes, 0);
+    Ok(formatter.out)
+}
+
+fn minify_embedded(element: &Element, content: &str) -> String {
+    let minified = match embedded_kind(element) {
+        EmbeddedKind::Style => minify_css(content).ok(),
+        EmbeddedKind::Json => minify_json(content).ok(),
+        // Joining script lines is unsafe without a real parser (automatic semicolon insertion)
+        EmbeddedKind::Script | EmbeddedKind::Other => None,
+    };
+    minified.unwrap_or_else(|| {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+fn is_block(node: &Node) -> bool {
+    match node {
+        Node::Element(element) => !is(INLINE_ELEMENTS, &element.name),
+        Node::Declaration(_) => true,
+        Node::Text(_) | Node::Comment(_) => false,
+    }
+}
+
+fn minify_nodes(out: &mut String, nodes: &[Node], parent_is_block: bool) {
+    // Comments are dropped first so they do not affect whitespace decisions
+    let nodes: Vec<&Node> = nodes
+        .iter()
+        .filter(|n| !matches!(n, Node::Comment(text) if !text.starts_with("[if") && !text.starts_with("<![endif]")))
+        .collect();
+
+    for (i, node) in nodes.iter().enumerate() {
+        match node {
+            Node::Text(text) => {
+                let mut text = collapse_whitespace(text);
+                let at_start = i == 0 && parent_is_block;
+                let at_end = i + 1 == nodes.len() && parent_is_block;
+                // Adjacent text nodes (around a dropped comment) share one space
+                if at_start || (i > 0 && is_block(nodes[i - 1])) || out.ends_with(' ') {
+                    text = text.trim_start().to_string();
+                }
+                if at_end || nodes.get(i + 1).is_some_and(|n| is_block(n)) {
+                    text = text.trim_end().to_string();
+                }
+                out.push_str(&text);
+            }
+            Node::Comment(text) => {
+                out.push_str("<!--");
+                out.push_str(text);
+                out.push_str("-->");
+            }
+            Node::Declaration(text) => out.push_str(text),
+            Node::Element(element) => {
+                write_open_tag(out, element, true);
+                if is(PRESERVE_ELEMENTS, &element.name) {
+                    out.push_str(&raw_content(element));
+                } else if element.name == "script" || element.name == "style" {
+                    out.push_str(&minify_embedded(element, &raw_content(element)));
+                } else {
+                    minify_nodes(out, &element.children, !is(INLINE_ELEMENTS, &element.name));
+                }
+                write_close_tag(out, element);
+            }
+        }
+    }
+}
+
+/// Minify HTML: drop comments and insignificant whitespace, unquote safe attribute values
+pub fn minify_html(html: &str) -> Result<String, HtmlError> {
+    let (nodes, _) = parse(html)?;
+    let mut out = String::new();
+    minify_nodes(&mut out, &nodes, true);
+    Ok(out)
+}
+
+/// Check HTML structure: unclosed and stray tags, duplicate ids and invalid nesting
+pub fn validate_html(html: &str) -> HtmlValidation {
+    let issues = match parse(html) {
+        Ok((_, issues)) => issues,
+        Err(HtmlError::ParseError { line, message }) => vec![HtmlIssue {
+            line,
+            severity: HtmlIssueSeverity::Error,
+            message,
+        }],
+    };
+    HtmlValidation {
+        valid: !issues
+            .iter()
+            .any(|i| i.severity == HtmlIssueSeverity::Error),
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_HTML: &str = r#"<!DOCTYPE html>
+<html><head><title>Demo</title>
+<style>body{margin:0;color:red}</style></head>
+<body><div class="card"><h1>Hello   <em>world</em></h1><p>Some <b>bold</b>
+text</p><img src="a.png" alt="A picture"><ul><li>One<li>Two</ul></div></body></html>"#;
+
+    #[test]
+    fn test_format_html() {
+        let result = format_html(SAMPLE_HTML, 2).unwrap();
+        let expected = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>Demo</title>
+    <style>
+      body {
+        margin: 0;
+        color: red;
+      }
+    </style>
+  </head>
+  <body>
+    <div class="card">
+      <h1>Hello <em>world</em></h1>
+      <p>Some <b>bold</b> text</p>
+      <img src="a.png" alt="A picture">
+      <ul>
+        <li>One</li>
+        <li>Two</li>
+      </ul>
+    </div>
+  </body>
+</html>
+"#;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_format_preserves_pre_and_delegates_script() {
+        let html =
+            "<div><pre>  keep\n    this </pre><script>\nif (a) {\nrun();\n}\n</script></div>";
+        let result = format_html(html, 4).unwrap();
+        assert!(result.contains("    <pre>  keep\n    this </pre>\n"));
+        assert!(result.contains(
+            "    <script>\n        if (a) {\n            run();\n        }\n    </script>\n"
+        ));
+    }
+
+    #[test]
+    fn test_minify_html() {
+        let html = r#"<div  class="a b" id="main" data-x='say "hi"'>
+  <!-- note -->
+  <p>Hello   <b>big</b>  world</p>
+  <input type="text" disabled value="">
+  <pre> spaced  out </pre>
+</div>"#;
+        let result = minify_html(html).unwrap();
+        assert_eq!(
+            result,
+            r#"<div class="a b" id=main data-x='say "hi"'><p>Hello <b>big</b> world</p><input type=text disabled value=""><pre> spaced  out </pre></div>"#
+        );
+    }
+
+    #[test]
+    fn test_minify_keeps_script_lines() {
+        let html =
+            "<script>\n  let a = 1\n  let b = 2\n</script><style> a { color : red ; } </style>";
+        let result = minify_html(html).unwrap();
+        assert_eq!(
+            result,
+            "<script>let a = 1\nlet b = 2</script><style>a{color:red}</style>"
+        );
+    }
+
+    #[test]
+    fn test_validate_html_valid() {
+        let report = validate_html(SAMPLE_HTML);
+        assert!(report.valid, "{:?}", report.issues);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_validate_html_issues() {
+        let html = "<div id=\"a\">\n<span><div>x</div></span>\n<p id=\"a\">text\n<b><i>y</b></i>\n<a href=\"#\"><a href=\"#\">z</a></a>\n<li>orphan</li>\n<section>";
+        let report = validate_html(html);
+        assert!(!report.valid);
+        let messages: Vec<(usize, &str)> = report
+            .issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect();
+        for expected in [
+            (2, "<div> is not allowed inside <span>"),
+            (3, "Duplicate id `a` (first used on line 1)"),
+            (4, "<i> opened on line 4 is not closed before </b>"),
+            (4, "Closing tag </i> has no matching opening tag"),
+            (5, "<a> cannot be nested inside another <a>"),
+            (
+                6,
+                "<li> must be inside <ul> or <ol> or <menu>, found in <p>",
+            ),
+            (7, "<section> opened on line 7 is never closed"),
+            (1, "<div> opened on line 1 is never closed"),
+        ] {
+            assert!(
+                messages.contains(&expected),
+                "missing {:?} in {:?}",
+                expected,
+                messages
+            );
+        }
+    }
+
+    #[test]
+    fn test_self_closing_foreign_elements() {
+        let html =
+            "<div><svg viewBox=\"0 0 10 10\"><path d=\"M0 0\"/><circle r=\"1\" /></svg><br/></div>";
+        let report = validate_html(html);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        let result = minify_html(html).unwrap();
+        assert_eq!(
+            result,
+            "<div><svg viewBox=\"0 0 10 10\"><path d=\"M0 0\" /><circle r=1 /></svg><br></div>"
+        );
+        assert!(format_html(html, 2)
+            .unwrap()
+            .contains("    <path d=\"M0 0\" />\n"));
+    }
+
+    #[test]
+    fn test_invalid_syntax() {
+        assert!(format_html("<div class=\"a", 2).is_err());
+        assert!(minify_html("<!-- open").is_err());
+        let report = validate_html("<p>ok</p><!-- open");
+        assert!(!report.valid);
+        assert_eq!(report.issues[0].message, "unterminated comment");
+    }
+
+    #[test]
+    fn test_non_ascii_text() {
+        let html = "<p>é</p><p>Ñandú <!-- nota --> café</p>ü<b>¿qué?</b>";
+        assert_eq!(
+            minify_html(html).unwrap(),
+            "<p>é</p><p>Ñandú café</p>ü<b>¿qué?</b>"
+        );
+        assert!(format_html(html, 2).unwrap().contains("<p>é</p>"));
+        assert!(validate_html(html).valid);
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let deep = "<div>".repeat(20_000);
+        assert!(format_html(&deep, 2).is_err());
+        assert!(minify_html(&deep).is_err());
+        assert!(!validate_html(&deep).valid);
+
+        let nested = format!("{}x{}", "<div>".repeat(MAX_DEPTH), "</div>".repeat(MAX_DEPTH));
+        assert!(format_html(&nested, 2).is_ok());
+        assert!(minify_html(&nested).is_ok());
+    }
+}
@@ -7,8 +7,10 @@ pub enum FormatError {
     InvalidJson(#[from] serde_json::Error),
 }
 
+/// Object keys are sorted, whatever serde_json features other crates enable
 pub fn format_json(input: &str, indent: usize) -> Result<String, FormatError> {
-    let parsed: Value = serde_json::from_str(input)?;
+    let mut parsed: Value = serde_json::from_str(input)?;
+    parsed.sort_all_objects();
     
     // Replace default 2-space indent with custom indent
     if indent != 2 {
@@ -28,11 +30,39 @@ pub fn format_json(input: &str, indent: usize) -> Result<String, FormatError> {
     }
 }
 
+/// Like `format_json`, object keys are sorted
 pub fn minify_json(input: &str) -> Result<String, FormatError> {
-    let parsed: Value = serde_json::from_str(input)?;
+    let mut parsed: Value = serde_json::from_str(input)?;
+    parsed.sort_all_objects();
     Ok(serde_json::to_string(&parsed)?)
 }
 
 pub fn validate_json(input: &str) -> bool {
     serde_json::from_str::<Value>(input).is_ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_sorted() {
+        let input = r#"{"zebra": 1, "apple": {"b": 2, "a": 3}, "mango": [1, 2]}"#;
+        assert_eq!(
+            minify_json(input).unwrap(),
+            r#"{"apple":{"a":3,"b":2},"mango":[1,2],"zebra":1}"#
+        );
+        let formatted = format_json(input, 4).unwrap();
+        let zebra = formatted.find("zebra").unwrap();
+        let apple = formatted.find("apple").unwrap();
+        assert!(apple < zebra);
+        assert!(formatted.contains("\n        \"a\": 3,\n        \"b\": 2"));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        assert!(format_json("{\"a\":}", 2).is_err());
+        assert!(!validate_json("[1,"));
+        assert!(validate_json("[1]"));
+    }
+}
@@ -0,0 +1,1312 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+
+use super::yaml::{parse_documents, YamlError};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestKind {
+    #[default]
+    Auto,
+    Kubernetes,
+    Compose,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ManifestLintOptions {
+    pub kind: ManifestKind,
+    /// Cluster version used for deprecated API checks, e.g. "1.29"; removed APIs are errors when omitted
+    pub kubernetes_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintIssue {
+    /// Zero-based index of the YAML document
+    pub document: usize,
+    /// `Kind/name` for Kubernetes resources, service name for Compose
+    pub resource: Option<String>,
+    pub path: String,
+    pub severity: LintSeverity,
+    pub rule: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintReport {
+    pub kind: ManifestKind,
+    pub documents: usize,
+    pub issues: Vec<LintIssue>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Lint Kubernetes manifests or a docker-compose file
+pub fn lint_manifest(yaml: &str, options: &ManifestLintOptions) -> Result<LintReport, YamlError> {
+    let documents: Vec<Value> = parse_documents(yaml)?
+        .into_iter()
+        .filter(|d| !d.is_null())
+        .collect();
+
+    let kind = match options.kind {
+        ManifestKind::Auto => detect_kind(&documents).ok_or_else(|| {
+            YamlError::FormatError(
+                "Could not detect manifest type: expected Kubernetes apiVersion/kind or Compose services".to_string(),
+            )
+        })?,
+        kind => kind,
+    };
+
+    let mut linter = Linter {
+        issues: Vec::new(),
+        document: 0,
+        resource: None,
+    };
+    match kind {
+        ManifestKind::Compose => {
+            for (i, document) in documents.iter().enumerate() {
+                linter.document = i;
+                linter.compose(document);
+            }
+        }
+        _ => {
+            let version = options.kubernetes_version.as_deref().and_then(parse_version);
+            for (i, document) in documents.iter().enumerate() {
+                linter.document = i;
+                linter.kubernetes(document, "", version);
+            }
+        }
+    }
+
+    let issues = linter.issues;
+    Ok(LintReport {
+        kind,
+        documents: documents.len(),
+        errors: issues.iter().filter(|i| i.severity == LintSeverity::Error).count(),
+        warnings: issues.iter().filter(|i| i.severity == LintSeverity::Warning).count(),
+        issues,
+    })
+}
+
+fn detect_kind(documents: &[Value]) -> Option<ManifestKind> {
+    if documents.iter().any(|d| d.get("apiVersion").is_some() && d.get("kind").is_some()) {
+        Some(ManifestKind::Kubernetes)
+    } else if documents.iter().any(|d| d.get("services").is_some()) {
+        Some(ManifestKind::Compose)
+    } else {
+        None
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// Follow a dotted path through nested mappings
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.').try_fold(value, |current, key| current.get(key))
+}
+
+fn join_path(base: &str, key: &str) -> String {
+    if base.is_empty() {
+        key.to_string()
+    } else if key.starts_with('[') {
+        format!("{}{}", base, key)
+    } else {
+        format!("{}.{}", base, key)
+    }
+}
+
+fn key_names(mapping: &Mapping) -> impl Iterator<Item = &str> {
+    mapping.keys().filter_map(Value::as_str)
+}
+
+/// Warn about images that float on `latest`, whether explicit or implied
+fn image_tag_problem(image: &str) -> Option<String> {
+    if image.contains('@') || image.contains('$') {
+        return None;
+    }
+    let name = image.rsplit('/').next().unwrap_or(image);
+    match name.split_once(':') {
+        Some((_, "latest")) => Some(format!("Image `{}` uses the mutable `latest` tag", image)),
+        Some(_) => None,
+        None => Some(format!("Image `{}` has no tag and implicitly uses `latest`", image)),
+    }
+}
+
+// Bundled Kubernetes schema
+
+struct KindSchema {
+    kind: &'static str,
+    api_versions: &'static [&'static str],
+    required: &'static [&'static str],
+    /// Top-level fields besides apiVersion, kind, metadata, spec and status
+    extra_fields: &'static [&'static str],
+    /// Where the pod spec lives for workload kinds
+    pod_spec: Option<&'static str>,
+    namespaced: bool,
+}
+
+const fn schema(
+    kind: &'static str,
+    api_versions: &'static [&'static str],
+    required: &'static [&'static str],
+) -> KindSchema {
+    KindSchema {
+        kind,
+        api_versions,
+        required,
+        extra_fields: &[],
+        pod_spec: None,
+        namespaced: true,
+    }
+}
+
+const KUBERNETES_SCHEMAS: &[KindSchema] = &[
+    KindSchema {
+        pod_spec: Some("spec"),
+        ..schema("Pod", &["v1"], &["spec.containers"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.template.spec"),
+        ..schema("Deployment", &["apps/v1"], &["spec.selector", "spec.template"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.template.spec"),
+        ..schema("StatefulSet", &["apps/v1"], &["spec.selector", "spec.template"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.template.spec"),
+        ..schema("DaemonSet", &["apps/v1"], &["spec.selector", "spec.template"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.template.spec"),
+        ..schema("ReplicaSet", &["apps/v1"], &["spec.selector", "spec.template"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.template.spec"),
+        ..schema("ReplicationController", &["v1"], &["spec.template"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.template.spec"),
+        ..schema("Job", &["batch/v1"], &["spec.template"])
+    },
+    KindSchema {
+        pod_spec: Some("spec.jobTemplate.spec.template.spec"),
+        ..schema("CronJob", &["batch/v1"], &["spec.schedule", "spec.jobTemplate"])
+    },
+    schema("Service", &["v1"], &["spec"]),
+    KindSchema {
+        extra_fields: &["data", "binaryData", "immutable"],
+        ..schema("ConfigMap", &["v1"], &[])
+    },
+    KindSchema {
+        extra_fields: &["data", "stringData", "type", "immutable"],
+        ..schema("Secret", &["v1"], &[])
+    },
+    KindSchema {
+        namespaced: false,
+        ..schema("Namespace", &["v1"], &[])
+    },
+    KindSchema {
+        extra_fields: &["secrets", "imagePullSecrets", "automountServiceAccountToken"],
+        ..schema("ServiceAccount", &["v1"], &[])
+    },
+    KindSchema {
+        namespaced: false,
+        ..schema("PersistentVolume", &["v1"], &["spec.capacity", "spec.accessModes"])
+    },
+    schema("PersistentVolumeClaim", &["v1"], &["spec.accessModes"]),
+    KindSchema {
+        extra_fields: &["subsets"],
+        ..schema("Endpoints", &["v1"], &[])
+    },
+    schema("LimitRange", &["v1"], &["spec.limits"]),
+    schema("ResourceQuota", &["v1"], &[]),
+    schema("Ingress", &["networking.k8s.io/v1"], &["spec"]),
+    KindSchema {
+        namespaced: false,
+        ..schema("IngressClass", &["networking.k8s.io/v1"], &[])
+    },
+    schema("NetworkPolicy", &["networking.k8s.io/v1"], &["spec.podSelector"]),
+    schema(
+        "HorizontalPodAutoscaler",
+        &["autoscaling/v2", "autoscaling/v1"],
+        &["spec.scaleTargetRef", "spec.maxReplicas"],
+    ),
+    schema("PodDisruptionBudget", &["policy/v1"], &[]),
+    KindSchema {
+        extra_fields: &["rules"],
+        ..schema("Role", &["rbac.authorization.k8s.io/v1"], &[])
+    },
+    KindSchema {
+        extra_fields: &["rules", "aggregationRule"],
+        namespaced: false,
+        ..schema("ClusterRole", &["rbac.authorization.k8s.io/v1"], &[])
+    },
+    KindSchema {
+        extra_fields: &["roleRef", "subjects"],
+        ..schema("RoleBinding", &["rbac.authorization.k8s.io/v1"], &["roleRef"])
+    },
+    KindSchema {
+        extra_fields: &["roleRef", "subjects"],
+        namespaced: false,
+        ..schema("ClusterRoleBinding", &["rbac.authorization.k8s.io/v1"], &["roleRef"])
+    },
+    KindSchema {
+        namespaced: false,
+        ..schema(
+            "CustomResourceDefinition",
+            &["apiextensions.k8s.io/v1"],
+            &["spec.group", "spec.names", "spec.scope", "spec.versions"],
+        )
+    },
+    KindSchema {
+        extra_fields: &[
+            "provisioner",
+            "parameters",
+            "reclaimPolicy",
+            "volumeBindingMode",
+            "allowVolumeExpansion",
+            "mountOptions",
+            "allowedTopologies",
+        ],
+        namespaced: false,
+        ..schema("StorageClass", &["storage.k8s.io/v1"], &["provisioner"])
+    },
+    KindSchema {
+        extra_fields: &["value", "globalDefault", "description", "preemptionPolicy"],
+        namespaced: false,
+        ..schema("PriorityClass", &["scheduling.k8s.io/v1"], &["value"])
+    },
+    KindSchema {
+        extra_fields: &["webhooks"],
+        namespaced: false,
+        ..schema("MutatingWebhookConfiguration", &["admissionregistration.k8s.io/v1"], &[])
+    },
+    KindSchema {
+        extra_fields: &["webhooks"],
+        namespaced: false,
+        ..schema("ValidatingWebhookConfiguration", &["admissionregistration.k8s.io/v1"], &[])
+    },
+    KindSchema {
+        extra_fields: &["addressType", "endpoints", "ports"],
+        ..schema("EndpointSlice", &["discovery.k8s.io/v1"], &["addressType"])
+    },
+    schema("Lease", &["coordination.k8s.io/v1"], &[]),
+];
+
+struct DeprecatedApi {
+    api_version: &'static str,
+    kind: &'static str,
+    replacement: Option<&'static str>,
+    /// Kubernetes release that stopped serving it
+    removed_in: (u32, u32),
+}
+
+const fn deprecated(
+    api_version: &'static str,
+    kind: &'static str,
+    replacement: Option<&'static str>,
+    removed_in: (u32, u32),
+) -> DeprecatedApi {
+    DeprecatedApi {
+        api_version,
+        kind,
+        replacement,
+        removed_in,
+    }
+}
+
+const DEPRECATED_APIS: &[DeprecatedApi] = &[
+    deprecated("extensions/v1beta1", "Deployment", Some("apps/v1"), (1, 16)),
+    deprecated("extensions/v1beta1", "DaemonSet", Some("apps/v1"), (1, 16)),
+    deprecated("extensions/v1beta1", "ReplicaSet", Some("apps/v1"), (1, 16)),
+    deprecated("extensions/v1beta1", "NetworkPolicy", Some("networking.k8s.io/v1"), (1, 16)),
+    deprecated("extensions/v1beta1", "PodSecurityPolicy", None, (1, 16)),
+    deprecated("extensions/v1beta1", "Ingress", Some("networking.k8s.io/v1"), (1, 22)),
+    deprecated("apps/v1beta1", "Deployment", Some("apps/v1"), (1, 16)),
+    deprecated("apps/v1beta1", "StatefulSet", Some("apps/v1"), (1, 16)),
+    deprecated("apps/v1beta2", "Deployment", Some("apps/v1"), (1, 16)),
+    deprecated("apps/v1beta2", "StatefulSet", Some("apps/v1"), (1, 16)),
+    deprecated("apps/v1beta2", "DaemonSet", Some("apps/v1"), (1, 16)),
+    deprecated("apps/v1beta2", "ReplicaSet", Some("apps/v1"), (1, 16)),
+    deprecated("networking.k8s.io/v1beta1", "Ingress", Some("networking.k8s.io/v1"), (1, 22)),
+    deprecated("networking.k8s.io/v1beta1", "IngressClass", Some("networking.k8s.io/v1"), (1, 22)),
+    deprecated("rbac.authorization.k8s.io/v1beta1", "Role", Some("rbac.authorization.k8s.io/v1"), (1, 22)),
+    deprecated("rbac.authorization.k8s.io/v1beta1", "ClusterRole", Some("rbac.authorization.k8s.io/v1"), (1, 22)),
+    deprecated("rbac.authorization.k8s.io/v1beta1", "RoleBinding", Some("rbac.authorization.k8s.io/v1"), (1, 22)),
+    deprecated("rbac.authorization.k8s.io/v1beta1", "ClusterRoleBinding", Some("rbac.authorization.k8s.io/v1"), (1, 22)),
+    deprecated("apiextensions.k8s.io/v1beta1", "CustomResourceDefinition", Some("apiextensions.k8s.io/v1"), (1, 22)),
+    deprecated("admissionregistration.k8s.io/v1beta1", "MutatingWebhookConfiguration", Some("admissionregistration.k8s.io/v1"), (1, 22)),
+    deprecated("admissionregistration.k8s.io/v1beta1", "ValidatingWebhookConfiguration", Some("admissionregistration.k8s.io/v1"), (1, 22)),
+    deprecated("storage.k8s.io/v1beta1", "StorageClass", Some("storage.k8s.io/v1"), (1, 22)),
+    deprecated("storage.k8s.io/v1beta1", "CSIDriver", Some("storage.k8s.io/v1"), (1, 22)),
+    deprecated("storage.k8s.io/v1beta1", "CSINode", Some("storage.k8s.io/v1"), (1, 22)),
+    deprecated("storage.k8s.io/v1beta1", "VolumeAttachment", Some("storage.k8s.io/v1"), (1, 22)),
+    deprecated("storage.k8s.io/v1beta1", "CSIStorageCapacity", Some("storage.k8s.io/v1"), (1, 27)),
+    deprecated("scheduling.k8s.io/v1beta1", "PriorityClass", Some("scheduling.k8s.io/v1"), (1, 22)),
+    deprecated("coordination.k8s.io/v1beta1", "Lease", Some("coordination.k8s.io/v1"), (1, 22)),
+    deprecated("certificates.k8s.io/v1beta1", "CertificateSigningRequest", Some("certificates.k8s.io/v1"), (1, 22)),
+    deprecated("batch/v1beta1", "CronJob", Some("batch/v1"), (1, 25)),
+    deprecated("policy/v1beta1", "PodDisruptionBudget", Some("policy/v1"), (1, 25)),
+    deprecated("policy/v1beta1", "PodSecurityPolicy", None, (1, 25)),
+    deprecated("discovery.k8s.io/v1beta1", "EndpointSlice", Some("discovery.k8s.io/v1"), (1, 25)),
+    deprecated("events.k8s.io/v1beta1", "Event", Some("events.k8s.io/v1"), (1, 25)),
+    deprecated("autoscaling/v2beta1", "HorizontalPodAutoscaler", Some("autoscaling/v2"), (1, 25)),
+    deprecated("autoscaling/v2beta2", "HorizontalPodAutoscaler", Some("autoscaling/v2"), (1, 26)),
+];
+
+const CONTAINER_FIELDS: &[&str] = &[
+    "name",
+    "image",
+    "command",
+    "args",
+    "workingDir",
+    "ports",
+    "envFrom",
+    "env",
+    "resources",
+    "resizePolicy",
+    "restartPolicy",
+    "volumeMounts",
+    "volumeDevices",
+    "livenessProbe",
+    "readinessProbe",
+    "startupProbe",
+    "lifecycle",
+    "terminationMessagePath",
+    "terminationMessagePolicy",
+    "imagePullPolicy",
+    "securityContext",
+    "stdin",
+    "stdinOnce",
+    "tty",
+];
+
+const SERVICE_TYPES: &[&str] = &["ClusterIP", "NodePort", "LoadBalancer", "ExternalName"];
+
+// Bundled Compose schema
+
+const COMPOSE_TOP_LEVEL: &[&str] = &[
+    "version", "name", "include", "services", "networks", "volumes", "configs", "secrets", "models",
+];
+
+const COMPOSE_SERVICE_FIELDS: &[&str] = &[
+    "annotations", "attach", "build", "blkio_config", "cap_add", "cap_drop", "cgroup", "cgroup_parent",
+    "command", "configs", "container_name", "cpu_count", "cpu_percent", "cpu_period", "cpu_quota",
+    "cpu_rt_period", "cpu_rt_runtime", "cpu_shares", "cpus", "cpuset", "credential_spec", "depends_on",
+    "deploy", "develop", "device_cgroup_rules", "devices", "dns", "dns_opt", "dns_search", "domainname",
+    "entrypoint", "env_file", "environment", "expose", "extends", "external_links", "extra_hosts", "gpus",
+    "group_add", "healthcheck", "hostname", "image", "init", "ipc", "isolation", "label_file", "labels",
+    "links", "logging", "mac_address", "mem_limit", "mem_reservation", "mem_swappiness", "memswap_limit",
+    "models", "network_mode", "networks", "oom_kill_disable", "oom_score_adj", "pid", "pids_limit",
+    "platform", "ports", "post_start", "pre_stop", "privileged", "profiles", "provider", "pull_policy",
+    "read_only", "restart", "runtime", "scale", "secrets", "security_opt", "shm_size", "stdin_open",
+    "stop_grace_period", "stop_signal", "storage_opt", "sysctls", "tmpfs", "tty", "ulimits",
+    "use_api_socket", "user", "userns_mode", "uts", "volumes", "volumes_from", "working_dir",
+];
+
+/// Port ranges larger than this are not expanded when looking for collisions
+const MAX_PORT_RANGE: u32 = 1024;
+
+// Linting
+
+struct Linter {
+    issues: Vec<LintIssue>,
+    document: usize,
+    resource: Option<String>,
+}
+
+impl Linter {
+    fn report(&mut self, severity: LintSeverity, rule: &str, path: &str, message: impl Into<String>) {
+        self.issues.push(LintIssue {
+            document: self.document,
+            resource: self.resource.clone(),
+            path: path.to_string(),
+            severity,
+            rule: rule.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn kubernetes(&mut self, resource: &Value, base: &str, version: Option<(u32, u32)>) {
+        self.resource = None;
+        let Some(mapping) = resource.as_mapping() else {
+            self.report(LintSeverity::Error, "k8s-not-an-object", base, "Manifest must be a mapping");
+            return;
+        };
+
+        let api_version = resource.get("apiVersion").and_then(Value::as_str);
+        let kind = resource.get("kind").and_then(Value::as_str);
+        let name = lookup(resource, "metadata.name").and_then(Value::as_str);
+        self.resource = kind.map(|k| format!("{}/{}", k, name.unwrap_or("<unnamed>")));
+
+        let (Some(api_version), Some(kind)) = (api_version, kind) else {
+            if api_version.is_none() {
+                let path = join_path(base, "apiVersion");
+                self.report(LintSeverity::Error, "k8s-required-field", &path, "Missing `apiVersion`");
+            }
+            if kind.is_none() {
+                self.report(LintSeverity::Error, "k8s-required-field", &join_path(base, "kind"), "Missing `kind`");
+            }
+            return;
+        };
+
+        if kind == "List" {
+            if let Some(items) = resource.get("items").and_then(Value::as_sequence) {
+                for (i, item) in items.iter().enumerate() {
+                    self.kubernetes(item, &join_path(base, &format!("items[{}]", i)), version);
+                }
+            }
+            return;
+        }
+
+        let deprecated = DEPRECATED_APIS
+            .iter()
+            .find(|d| d.api_version == api_version && d.kind == kind);
+        if let Some(api) = deprecated {
+            let removed = api.removed_in;
+            let removed_in_target = version.map(|v| v >= removed).unwrap_or(true);
+            let replacement = match api.replacement {
+                Some(replacement) => format!("; use `{}` instead", replacement),
+                None => "; there is no replacement".to_string(),
+            };
+            self.report(
+                if removed_in_target { LintSeverity::Error } else { LintSeverity::Warning },
+                "k8s-deprecated-api",
+                &join_path(base, "apiVersion"),
+                format!(
+                    "`{}` {} is deprecated and removed in Kubernetes {}.{}{}",
+                    api_version, kind, removed.0, removed.1, replacement
+                ),
+            );
+        }
+
+        let Some(schema) = KUBERNETES_SCHEMAS.iter().find(|s| s.kind == kind) else {
+            self.report(
+                LintSeverity::Info,
+                "k8s-unknown-kind",
+                &join_path(base, "kind"),
+                format!("`{}` is not a built-in kind; schema checks skipped", kind),
+            );
+            return;
+        };
+        if !schema.api_versions.contains(&api_version) && deprecated.is_none() {
+            self.report(
+                LintSeverity::Error,
+                "k8s-api-version",
+                &join_path(base, "apiVersion"),
+                format!(
+                    "`{}` does not serve {}; expected {}",
+                    api_version,
+                    kind,
+                    schema.api_versions.join(" or ")
+                ),
+            );
+        }
+
+        for field in key_names(mapping) {
+            let known = ["apiVersion", "kind", "metadata", "spec", "status"].contains(&field)
+                || schema.extra_fields.contains(&field);
+            if !known {
+                self.report(
+                    LintSeverity::Warning,
+                    "k8s-unknown-field",
+                    &join_path(base, field),
+                    format!("Unknown field `{}` for {}", field, kind),
+                );
+            }
+        }
+
+        self.metadata(resource, base, schema);
+        for field in schema.required {
+            if lookup(resource, field).is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    "k8s-required-field",
+                    &join_path(base, field),
+                    format!("{} requires `{}`", kind, field),
+                );
+            }
+        }
+
+        match kind {
+            "Service" => self.service(resource, base),
+            "Deployment" | "StatefulSet" | "DaemonSet" | "ReplicaSet" => self.selector(resource, base),
+            _ => {}
+        }
+        if let Some(replicas) = lookup(resource, "spec.replicas") {
+            if replicas.as_u64().is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    "k8s-field-type",
+                    &join_path(base, "spec.replicas"),
+                    "`spec.replicas` must be a non-negative integer",
+                );
+            }
+        }
+        if let Some(pod_path) = schema.pod_spec {
+            if let Some(pod) = lookup(resource, pod_path) {
+                let long_running = !matches!(kind, "Job" | "CronJob" | "Pod");
+                self.pod_spec(pod, &join_path(base, pod_path), long_running);
+            }
+        }
+    }
+
+    fn metadata(&mut self, resource: &Value, base: &str, schema: &KindSchema) {
+        let path = join_path(base, "metadata");
+        let Some(metadata) = resource.get("metadata") else {
+            self.report(LintSeverity::Error, "k8s-required-field", &path, "Missing `metadata`");
+            return;
+        };
+
+        match metadata.get("name") {
+            Some(Value::String(name)) => {
+                let valid = !name.is_empty()
+                    && name.len() <= 253
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+                    && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+                    && name.ends_with(|c: char| c.is_ascii_alphanumeric());
+                if !valid {
+                    self.report(
+                        LintSeverity::Error,
+                        "k8s-invalid-name",
+                        &join_path(&path, "name"),
+                        format!("`{}` is not a valid DNS subdomain name (lowercase alphanumerics, `-` and `.`)", name),
+                    );
+                }
+            }
+            Some(_) => self.report(
+                LintSeverity::Error,
+                "k8s-field-type",
+                &join_path(&path, "name"),
+                "`metadata.name` must be a string",
+            ),
+            None if metadata.get("generateName").is_none() => self.report(
+                LintSeverity::Error,
+                "k8s-required-field",
+                &join_path(&path, "name"),
+                "Missing `metadata.name`",
+            ),
+            None => {}
+        }
+
+        if !schema.namespaced && metadata.get("namespace").is_some() {
+            self.report(
+                LintSeverity::Warning,
+                "k8s-cluster-scoped",
+                &join_path(&path, "namespace"),
+                format!("{} is cluster-scoped; `metadata.namespace` is ignored", schema.kind),
+            );
+        }
+
+        for field in ["labels", "annotations"] {
+            if let Some(entries) = metadata.get(field).and_then(Value::as_mapping) {
+                for (key, value) in entries {
+                    if !value.is_string() {
+                        let key = key.as_str().unwrap_or_default();
+                        self.report(
+                            LintSeverity::Error,
+                            "k8s-field-type",
+                            &join_path(&path, &format!("{}.{}", field, key)),
+                            format!("Value of {} `{}` must be a string; quote it", &field[..field.len() - 1], key),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn selector(&mut self, resource: &Value, base: &str) {
+        let selector = lookup(resource, "spec.selector.matchLabels").and_then(Value::as_mapping);
+        let labels = lookup(resource, "spec.template.metadata.labels").and_then(Value::as_mapping);
+        let (Some(selector), Some(labels)) = (selector, labels) else { return };
+        for (key, value) in selector {
+            if labels.get(key) != Some(value) {
+                self.report(
+                    LintSeverity::Error,
+                    "k8s-selector-mismatch",
+                    &join_path(base, "spec.selector.matchLabels"),
+                    format!(
+                        "Selector `{}` does not match the pod template labels",
+                        key.as_str().unwrap_or_default()
+                    ),
+                );
+            }
+        }
+    }
+
+    fn service(&mut self, resource: &Value, base: &str) {
+        let service_type = lookup(resource, "spec.type").and_then(Value::as_str).unwrap_or("ClusterIP");
+        if !SERVICE_TYPES.contains(&service_type) {
+            self.report(
+                LintSeverity::Error,
+                "k8s-invalid-value",
+                &join_path(base, "spec.type"),
+                format!("Unknown Service type `{}`", service_type),
+            );
+        }
+
+        let ports = lookup(resource, "spec.ports").and_then(Value::as_sequence);
+        if ports.map(|p| p.is_empty()).unwrap_or(true) && service_type != "ExternalName" {
+            self.report(
+                LintSeverity::Error,
+                "k8s-required-field",
+                &join_path(base, "spec.ports"),
+                "Service requires at least one port",
+            );
+        }
+
+        let mut seen = HashSet::new();
+        for (i, port) in ports.into_iter().flatten().enumerate() {
+            let path = join_path(base, &format!("spec.ports[{}]", i));
+            match port.get("port").and_then(Value::as_u64) {
+                Some(number @ 1..=65535) => {
+                    let protocol = port.get("protocol").and_then(Value::as_str).unwrap_or("TCP");
+                    if !seen.insert((number, protocol.to_string())) {
+                        self.report(
+                            LintSeverity::Error,
+                            "k8s-duplicate-port",
+                            &join_path(&path, "port"),
+                            format!("Port {}/{} is declared more than once", number, protocol),
+                        );
+                    }
+                }
+                _ => self.report(
+                    LintSeverity::Error,
+                    "k8s-invalid-value",
+                    &join_path(&path, "port"),
+                    "`port` must be an integer between 1 and 65535",
+                ),
+            }
+            if let Some(node_port) = port.get("nodePort").and_then(Value::as_u64) {
+                if !(30000..=32767).contains(&node_port) {
+                    self.report(
+                        LintSeverity::Warning,
+                        "k8s-node-port-range",
+                        &join_path(&path, "nodePort"),
+                        format!("nodePort {} is outside the default 30000-32767 range", node_port),
+                    );
+                }
+            }
+            if ports.map(|p| p.len() > 1).unwrap_or(false) && port.get("name").is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    "k8s-required-field",
+                    &join_path(&path, "name"),
+                    "Ports must be named when a Service exposes more than one",
+                );
+            }
+        }
+    }
+
+    fn pod_spec(&mut self, pod: &Value, path: &str, long_running: bool) {
+        let containers = pod.get("containers").and_then(Value::as_sequence);
+        if containers.map(|c| c.is_empty()).unwrap_or(true) {
+            self.report(
+                LintSeverity::Error,
+                "k8s-required-field",
+                &join_path(path, "containers"),
+                "Pod spec requires at least one container",
+            );
+        }
+
+        let mut names = HashSet::new();
+        let mut ports = HashSet::new();
+        for field in ["initContainers", "containers"] {
+            let Some(list) = pod.get(field).and_then(Value::as_sequence) else { continue };
+            for (i, container) in list.iter().enumerate() {
+                let container_path = join_path(path, &format!("{}[{}]", field, i));
+                if let Some(name) = container.get("name").and_then(Value::as_str) {
+                    if !names.insert(name.to_string()) {
+                        self.report(
+                            LintSeverity::Error,
+                            "k8s-duplicate-container",
+                            &join_path(&container_path, "name"),
+                            format!("Container name `{}` is used more than once", name),
+                        );
+                    }
+                }
+                self.container(container, &container_path, field == "containers" && long_running, &mut ports);
+            }
+        }
+    }
+
+    fn container(&mut self, container: &Value, path: &str, long_running: bool, ports: &mut HashSet<(u64, String)>) {
+        let Some(mapping) = container.as_mapping() else {
+            self.report(LintSeverity::Error, "k8s-field-type", path, "Container must be a mapping");
+            return;
+        };
+
+        for field in key_names(mapping) {
+            if !CONTAINER_FIELDS.contains(&field) {
+                self.report(
+                    LintSeverity::Warning,
+                    "k8s-unknown-field",
+                    &join_path(path, field),
+                    format!("Unknown container field `{}`", field),
+                );
+            }
+        }
+        for field in ["name", "image"] {
+            if container.get(field).is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    "k8s-required-field",
+                    &join_path(path, field),
+                    format!("Container requires `{}`", field),
+                );
+            }
+        }
+
+        if let Some(image) = container.get("image").and_then(Value::as_str) {
+            if let Some(message) = image_tag_problem(image) {
+                self.report(LintSeverity::Warning, "image-latest-tag", &join_path(path, "image"), message);
+            }
+        }
+
+        for resource in ["cpu", "memory"] {
+            if lookup(container, &format!("resources.limits.{}", resource)).is_none() {
+                self.report(
+                    LintSeverity::Warning,
+                    "k8s-resource-limits",
+                    &join_path(path, "resources.limits"),
+                    format!("No {} limit set", resource),
+                );
+            }
+        }
+        if long_running && container.get("readinessProbe").is_none() {
+            self.report(
+                LintSeverity::Info,
+                "k8s-missing-probe",
+                &join_path(path, "readinessProbe"),
+                "No readinessProbe; traffic may reach the container before it is ready",
+            );
+        }
+
+        for (i, port) in container
+            .get("ports")
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let port_path = join_path(path, &format!("ports[{}].containerPort", i));
+            match port.get("containerPort").and_then(Value::as_u64) {
+                Some(number @ 1..=65535) => {
+                    let protocol = port.get("protocol").and_then(Value::as_str).unwrap_or("TCP");
+                    if !ports.insert((number, protocol.to_string())) {
+                        self.report(
+                            LintSeverity::Warning,
+                            "k8s-duplicate-port",
+                            &port_path,
+                            format!("containerPort {}/{} is declared more than once in the pod", number, protocol),
+                        );
+                    }
+                }
+                _ => self.report(
+                    LintSeverity::Error,
+                    "k8s-invalid-value",
+                    &port_path,
+                    "`containerPort` must be an integer between 1 and 65535",
+                ),
+            }
+        }
+    }
+
+    fn compose(&mut self, document: &Value) {
+        self.resource = None;
+        let Some(mapping) = document.as_mapping() else {
+            self.report(LintSeverity::Error, "compose-not-an-object", "", "Compose file must be a mapping");
+            return;
+        };
+
+        for field in key_names(mapping) {
+            if field == "version" {
+                self.report(
+                    LintSeverity::Info,
+                    "compose-obsolete-version",
+                    "version",
+                    "The top-level `version` field is obsolete and ignored by Compose v2",
+                );
+            } else if !COMPOSE_TOP_LEVEL.contains(&field) && !field.starts_with("x-") {
+                self.report(
+                    LintSeverity::Warning,
+                    "compose-unknown-key",
+                    field,
+                    format!("Unknown top-level key `{}`", field),
+                );
+            }
+        }
+
+        let declared = |section: &str| -> HashSet<String> {
+            document
+                .get(section)
+                .and_then(Value::as_mapping)
+                .map(|m| key_names(m).map(String::from).collect())
+                .unwrap_or_default()
+        };
+        let networks = declared("networks");
+        let volumes = declared("volumes");
+        let secrets = declared("secrets");
+        let configs = declared("configs");
+
+        let Some(services) = document.get("services").and_then(Value::as_mapping) else {
+            if document.get("include").is_none() {
+                self.report(LintSeverity::Error, "compose-required-field", "services", "Missing `services`");
+            }
+            return;
+        };
+        let service_names: HashSet<String> = key_names(services).map(String::from).collect();
+
+        // (host port, protocol) -> (host IP, service) bindings; an empty IP is the wildcard address
+        let mut published: HashMap<(u32, String), Vec<(String, String)>> = HashMap::new();
+        let mut container_names: HashMap<String, String> = HashMap::new();
+
+        for (name, service) in services {
+            let name = name.as_str().unwrap_or_default().to_string();
+            self.resource = Some(name.clone());
+            let path = format!("services.{}", name);
+            let Some(fields) = service.as_mapping() else {
+                self.report(LintSeverity::Error, "compose-field-type", &path, "Service must be a mapping");
+                continue;
+            };
+
+            for field in key_names(fields) {
+                if !COMPOSE_SERVICE_FIELDS.contains(&field) && !field.starts_with("x-") {
+                    self.report(
+                        LintSeverity::Warning,
+                        "compose-unknown-key",
+                        &join_path(&path, field),
+                        format!("Unknown service key `{}`", field),
+                    );
+                }
+            }
+
+            if service.get("image").is_none() && service.get("build").is_none() && service.get("extends").is_none() {
+                self.report(
+                    LintSeverity::Error,
+                    "compose-required-field",
+                    &path,
+                    "Service needs either `image` or `build`",
+                );
+            }
+            if let Some(image) = service.get("image").and_then(Value::as_str) {
+                if let Some(message) = image_tag_problem(image) {
+                    self.report(LintSeverity::Warning, "image-latest-tag", &join_path(&path, "image"), message);
+                }
+            }
+
+            if let Some(container_name) = service.get("container_name").and_then(Value::as_str) {
+                if let Some(other) = container_names.insert(container_name.to_string(), name.clone()) {
+                    self.report(
+                        LintSeverity::Error,
+                        "compose-duplicate-container-name",
+                        &join_path(&path, "container_name"),
+                        format!("container_name `{}` is already used by service `{}`", container_name, other),
+                    );
+                }
+            }
+
+            for (i, port) in service
+                .get("ports")
+                .and_then(Value::as_sequence)
+                .into_iter()
+                .flatten()
+                .enumerate()
+            {
+                let port_path = join_path(&path, &format!("ports[{}]", i));
+                let Some((host_ip, host_ports, protocol)) = published_ports(port) else {
+                    continue;
+                };
+                for host_port in host_ports {
+                    let bindings = published.entry((host_port, protocol.clone())).or_default();
+                    let other = bindings
+                        .iter()
+                        .find(|(ip, _)| ip.is_empty() || host_ip.is_empty() || *ip == host_ip)
+                        .map(|(_, service)| service.clone());
+                    bindings.push((host_ip.clone(), name.clone()));
+                    if let Some(other) = other {
+                        self.report(
+                            LintSeverity::Error,
+                            "compose-port-collision",
+                            &port_path,
+                            format!("Host port {}/{} is also published by service `{}`", host_port, protocol, other),
+                        );
+                    }
+                }
+            }
+
+            self.compose_references(service, &path, "networks", &networks, &["default"]);
+            self.compose_references(service, &path, "secrets", &secrets, &[]);
+            self.compose_references(service, &path, "configs", &configs, &[]);
+            self.compose_references(service, &path, "depends_on", &service_names, &[]);
+
+            if let Some(mode) = service.get("network_mode").and_then(Value::as_str) {
+                if let Some(target) = mode.strip_prefix("service:") {
+                    if !service_names.contains(target) {
+                        self.report(
+                            LintSeverity::Error,
+                            "compose-undefined-reference",
+                            &join_path(&path, "network_mode"),
+                            format!("network_mode refers to undefined service `{}`", target),
+                        );
+                    }
+                }
+            }
+
+            for (i, volume) in service
+                .get("volumes")
+                .and_then(Value::as_sequence)
+                .into_iter()
+                .flatten()
+                .enumerate()
+            {
+                let Some(source) = named_volume(volume) else { continue };
+                if !volumes.contains(&source) {
+                    self.report(
+                        LintSeverity::Error,
+                        "compose-undefined-reference",
+                        &join_path(&path, &format!("volumes[{}]", i)),
+                        format!("Named volume `{}` is not declared under top-level `volumes`", source),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check that names listed under a service field are declared elsewhere in the file
+    fn compose_references(
+        &mut self,
+        service: &Value,
+        path: &str,
+        field: &str,
+        declared: &HashSet<String>,
+        implicit: &[&str],
+    ) {
+        let names: Vec<String> = match service.get(field) {
+            Some(Value::Sequence(items)) => items
+                .iter()
+                .filter_map(|item| match item {
+                    Value::String(name) => Some(name.clone()),
+                    other => other.get("source").and_then(Value::as_str).map(String::from),
+                })
+                .collect(),
+            Some(Value::Mapping(entries)) => key_names(entries).map(String::from).collect(),
+            _ => return,
+        };
+        let what = match field {
+            "depends_on" => "service",
+            "networks" => "network",
+            "secrets" => "secret",
+            _ => "config",
+        };
+        for name in names {
+            if !declared.contains(&name) && !implicit.contains(&name.as_str()) {
+                self.report(
+                    LintSeverity::Error,
+                    "compose-undefined-reference",
+                    &join_path(path, field),
+                    format!("Undefined {} `{}`", what, name),
+                );
+            }
+        }
+    }
+}
+
+/// Host IP, host ports and protocol published by a short or long port definition
+fn published_ports(port: &Value) -> Option<(String, Vec<u32>, String)> {
+    let (host_ip, published, protocol) = match port {
+        Value::String(spec) => {
+            if spec.contains('$') {
+                return None;
+            }
+            let (spec, protocol) = spec.split_once('/').unwrap_or((spec, "tcp"));
+            // IPv6 host addresses are bracketed: [::1]:8080:80
+            let (host_ip, rest) = match spec.strip_prefix('[') {
+                Some(rest) => {
+                    let (ip, rest) = rest.split_once("]:")?;
+                    (ip.to_string(), rest)
+                }
+                None => match spec.matches(':').count() {
+                    2 => {
+                        let (ip, rest) = spec.split_once(':')?;
+                        (ip.to_string(), rest)
+                    }
+                    _ => (String::new(), spec),
+                },
+            };
+            // A lone container port is published on a random host port
+            let (published, _) = rest.split_once(':')?;
+            (host_ip, published.to_string(), protocol.to_string())
+        }
+        Value::Number(_) => return None,
+        Value::Mapping(_) => {
+            let published = match port.get("published")? {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s.clone(),
+                _ => return None,
+            };
+            let host_ip = port.get("host_ip").and_then(Value::as_str).unwrap_or("").to_string();
+            let protocol = port.get("protocol").and_then(Value::as_str).unwrap_or("tcp").to_string();
+            (host_ip, published, protocol)
+        }
+        _ => return None,
+    };
+
+    let ports = match published.split_once('-') {
+        Some((start, end)) => {
+            let (start, end): (u32, u32) = (start.parse().ok()?, end.parse().ok()?);
+            if end < start || end - start > MAX_PORT_RANGE {
+                return None;
+            }
+            (start..=end).collect()
+        }
+        None if published.is_empty() => return None,
+        None => vec![published.parse().ok()?],
+    };
+    let host_ip = match host_ip.as_str() {
+        "" | "0.0.0.0" | "::" => String::new(),
+        ip => ip.to_string(),
+    };
+    Some((host_ip, ports, protocol.to_lowercase()))
+}
+
+/// Name of the named volume mounted by a service volume entry, if it is not a bind mount
+fn named_volume(volume: &Value) -> Option<String> {
+    let source = match volume {
+        Value::String(spec) => {
+            let (source, _) = spec.split_once(':')?;
+            source.to_string()
+        }
+        Value::Mapping(_) => {
+            if volume.get("type").and_then(Value::as_str) != Some("volume") {
+                return None;
+            }
+            volume.get("source")?.as_str()?.to_string()
+        }
+        _ => return None,
+    };
+    let bind_mount = source.starts_with(['/', '.', '~', '$']) || source.contains(['/', '\\']);
+    (!bind_mount && !source.is_empty()).then_some(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(yaml: &str) -> LintReport {
+        lint_manifest(yaml, &ManifestLintOptions::default()).unwrap()
+    }
+
+    fn rules(report: &LintReport) -> Vec<&str> {
+        report.issues.iter().map(|i| i.rule.as_str()).collect()
+    }
+
+    const DEPLOYMENT: &str = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  labels:
+    tier: frontend
+spec:
+  replicas: 2
+  selector:
+    matchLabels:
+      app: web
+  template:
+    metadata:
+      labels:
+        app: web
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.25
+          ports:
+            - containerPort: 80
+          readinessProbe:
+            httpGet: {path: /, port: 80}
+          resources:
+            limits: {cpu: 500m, memory: 128Mi}
+"#;
+
+    #[test]
+    fn test_clean_deployment() {
+        let report = lint(DEPLOYMENT);
+        assert_eq!(report.kind, ManifestKind::Kubernetes);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_kubernetes_problems() {
+        let yaml = r#"
+apiVersion: extensions/v1beta1
+kind: Deployment
+metadata:
+  name: Web_App
+  labels:
+    version: 2
+spec:
+  selector:
+    matchLabels:
+      app: api
+  template:
+    metadata:
+      labels:
+        app: web
+    spec:
+      containers:
+        - name: web
+          image: nginx
+          imagePullpolicy: Always
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+spec:
+  ports:
+    - port: 80
+    - port: 80
+"#;
+        let report = lint(yaml);
+        let rules = rules(&report);
+        for rule in [
+            "k8s-deprecated-api",
+            "k8s-invalid-name",
+            "k8s-field-type",
+            "k8s-selector-mismatch",
+            "image-latest-tag",
+            "k8s-unknown-field",
+            "k8s-resource-limits",
+            "k8s-duplicate-port",
+        ] {
+            assert!(rules.contains(&rule), "missing {}: {:?}", rule, rules);
+        }
+        assert_eq!(report.documents, 2);
+        let service_issue = report.issues.iter().find(|i| i.rule == "k8s-duplicate-port").unwrap();
+        assert_eq!(service_issue.document, 1);
+        assert_eq!(service_issue.resource.as_deref(), Some("Service/web"));
+        assert_eq!(service_issue.path, "spec.ports[1].port");
+    }
+
+    #[test]
+    fn test_deprecated_api_target_version() {
+        let yaml = "apiVersion: batch/v1beta1\nkind: CronJob\nmetadata:\n  name: job\nspec:\n  schedule: '* * * * *'\n  jobTemplate: {}\n";
+        let options = ManifestLintOptions {
+            kubernetes_version: Some("1.24".to_string()),
+            ..Default::default()
+        };
+        let report = lint_manifest(yaml, &options).unwrap();
+        let issue = report.issues.iter().find(|i| i.rule == "k8s-deprecated-api").unwrap();
+        assert_eq!(issue.severity, LintSeverity::Warning);
+        assert!(issue.message.contains("batch/v1"));
+
+        let report = lint(yaml);
+        let issue = report.issues.iter().find(|i| i.rule == "k8s-deprecated-api").unwrap();
+        assert_eq!(issue.severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_missing_fields_and_custom_resources() {
+        let report = lint("apiVersion: v1\nkind: Pod\nmetadata: {}\n---\napiVersion: example.com/v1\nkind: Widget\nmetadata:\n  name: w\n");
+        let paths: Vec<&str> = report.issues.iter().map(|i| i.path.as_str()).collect();
+        assert!(paths.contains(&"metadata.name"));
+        assert!(paths.contains(&"spec.containers"));
+        assert!(rules(&report).contains(&"k8s-unknown-kind"));
+    }
+
+    const COMPOSE: &str = r#"
+version: "3.8"
+services:
+  web:
+    image: nginx:latest
+    ports:
+      - "8080:80"
+      - "127.0.0.1:9000:9000"
+    networks: [frontend, backend]
+    depends_on: [db, cache]
+    volumes:
+      - data:/var/lib/data
+      - ./src:/src
+  api:
+    build: .
+    ports:
+      - target: 3000
+        published: 8080
+    restart_policy: always
+  db:
+    image: postgres:16
+    volumes:
+      - type: volume
+        source: pgdata
+        target: /var/lib/postgresql/data
+networks:
+  frontend: {}
+volumes:
+  data: {}
+"#;
+
+    #[test]
+    fn test_compose_problems() {
+        let report = lint(COMPOSE);
+        assert_eq!(report.kind, ManifestKind::Compose);
+        let messages: Vec<&str> = report.issues.iter().map(|i| i.message.as_str()).collect();
+        assert!(messages.iter().any(|m| m.contains("Host port 8080/tcp is also published by service `web`")));
+        assert!(messages.iter().any(|m| m.contains("Undefined network `backend`")));
+        assert!(messages.iter().any(|m| m.contains("Undefined service `cache`")));
+        assert!(messages.iter().any(|m| m.contains("Named volume `pgdata`")));
+        assert!(messages.iter().any(|m| m.contains("Unknown service key `restart_policy`")));
+        assert!(messages.iter().any(|m| m.contains("`latest` tag")));
+        assert!(rules(&report).contains(&"compose-obsolete-version"));
+        // Bind mounts and declared volumes are fine
+        assert!(!messages.iter().any(|m| m.contains("`data`") || m.contains("./src")));
+    }
+
+    #[test]
+    fn test_compose_port_collisions_across_host_ips() {
+        let collisions = |a: &str, b: &str| {
+            let yaml = format!(
+                "services:\n  a:\n    image: x:1\n    ports: [\"{}\"]\n  b:\n    image: x:1\n    ports: [\"{}\"]\n",
+                a, b
+            );
+            rules(&lint(&yaml)).contains(&"compose-port-collision")
+        };
+        assert!(collisions("8080:80", "0.0.0.0:8080:80"));
+        assert!(collisions("127.0.0.1:8080:80", "8080:81"));
+        assert!(collisions("0.0.0.0:8080:80", "127.0.0.1:8080:80"));
+        assert!(collisions("127.0.0.1:8080:80", "127.0.0.1:8080:81"));
+        assert!(!collisions("127.0.0.1:8080:80", "127.0.0.2:8080:80"));
+        assert!(!collisions("8080:80", "8080:80/udp"));
+    }
+
+    #[test]
+    fn test_published_ports() {
+        let ports = |spec: &str| published_ports(&Value::String(spec.to_string())).map(|p| p.1);
+        assert_eq!(ports("8080:80"), Some(vec![8080]));
+        assert_eq!(ports("127.0.0.1:5000-5002:5000-5002/udp"), Some(vec![5000, 5001, 5002]));
+        assert_eq!(ports("[::1]:6000:6000"), Some(vec![6000]));
+        assert_eq!(ports("3000"), None);
+        assert_eq!(ports("${PORT}:80"), None);
+    }
+
+    #[test]
+    fn test_detection_failure() {
+        assert!(lint_manifest("foo: bar", &ManifestLintOptions::default()).is_err());
+        assert!(lint_manifest("foo: [", &ManifestLintOptions::default()).is_err());
+    }
+}
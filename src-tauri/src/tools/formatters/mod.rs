@@ -2,6 +2,10 @@ pub mod json;
 pub mod xml;
 pub mod sql;
 pub mod css;
+pub mod html;
+pub mod graphql;
+pub mod protobuf;
 pub mod javascript;
 pub mod yaml;
+pub mod manifest_lint;
 pub mod rustfmt;
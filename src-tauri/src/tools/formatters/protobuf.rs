@@ -0,0 +1,2489 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ProtobufError {
+    #[error("Proto syntax error on line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("Schema error: {0}")]
+    Schema(String),
+    #[error("Decode error: {0}")]
+    Decode(String),
+    #[error("Encode error: {0}")]
+    Encode(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProtoIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProtoValidation {
+    pub valid: bool,
+    pub issues: Vec<ProtoIssue>,
+}
+
+const MAX_FIELD_NUMBER: i64 = 536_870_911;
+const RESERVED_FIELD_NUMBERS: std::ops::RangeInclusive<i64> = 19_000..=19_999;
+/// Deepest nesting of `.proto` blocks and of messages on the wire
+const MAX_NESTING: usize = 64;
+
+// Lexer
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    Ident,
+    Number,
+    String,
+    Punct,
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    line: usize,
+    /// Comments found before this token
+    comments: Vec<String>,
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> ProtobufError {
+    ProtobufError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, ProtobufError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+
+    loop {
+        if i >= bytes.len() {
+            tokens.push(Token {
+                kind: TokenKind::Eof,
+                text: "",
+                line,
+                comments,
+            });
+            return Ok(tokens);
+        }
+        let start = i;
+        let token_line = line;
+        let kind = match bytes[i] {
+            b'\n' => {
+                line += 1;
+                i += 1;
+                continue;
+            }
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'/' if source[i..].starts_with("//") => {
+                let end = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                comments.push(source[i..end].trim_end().to_string());
+                i = end;
+                continue;
+            }
+            b'/' if source[i..].starts_with("/*") => {
+                let end = source[i + 2..]
+                    .find("*/")
+                    .map(|n| i + 2 + n + 2)
+                    .ok_or_else(|| parse_error(line, "unterminated block comment"))?;
+                let text = &source[i..end];
+                line += text.matches('\n').count();
+                comments.push(text.to_string());
+                i = end;
+                continue;
+            }
+            b'_' | b'a'..=b'z' | b'A'..=b'Z' => {
+                while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                TokenKind::Ident
+            }
+            b'0'..=b'9' => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric()
+                        || bytes[i] == b'.'
+                        || ((bytes[i] == b'+' || bytes[i] == b'-')
+                            && matches!(bytes[i - 1], b'e' | b'E')))
+                {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            b'.' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        None | Some(b'\n') => return Err(parse_error(line, "unterminated string")),
+                        Some(b'\\') => i += 2,
+                        Some(&c) if c == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(_) => i += 1,
+                    }
+                }
+                TokenKind::String
+            }
+            b';' | b'{' | b'}' | b'[' | b']' | b'(' | b')' | b'<' | b'>' | b'=' | b',' | b'.'
+            | b'-' | b'+' | b':' | b'/' => {
+                i += 1;
+                TokenKind::Punct
+            }
+            _ => {
+                let c = source[i..].chars().next().unwrap_or('?');
+                return Err(parse_error(line, format!("unexpected character `{}`", c)));
+            }
+        };
+        tokens.push(Token {
+            kind,
+            text: &source[start..i],
+            line: token_line,
+            comments: std::mem::take(&mut comments),
+        });
+    }
+}
+
+// AST
+
+#[derive(Debug, Clone)]
+struct OptionDef {
+    name: String,
+    value: String,
+}
+
+fn print_field_options(options: &[OptionDef]) -> String {
+    if options.is_empty() {
+        return String::new();
+    }
+    let inner: Vec<String> = options
+        .iter()
+        .map(|o| format!("{} = {}", o.name, o.value))
+        .collect();
+    format!(" [{}]", inner.join(", "))
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    label: Option<String>,
+    /// Type as written, or the value type for maps
+    ty: String,
+    map_key: Option<String>,
+    name: String,
+    number: i64,
+    options: Vec<OptionDef>,
+    line: usize,
+}
+
+impl Field {
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|o| o.name == name)
+            .map(|o| o.value.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ReservedItem {
+    Range(i64, Option<i64>),
+    Max(i64),
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+struct Reserved {
+    keyword: &'static str,
+    items: Vec<ReservedItem>,
+}
+
+impl Reserved {
+    fn render(&self) -> String {
+        let items: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| match item {
+                ReservedItem::Range(start, None) => start.to_string(),
+                ReservedItem::Range(start, Some(end)) => format!("{} to {}", start, end),
+                ReservedItem::Max(start) => format!("{} to max", start),
+                ReservedItem::Name(name) => name.clone(),
+            })
+            .collect();
+        format!("{} {};", self.keyword, items.join(", "))
+    }
+
+    fn contains_number(&self, number: i64, max: i64) -> bool {
+        self.items.iter().any(|item| match item {
+            ReservedItem::Range(start, end) => (*start..=end.unwrap_or(*start)).contains(&number),
+            ReservedItem::Max(start) => (*start..=max).contains(&number),
+            ReservedItem::Name(_) => false,
+        })
+    }
+
+    fn contains_name(&self, name: &str) -> bool {
+        self.items.iter().any(|item| match item {
+            ReservedItem::Name(n) => n.trim_matches(|c| c == '"' || c == '\'') == name,
+            _ => false,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rpc {
+    name: String,
+    request_stream: bool,
+    request: String,
+    response_stream: bool,
+    response: String,
+    body: Option<Vec<Element>>,
+}
+
+#[derive(Debug, Clone)]
+enum ElementKind {
+    Syntax(String),
+    Package(String),
+    Import(Option<String>, String),
+    Option(OptionDef),
+    Field(Field),
+    Oneof(String, Vec<Element>),
+    Message(String, Vec<Element>),
+    Enum(String, Vec<Element>),
+    EnumValue(String, i64, Vec<OptionDef>),
+    Service(String, Vec<Element>),
+    Rpc(Rpc),
+    Extend(String, Vec<Element>),
+    Reserved(Reserved),
+    Extensions(Reserved),
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    comments: Vec<String>,
+    line: usize,
+    kind: ElementKind,
+    /// Comments before the closing brace of a block element
+    trailing_comments: Vec<String>,
+}
+
+impl Element {
+    fn is_block(&self) -> bool {
+        matches!(
+            self.kind,
+            ElementKind::Message(..)
+                | ElementKind::Oneof(..)
+                | ElementKind::Enum(..)
+                | ElementKind::Service(..)
+                | ElementKind::Extend(..)
+        ) || matches!(&self.kind, ElementKind::Rpc(rpc) if rpc.body.is_some())
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProtoFile {
+    elements: Vec<Element>,
+    trailing_comments: Vec<String>,
+}
+
+impl ProtoFile {
+    fn syntax(&self) -> &str {
+        self.elements
+            .iter()
+            .find_map(|e| match &e.kind {
+                ElementKind::Syntax(s) => Some(s.trim_matches(|c| c == '"' || c == '\'')),
+                _ => None,
+            })
+            .unwrap_or("proto2")
+    }
+
+    fn package(&self) -> Option<&str> {
+        self.elements.iter().find_map(|e| match &e.kind {
+            ElementKind::Package(p) => Some(p.as_str()),
+            _ => None,
+        })
+    }
+}
+
+// Parser
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    File,
+    Message,
+    Oneof,
+    Enum,
+    Service,
+    Rpc,
+    Extend,
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+    pending: Vec<String>,
+    depth: usize,
+}
+
+type ParseResult<T> = Result<T, ProtobufError>;
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &Token<'a> {
+        &self.tokens[self.index]
+    }
+
+    fn is(&self, text: &str) -> bool {
+        self.peek().kind != TokenKind::String && self.peek().text == text
+    }
+
+    fn advance(&mut self) -> Token<'a> {
+        let mut token = self.tokens[self.index].clone();
+        self.pending.append(&mut token.comments);
+        self.tokens[self.index].comments.clear();
+        if token.kind != TokenKind::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn take_comments(&mut self) -> Vec<String> {
+        let mut comments = std::mem::take(&mut self.pending);
+        comments.append(&mut self.tokens[self.index].comments);
+        comments
+    }
+
+    fn unexpected(&self, expected: &str) -> ProtobufError {
+        let token = self.peek();
+        let found = if token.kind == TokenKind::Eof {
+            "end of file".to_string()
+        } else {
+            format!("`{}`", token.text)
+        };
+        parse_error(
+            token.line,
+            format!("expected {}, found {}", expected, found),
+        )
+    }
+
+    fn expect(&mut self, text: &str) -> ParseResult<Token<'a>> {
+        if self.is(text) {
+            Ok(self.advance())
+        } else {
+            Err(self.unexpected(&format!("`{}`", text)))
+        }
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let matched = self.is(text);
+        if matched {
+            self.advance();
+        }
+        matched
+    }
+
+    fn ident(&mut self) -> ParseResult<String> {
+        if self.peek().kind == TokenKind::Ident {
+            Ok(self.advance().text.to_string())
+        } else {
+            Err(self.unexpected("an identifier"))
+        }
+    }
+
+    /// Dotted name such as `google.protobuf.Timestamp` or `.pkg.Msg`
+    fn full_ident(&mut self) -> ParseResult<String> {
+        let mut name = String::new();
+        if self.eat(".") {
+            name.push('.');
+        }
+        name.push_str(&self.ident()?);
+        while self.eat(".") {
+            name.push('.');
+            name.push_str(&self.ident()?);
+        }
+        Ok(name)
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        if self.peek().kind != TokenKind::String {
+            return Err(self.unexpected("a string"));
+        }
+        let mut text = self.advance().text.to_string();
+        // Adjacent string literals are concatenated
+        while self.peek().kind == TokenKind::String {
+            text.push(' ');
+            text.push_str(self.advance().text);
+        }
+        Ok(text)
+    }
+
+    fn integer(&mut self) -> ParseResult<i64> {
+        let negative = self.eat("-");
+        let token = self.peek().clone();
+        if token.kind != TokenKind::Number {
+            return Err(self.unexpected("an integer"));
+        }
+        self.advance();
+        let text = token.text;
+        let value =
+            if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                i64::from_str_radix(hex, 16)
+            } else if text.len() > 1 && text.starts_with('0') {
+                i64::from_str_radix(&text[1..], 8)
+            } else {
+                text.parse()
+            }
+            .map_err(|_| parse_error(token.line, format!("invalid integer `{}`", text)))?;
+        Ok(if negative { -value } else { value })
+    }
+
+    fn option_name(&mut self) -> ParseResult<String> {
+        let mut name = String::new();
+        loop {
+            if self.eat("(") {
+                name.push('(');
+                name.push_str(&self.full_ident()?);
+                self.expect(")")?;
+                name.push(')');
+            } else {
+                name.push_str(&self.ident()?);
+            }
+            if !self.eat(".") {
+                return Ok(name);
+            }
+            name.push('.');
+        }
+    }
+
+    fn constant(&mut self) -> ParseResult<String> {
+        if self.is("{") {
+            return self.aggregate();
+        }
+        let mut text = String::new();
+        if self.is("-") || self.is("+") {
+            text.push_str(self.advance().text);
+        }
+        match self.peek().kind {
+            TokenKind::String => text.push_str(&self.string()?),
+            TokenKind::Number | TokenKind::Ident => text.push_str(&self.full_ident_or_number()?),
+            _ => return Err(self.unexpected("a constant")),
+        }
+        Ok(text)
+    }
+
+    fn full_ident_or_number(&mut self) -> ParseResult<String> {
+        if self.peek().kind == TokenKind::Number {
+            Ok(self.advance().text.to_string())
+        } else {
+            self.full_ident()
+        }
+    }
+
+    /// Text-format aggregate value used in custom options, e.g. `{ a: 1 b: "x" }`
+    fn aggregate(&mut self) -> ParseResult<String> {
+        let mut parts: Vec<String> = Vec::new();
+        let mut depth = 0;
+        loop {
+            let token = self.advance();
+            match token.text {
+                "{" | "[" | "<" if token.kind == TokenKind::Punct => depth += 1,
+                "}" | "]" | ">" if token.kind == TokenKind::Punct => depth -= 1,
+                _ if token.kind == TokenKind::Eof => {
+                    return Err(parse_error(token.line, "unterminated option value"))
+                }
+                _ => {}
+            }
+            match token.text {
+                ":" | "," if token.kind == TokenKind::Punct => {
+                    if let Some(last) = parts.last_mut() {
+                        last.push_str(token.text);
+                    }
+                }
+                _ => parts.push(token.text.to_string()),
+            }
+            if depth == 0 {
+                return Ok(parts.join(" "));
+            }
+        }
+    }
+
+    fn field_options(&mut self) -> ParseResult<Vec<OptionDef>> {
+        let mut options = Vec::new();
+        if self.eat("[") {
+            loop {
+                let name = self.option_name()?;
+                self.expect("=")?;
+                options.push(OptionDef {
+                    name,
+                    value: self.constant()?,
+                });
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect("]")?;
+        }
+        Ok(options)
+    }
+
+    fn block(&mut self, scope: Scope) -> ParseResult<(Vec<Element>, Vec<String>)> {
+        let open = self.expect("{")?;
+        if self.depth >= MAX_NESTING {
+            return Err(parse_error(
+                open.line,
+                format!("blocks are nested more than {} levels deep", MAX_NESTING),
+            ));
+        }
+        self.depth += 1;
+        let mut elements = Vec::new();
+        while !self.is("}") {
+            if self.peek().kind == TokenKind::Eof {
+                return Err(self.unexpected("`}`"));
+            }
+            if self.eat(";") {
+                continue;
+            }
+            elements.push(self.element(scope)?);
+        }
+        self.depth -= 1;
+        let trailing = self.take_comments();
+        self.advance();
+        Ok((elements, trailing))
+    }
+
+    fn file(&mut self) -> ParseResult<ProtoFile> {
+        let mut file = ProtoFile::default();
+        while self.peek().kind != TokenKind::Eof {
+            if self.eat(";") {
+                continue;
+            }
+            file.elements.push(self.element(Scope::File)?);
+        }
+        file.trailing_comments = self.take_comments();
+        Ok(file)
+    }
+
+    fn element(&mut self, scope: Scope) -> ParseResult<Element> {
+        let comments = self.take_comments();
+        let line = self.peek().line;
+        let keyword = self.peek().text;
+        let mut trailing_comments = Vec::new();
+        let mut block = |parser: &mut Self, inner: Scope| -> ParseResult<Vec<Element>> {
+            let (elements, trailing) = parser.block(inner)?;
+            trailing_comments = trailing;
+            Ok(elements)
+        };
+
+        let kind = match (scope, keyword) {
+            (Scope::File, "syntax") | (Scope::File, "edition") => {
+                self.advance();
+                self.expect("=")?;
+                let value = self.string()?;
+                self.expect(";")?;
+                ElementKind::Syntax(value)
+            }
+            (Scope::File, "package") => {
+                self.advance();
+                let name = self.full_ident()?;
+                self.expect(";")?;
+                ElementKind::Package(name)
+            }
+            (Scope::File, "import") => {
+                self.advance();
+                let modifier = if self.is("public") || self.is("weak") {
+                    Some(self.advance().text.to_string())
+                } else {
+                    None
+                };
+                let path = self.string()?;
+                self.expect(";")?;
+                ElementKind::Import(modifier, path)
+            }
+            (_, "option") => {
+                self.advance();
+                let name = self.option_name()?;
+                self.expect("=")?;
+                let value = self.constant()?;
+                self.expect(";")?;
+                ElementKind::Option(OptionDef { name, value })
+            }
+            (Scope::File | Scope::Message, "message") => {
+                self.advance();
+                let name = self.ident()?;
+                ElementKind::Message(name, block(self, Scope::Message)?)
+            }
+            (Scope::File | Scope::Message, "enum") => {
+                self.advance();
+                let name = self.ident()?;
+                ElementKind::Enum(name, block(self, Scope::Enum)?)
+            }
+            (Scope::File | Scope::Message, "extend") => {
+                self.advance();
+                let name = self.full_ident()?;
+                ElementKind::Extend(name, block(self, Scope::Extend)?)
+            }
+            (Scope::File, "service") => {
+                self.advance();
+                let name = self.ident()?;
+                ElementKind::Service(name, block(self, Scope::Service)?)
+            }
+            (Scope::Message, "oneof") => {
+                self.advance();
+                let name = self.ident()?;
+                ElementKind::Oneof(name, block(self, Scope::Oneof)?)
+            }
+            (Scope::Message | Scope::Enum, "reserved") | (Scope::Message, "extensions") => {
+                self.advance();
+                let reserved = self.reserved(if keyword == "reserved" {
+                    "reserved"
+                } else {
+                    "extensions"
+                })?;
+                if keyword == "reserved" {
+                    ElementKind::Reserved(reserved)
+                } else {
+                    ElementKind::Extensions(reserved)
+                }
+            }
+            (Scope::Service, "rpc") => {
+                self.advance();
+                let name = self.ident()?;
+                self.expect("(")?;
+                let request_stream = self.is("stream")
+                    && self.tokens[self.index + 1].text != ")"
+                    && self.eat("stream");
+                let request = self.full_ident()?;
+                self.expect(")")?;
+                self.expect("returns")?;
+                self.expect("(")?;
+                let response_stream = self.is("stream")
+                    && self.tokens[self.index + 1].text != ")"
+                    && self.eat("stream");
+                let response = self.full_ident()?;
+                self.expect(")")?;
+                let body = if self.is("{") {
+                    Some(block(self, Scope::Rpc)?)
+                } else {
+                    self.expect(";")?;
+                    None
+                };
+                ElementKind::Rpc(Rpc {
+                    name,
+                    request_stream,
+                    request,
+                    response_stream,
+                    response,
+                    body,
+                })
+            }
+            (Scope::Enum, _) => {
+                let name = self.ident()?;
+                self.expect("=")?;
+                let number = self.integer()?;
+                let options = self.field_options()?;
+                self.expect(";")?;
+                ElementKind::EnumValue(name, number, options)
+            }
+            (Scope::Message | Scope::Oneof | Scope::Extend, _) => {
+                ElementKind::Field(self.field(scope)?)
+            }
+            _ => return Err(self.unexpected("a definition")),
+        };
+        Ok(Element {
+            comments,
+            line,
+            kind,
+            trailing_comments,
+        })
+    }
+
+    fn field(&mut self, scope: Scope) -> ParseResult<Field> {
+        let line = self.peek().line;
+        let next_is_type = |p: &Self| {
+            let next = &p.tokens[(p.index + 1).min(p.tokens.len() - 1)];
+            next.kind == TokenKind::Ident || next.text == "."
+        };
+        let label = if scope != Scope::Oneof
+            && matches!(self.peek().text, "optional" | "required" | "repeated")
+            && next_is_type(self)
+        {
+            Some(self.advance().text.to_string())
+        } else {
+            None
+        };
+        if self.is("group") {
+            return Err(parse_error(line, "groups are not supported"));
+        }
+        let (ty, map_key) = if self.is("map") && self.tokens[self.index + 1].text == "<" {
+            self.advance();
+            self.advance();
+            let key = self.ident()?;
+            self.expect(",")?;
+            let value = self.full_ident()?;
+            self.expect(">")?;
+            (value, Some(key))
+        } else {
+            (self.full_ident()?, None)
+        };
+        let name = self.ident()?;
+        self.expect("=")?;
+        let number = self.integer()?;
+        let options = self.field_options()?;
+        self.expect(";")?;
+        Ok(Field {
+            label,
+            ty,
+            map_key,
+            name,
+            number,
+            options,
+            line,
+        })
+    }
+
+    fn reserved(&mut self, keyword: &'static str) -> ParseResult<Reserved> {
+        let mut items = Vec::new();
+        loop {
+            if self.peek().kind == TokenKind::String {
+                items.push(ReservedItem::Name(self.advance().text.to_string()));
+            } else if self.peek().kind == TokenKind::Ident {
+                items.push(ReservedItem::Name(self.ident()?));
+            } else {
+                let start = self.integer()?;
+                if self.eat("to") {
+                    if self.eat("max") {
+                        items.push(ReservedItem::Max(start));
+                    } else {
+                        items.push(ReservedItem::Range(start, Some(self.integer()?)));
+                    }
+                } else {
+                    items.push(ReservedItem::Range(start, None));
+                }
+            }
+            if !self.eat(",") {
+                break;
+            }
+        }
+        // Extension ranges may carry options
+        self.field_options()?;
+        self.expect(";")?;
+        Ok(Reserved { keyword, items })
+    }
+}
+
+fn parse(source: &str) -> ParseResult<ProtoFile> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        index: 0,
+        pending: Vec::new(),
+        depth: 0,
+    };
+    parser.file()
+}
+
+// Formatter
+
+struct Printer {
+    indent: String,
+    out: String,
+}
+
+impl Printer {
+    fn line(&mut self, level: usize, text: &str) {
+        self.out.push_str(&self.indent.repeat(level));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn comments(&mut self, comments: &[String], level: usize) {
+        for comment in comments {
+            for (i, line) in comment.lines().enumerate() {
+                // Re-indent continuation lines of block comments
+                let line = if i > 0 {
+                    format!(" {}", line.trim_start())
+                } else {
+                    line.to_string()
+                };
+                self.line(level, &line);
+            }
+        }
+    }
+
+    fn elements(&mut self, elements: &[Element], level: usize) {
+        for (i, element) in elements.iter().enumerate() {
+            if i > 0 {
+                let previous = &elements[i - 1];
+                let grouped =
+                    std::mem::discriminant(&previous.kind) == std::mem::discriminant(&element.kind);
+                let separate = if level == 0 {
+                    !grouped || element.is_block() || matches!(element.kind, ElementKind::Syntax(_))
+                } else {
+                    element.is_block() || previous.is_block()
+                };
+                if separate {
+                    self.out.push('\n');
+                }
+            }
+            self.element(element, level);
+        }
+    }
+
+    fn block(&mut self, head: &str, element: &Element, children: &[Element], level: usize) {
+        if children.is_empty() && element.trailing_comments.is_empty() {
+            self.line(level, &format!("{} {{}}", head));
+            return;
+        }
+        self.line(level, &format!("{} {{", head));
+        self.elements(children, level + 1);
+        self.comments(&element.trailing_comments, level + 1);
+        self.line(level, "}");
+    }
+
+    fn element(&mut self, element: &Element, level: usize) {
+        self.comments(&element.comments, level);
+        match &element.kind {
+            ElementKind::Syntax(value) => {
+                let keyword = if value.contains("editions")
+                    || value.trim_matches('"').parse::<u32>().is_ok()
+                {
+                    "edition"
+                } else {
+                    "syntax"
+                };
+                self.line(level, &format!("{} = {};", keyword, value));
+            }
+            ElementKind::Package(name) => self.line(level, &format!("package {};", name)),
+            ElementKind::Import(modifier, path) => match modifier {
+                Some(modifier) => self.line(level, &format!("import {} {};", modifier, path)),
+                None => self.line(level, &format!("import {};", path)),
+            },
+            ElementKind::Option(option) => self.line(
+                level,
+                &format!("option {} = {};", option.name, option.value),
+            ),
+            ElementKind::Field(field) => {
+                let mut text = String::new();
+                if let Some(label) = &field.label {
+                    text.push_str(label);
+                    text.push(' ');
+                }
+                match &field.map_key {
+                    Some(key) => text.push_str(&format!("map<{}, {}>", key, field.ty)),
+                    None => text.push_str(&field.ty),
+                }
+                text.push_str(&format!(
+                    " {} = {}{};",
+                    field.name,
+                    field.number,
+                    print_field_options(&field.options)
+                ));
+                self.line(level, &text);
+            }
+            ElementKind::EnumValue(name, number, options) => self.line(
+                level,
+                &format!("{} = {}{};", name, number, print_field_options(options)),
+            ),
+            ElementKind::Reserved(reserved) | ElementKind::Extensions(reserved) => {
+                self.line(level, &reserved.render())
+            }
+            ElementKind::Oneof(name, children) => {
+                self.block(&format!("oneof {}", name), element, children, level)
+            }
+            ElementKind::Message(name, children) => {
+                self.block(&format!("message {}", name), element, children, level)
+            }
+            ElementKind::Enum(name, children) => {
+                self.block(&format!("enum {}", name), element, children, level)
+            }
+            ElementKind::Service(name, children) => {
+                self.block(&format!("service {}", name), element, children, level)
+            }
+            ElementKind::Extend(name, children) => {
+                self.block(&format!("extend {}", name), element, children, level)
+            }
+            ElementKind::Rpc(rpc) => {
+                let stream = |s: bool| if s { "stream " } else { "" };
+                let head = format!(
+                    "rpc {}({}{}) returns ({}{})",
+                    rpc.name,
+                    stream(rpc.request_stream),
+                    rpc.request,
+                    stream(rpc.response_stream),
+                    rpc.response
+                );
+                match &rpc.body {
+                    Some(children) => self.block(&head, element, children, level),
+                    None => self.line(level, &format!("{};", head)),
+                }
+            }
+        }
+    }
+}
+
+/// Format a `.proto` file with consistent indentation and spacing
+pub fn format_proto(input: &str, indent_size: usize) -> Result<String, ProtobufError> {
+    let file = parse(input)?;
+    let mut printer = Printer {
+        indent: " ".repeat(indent_size),
+        out: String::new(),
+    };
+    printer.elements(&file.elements, 0);
+    if !file.trailing_comments.is_empty() {
+        if !printer.out.is_empty() {
+            printer.out.push('\n');
+        }
+        printer.comments(&file.trailing_comments, 0);
+    }
+    Ok(printer.out)
+}
+
+// Schema model
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Scalar::Double,
+            "float" => Scalar::Float,
+            "int32" => Scalar::Int32,
+            "int64" => Scalar::Int64,
+            "uint32" => Scalar::Uint32,
+            "uint64" => Scalar::Uint64,
+            "sint32" => Scalar::Sint32,
+            "sint64" => Scalar::Sint64,
+            "fixed32" => Scalar::Fixed32,
+            "fixed64" => Scalar::Fixed64,
+            "sfixed32" => Scalar::Sfixed32,
+            "sfixed64" => Scalar::Sfixed64,
+            "bool" => Scalar::Bool,
+            "string" => Scalar::String,
+            "bytes" => Scalar::Bytes,
+            _ => return None,
+        })
+    }
+
+    fn wire_type(self) -> u8 {
+        match self {
+            Scalar::Double | Scalar::Fixed64 | Scalar::Sfixed64 => WIRE_I64,
+            Scalar::Float | Scalar::Fixed32 | Scalar::Sfixed32 => WIRE_I32,
+            Scalar::String | Scalar::Bytes => WIRE_LEN,
+            _ => WIRE_VARINT,
+        }
+    }
+
+    fn valid_map_key(self) -> bool {
+        !matches!(self, Scalar::Double | Scalar::Float | Scalar::Bytes)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldKind {
+    Scalar(Scalar),
+    Enum(String),
+    Message(String),
+    Map(Scalar, Box<FieldKind>),
+}
+
+impl FieldKind {
+    fn wire_type(&self) -> u8 {
+        match self {
+            FieldKind::Scalar(s) => s.wire_type(),
+            FieldKind::Enum(_) => WIRE_VARINT,
+            FieldKind::Message(_) | FieldKind::Map(..) => WIRE_LEN,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FieldDef {
+    name: String,
+    json_name: String,
+    number: u32,
+    repeated: bool,
+    packed: bool,
+    kind: FieldKind,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MessageDef {
+    fields: Vec<FieldDef>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct EnumDef {
+    values: Vec<(String, i64)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Declared {
+    Message,
+    Enum,
+}
+
+/// Fully-qualified message and enum definitions from a `.proto` file
+#[derive(Debug, Default)]
+struct Registry {
+    messages: HashMap<String, MessageDef>,
+    enums: HashMap<String, EnumDef>,
+}
+
+fn lower_camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+/// Collect every declared type name with its kind
+fn declare(elements: &[Element], scope: &str, declared: &mut HashMap<String, Declared>) {
+    for element in elements {
+        match &element.kind {
+            ElementKind::Message(name, children) => {
+                let full = qualify(scope, name);
+                declared.insert(full.clone(), Declared::Message);
+                declare(children, &full, declared);
+            }
+            ElementKind::Enum(name, _) => {
+                declared.insert(qualify(scope, name), Declared::Enum);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resolve a type reference using protobuf's innermost-scope-first lookup
+fn resolve(
+    name: &str,
+    scope: &str,
+    declared: &HashMap<String, Declared>,
+) -> Option<(String, Declared)> {
+    if let Some(absolute) = name.strip_prefix('.') {
+        return declared.get(absolute).map(|d| (absolute.to_string(), *d));
+    }
+    let mut scope = scope.to_string();
+    loop {
+        let candidate = qualify(&scope, name);
+        if let Some(kind) = declared.get(&candidate) {
+            return Some((candidate, *kind));
+        }
+        if scope.is_empty() {
+            return None;
+        }
+        scope = scope
+            .rfind('.')
+            .map_or(String::new(), |i| scope[..i].to_string());
+    }
+}
+
+struct SchemaBuilder<'a> {
+    declared: HashMap<String, Declared>,
+    proto3: bool,
+    registry: Registry,
+    issues: &'a mut Vec<ProtoIssue>,
+}
+
+impl SchemaBuilder<'_> {
+    fn issue(&mut self, line: usize, message: String) {
+        self.issues.push(ProtoIssue { line, message });
+    }
+
+    fn field_kind(&mut self, field: &Field, scope: &str) -> Option<FieldKind> {
+        let value = match Scalar::parse(&field.ty) {
+            Some(scalar) => FieldKind::Scalar(scalar),
+            None => match resolve(&field.ty, scope, &self.declared) {
+                Some((full, Declared::Message)) => FieldKind::Message(full),
+                Some((full, Declared::Enum)) => FieldKind::Enum(full),
+                None => {
+                    self.issue(
+                        field.line,
+                        format!("Unknown type `{}` for field `{}`", field.ty, field.name),
+                    );
+                    return None;
+                }
+            },
+        };
+        match &field.map_key {
+            None => Some(value),
+            Some(key) => match Scalar::parse(key).filter(|k| k.valid_map_key()) {
+                Some(key) => {
+                    if matches!(value, FieldKind::Map(..)) {
+                        self.issue(field.line, "Map values cannot be maps".to_string());
+                    }
+                    Some(FieldKind::Map(key, Box::new(value)))
+                }
+                None => {
+                    self.issue(field.line, format!("`{}` is not a valid map key type", key));
+                    None
+                }
+            },
+        }
+    }
+
+    fn message(&mut self, name: &str, elements: &[Element]) {
+        let mut fields = Vec::new();
+        let mut reserved = Vec::new();
+        let mut collected: Vec<&Field> = Vec::new();
+        for element in elements {
+            match &element.kind {
+                ElementKind::Field(field) => collected.push(field),
+                ElementKind::Oneof(_, children) => {
+                    for child in children {
+                        if let ElementKind::Field(field) = &child.kind {
+                            if field.map_key.is_some() {
+                                self.issue(
+                                    field.line,
+                                    format!("Map field `{}` cannot be part of a oneof", field.name),
+                                );
+                            }
+                            collected.push(field);
+                        }
+                    }
+                }
+                ElementKind::Reserved(r) => reserved.push(r.clone()),
+                ElementKind::Message(child, children) => {
+                    self.message(&qualify(name, child), children)
+                }
+                ElementKind::Enum(child, children) => {
+                    self.enumeration(&qualify(name, child), children, element.line)
+                }
+                _ => {}
+            }
+        }
+
+        let mut numbers: HashMap<i64, &str> = HashMap::new();
+        let mut names = HashSet::new();
+        let mut json_names: HashMap<String, &str> = HashMap::new();
+        for field in collected {
+            if !(1..=MAX_FIELD_NUMBER).contains(&field.number) {
+                self.issue(
+                    field.line,
+                    format!(
+                        "Field number {} of `{}` is out of range 1..{}",
+                        field.number, field.name, MAX_FIELD_NUMBER
+                    ),
+                );
+            } else if RESERVED_FIELD_NUMBERS.contains(&field.number) {
+                self.issue(
+                    field.line,
+                    format!(
+                        "Field number {} of `{}` is reserved for the protobuf implementation",
+                        field.number, field.name
+                    ),
+                );
+            }
+            if let Some(other) = numbers.insert(field.number, &field.name) {
+                self.issue(
+                    field.line,
+                    format!(
+                        "Field number {} is used by both `{}` and `{}`",
+                        field.number, other, field.name
+                    ),
+                );
+            }
+            if !names.insert(field.name.as_str()) {
+                self.issue(
+                    field.line,
+                    format!("Field `{}` is defined more than once", field.name),
+                );
+            }
+            if reserved
+                .iter()
+                .any(|r| r.contains_number(field.number, MAX_FIELD_NUMBER))
+            {
+                self.issue(
+                    field.line,
+                    format!(
+                        "Field `{}` uses reserved number {}",
+                        field.name, field.number
+                    ),
+                );
+            }
+            if reserved.iter().any(|r| r.contains_name(&field.name)) {
+                self.issue(
+                    field.line,
+                    format!("Field name `{}` is reserved", field.name),
+                );
+            }
+            if self.proto3 {
+                if field.label.as_deref() == Some("required") {
+                    self.issue(
+                        field.line,
+                        format!(
+                            "Required fields are not allowed in proto3 (`{}`)",
+                            field.name
+                        ),
+                    );
+                }
+                if field.option("default").is_some() {
+                    self.issue(
+                        field.line,
+                        format!(
+                            "Explicit default values are not allowed in proto3 (`{}`)",
+                            field.name
+                        ),
+                    );
+                }
+            }
+            if field.map_key.is_some() && field.label.is_some() {
+                self.issue(
+                    field.line,
+                    format!("Map field `{}` cannot have a label", field.name),
+                );
+            }
+            let json_name = field
+                .option("json_name")
+                .map(|v| v.trim_matches('"').to_string())
+                .unwrap_or_else(|| lower_camel_case(&field.name));
+            if let Some(other) = json_names.insert(json_name.clone(), &field.name) {
+                self.issue(
+                    field.line,
+                    format!(
+                        "Fields `{}` and `{}` have the same JSON name `{}`",
+                        other, field.name, json_name
+                    ),
+                );
+            }
+
+            let Some(kind) = self.field_kind(field, name) else {
+                continue;
+            };
+            let repeated = field.label.as_deref() == Some("repeated") || field.map_key.is_some();
+            let packable = matches!(kind, FieldKind::Enum(_))
+                || matches!(kind, FieldKind::Scalar(s) if s.wire_type() != WIRE_LEN);
+            let packed = repeated
+                && packable
+                && match field.option("packed") {
+                    Some(value) => value == "true",
+                    None => self.proto3,
+                };
+            fields.push(FieldDef {
+                name: field.name.clone(),
+                json_name,
+                number: field.number.clamp(0, u32::MAX as i64) as u32,
+                repeated,
+                packed,
+                kind,
+            });
+        }
+        self.registry
+            .messages
+            .insert(name.to_string(), MessageDef { fields });
+    }
+
+    fn enumeration(&mut self, name: &str, elements: &[Element], line: usize) {
+        let allow_alias = elements
+            .iter()
+            .any(|e| matches!(&e.kind, ElementKind::Option(o) if o.name == "allow_alias" && o.value == "true"));
+        let reserved: Vec<&Reserved> = elements
+            .iter()
+            .filter_map(|e| match &e.kind {
+                ElementKind::Reserved(r) => Some(r),
+                _ => None,
+            })
+            .collect();
+        let mut values = Vec::new();
+        let mut seen: HashMap<i64, String> = HashMap::new();
+        for element in elements {
+            let ElementKind::EnumValue(value, number, _) = &element.kind else {
+                continue;
+            };
+            if values.is_empty() && self.proto3 && *number != 0 {
+                self.issue(
+                    element.line,
+                    format!("The first value of proto3 enum `{}` must be zero", name),
+                );
+            }
+            if let Some(other) = seen.insert(*number, value.clone()) {
+                if !allow_alias {
+                    self.issue(element.line, format!("`{}` and `{}` share value {}; set `option allow_alias = true` to allow this", other, value, number));
+                }
+            }
+            if reserved
+                .iter()
+                .any(|r| r.contains_number(*number, i32::MAX as i64) || r.contains_name(value))
+            {
+                self.issue(element.line, format!("Enum value `{}` is reserved", value));
+            }
+            values.push((value.clone(), *number));
+        }
+        if values.is_empty() {
+            self.issue(
+                line,
+                format!("Enum `{}` must have at least one value", name),
+            );
+        }
+        self.registry
+            .enums
+            .insert(name.to_string(), EnumDef { values });
+    }
+
+    fn file(&mut self, elements: &[Element], scope: &str) {
+        for element in elements {
+            match &element.kind {
+                ElementKind::Message(name, children) => {
+                    self.message(&qualify(scope, name), children)
+                }
+                ElementKind::Enum(name, children) => {
+                    self.enumeration(&qualify(scope, name), children, element.line)
+                }
+                ElementKind::Service(_, children) => {
+                    for child in children {
+                        if let ElementKind::Rpc(rpc) = &child.kind {
+                            for ty in [&rpc.request, &rpc.response] {
+                                if !matches!(
+                                    resolve(ty, scope, &self.declared),
+                                    Some((_, Declared::Message))
+                                ) {
+                                    self.issue(
+                                        child.line,
+                                        format!(
+                                            "Unknown message type `{}` in rpc `{}`",
+                                            ty, rpc.name
+                                        ),
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn build_registry(file: &ProtoFile, issues: &mut Vec<ProtoIssue>) -> Registry {
+    let scope = file.package().unwrap_or("").to_string();
+    let mut declared = HashMap::new();
+    declare(&file.elements, &scope, &mut declared);
+    let mut builder = SchemaBuilder {
+        declared,
+        proto3: file.syntax() != "proto2",
+        registry: Registry::default(),
+        issues,
+    };
+    builder.file(&file.elements, &scope);
+    builder.registry
+}
+
+/// Check syntax and the semantic rules protoc enforces for a single file
+pub fn validate_proto(input: &str) -> ProtoValidation {
+    let mut issues = Vec::new();
+    match parse(input) {
+        Ok(file) => {
+            let syntax = file.syntax();
+            if !matches!(syntax, "proto2" | "proto3") && syntax.parse::<u32>().is_err() {
+                issues.push(ProtoIssue {
+                    line: 1,
+                    message: format!("Unknown syntax `{}`", syntax),
+                });
+            }
+            build_registry(&file, &mut issues);
+        }
+        Err(ProtobufError::Parse { line, message }) => {
+            issues.push(ProtoIssue { line, message })
+        }
+        Err(e) => issues.push(ProtoIssue {
+            line: 0,
+            message: e.to_string(),
+        }),
+    }
+    issues.sort_by_key(|i| i.line);
+    ProtoValidation {
+        valid: issues.is_empty(),
+        issues,
+    }
+}
+
+fn load_schema(proto: &str) -> Result<Registry, ProtobufError> {
+    let file = parse(proto)?;
+    let mut issues = Vec::new();
+    let registry = build_registry(&file, &mut issues);
+    match issues.first() {
+        Some(issue) => Err(ProtobufError::Schema(format!(
+            "line {}: {}",
+            issue.line, issue.message
+        ))),
+        None => Ok(registry),
+    }
+}
+
+impl Registry {
+    /// Find a message by full name, or by a unique unqualified suffix
+    fn find_message(&self, name: &str) -> Result<String, ProtobufError> {
+        let name = name.trim_start_matches('.');
+        if self.messages.contains_key(name) {
+            return Ok(name.to_string());
+        }
+        let suffix = format!(".{}", name);
+        let mut matches: Vec<&String> = self
+            .messages
+            .keys()
+            .filter(|k| k.ends_with(&suffix))
+            .collect();
+        matches.sort();
+        match matches.as_slice() {
+            [only] => Ok((*only).clone()),
+            [] => Err(ProtobufError::Schema(format!(
+                "Message type `{}` not found",
+                name
+            ))),
+            _ => Err(ProtobufError::Schema(format!(
+                "Message type `{}` is ambiguous: {}",
+                name,
+                matches
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+}
+
+// Wire format
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_I64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+const WIRE_SGROUP: u8 = 3;
+const WIRE_EGROUP: u8 = 4;
+const WIRE_I32: u8 = 5;
+
+fn wire_type_name(wire_type: u8) -> &'static str {
+    match wire_type {
+        WIRE_VARINT => "VARINT",
+        WIRE_I64 => "I64",
+        WIRE_LEN => "LEN",
+        WIRE_SGROUP => "SGROUP",
+        WIRE_EGROUP => "EGROUP",
+        WIRE_I32 => "I32",
+        _ => "INVALID",
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..70).step_by(7) {
+            let byte = *self
+                .bytes
+                .get(self.pos)
+                .ok_or_else(|| format!("truncated varint at offset {}", self.pos))?;
+            self.pos += 1;
+            if shift == 63 && byte > 1 {
+                return Err(format!("varint overflow at offset {}", self.pos - 1));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(format!("varint too long at offset {}", self.pos))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| {
+                format!(
+                    "{} bytes requested at offset {} but only {} remain",
+                    len,
+                    self.pos,
+                    self.bytes.len() - self.pos
+                )
+            })?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn fixed32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn fixed64(&mut self) -> Result<u64, String> {
+        let bytes = self.take(8)?;
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Read a tag, returning (field number, wire type)
+    fn tag(&mut self) -> Result<(u32, u8), String> {
+        let offset = self.pos;
+        let tag = self.varint()?;
+        let number = tag >> 3;
+        let wire_type = (tag & 7) as u8;
+        if number == 0 || number > MAX_FIELD_NUMBER as u64 {
+            return Err(format!(
+                "invalid field number {} at offset {}",
+                number, offset
+            ));
+        }
+        if wire_type > WIRE_I32 {
+            return Err(format!(
+                "invalid wire type {} at offset {}",
+                wire_type, offset
+            ));
+        }
+        Ok((number as u32, wire_type))
+    }
+}
+
+fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_tag(out: &mut Vec<u8>, number: u32, wire_type: u8) {
+    write_varint(out, (u64::from(number) << 3) | u64::from(wire_type));
+}
+
+/// A field as seen on the wire, without a schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawField {
+    pub number: u32,
+    pub wire_type: u8,
+    pub wire_type_name: String,
+    pub offset: usize,
+    pub value: RawValue,
+}
+
+/// Interpretations of a raw field value; several may be plausible for one field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RawValue {
+    Varint {
+        unsigned: u64,
+        signed: i64,
+        zigzag: i64,
+        boolean: Option<bool>,
+    },
+    Fixed64 {
+        hex: String,
+        unsigned: u64,
+        signed: i64,
+        double: f64,
+    },
+    Fixed32 {
+        hex: String,
+        unsigned: u32,
+        signed: i32,
+        float: f32,
+    },
+    LengthDelimited {
+        length: usize,
+        hex: String,
+        string: Option<String>,
+        message: Option<Vec<RawField>>,
+        packed_varints: Option<Vec<u64>>,
+    },
+    Group {
+        fields: Vec<RawField>,
+    },
+}
+
+fn looks_like_text(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+}
+
+fn decode_raw_fields(bytes: &[u8], depth: usize, base: usize) -> Result<Vec<RawField>, String> {
+    let mut reader = Reader::new(bytes);
+    let mut fields = Vec::new();
+    let mut group_stack: Vec<(u32, Vec<RawField>, usize)> = Vec::new();
+
+    while !reader.done() {
+        let offset = base + reader.pos;
+        let (number, wire_type) = reader.tag()?;
+        let value = match wire_type {
+            WIRE_VARINT => {
+                let unsigned = reader.varint()?;
+                RawValue::Varint {
+                    unsigned,
+                    signed: unsigned as i64,
+                    zigzag: zigzag_decode(unsigned),
+                    boolean: (unsigned <= 1).then_some(unsigned == 1),
+                }
+            }
+            WIRE_I64 => {
+                let unsigned = reader.fixed64()?;
+                RawValue::Fixed64 {
+                    hex: format!("{:016x}", unsigned),
+                    unsigned,
+                    signed: unsigned as i64,
+                    double: f64::from_bits(unsigned),
+                }
+            }
+            WIRE_I32 => {
+                let unsigned = reader.fixed32()?;
+                RawValue::Fixed32 {
+                    hex: format!("{:08x}", unsigned),
+                    unsigned,
+                    signed: unsigned as i32,
+                    float: f32::from_bits(unsigned),
+                }
+            }
+            WIRE_LEN => {
+                let length = reader.varint()? as usize;
+                let start = base + reader.pos;
+                let data = reader.take(length)?;
+                let message = if depth < MAX_NESTING && !data.is_empty() {
+                    decode_raw_fields(data, depth + 1, start).ok()
+                } else {
+                    None
+                };
+                let mut packed = Reader::new(data);
+                let mut packed_varints = Vec::new();
+                while !packed.done() {
+                    match packed.varint() {
+                        Ok(v) => packed_varints.push(v),
+                        Err(_) => break,
+                    }
+                }
+                let packed_varints =
+                    (packed.done() && packed_varints.len() > 1).then_some(packed_varints);
+                RawValue::LengthDelimited {
+                    length,
+                    hex: hex::encode(data),
+                    string: std::str::from_utf8(data)
+                        .ok()
+                        .filter(|s| looks_like_text(s))
+                        .map(String::from),
+                    message,
+                    packed_varints,
+                }
+            }
+            WIRE_SGROUP => {
+                group_stack.push((number, std::mem::take(&mut fields), offset));
+                continue;
+            }
+            _ => {
+                let (open, mut parent, group_offset) = group_stack
+                    .pop()
+                    .ok_or_else(|| format!("unexpected end-group at offset {}", offset))?;
+                if open != number {
+                    return Err(format!(
+                        "end-group {} does not match start-group {} at offset {}",
+                        number, open, offset
+                    ));
+                }
+                parent.push(RawField {
+                    number,
+                    wire_type: WIRE_SGROUP,
+                    wire_type_name: wire_type_name(WIRE_SGROUP).to_string(),
+                    offset: group_offset,
+                    value: RawValue::Group {
+                        fields: std::mem::take(&mut fields),
+                    },
+                });
+                fields = parent;
+                continue;
+            }
+        };
+        fields.push(RawField {
+            number,
+            wire_type,
+            wire_type_name: wire_type_name(wire_type).to_string(),
+            offset,
+            value,
+        });
+    }
+    if let Some((number, _, offset)) = group_stack.last() {
+        return Err(format!(
+            "group {} starting at offset {} is never closed",
+            number, offset
+        ));
+    }
+    Ok(fields)
+}
+
+/// Decode protobuf bytes without a schema, guessing at each field's meaning
+pub fn decode_protobuf_raw(bytes: &[u8]) -> Result<Vec<RawField>, ProtobufError> {
+    decode_raw_fields(bytes, 0, 0).map_err(ProtobufError::Decode)
+}
+
+struct Codec<'a> {
+    registry: &'a Registry,
+}
+
+impl Codec<'_> {
+    fn decode_scalar(
+        &self,
+        kind: &FieldKind,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> Result<Value, String> {
+        let float = |f: f64| {
+            if f.is_nan() {
+                Value::from("NaN")
+            } else if f.is_infinite() {
+                Value::from(if f > 0.0 { "Infinity" } else { "-Infinity" })
+            } else {
+                serde_json::Number::from_f64(f).map_or(Value::Null, Value::Number)
+            }
+        };
+        Ok(match kind {
+            FieldKind::Scalar(scalar) => match scalar {
+                Scalar::Int32 => Value::from(reader.varint()? as i32),
+                Scalar::Int64 => Value::from((reader.varint()? as i64).to_string()),
+                Scalar::Uint32 => Value::from(reader.varint()? as u32),
+                Scalar::Uint64 => Value::from(reader.varint()?.to_string()),
+                Scalar::Sint32 => Value::from(zigzag_decode(reader.varint()?) as i32),
+                Scalar::Sint64 => Value::from(zigzag_decode(reader.varint()?).to_string()),
+                Scalar::Bool => Value::from(reader.varint()? != 0),
+                Scalar::Fixed32 => Value::from(reader.fixed32()?),
+                Scalar::Sfixed32 => Value::from(reader.fixed32()? as i32),
+                Scalar::Fixed64 => Value::from(reader.fixed64()?.to_string()),
+                Scalar::Sfixed64 => Value::from((reader.fixed64()? as i64).to_string()),
+                Scalar::Float => float(f64::from(f32::from_bits(reader.fixed32()?))),
+                Scalar::Double => float(f64::from_bits(reader.fixed64()?)),
+                Scalar::String => {
+                    let len = reader.varint()? as usize;
+                    let data = reader.take(len)?;
+                    Value::from(
+                        std::str::from_utf8(data)
+                            .map_err(|_| "string field is not valid UTF-8".to_string())?,
+                    )
+                }
+                Scalar::Bytes => {
+                    let len = reader.varint()? as usize;
+                    Value::from(general_purpose::STANDARD.encode(reader.take(len)?))
+                }
+            },
+            FieldKind::Enum(name) => {
+                let number = reader.varint()? as i32 as i64;
+                self.registry.enums[name]
+                    .values
+                    .iter()
+                    .find(|(_, n)| *n == number)
+                    .map_or(Value::from(number), |(v, _)| Value::from(v.clone()))
+            }
+            FieldKind::Message(name) => {
+                let len = reader.varint()? as usize;
+                Value::Object(self.decode_message(reader.take(len)?, name, depth + 1)?)
+            }
+            FieldKind::Map(..) => unreachable!("map entries are decoded by decode_message"),
+        })
+    }
+
+    fn default_value(&self, kind: &FieldKind) -> Value {
+        match kind {
+            FieldKind::Scalar(Scalar::String) | FieldKind::Scalar(Scalar::Bytes) => Value::from(""),
+            FieldKind::Scalar(Scalar::Bool) => Value::from(false),
+            FieldKind::Scalar(
+                Scalar::Int64
+                | Scalar::Uint64
+                | Scalar::Sint64
+                | Scalar::Fixed64
+                | Scalar::Sfixed64,
+            ) => Value::from("0"),
+            FieldKind::Scalar(_) => Value::from(0),
+            FieldKind::Enum(name) => self.registry.enums[name]
+                .values
+                .first()
+                .map_or(Value::from(0), |(v, _)| Value::from(v.clone())),
+            FieldKind::Message(_) | FieldKind::Map(..) => Value::Object(Map::new()),
+        }
+    }
+
+    fn decode_message(
+        &self,
+        bytes: &[u8],
+        name: &str,
+        depth: usize,
+    ) -> Result<Map<String, Value>, String> {
+        if depth > MAX_NESTING {
+            return Err("message nesting is too deep".to_string());
+        }
+        let message = &self.registry.messages[name];
+        let mut values: HashMap<u32, Value> = HashMap::new();
+        let mut reader = Reader::new(bytes);
+
+        while !reader.done() {
+            let (number, wire_type) = reader.tag()?;
+            let Some(field) = message.fields.iter().find(|f| f.number == number) else {
+                skip_field(&mut reader, number, wire_type)?;
+                continue;
+            };
+            let context = |e: String| format!("{}.{}: {}", name, field.name, e);
+
+            if let FieldKind::Map(key_kind, value_kind) = &field.kind {
+                let len = reader.varint()? as usize;
+                let mut entry = Reader::new(reader.take(len)?);
+                let mut key = self.default_value(&FieldKind::Scalar(*key_kind));
+                let mut value = self.default_value(value_kind);
+                while !entry.done() {
+                    match entry.tag()? {
+                        (1, _) => {
+                            key = self
+                                .decode_scalar(&FieldKind::Scalar(*key_kind), &mut entry, depth)
+                                .map_err(context)?
+                        }
+                        (2, _) => {
+                            value = self
+                                .decode_scalar(value_kind, &mut entry, depth)
+                                .map_err(context)?
+                        }
+                        (n, w) => skip_field(&mut entry, n, w)?,
+                    }
+                }
+                let key = match key {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                if let Value::Object(map) = values
+                    .entry(number)
+                    .or_insert_with(|| Value::Object(Map::new()))
+                {
+                    map.insert(key, value);
+                }
+                continue;
+            }
+
+            let expected = field.kind.wire_type();
+            let mut decoded = Vec::new();
+            if field.repeated && wire_type == WIRE_LEN && expected != WIRE_LEN {
+                let len = reader.varint()? as usize;
+                let mut packed = Reader::new(reader.take(len)?);
+                while !packed.done() {
+                    decoded.push(
+                        self.decode_scalar(&field.kind, &mut packed, depth)
+                            .map_err(context)?,
+                    );
+                }
+            } else if wire_type == expected {
+                decoded.push(
+                    self.decode_scalar(&field.kind, &mut reader, depth)
+                        .map_err(context)?,
+                );
+            } else {
+                return Err(context(format!(
+                    "expected wire type {} but found {}",
+                    wire_type_name(expected),
+                    wire_type_name(wire_type)
+                )));
+            }
+
+            if field.repeated {
+                if let Value::Array(items) = values
+                    .entry(number)
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                {
+                    items.extend(decoded);
+                }
+            } else if let Some(value) = decoded.pop() {
+                match (values.get_mut(&number), value) {
+                    // Repeated occurrences of a singular message field are merged
+                    (Some(Value::Object(existing)), Value::Object(update)) => {
+                        existing.extend(update)
+                    }
+                    (_, value) => {
+                        values.insert(number, value);
+                    }
+                }
+            }
+        }
+
+        let mut out = Map::new();
+        for field in &message.fields {
+            if let Some(value) = values.remove(&field.number) {
+                out.insert(field.json_name.clone(), value);
+            }
+        }
+        Ok(out)
+    }
+
+    fn encode_scalar(
+        &self,
+        kind: &FieldKind,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<(), String> {
+        fn integer(value: &Value) -> Result<i128, String> {
+            match value {
+                Value::Number(n) => n
+                    .as_i64()
+                    .map(i128::from)
+                    .or_else(|| n.as_u64().map(i128::from))
+                    .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i128))
+                    .ok_or_else(|| format!("{} is not an integer", n)),
+                Value::String(s) => s
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("\"{}\" is not an integer", s)),
+                other => Err(format!("expected an integer, found {}", other)),
+            }
+        }
+        fn ranged(value: &Value, min: i128, max: i128) -> Result<i128, String> {
+            let n = integer(value)?;
+            if (min..=max).contains(&n) {
+                Ok(n)
+            } else {
+                Err(format!("{} is out of range", n))
+            }
+        }
+        fn float(value: &Value) -> Result<f64, String> {
+            match value {
+                Value::Number(n) => n.as_f64().ok_or_else(|| format!("{} is not a number", n)),
+                Value::String(s) => match s.as_str() {
+                    "NaN" => Ok(f64::NAN),
+                    "Infinity" => Ok(f64::INFINITY),
+                    "-Infinity" => Ok(f64::NEG_INFINITY),
+                    s => s.parse().map_err(|_| format!("\"{}\" is not a number", s)),
+                },
+                other => Err(format!("expected a number, found {}", other)),
+            }
+        }
+
+        match kind {
+            FieldKind::Scalar(scalar) => match scalar {
+                Scalar::Int32 => write_varint(
+                    out,
+                    ranged(value, i32::MIN.into(), i32::MAX.into())? as i64 as u64,
+                ),
+                Scalar::Int64 => write_varint(
+                    out,
+                    ranged(value, i64::MIN.into(), i64::MAX.into())? as i64 as u64,
+                ),
+                Scalar::Uint32 => write_varint(out, ranged(value, 0, u32::MAX.into())? as u64),
+                Scalar::Uint64 => write_varint(out, ranged(value, 0, u64::MAX.into())? as u64),
+                Scalar::Sint32 => write_varint(
+                    out,
+                    zigzag_encode(ranged(value, i32::MIN.into(), i32::MAX.into())? as i64),
+                ),
+                Scalar::Sint64 => write_varint(
+                    out,
+                    zigzag_encode(ranged(value, i64::MIN.into(), i64::MAX.into())? as i64),
+                ),
+                Scalar::Bool => match value {
+                    Value::Bool(b) => write_varint(out, u64::from(*b)),
+                    other => return Err(format!("expected a boolean, found {}", other)),
+                },
+                Scalar::Fixed32 => {
+                    out.extend((ranged(value, 0, u32::MAX.into())? as u32).to_le_bytes())
+                }
+                Scalar::Sfixed32 => out.extend(
+                    (ranged(value, i32::MIN.into(), i32::MAX.into())? as i32).to_le_bytes(),
+                ),
+                Scalar::Fixed64 => {
+                    out.extend((ranged(value, 0, u64::MAX.into())? as u64).to_le_bytes())
+                }
+                Scalar::Sfixed64 => out.extend(
+                    (ranged(value, i64::MIN.into(), i64::MAX.into())? as i64).to_le_bytes(),
+                ),
+                Scalar::Float => out.extend((float(value)? as f32).to_le_bytes()),
+                Scalar::Double => out.extend(float(value)?.to_le_bytes()),
+                Scalar::String => match value {
+                    Value::String(s) => {
+                        write_varint(out, s.len() as u64);
+                        out.extend(s.as_bytes());
+                    }
+                    other => return Err(format!("expected a string, found {}", other)),
+                },
+                Scalar::Bytes => match value {
+                    Value::String(s) => {
+                        let data = general_purpose::STANDARD
+                            .decode(s)
+                            .or_else(|_| {
+                                general_purpose::URL_SAFE_NO_PAD.decode(s.trim_end_matches('='))
+                            })
+                            .map_err(|_| "bytes fields must be base64".to_string())?;
+                        write_varint(out, data.len() as u64);
+                        out.extend(data);
+                    }
+                    other => return Err(format!("expected a base64 string, found {}", other)),
+                },
+            },
+            FieldKind::Enum(name) => {
+                let number = match value {
+                    Value::String(s) => self.registry.enums[name]
+                        .values
+                        .iter()
+                        .find(|(v, _)| v == s)
+                        .map(|(_, n)| *n)
+                        .ok_or_else(|| format!("`{}` is not a value of enum `{}`", s, name))?,
+                    other => ranged(other, i32::MIN.into(), i32::MAX.into())? as i64,
+                };
+                write_varint(out, number as u64);
+            }
+            FieldKind::Message(name) => {
+                let Value::Object(object) = value else {
+                    return Err(format!("expected an object for message `{}`", name));
+                };
+                let body = self.encode_message(object, name, depth + 1)?;
+                write_varint(out, body.len() as u64);
+                out.extend(body);
+            }
+            FieldKind::Map(..) => unreachable!("map entries are encoded by encode_message"),
+        }
+        Ok(())
+    }
+
+    fn encode_message(
+        &self,
+        object: &Map<String, Value>,
+        name: &str,
+        depth: usize,
+    ) -> Result<Vec<u8>, String> {
+        if depth > MAX_NESTING {
+            return Err("message nesting is too deep".to_string());
+        }
+        let message = &self.registry.messages[name];
+        for key in object.keys() {
+            if !message
+                .fields
+                .iter()
+                .any(|f| &f.json_name == key || &f.name == key)
+            {
+                return Err(format!("`{}` has no field `{}`", name, key));
+            }
+        }
+
+        let mut out = Vec::new();
+        for field in &message.fields {
+            let value = object
+                .get(&field.json_name)
+                .or_else(|| object.get(&field.name));
+            let Some(value) = value.filter(|v| !v.is_null()) else {
+                continue;
+            };
+            let context = |e: String| format!("{}.{}: {}", name, field.name, e);
+
+            if let FieldKind::Map(key_kind, value_kind) = &field.kind {
+                let Value::Object(entries) = value else {
+                    return Err(context("expected an object for map field".to_string()));
+                };
+                for (key, entry_value) in entries {
+                    let key_value = match key_kind {
+                        Scalar::String => Value::from(key.clone()),
+                        Scalar::Bool => Value::from(key == "true"),
+                        _ => Value::from(key.clone()),
+                    };
+                    let mut entry = Vec::new();
+                    write_tag(&mut entry, 1, key_kind.wire_type());
+                    self.encode_scalar(
+                        &FieldKind::Scalar(*key_kind),
+                        &key_value,
+                        &mut entry,
+                        depth,
+                    )
+                    .map_err(context)?;
+                    write_tag(&mut entry, 2, value_kind.wire_type());
+                    self.encode_scalar(value_kind, entry_value, &mut entry, depth)
+                        .map_err(context)?;
+                    write_tag(&mut out, field.number, WIRE_LEN);
+                    write_varint(&mut out, entry.len() as u64);
+                    out.extend(entry);
+                }
+                continue;
+            }
+
+            let items: Vec<&Value> = match (field.repeated, value) {
+                (true, Value::Array(items)) => items.iter().collect(),
+                (true, _) => {
+                    return Err(context("expected an array for repeated field".to_string()))
+                }
+                (false, value) => vec![value],
+            };
+            if field.packed {
+                if items.is_empty() {
+                    continue;
+                }
+                let mut packed = Vec::new();
+                for item in items {
+                    self.encode_scalar(&field.kind, item, &mut packed, depth)
+                        .map_err(context)?;
+                }
+                write_tag(&mut out, field.number, WIRE_LEN);
+                write_varint(&mut out, packed.len() as u64);
+                out.extend(packed);
+            } else {
+                for item in items {
+                    write_tag(&mut out, field.number, field.kind.wire_type());
+                    self.encode_scalar(&field.kind, item, &mut out, depth)
+                        .map_err(context)?;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn skip_field(reader: &mut Reader, number: u32, wire_type: u8) -> Result<(), String> {
+    match wire_type {
+        WIRE_VARINT => {
+            reader.varint()?;
+        }
+        WIRE_I64 => {
+            reader.take(8)?;
+        }
+        WIRE_I32 => {
+            reader.take(4)?;
+        }
+        WIRE_LEN => {
+            let len = reader.varint()? as usize;
+            reader.take(len)?;
+        }
+        WIRE_SGROUP => loop {
+            let (inner, inner_type) = reader.tag()?;
+            if inner_type == WIRE_EGROUP {
+                if inner != number {
+                    return Err(format!("mismatched end-group for field {}", number));
+                }
+                break;
+            }
+            skip_field(reader, inner, inner_type)?;
+        },
+        _ => return Err(format!("unexpected end-group for field {}", number)),
+    }
+    Ok(())
+}
+
+/// Decode protobuf bytes into proto3 JSON using a message type from `proto`
+pub fn decode_protobuf(
+    bytes: &[u8],
+    proto: &str,
+    message_type: &str,
+) -> Result<Value, ProtobufError> {
+    let registry = load_schema(proto)?;
+    let name = registry.find_message(message_type)?;
+    let codec = Codec {
+        registry: &registry,
+    };
+    codec
+        .decode_message(bytes, &name, 0)
+        .map(Value::Object)
+        .map_err(ProtobufError::Decode)
+}
+
+/// Encode a JSON object into protobuf bytes using a message type from `proto`
+///
+/// Field names may be given as JSON (lowerCamelCase) or as declared in the file.
+pub fn encode_protobuf(
+    json: &str,
+    proto: &str,
+    message_type: &str,
+) -> Result<Vec<u8>, ProtobufError> {
+    let registry = load_schema(proto)?;
+    let name = registry.find_message(message_type)?;
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| ProtobufError::Encode(format!("Invalid JSON: {}", e)))?;
+    let Value::Object(object) = value else {
+        return Err(ProtobufError::Encode(
+            "Top-level JSON value must be an object".to_string(),
+        ));
+    };
+    let codec = Codec {
+        registry: &registry,
+    };
+    codec
+        .encode_message(&object, &name, 0)
+        .map_err(ProtobufError::Encode)
+}
+
+/// Raw wire dump, plus typed JSON when a schema is supplied
+#[derive(Debug, Serialize)]
+pub struct ProtobufInspection {
+    pub fields: Vec<RawField>,
+    pub json: Option<Value>,
+}
+
+pub fn inspect_protobuf(
+    bytes: &[u8],
+    proto: Option<&str>,
+    message_type: Option<&str>,
+) -> Result<ProtobufInspection, ProtobufError> {
+    let fields = decode_protobuf_raw(bytes)?;
+    let json = match (proto.filter(|p| !p.trim().is_empty()), message_type) {
+        (Some(proto), Some(message_type)) => Some(decode_protobuf(bytes, proto, message_type)?),
+        (Some(_), None) => {
+            return Err(ProtobufError::Schema(
+                "A message type is required to decode with a .proto file".to_string(),
+            ))
+        }
+        (None, _) => None,
+    };
+    Ok(ProtobufInspection { fields, json })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTO: &str = r#"
+syntax = "proto3";
+package demo.v1;
+
+// A user account
+message User {
+  string name = 1;
+  int64 id = 2;
+  repeated int32 scores = 3;
+  Role role = 4;
+  map<string, int32> counters = 5;
+  Address address = 6;
+  bytes avatar = 7;
+  sint32 delta = 8;
+  double ratio = 9;
+  enum Role {
+    ROLE_UNSPECIFIED = 0;
+    ROLE_ADMIN = 1;
+  }
+}
+
+message Address { string city = 1; }
+"#;
+
+    #[test]
+    fn test_format_proto() {
+        let input = r#"syntax="proto3";package  a.b;
+import "x.proto";import public "y.proto";
+option java_package="com.a";
+// The request
+message Req{string name=1 [deprecated=true,json_name="n"];map<string,Req> children=2;
+oneof kind{int32 a=3;string b=4;}
+message Inner{}
+reserved 5,6 to 8;reserved "old";}
+service Svc{rpc Get(Req)returns(stream Req){option deprecated=true;}}"#;
+        let expected = r#"syntax = "proto3";
+
+package a.b;
+
+import "x.proto";
+import public "y.proto";
+
+option java_package = "com.a";
+
+// The request
+message Req {
+  string name = 1 [deprecated = true, json_name = "n"];
+  map<string, Req> children = 2;
+
+  oneof kind {
+    int32 a = 3;
+    string b = 4;
+  }
+
+  message Inner {}
+
+  reserved 5, 6 to 8;
+  reserved "old";
+}
+
+service Svc {
+  rpc Get(Req) returns (stream Req) {
+    option deprecated = true;
+  }
+}
+"#;
+        let formatted = format_proto(input, 2).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_proto(&formatted, 2).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_validate_proto() {
+        assert!(validate_proto(PROTO).valid);
+
+        let report = validate_proto(
+            r#"syntax = "proto3";
+message A {
+  required string a = 1;
+  int32 b = 1;
+  Missing c = 3;
+  int32 d = 19500;
+  int32 e = 7;
+  reserved 7;
+}
+enum E { ONE = 1; TWO = 1; }"#,
+        );
+        let messages: Vec<(usize, &str)> = report
+            .issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (3, "Required fields are not allowed in proto3 (`a`)"),
+                (4, "Field number 1 is used by both `a` and `b`"),
+                (5, "Unknown type `Missing` for field `c`"),
+                (
+                    6,
+                    "Field number 19500 of `d` is reserved for the protobuf implementation"
+                ),
+                (7, "Field `e` uses reserved number 7"),
+                (10, "The first value of proto3 enum `E` must be zero"),
+                (
+                    10,
+                    "`ONE` and `TWO` share value 1; set `option allow_alias = true` to allow this"
+                ),
+            ]
+        );
+
+        let report = validate_proto("message A { string a = 1 }");
+        assert_eq!(report.issues[0].message, "expected `;`, found `}`");
+    }
+
+    #[test]
+    fn test_decode_raw() {
+        // field 1: varint 150, field 2: "hi", field 3: nested { 1: 1 }, field 4: fixed32
+        let bytes = hex::decode("089601120268691a0208012504030201").unwrap();
+        let fields = decode_protobuf_raw(&bytes).unwrap();
+        assert_eq!(fields.len(), 4);
+        assert!(matches!(
+            fields[0].value,
+            RawValue::Varint {
+                unsigned: 150,
+                zigzag: 75,
+                ..
+            }
+        ));
+        match &fields[1].value {
+            RawValue::LengthDelimited { string, .. } => assert_eq!(string.as_deref(), Some("hi")),
+            other => panic!("unexpected {:?}", other),
+        }
+        match &fields[2].value {
+            RawValue::LengthDelimited {
+                message: Some(inner),
+                ..
+            } => assert_eq!(inner[0].number, 1),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(fields[3].wire_type_name, "I32");
+        assert!(matches!(
+            fields[3].value,
+            RawValue::Fixed32 {
+                unsigned: 0x01020304,
+                ..
+            }
+        ));
+
+        assert!(decode_protobuf_raw(&[0x08]).is_err());
+        assert!(decode_protobuf_raw(&[0x12, 0x05, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let json = r#"{"name":"Ferris","id":"9007199254740993","scores":[1,2,300],"role":"ROLE_ADMIN",
+            "counters":{"a":1,"b":2},"address":{"city":"Oslo"},"avatar":"AQID","delta":-3,"ratio":0.5}"#;
+        let bytes = encode_protobuf(json, PROTO, "User").unwrap();
+        // Packed repeated int32 in proto3
+        assert!(hex::encode(&bytes).contains("1a040102ac02"));
+
+        let decoded = decode_protobuf(&bytes, PROTO, "demo.v1.User").unwrap();
+        let expected: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(decoded, expected);
+
+        // Original field names are accepted too
+        let bytes = encode_protobuf(r#"{"name":"x"}"#, PROTO, "User").unwrap();
+        assert_eq!(bytes, vec![0x0a, 0x01, b'x']);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        assert!(encode_protobuf(r#"{"nope":1}"#, PROTO, "User").is_err());
+        assert!(encode_protobuf(r#"{"role":"ROLE_X"}"#, PROTO, "User").is_err());
+        assert!(encode_protobuf(r#"{"scores":[1e20]}"#, PROTO, "User").is_err());
+        assert!(encode_protobuf("{}", PROTO, "Missing").is_err());
+        assert!(encode_protobuf("{}", "message A { B b = 1; }", "A").is_err());
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let deep = format!("{}{}", "message M {".repeat(2000), "}".repeat(2000));
+        assert!(matches!(
+            format_proto(&deep, 2),
+            Err(ProtobufError::Parse { .. })
+        ));
+        assert!(!validate_proto(&deep).valid);
+
+        let nested = format!("{}{}", "message M {".repeat(MAX_NESTING), "}".repeat(MAX_NESTING));
+        assert!(format_proto(&nested, 2).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
+use serde::{Deserialize, Serialize};
 use serde_yaml::{self, Value};
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -7,60 +9,1375 @@ pub enum YamlError {
     ParseError(#[from] serde_yaml::Error),
     #[error("YAML format error: {0}")]
     FormatError(String),
+    /// Valid YAML the comment-preserving parser cannot represent
+    #[error("Unsupported YAML construct: {0}")]
+    Unsupported(String),
 }
 
-/// Format YAML with proper indentation
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum YamlKeyOrder {
+    #[default]
+    Preserve,
+    Alphabetical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct YamlFormatOptions {
+    pub indent: usize,
+    pub preserve_comments: bool,
+    /// Replace aliases with copies of their anchored nodes and apply `<<` merge keys
+    pub expand_anchors: bool,
+    pub key_order: YamlKeyOrder,
+    /// Keys moved to the front of every mapping, in this order (e.g. apiVersion, kind, metadata)
+    pub priority_keys: Vec<String>,
+    /// Indent block sequences under their parent key instead of aligning the dashes with it
+    pub indent_sequences: bool,
+}
+
+impl Default for YamlFormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            preserve_comments: true,
+            expand_anchors: false,
+            key_order: YamlKeyOrder::Preserve,
+            priority_keys: Vec::new(),
+            indent_sequences: true,
+        }
+    }
+}
+
+/// Format YAML with proper indentation, keeping comments, anchors and every document
 pub fn format_yaml(yaml: &str, indent_size: usize) -> Result<String, YamlError> {
-    // Parse YAML
-    let value: Value = serde_yaml::from_str(yaml)?;
-    
-    // Serialize back with formatting
-    let formatted = serde_yaml::to_string(&value)?;
-    
-    // Adjust indentation if needed (serde_yaml uses 2-space indent by default)
-    if indent_size != 2 {
-        Ok(adjust_indentation(&formatted, indent_size))
-    } else {
-        Ok(formatted)
+    let options = YamlFormatOptions {
+        indent: indent_size,
+        ..Default::default()
+    };
+    format_yaml_with_options(yaml, &options)
+}
+
+/// Format YAML using the given options
+/// Input the layout-preserving parser does not support (such as complex `? key` mappings) is
+/// re-serialized through serde_yaml instead, which drops comments and anchors
+pub fn format_yaml_with_options(yaml: &str, options: &YamlFormatOptions) -> Result<String, YamlError> {
+    let expected = parse_documents(yaml)?;
+    if yaml.trim().is_empty() {
+        return Ok(String::new());
     }
+
+    let mut documents = match parse_stream(yaml) {
+        Err(YamlError::Unsupported(_)) => {
+            let plain = serialize_documents(&expected)?;
+            let indented = adjust_indentation(&plain, options.indent);
+            // Re-indenting compact `? - item` lines can change their meaning
+            return match check_equivalent(&expected, &indented, false) {
+                Ok(()) => Ok(indented),
+                Err(_) => Ok(plain),
+            };
+        }
+        result => result?,
+    };
+    prepare_documents(&mut documents, options)?;
+
+    let mut emitter = Emitter {
+        options,
+        lines: Vec::new(),
+    };
+    for (i, document) in documents.iter().enumerate() {
+        emitter.document(document, i)?;
+    }
+    let formatted = emitter.finish();
+
+    check_equivalent(&expected, &formatted, options.expand_anchors)?;
+    Ok(formatted)
 }
 
 /// Minify YAML (compact representation)
+/// Every document is written on a single line in flow style; anchors and aliases are kept
 pub fn minify_yaml(yaml: &str) -> Result<String, YamlError> {
-    let value: Value = serde_yaml::from_str(yaml)?;
-    
-    // Serialize in compact form (though YAML is inherently verbose)
-    let mut result = serde_yaml::to_string(&value)?;
-    
-    // Remove unnecessary blank lines
-    result = result
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .collect::<Vec<_>>()
-        .join("\n");
-    
+    let expected = parse_documents(yaml)?;
+    if yaml.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    let documents = match parse_stream(yaml) {
+        Err(YamlError::Unsupported(_)) => {
+            let plain = serialize_documents(&expected)?;
+            return Ok(plain.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>().join("\n"));
+        }
+        result => result?,
+    };
+    let mut lines = Vec::new();
+    for (i, document) in documents.iter().enumerate() {
+        lines.extend(document.directives.iter().cloned());
+        if i > 0 || !document.directives.is_empty() {
+            lines.push("---".to_string());
+        }
+        let root = flow_text(&document.root)?;
+        if !root.is_empty() {
+            lines.push(root);
+        }
+    }
+    let result = lines.join("\n");
+
+    check_equivalent(&expected, &result, false)?;
     Ok(result)
 }
 
 /// Validate YAML syntax
 pub fn validate_yaml(yaml: &str) -> bool {
-    serde_yaml::from_str::<Value>(yaml).is_ok()
+    parse_documents(yaml).is_ok()
 }
 
-/// Adjust indentation level
+/// Parse every document of a YAML stream (`---` separated) into values
+pub fn parse_documents(yaml: &str) -> Result<Vec<Value>, YamlError> {
+    serde_yaml::Deserializer::from_str(yaml)
+        .map(|document| Value::deserialize(document).map_err(YamlError::from))
+        .collect()
+}
+
+/// Plain serde_yaml output for every document, without comments or anchors
+fn serialize_documents(documents: &[Value]) -> Result<String, YamlError> {
+    let parts = documents
+        .iter()
+        .map(serde_yaml::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(parts.join("---\n"))
+}
+
+/// Re-indent serde_yaml output, which always uses two spaces
 fn adjust_indentation(yaml: &str, target_indent: usize) -> String {
+    if target_indent == 2 {
+        return yaml.to_string();
+    }
     yaml.lines()
         .map(|line| {
             let trimmed = line.trim_start();
-            let current_indent = line.len() - trimmed.len();
-            let level = current_indent / 2; // assuming current is 2-space
-            let new_indent = " ".repeat(level * target_indent);
-            format!("{}{}", new_indent, trimmed)
+            let level = (line.len() - trimmed.len()) / 2;
+            format!("{}{}", " ".repeat(level * target_indent), trimmed)
         })
         .collect::<Vec<_>>()
         .join("\n")
 }
 
+/// Guard against layout bugs: the output must load to exactly the same data as the input
+fn check_equivalent(expected: &[Value], output: &str, apply_merge: bool) -> Result<(), YamlError> {
+    let mut expected = expected.to_vec();
+    let mut actual = parse_documents(output)
+        .map_err(|e| YamlError::FormatError(format!("Formatter produced invalid YAML: {}", e)))?;
+    if apply_merge {
+        for value in expected.iter_mut().chain(actual.iter_mut()) {
+            value.apply_merge()?;
+        }
+    }
+    if expected != actual {
+        return Err(YamlError::FormatError(
+            "Formatted output does not load to the same data as the input".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Syntax tree
+
+#[derive(Debug, Clone, PartialEq)]
+enum Trivia {
+    Blank,
+    Comment(String),
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    anchor: Option<String>,
+    tag: Option<String>,
+    value: NodeValue,
+}
+
+#[derive(Debug, Clone, Default)]
+enum NodeValue {
+    #[default]
+    Empty,
+    /// Source lines of a plain or quoted scalar, without indentation
+    Scalar(Vec<String>),
+    Block {
+        style: char,
+        chomping: Option<char>,
+        lines: Vec<String>,
+    },
+    Alias(String),
+    Mapping {
+        entries: Vec<Entry>,
+        flow: bool,
+    },
+    Sequence {
+        entries: Vec<Entry>,
+        flow: bool,
+    },
+}
+
+/// A mapping entry (with a key) or a sequence item (without one)
+#[derive(Debug, Clone)]
+struct Entry {
+    trivia: Vec<Trivia>,
+    key: Option<String>,
+    value: Node,
+    comment: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Document {
+    directives: Vec<String>,
+    preamble: Vec<Trivia>,
+    explicit_start: bool,
+    start_comment: Option<String>,
+    root_trivia: Vec<Trivia>,
+    root: Node,
+    trailing: Vec<Trivia>,
+    explicit_end: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Line<'a> {
+    raw: &'a str,
+    indent: usize,
+    text: &'a str,
+    number: usize,
+}
+
+impl<'a> Line<'a> {
+    fn new(raw: &'a str, number: usize) -> Self {
+        let raw = raw.trim_end_matches('\r');
+        let content = raw.trim_start_matches(' ');
+        Line {
+            raw,
+            indent: raw.len() - content.len(),
+            text: content.trim(),
+            number,
+        }
+    }
+
+    fn is_trivia(&self) -> bool {
+        self.text.is_empty() || self.text.starts_with('#')
+    }
+}
+
+/// The input already passed serde_yaml, so a layout parser failure means the
+/// construct is beyond it; callers fall back to plain serde_yaml output
+fn unsupported(line: &Line, message: &str) -> YamlError {
+    YamlError::Unsupported(format!("{} at line {}", message, line.number))
+}
+
+// Parsing
+
+fn is_document_marker(raw: &str, marker: &str) -> bool {
+    raw == marker
+        || raw
+            .strip_prefix(marker)
+            .map(|rest| rest.starts_with([' ', '\t']))
+            .unwrap_or(false)
+}
+
+fn parse_stream(yaml: &str) -> Result<Vec<Document>, YamlError> {
+    struct Pending<'a> {
+        document: Document,
+        start_rest: &'a str,
+        lines: Vec<Line<'a>>,
+    }
+
+    fn has_content(pending: &Pending) -> bool {
+        pending.document.explicit_start || pending.lines.iter().any(|l| !l.is_trivia())
+    }
+
+    let mut pending = Vec::new();
+    let mut current = Pending {
+        document: Document::default(),
+        start_rest: "",
+        lines: Vec::new(),
+    };
+
+    for (i, raw) in yaml.lines().enumerate() {
+        let line = Line::new(raw, i + 1);
+        if line.indent == 0 && line.text.starts_with('%') && !has_content(&current) {
+            current.document.directives.push(line.text.to_string());
+        } else if line.indent == 0 && is_document_marker(line.raw, "---") {
+            if has_content(&current) {
+                let next = Pending {
+                    document: Document::default(),
+                    start_rest: "",
+                    lines: Vec::new(),
+                };
+                pending.push(std::mem::replace(&mut current, next));
+            } else {
+                current.document.preamble = collect_trivia(&current.lines);
+                current.lines.clear();
+            }
+            current.document.explicit_start = true;
+            current.start_rest = line.raw[3..].trim();
+        } else if line.indent == 0 && is_document_marker(line.raw, "...") {
+            current.document.explicit_end = true;
+            let next = Pending {
+                document: Document::default(),
+                start_rest: "",
+                lines: Vec::new(),
+            };
+            pending.push(std::mem::replace(&mut current, next));
+        } else {
+            current.lines.push(line);
+        }
+    }
+    if has_content(&current) || !current.lines.is_empty() || !current.document.directives.is_empty() {
+        pending.push(current);
+    }
+
+    pending
+        .into_iter()
+        .map(|Pending { mut document, start_rest, lines }| {
+            let mut parser = Parser { lines, pos: 0 };
+            let (trivia, root) = if start_rest.is_empty() {
+                parser.block_node(-1, false)?
+            } else if start_rest.starts_with('#') {
+                document.start_comment = Some(start_rest.to_string());
+                parser.block_node(-1, false)?
+            } else {
+                let (root, comment, trivia) = parser.inline_value(start_rest, -1, false)?;
+                document.start_comment = comment;
+                (trivia, root)
+            };
+            document.root_trivia = trivia;
+            document.root = root;
+
+            let (trailing, next) = parser.peek_trivia();
+            if let Some(line) = parser.lines.get(next) {
+                return Err(unsupported(line, "Unexpected content"));
+            }
+            document.trailing = trailing;
+            Ok(document)
+        })
+        .collect()
+}
+
+fn collect_trivia(lines: &[Line]) -> Vec<Trivia> {
+    let mut trivia = Vec::new();
+    for line in lines {
+        if line.text.is_empty() {
+            if trivia.last() != Some(&Trivia::Blank) {
+                trivia.push(Trivia::Blank);
+            }
+        } else {
+            trivia.push(Trivia::Comment(line.text.to_string()));
+        }
+    }
+    trivia
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ") || text.starts_with("-\t")
+}
+
+/// Byte offset of the `:` separating an implicit block key from its value
+fn find_mapping_colon(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let value_indicator = |i: usize| bytes.get(i + 1).map(|b| *b == b' ' || *b == b'\t').unwrap_or(true);
+
+    match bytes.first() {
+        None | Some(b'[') | Some(b'{') | Some(b'#') => None,
+        Some(&quote) if quote == b'"' || quote == b'\'' => {
+            let close = find_closing_quote(text, 0)?;
+            let after = text[close + 1..].trim_start();
+            let offset = text.len() - after.len();
+            (after.starts_with(':') && value_indicator(offset)).then_some(offset)
+        }
+        _ => {
+            let mut previous_blank = false;
+            for (i, &b) in bytes.iter().enumerate() {
+                if b == b'#' && previous_blank {
+                    return None;
+                }
+                if b == b':' && value_indicator(i) {
+                    return Some(i);
+                }
+                previous_blank = b == b' ' || b == b'\t';
+            }
+            None
+        }
+    }
+}
+
+/// Index of the quote closing the quoted scalar that opens at `start`
+fn find_closing_quote(text: &str, start: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if quote == b'"' => i += 1,
+            b'\'' if quote == b'\'' && bytes.get(i + 1) == Some(&b'\'') => i += 1,
+            b if b == quote => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split a single-line value from its trailing comment
+fn split_comment(text: &str) -> (&str, Option<String>) {
+    let bytes = text.as_bytes();
+    let mut token_start = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if token_start && (b == b'"' || b == b'\'') {
+            match find_closing_quote(text, i) {
+                Some(close) => {
+                    i = close + 1;
+                    token_start = false;
+                    continue;
+                }
+                None => return (text, None),
+            }
+        }
+        if b == b'#' && (i == 0 || bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') {
+            return (text[..i].trim_end(), Some(text[i..].to_string()));
+        }
+        token_start = matches!(b, b' ' | b'\t' | b'[' | b'{' | b',');
+        i += 1;
+    }
+    (text, None)
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Comments and blank lines from the current position, without consuming them
+    fn peek_trivia(&self) -> (Vec<Trivia>, usize) {
+        let mut next = self.pos;
+        while next < self.lines.len() && self.lines[next].is_trivia() {
+            next += 1;
+        }
+        (collect_trivia(&self.lines[self.pos..next]), next)
+    }
+
+    /// Parse the node starting on the next line, if it is indented deeper than `parent`
+    fn block_node(&mut self, parent: isize, allow_indentless: bool) -> Result<(Vec<Trivia>, Node), YamlError> {
+        let (trivia, next) = self.peek_trivia();
+        let Some(line) = self.lines.get(next).copied() else {
+            return Ok((Vec::new(), Node::default()));
+        };
+        let indent = line.indent as isize;
+        let indentless = allow_indentless && indent == parent && is_sequence_item(line.text);
+        if indent <= parent && !indentless {
+            return Ok((Vec::new(), Node::default()));
+        }
+
+        if is_sequence_item(line.text) {
+            Ok((Vec::new(), self.sequence(line.indent)?))
+        } else if line.text.starts_with('?') {
+            Err(unsupported(&line, "Complex mapping key"))
+        } else if find_mapping_colon(line.text).is_some() {
+            Ok((Vec::new(), self.mapping(line.indent)?))
+        } else {
+            self.pos = next + 1;
+            let (node, comment, mut extra) = self.inline_value(line.text, parent, allow_indentless)?;
+            let mut leading = trivia;
+            leading.append(&mut extra);
+            if let Some(comment) = comment {
+                leading.push(Trivia::Comment(comment));
+            }
+            Ok((leading, node))
+        }
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Node, YamlError> {
+        let mut entries = Vec::new();
+        loop {
+            let (mut trivia, next) = self.peek_trivia();
+            let Some(line) = self.lines.get(next).copied() else { break };
+            if line.indent < indent || (line.indent == indent && is_sequence_item(line.text)) {
+                break;
+            }
+            if line.text.starts_with('?') {
+                return Err(unsupported(&line, "Complex mapping key"));
+            }
+            let colon = match find_mapping_colon(line.text) {
+                Some(colon) if line.indent == indent => colon,
+                _ => return Err(unsupported(&line, "Unexpected content")),
+            };
+
+            self.pos = next + 1;
+            let key = line.text[..colon].trim_end().to_string();
+            let rest = line.text[colon + 1..].trim_start();
+            let (value, comment, mut extra) = self.inline_value(rest, indent as isize, true)?;
+            trivia.append(&mut extra);
+            entries.push(Entry {
+                trivia,
+                key: Some(key),
+                value,
+                comment,
+            });
+        }
+        Ok(Node {
+            value: NodeValue::Mapping { entries, flow: false },
+            ..Default::default()
+        })
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Node, YamlError> {
+        let mut entries = Vec::new();
+        loop {
+            let (mut trivia, next) = self.peek_trivia();
+            let Some(line) = self.lines.get(next).copied() else { break };
+            if line.indent < indent || (line.indent == indent && !is_sequence_item(line.text)) {
+                break;
+            }
+            if line.indent > indent {
+                return Err(unsupported(&line, "Unexpected content"));
+            }
+
+            self.pos = next + 1;
+            let rest = line.text[1..].trim_start();
+            let column = line.indent + line.text.len() - rest.len();
+            let nested = is_sequence_item(rest) || (find_mapping_colon(rest).is_some() && !rest.starts_with('?'));
+
+            let (value, comment) = if nested {
+                // Compact notation (`- key: value`, `- - item`): reparse the rest as its own line
+                self.pos -= 1;
+                self.lines[self.pos] = Line {
+                    raw: rest,
+                    indent: column,
+                    text: rest,
+                    number: line.number,
+                };
+                let node = if is_sequence_item(rest) {
+                    self.sequence(column)?
+                } else {
+                    self.mapping(column)?
+                };
+                (node, None)
+            } else {
+                let (value, comment, mut extra) = self.inline_value(rest, indent as isize, false)?;
+                trivia.append(&mut extra);
+                (value, comment)
+            };
+            entries.push(Entry {
+                trivia,
+                key: None,
+                value,
+                comment,
+            });
+        }
+        Ok(Node {
+            value: NodeValue::Sequence { entries, flow: false },
+            ..Default::default()
+        })
+    }
+
+    /// Parse the value that follows `key:` or `- ` on the current line
+    /// Returns the node, its trailing comment and any trivia found before a value on a later line
+    fn inline_value(
+        &mut self,
+        rest: &str,
+        parent: isize,
+        allow_indentless: bool,
+    ) -> Result<(Node, Option<String>, Vec<Trivia>), YamlError> {
+        let mut node = Node::default();
+        let mut rest = rest;
+        loop {
+            let end = if rest.starts_with("!<") {
+                rest.find('>').map(|i| i + 1).unwrap_or(rest.len())
+            } else {
+                rest.find([' ', '\t']).unwrap_or(rest.len())
+            };
+            if rest.starts_with('&') {
+                node.anchor = Some(rest[1..end].to_string());
+            } else if rest.starts_with('!') {
+                node.tag = Some(rest[..end].to_string());
+            } else {
+                break;
+            }
+            rest = rest[end..].trim_start();
+        }
+
+        if rest.is_empty() || rest.starts_with('#') {
+            let comment = (!rest.is_empty()).then(|| rest.to_string());
+            let (trivia, child) = self.block_node(parent, allow_indentless)?;
+            node.anchor = node.anchor.or(child.anchor);
+            node.tag = node.tag.or(child.tag);
+            node.value = child.value;
+            return Ok((node, comment, trivia));
+        }
+
+        let mut trivia = Vec::new();
+        let comment = match rest.as_bytes()[0] {
+            b'|' | b'>' => {
+                let (header, comment) = split_comment(rest);
+                let mut explicit = None;
+                let mut chomping = None;
+                for c in header[1..].chars() {
+                    match c {
+                        '1'..='9' => explicit = c.to_digit(10).map(|d| d as usize),
+                        '+' | '-' => chomping = Some(c),
+                        _ => {
+                            return Err(YamlError::Unsupported(format!(
+                                "Invalid block scalar header: {}",
+                                header
+                            )))
+                        }
+                    }
+                }
+                let lines = self.block_scalar_lines(parent, explicit, chomping == Some('+'));
+                node.value = NodeValue::Block {
+                    style: rest.as_bytes()[0] as char,
+                    chomping,
+                    lines,
+                };
+                comment
+            }
+            b'[' | b'{' => {
+                let mut text = String::new();
+                let mut segment = rest;
+                let comment = loop {
+                    let (content, line_comment) = split_comment(segment);
+                    text.push_str(content);
+                    if flow_is_closed(&text) {
+                        break line_comment;
+                    }
+                    if let Some(line_comment) = line_comment {
+                        trivia.push(Trivia::Comment(line_comment));
+                    }
+                    let Some(line) = self.lines.get(self.pos) else {
+                        return Err(YamlError::Unsupported("Unclosed flow collection".to_string()));
+                    };
+                    self.pos += 1;
+                    text.push('\n');
+                    segment = line.text;
+                };
+                let mut parser = FlowParser {
+                    text: &text,
+                    position: 0,
+                };
+                let parsed = parser.node()?;
+                parser.skip_whitespace();
+                if parser.position < text.len() {
+                    return Err(YamlError::Unsupported(format!(
+                        "Unexpected content after flow collection: {}",
+                        &text[parser.position..]
+                    )));
+                }
+                node.value = parsed.value;
+                comment
+            }
+            b'*' => {
+                let (alias, comment) = split_comment(rest);
+                node.value = NodeValue::Alias(alias[1..].to_string());
+                comment
+            }
+            b'"' | b'\'' => {
+                // Multi-line quoted scalars keep their line breaks, which fold to spaces
+                let mut joined = rest.to_string();
+                let mut lines = Vec::new();
+                let mut segment = rest;
+                let comment = loop {
+                    if let Some(close) = find_closing_quote(&joined, 0) {
+                        let close = close - (joined.len() - segment.len());
+                        let (after, comment) = split_comment(segment[close + 1..].trim_start());
+                        if !after.is_empty() {
+                            return Err(YamlError::Unsupported(format!(
+                                "Unexpected content after quoted scalar: {}",
+                                after
+                            )));
+                        }
+                        lines.push(segment[..=close].to_string());
+                        break comment;
+                    }
+                    lines.push(segment.to_string());
+                    let Some(line) = self.lines.get(self.pos) else {
+                        return Err(YamlError::Unsupported("Unclosed quoted scalar".to_string()));
+                    };
+                    self.pos += 1;
+                    segment = line.text;
+                    joined.push('\n');
+                    joined.push_str(segment);
+                };
+                node.value = NodeValue::Scalar(lines);
+                comment
+            }
+            _ => {
+                let (content, mut comment) = split_comment(rest);
+                let mut lines = vec![content.to_string()];
+                while comment.is_none() {
+                    let mut next = self.pos;
+                    while next < self.lines.len() && self.lines[next].text.is_empty() {
+                        next += 1;
+                    }
+                    let Some(line) = self.lines.get(next) else { break };
+                    if line.indent as isize <= parent || line.text.starts_with('#') {
+                        break;
+                    }
+                    lines.extend(std::iter::repeat_n(String::new(), next - self.pos));
+                    let (content, line_comment) = split_comment(line.text);
+                    lines.push(content.to_string());
+                    comment = line_comment;
+                    self.pos = next + 1;
+                }
+                node.value = NodeValue::Scalar(lines);
+                comment
+            }
+        };
+        Ok((node, comment, trivia))
+    }
+
+    fn block_scalar_lines(&mut self, parent: isize, explicit: Option<usize>, keep: bool) -> Vec<String> {
+        let content_indent = match explicit {
+            Some(n) => parent.max(0) as usize + n,
+            None => match self.lines[self.pos..].iter().find(|l| !l.text.is_empty()) {
+                Some(line) if line.indent as isize > parent => line.indent,
+                _ => return Vec::new(),
+            },
+        };
+
+        let mut lines = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.text.is_empty() {
+                lines.push(line.raw.get(content_indent..).unwrap_or("").to_string());
+            } else if line.indent >= content_indent {
+                lines.push(line.raw[content_indent..].to_string());
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+        if !keep {
+            while lines.last().map(|l| l.trim().is_empty()).unwrap_or(false) {
+                lines.pop();
+                self.pos -= 1;
+            }
+        }
+        lines
+    }
+}
+
+/// Whether every bracket opened in a flow collection has been closed
+fn flow_is_closed(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut depth = 0i32;
+    let mut token_start = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if token_start && (b == b'"' || b == b'\'') {
+            match find_closing_quote(text, i) {
+                Some(close) => i = close,
+                None => return false,
+            }
+        } else if b == b'[' || b == b'{' {
+            depth += 1;
+        } else if b == b']' || b == b'}' {
+            depth -= 1;
+        }
+        token_start = matches!(b, b' ' | b'\t' | b'\n' | b'[' | b'{' | b',' | b':');
+        i += 1;
+    }
+    depth <= 0
+}
+
+/// Recursive descent parser for flow collections (`[a, b]`, `{k: v}`)
+struct FlowParser<'s> {
+    text: &'s str,
+    position: usize,
+}
+
+impl<'s> FlowParser<'s> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n')) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> YamlError {
+        YamlError::Unsupported(format!("{} in flow collection: {}", message, self.text))
+    }
+
+    fn is_value_indicator(&self, at: usize) -> bool {
+        let bytes = self.text.as_bytes();
+        bytes[at] == b':'
+            && bytes
+                .get(at + 1)
+                .map(|b| matches!(b, b' ' | b'\t' | b'\n' | b',' | b'[' | b']' | b'{' | b'}'))
+                .unwrap_or(true)
+    }
+
+    /// A scalar token, returned as source text
+    fn scalar(&mut self) -> Result<String, YamlError> {
+        let start = self.position;
+        if matches!(self.peek(), Some(b'"' | b'\'')) {
+            let close = find_closing_quote(self.text, start).ok_or_else(|| self.error("Unclosed quote"))?;
+            self.position = close + 1;
+            return Ok(self.text[start..self.position].to_string());
+        }
+        let bytes = self.text.as_bytes();
+        while self.position < bytes.len() {
+            let b = bytes[self.position];
+            if matches!(b, b',' | b'[' | b']' | b'{' | b'}') || self.is_value_indicator(self.position) {
+                break;
+            }
+            self.position += 1;
+        }
+        Ok(self.text[start..self.position].split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    fn node(&mut self) -> Result<Node, YamlError> {
+        let mut node = Node::default();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'&') | Some(b'!') => {
+                    let start = self.position;
+                    while let Some(b) = self.peek() {
+                        if matches!(b, b' ' | b'\t' | b'\n' | b',' | b']' | b'}') {
+                            break;
+                        }
+                        self.position += 1;
+                    }
+                    let token = &self.text[start..self.position];
+                    match token.strip_prefix('&') {
+                        Some(anchor) => node.anchor = Some(anchor.to_string()),
+                        None => node.tag = Some(token.to_string()),
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        node.value = match self.peek() {
+            Some(b'[') => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b']') => {
+                            self.position += 1;
+                            break;
+                        }
+                        None => return Err(self.error("Unclosed sequence")),
+                        _ => {}
+                    }
+                    let mut value = self.node()?;
+                    self.skip_whitespace();
+                    if self.peek() == Some(b':') {
+                        // Single pair mapping inside a sequence: `[a: 1]`
+                        self.position += 1;
+                        let key = flow_text(&value)?;
+                        value = Node {
+                            value: NodeValue::Mapping {
+                                entries: vec![Entry {
+                                    trivia: Vec::new(),
+                                    key: Some(key),
+                                    value: self.node()?,
+                                    comment: None,
+                                }],
+                                flow: true,
+                            },
+                            ..Default::default()
+                        };
+                        self.skip_whitespace();
+                    }
+                    entries.push(Entry {
+                        trivia: Vec::new(),
+                        key: None,
+                        value,
+                        comment: None,
+                    });
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {}
+                        _ => return Err(self.error("Expected `,` or `]`")),
+                    }
+                }
+                NodeValue::Sequence { entries, flow: true }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b'}') => {
+                            self.position += 1;
+                            break;
+                        }
+                        None => return Err(self.error("Unclosed mapping")),
+                        _ => {}
+                    }
+                    let key = self.scalar()?;
+                    self.skip_whitespace();
+                    let value = if self.peek() == Some(b':') {
+                        self.position += 1;
+                        self.node()?
+                    } else {
+                        Node::default()
+                    };
+                    entries.push(Entry {
+                        trivia: Vec::new(),
+                        key: Some(key),
+                        value,
+                        comment: None,
+                    });
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {}
+                        _ => return Err(self.error("Expected `,` or `}`")),
+                    }
+                }
+                NodeValue::Mapping { entries, flow: true }
+            }
+            Some(b'*') => {
+                self.position += 1;
+                NodeValue::Alias(self.scalar()?)
+            }
+            _ => {
+                let scalar = self.scalar()?;
+                if scalar.is_empty() {
+                    NodeValue::Empty
+                } else if scalar.contains('\n') {
+                    NodeValue::Scalar(scalar.lines().map(|l| l.trim().to_string()).collect())
+                } else {
+                    NodeValue::Scalar(vec![scalar])
+                }
+            }
+        };
+        Ok(node)
+    }
+}
+
+// Transformations
+
+fn prepare_documents(documents: &mut [Document], options: &YamlFormatOptions) -> Result<(), YamlError> {
+    for document in documents.iter_mut() {
+        if options.expand_anchors {
+            expand_anchors(&mut document.root, &mut HashMap::new())?;
+        }
+        if options.key_order != YamlKeyOrder::Preserve || !options.priority_keys.is_empty() {
+            order_keys(&mut document.root, options);
+        }
+    }
+    Ok(())
+}
+
+/// Key text used for ordering and merging, without properties or quotes
+fn key_text(raw: &str) -> &str {
+    let mut key = raw.trim();
+    while key.starts_with(['&', '!']) {
+        key = key.find(' ').map(|i| key[i..].trim_start()).unwrap_or("");
+    }
+    if key.len() >= 2 && (key.starts_with('"') && key.ends_with('"') || key.starts_with('\'') && key.ends_with('\'')) {
+        key = &key[1..key.len() - 1];
+    }
+    key
+}
+
+fn order_keys(node: &mut Node, options: &YamlFormatOptions) {
+    match &mut node.value {
+        NodeValue::Mapping { entries, .. } => {
+            let rank = |entry: &Entry| {
+                let key = key_text(entry.key.as_deref().unwrap_or(""));
+                options
+                    .priority_keys
+                    .iter()
+                    .position(|k| k == key)
+                    .unwrap_or(usize::MAX)
+            };
+            match options.key_order {
+                YamlKeyOrder::Preserve => entries.sort_by_key(rank),
+                YamlKeyOrder::Alphabetical => entries.sort_by(|a, b| {
+                    rank(a).cmp(&rank(b)).then_with(|| {
+                        key_text(a.key.as_deref().unwrap_or("")).cmp(key_text(b.key.as_deref().unwrap_or("")))
+                    })
+                }),
+            }
+            entries.iter_mut().for_each(|e| order_keys(&mut e.value, options));
+        }
+        NodeValue::Sequence { entries, .. } => {
+            entries.iter_mut().for_each(|e| order_keys(&mut e.value, options));
+        }
+        _ => {}
+    }
+}
+
+fn expand_anchors(node: &mut Node, anchors: &mut HashMap<String, Node>) -> Result<(), YamlError> {
+    match &mut node.value {
+        NodeValue::Alias(name) => {
+            let target = anchors
+                .get(name.as_str())
+                .ok_or_else(|| YamlError::FormatError(format!("Unknown alias: *{}", name)))?;
+            node.tag = target.tag.clone();
+            node.value = target.value.clone();
+            return Ok(());
+        }
+        NodeValue::Mapping { entries, .. } => {
+            for entry in entries.iter_mut() {
+                expand_anchors(&mut entry.value, anchors)?;
+            }
+            apply_merge(entries);
+        }
+        NodeValue::Sequence { entries, .. } => {
+            for entry in entries.iter_mut() {
+                expand_anchors(&mut entry.value, anchors)?;
+            }
+        }
+        _ => {}
+    }
+    if let Some(anchor) = node.anchor.take() {
+        anchors.insert(anchor, node.clone());
+    }
+    Ok(())
+}
+
+/// Replace a `<<` merge key with the entries it pulls in; explicit keys win
+fn apply_merge(entries: &mut Vec<Entry>) {
+    let Some(index) = entries
+        .iter()
+        .position(|e| e.key.as_deref().map(key_text) == Some("<<"))
+    else {
+        return;
+    };
+
+    let sources: Vec<&Vec<Entry>> = match &entries[index].value.value {
+        NodeValue::Mapping { entries, .. } => vec![entries],
+        NodeValue::Sequence { entries, .. } => {
+            let maps: Vec<&Vec<Entry>> = entries
+                .iter()
+                .filter_map(|e| match &e.value.value {
+                    NodeValue::Mapping { entries, .. } => Some(entries),
+                    _ => None,
+                })
+                .collect();
+            if maps.len() != entries.len() {
+                return;
+            }
+            maps
+        }
+        _ => return,
+    };
+
+    let mut seen: HashSet<String> = entries
+        .iter()
+        .filter_map(|e| e.key.as_deref().map(|k| key_text(k).to_string()))
+        .collect();
+    let mut merged = Vec::new();
+    for source in sources {
+        for entry in source {
+            let key = key_text(entry.key.as_deref().unwrap_or("")).to_string();
+            if seen.insert(key) {
+                merged.push(Entry {
+                    trivia: Vec::new(),
+                    ..entry.clone()
+                });
+            }
+        }
+    }
+
+    let removed = entries.remove(index);
+    if let Some(first) = merged.first_mut() {
+        first.trivia = removed.trivia;
+    }
+    entries.splice(index..index, merged);
+}
+
+// Emitting
+
+fn properties(node: &Node) -> String {
+    let mut parts = Vec::new();
+    if let Some(anchor) = &node.anchor {
+        parts.push(format!("&{}", anchor));
+    }
+    if let Some(tag) = &node.tag {
+        parts.push(tag.clone());
+    }
+    parts.join(" ")
+}
+
+/// Decode a scalar with serde_yaml so it can be written on one line as a JSON-style string
+fn single_line_scalar(snippet: String) -> Result<String, YamlError> {
+    let value: Value = serde_yaml::from_str(&snippet)?;
+    match value.get("v") {
+        Some(Value::String(s)) => {
+            serde_json::to_string(s).map_err(|e| YamlError::FormatError(e.to_string()))
+        }
+        Some(other) => Ok(serde_yaml::to_string(other)?.trim_end().to_string()),
+        None => Err(YamlError::FormatError("Could not decode scalar".to_string())),
+    }
+}
+
+/// Render a node in flow style on a single line
+fn flow_text(node: &Node) -> Result<String, YamlError> {
+    let body = match &node.value {
+        NodeValue::Empty => String::new(),
+        NodeValue::Alias(name) => format!("*{}", name),
+        NodeValue::Scalar(lines) if lines.len() == 1 => {
+            let text = &lines[0];
+            let plain = !text.starts_with(['"', '\'']);
+            if plain && text.contains([',', '[', ']', '{', '}']) {
+                serde_json::to_string(text).map_err(|e| YamlError::FormatError(e.to_string()))?
+            } else {
+                text.clone()
+            }
+        }
+        NodeValue::Scalar(lines) => single_line_scalar(format!("v: {}", lines.join("\n  ")))?,
+        NodeValue::Block { style, chomping, lines } => {
+            let mut snippet = format!("v: {}2{}\n", style, chomping.map(String::from).unwrap_or_default());
+            for line in lines {
+                snippet.push_str(&format!("  {}\n", line));
+            }
+            single_line_scalar(snippet)?
+        }
+        NodeValue::Mapping { entries, .. } => {
+            let items = entries
+                .iter()
+                .map(|entry| {
+                    let key = entry.key.clone().unwrap_or_default();
+                    let key = if key.starts_with(['"', '\'']) || !key.contains([',', '[', ']', '{', '}']) {
+                        key
+                    } else {
+                        serde_json::to_string(&key).map_err(|e| YamlError::FormatError(e.to_string()))?
+                    };
+                    let value = flow_text(&entry.value)?;
+                    Ok(if value.is_empty() {
+                        key
+                    } else {
+                        format!("{}: {}", key, value)
+                    })
+                })
+                .collect::<Result<Vec<_>, YamlError>>()?;
+            format!("{{{}}}", items.join(", "))
+        }
+        NodeValue::Sequence { entries, .. } => {
+            let items = entries
+                .iter()
+                .map(|entry| flow_text(&entry.value))
+                .collect::<Result<Vec<_>, YamlError>>()?;
+            format!("[{}]", items.join(", "))
+        }
+    };
+    let props = properties(node);
+    Ok(match (props.is_empty(), body.is_empty()) {
+        (true, _) => body,
+        (false, true) => props,
+        (false, false) => format!("{} {}", props, body),
+    })
+}
+
+struct Emitter<'o> {
+    options: &'o YamlFormatOptions,
+    lines: Vec<String>,
+}
+
+fn pad(width: usize) -> String {
+    " ".repeat(width)
+}
+
+fn join_parts(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .filter(|p| !p.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl<'o> Emitter<'o> {
+    fn step(&self) -> usize {
+        self.options.indent.clamp(1, 9)
+    }
+
+    fn comment<'c>(&self, comment: &'c Option<String>) -> &'c str {
+        match comment {
+            Some(comment) if self.options.preserve_comments => comment,
+            _ => "",
+        }
+    }
+
+    fn trivia(&mut self, trivia: &[Trivia], indent: usize) {
+        for item in trivia {
+            match item {
+                Trivia::Blank => {
+                    if self.lines.last().map(|l| !l.is_empty()).unwrap_or(false) {
+                        self.lines.push(String::new());
+                    }
+                }
+                Trivia::Comment(comment) if self.options.preserve_comments => {
+                    self.lines.push(format!("{}{}", pad(indent), comment));
+                }
+                Trivia::Comment(_) => {}
+            }
+        }
+    }
+
+    fn document(&mut self, document: &Document, index: usize) -> Result<(), YamlError> {
+        self.lines.extend(document.directives.iter().cloned());
+        self.trivia(&document.preamble, 0);
+
+        let root = &document.root;
+        let props = properties(root);
+        let marker = document.explicit_start || index > 0 || !document.directives.is_empty() || !props.is_empty();
+        let start_comment = self.comment(&document.start_comment).to_string();
+
+        match &root.value {
+            NodeValue::Mapping { entries, flow: false } | NodeValue::Sequence { entries, flow: false } => {
+                if marker {
+                    self.lines.push(join_parts(&["---", &props, &start_comment]));
+                }
+                self.trivia(&document.root_trivia, 0);
+                self.entries(entries, 0, false)?;
+            }
+            NodeValue::Empty if props.is_empty() => {
+                if marker {
+                    self.lines.push(join_parts(&["---", &start_comment]));
+                }
+                self.trivia(&document.root_trivia, 0);
+            }
+            _ => {
+                if !marker {
+                    self.trivia(&document.root_trivia, 0);
+                }
+                let head = if marker { "---" } else { "" };
+                self.value(head.to_string(), root, 0, &document.start_comment, false)?;
+                if marker {
+                    self.trivia(&document.root_trivia, 0);
+                }
+            }
+        }
+
+        self.trivia(&document.trailing, 0);
+        if document.explicit_end {
+            self.lines.push("...".to_string());
+        }
+        Ok(())
+    }
+
+    fn entries(&mut self, entries: &[Entry], indent: usize, skip_first_trivia: bool) -> Result<(), YamlError> {
+        for (i, entry) in entries.iter().enumerate() {
+            if !(skip_first_trivia && i == 0) {
+                self.trivia(&entry.trivia, indent);
+            }
+            let head = match &entry.key {
+                Some(key) => format!("{}{}:", pad(indent), key),
+                None => format!("{}-", pad(indent)),
+            };
+            self.value(head, &entry.value, indent, &entry.comment, entry.key.is_none())?;
+        }
+        Ok(())
+    }
+
+    fn value(
+        &mut self,
+        head: String,
+        node: &Node,
+        indent: usize,
+        comment: &Option<String>,
+        in_sequence: bool,
+    ) -> Result<(), YamlError> {
+        let props = properties(node);
+        let comment = self.comment(comment).to_string();
+        let child_indent = indent + self.step();
+
+        match &node.value {
+            NodeValue::Empty => self.lines.push(join_parts(&[&head, &props, &comment])),
+            NodeValue::Alias(name) => {
+                self.lines.push(join_parts(&[&head, &format!("*{}", name), &comment]));
+            }
+            NodeValue::Scalar(lines) => {
+                let last = lines.len() - 1;
+                for (i, line) in lines.iter().enumerate() {
+                    let trailing = if i == last { comment.as_str() } else { "" };
+                    if i == 0 {
+                        self.lines.push(join_parts(&[&head, &props, line, trailing]));
+                    } else if line.is_empty() {
+                        self.lines.push(String::new());
+                    } else {
+                        self.lines.push(join_parts(&[&format!("{}{}", pad(child_indent), line), trailing]));
+                    }
+                }
+            }
+            NodeValue::Block { style, chomping, lines } => {
+                let needs_indicator = lines
+                    .iter()
+                    .find(|l| !l.trim().is_empty())
+                    .map(|l| l.starts_with([' ', '\t']))
+                    .unwrap_or(false);
+                let header = format!(
+                    "{}{}{}",
+                    style,
+                    if needs_indicator { self.step().to_string() } else { String::new() },
+                    chomping.map(String::from).unwrap_or_default()
+                );
+                self.lines.push(join_parts(&[&head, &props, &header, &comment]));
+                for line in lines {
+                    if line.is_empty() {
+                        self.lines.push(String::new());
+                    } else {
+                        self.lines.push(format!("{}{}", pad(child_indent), line));
+                    }
+                }
+            }
+            NodeValue::Mapping { flow: true, .. } | NodeValue::Sequence { flow: true, .. } => {
+                let flow = flow_text(&Node {
+                    anchor: None,
+                    tag: None,
+                    value: node.value.clone(),
+                })?;
+                self.lines.push(join_parts(&[&head, &props, &flow, &comment]));
+            }
+            NodeValue::Mapping { entries, .. } | NodeValue::Sequence { entries, .. } => {
+                let is_sequence = matches!(node.value, NodeValue::Sequence { .. });
+                let nested_indent = if in_sequence {
+                    indent + 2
+                } else if is_sequence && !self.options.indent_sequences {
+                    indent
+                } else {
+                    child_indent
+                };
+
+                if in_sequence && props.is_empty() && comment.is_empty() {
+                    // Compact notation: first entry shares the line with the dash
+                    self.trivia(&entries[0].trivia, indent);
+                    let start = self.lines.len();
+                    self.entries(entries, nested_indent, true)?;
+                    self.lines[start].replace_range(indent..indent + 2, "- ");
+                } else {
+                    self.lines.push(join_parts(&[&head, &props, &comment]));
+                    self.entries(entries, nested_indent, false)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> String {
+        let mut lines = self.lines;
+        while lines.last().map(|l| l.is_empty()).unwrap_or(false) {
+            lines.pop();
+        }
+        let start = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+        let mut output = lines[start..].join("\n");
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,8 +1454,137 @@ spec:
         let formatted = format_yaml(SAMPLE_YAML, 2).unwrap();
         let minified = minify_yaml(&formatted).unwrap();
         let reformatted = format_yaml(&minified, 2).unwrap();
-        
+
         assert!(reformatted.contains("apiVersion"));
         assert!(reformatted.contains("kind"));
     }
+
+    const MANIFESTS: &str = r#"# Frontend deployment
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+    name: web   # public name
+spec:
+    template:
+        spec:
+            containers:
+            - name: web
+              image: nginx:1.25
+              args: ["--port", "8080"]
+              env:
+                - {name: MODE, value: prod}
+
+---
+# Config shared by every service
+defaults: &defaults
+    retries: 3
+    timeout: 30s
+service:
+    <<: *defaults
+    timeout: 10s
+script: |
+    echo "hello"
+      indented
+"#;
+
+    #[test]
+    fn test_format_preserves_comments_and_documents() {
+        let result = format_yaml(MANIFESTS, 2).unwrap();
+        assert!(result.starts_with("# Frontend deployment\napiVersion: apps/v1\n"));
+        assert!(result.contains("  name: web # public name\n"));
+        assert!(result.contains(
+            "      containers:\n        - name: web\n          image: nginx:1.25\n          args: [\"--port\", \"8080\"]\n"
+        ));
+        assert!(result.contains("\n\n---\n# Config shared by every service\ndefaults: &defaults\n  retries: 3\n"));
+        assert!(result.contains("  <<: *defaults\n"));
+        assert!(result.contains("script: |\n  echo \"hello\"\n    indented\n"));
+        assert_eq!(parse_documents(&result).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_format_expand_anchors() {
+        let options = YamlFormatOptions {
+            expand_anchors: true,
+            ..Default::default()
+        };
+        let result = format_yaml_with_options(MANIFESTS, &options).unwrap();
+        assert!(!result.contains('&'));
+        assert!(!result.contains("*defaults"));
+        assert!(result.contains("service:\n  retries: 3\n  timeout: 10s\n"));
+    }
+
+    #[test]
+    fn test_format_key_order() {
+        let yaml = "spec: {}\nmetadata:\n  name: x\n  labels: {b: 1, a: 2}\nkind: Pod\napiVersion: v1\n";
+        let options = YamlFormatOptions {
+            priority_keys: vec!["apiVersion".to_string(), "kind".to_string()],
+            ..Default::default()
+        };
+        let result = format_yaml_with_options(yaml, &options).unwrap();
+        assert!(result.starts_with("apiVersion: v1\nkind: Pod\nspec: {}\nmetadata:\n"));
+
+        let options = YamlFormatOptions {
+            key_order: YamlKeyOrder::Alphabetical,
+            ..Default::default()
+        };
+        let result = format_yaml_with_options(yaml, &options).unwrap();
+        assert_eq!(
+            result,
+            "apiVersion: v1\nkind: Pod\nmetadata:\n  labels: {a: 2, b: 1}\n  name: x\nspec: {}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_options_without_comments_and_indentless_sequences() {
+        let options = YamlFormatOptions {
+            indent: 4,
+            preserve_comments: false,
+            indent_sequences: false,
+            ..Default::default()
+        };
+        let result = format_yaml_with_options("# c\nitems:\n  - a # x\n  - b\n", &options).unwrap();
+        assert_eq!(result, "items:\n- a\n- b\n");
+    }
+
+    #[test]
+    fn test_format_scalars() {
+        let yaml = "a: 'it''s # not a comment'\nb: \"multi\n  line\"\nc: plain\n  continued\nd: >-\n  folded\n  text\n\ne: ~\n";
+        let result = format_yaml(yaml, 2).unwrap();
+        assert!(result.contains("a: 'it''s # not a comment'\n"));
+        assert!(result.contains("b: \"multi\n  line\"\n"));
+        assert!(result.contains("c: plain\n  continued\n"));
+        assert!(result.contains("d: >-\n  folded\n  text\n\ne: ~\n"));
+    }
+
+    #[test]
+    fn test_complex_keys_fall_back_to_plain_formatting() {
+        let yaml = "# pairs\n? [a, b]\n: both\nplain:\n  nested: 1\n";
+        let expected = parse_documents(yaml).unwrap();
+
+        let formatted = format_yaml(yaml, 2).unwrap();
+        assert!(formatted.contains("plain:\n  nested: 1"));
+        assert_eq!(parse_documents(&formatted).unwrap(), expected);
+        let formatted = format_yaml(yaml, 4).unwrap();
+        assert_eq!(parse_documents(&formatted).unwrap(), expected);
+        let formatted = format_yaml("? plain\n: 1\nmap:\n  key: 2\n", 4).unwrap();
+        assert!(formatted.contains("map:\n    key: 2"));
+
+        let minified = minify_yaml(yaml).unwrap();
+        assert_eq!(parse_documents(&minified).unwrap(), expected);
+
+        // Flow collections used as implicit keys also fall back instead of failing
+        let flow_key = "[a, b]: c\n";
+        let formatted = format_yaml(flow_key, 2).unwrap();
+        assert_eq!(parse_documents(&formatted).unwrap(), parse_documents(flow_key).unwrap());
+    }
+
+    #[test]
+    fn test_minify_multi_document() {
+        let result = minify_yaml(MANIFESTS).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "---");
+        assert!(lines[2].starts_with("{defaults: &defaults {retries: 3, timeout: 30s}, service: {<<: *defaults"));
+        assert!(lines[2].ends_with("script: \"echo \\\"hello\\\"\\n  indented\\n\"}"));
+    }
 }
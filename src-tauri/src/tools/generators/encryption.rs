@@ -0,0 +1,566 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Envelope layout (all integers little-endian):
+///
+/// ```text
+/// "FBENC" | version u8 | cipher u8 | kdf u8 | kdf params | salt len u8 | salt | nonce (12) | ciphertext + tag
+/// ```
+///
+/// Argon2id params are memory KiB, iterations and parallelism as u32; PBKDF2 is one u32
+/// iteration count. Everything before the ciphertext is authenticated as associated data.
+const MAGIC: &[u8; 5] = b"FBENC";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const ARMOR_BEGIN: &str = "-----BEGIN FERRISBOX ENCRYPTED DATA-----";
+const ARMOR_END: &str = "-----END FERRISBOX ENCRYPTED DATA-----";
+
+/// Upper bounds accepted when decrypting, so a crafted header cannot exhaust memory or CPU
+const MAX_ARGON2_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ARGON2_ITERATIONS: u32 = 64;
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+/// OWASP floors enforced when encrypting; older or foreign envelopes still decrypt
+const MIN_ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const MIN_ARGON2_ITERATIONS: u32 = 2;
+const MIN_PBKDF2_ITERATIONS: u32 = 600_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymmetricCipher {
+    #[default]
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "camelCase")]
+pub enum KeyDerivation {
+    #[serde(rename_all = "camelCase")]
+    Argon2id {
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+    Pbkdf2 {
+        iterations: u32,
+    },
+}
+
+impl Default for KeyDerivation {
+    /// OWASP-recommended minimum for Argon2id
+    fn default() -> Self {
+        KeyDerivation::Argon2id {
+            memory_kib: MIN_ARGON2_MEMORY_KIB,
+            iterations: MIN_ARGON2_ITERATIONS,
+            parallelism: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EncryptionOptions {
+    pub cipher: SymmetricCipher,
+    pub kdf: KeyDerivation,
+}
+
+/// Header of an envelope, readable without the password
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvelopeInfo {
+    pub version: u8,
+    pub cipher: SymmetricCipher,
+    pub kdf: KeyDerivation,
+    pub salt_hex: String,
+    pub nonce_hex: String,
+    /// Encrypted payload size without the 16-byte authentication tag
+    pub plaintext_size: usize,
+}
+
+struct Envelope<'a> {
+    info: EnvelopeInfo,
+    header: &'a [u8],
+    salt: &'a [u8],
+    nonce: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+fn derive_key(password: &str, salt: &[u8], kdf: &KeyDerivation) -> Result<[u8; KEY_LEN], String> {
+    let mut key = [0u8; KEY_LEN];
+    match *kdf {
+        KeyDerivation::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            let params = Params::new(memory_kib, iterations, parallelism, Some(KEY_LEN))
+                .map_err(|e| format!("Invalid Argon2id parameters: {}", e))?;
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(password.as_bytes(), salt, &mut key)
+                .map_err(|e| e.to_string())?;
+        }
+        KeyDerivation::Pbkdf2 { iterations } => {
+            if iterations == 0 {
+                return Err("PBKDF2 iterations must be at least 1".to_string());
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
+        }
+    }
+    Ok(key)
+}
+
+fn seal(
+    cipher: SymmetricCipher,
+    key: &[u8; KEY_LEN],
+    nonce: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, String> {
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+    let result = match cipher {
+        SymmetricCipher::Aes256Gcm => Aes256Gcm::new(key.into()).encrypt(nonce.into(), payload),
+        SymmetricCipher::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).encrypt(nonce.into(), payload)
+        }
+    };
+    result.map_err(|_| "Encryption failed".to_string())
+}
+
+fn open(
+    cipher: SymmetricCipher,
+    key: &[u8; KEY_LEN],
+    nonce: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, String> {
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    let result = match cipher {
+        SymmetricCipher::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt(nonce.into(), payload),
+        SymmetricCipher::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).decrypt(nonce.into(), payload)
+        }
+    };
+    result.map_err(|_| "Decryption failed: wrong password or corrupted data".to_string())
+}
+
+fn write_header(options: &EncryptionOptions, salt: &[u8]) -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.push(VERSION);
+    header.push(match options.cipher {
+        SymmetricCipher::Aes256Gcm => 1,
+        SymmetricCipher::ChaCha20Poly1305 => 2,
+    });
+    match options.kdf {
+        KeyDerivation::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            header.push(1);
+            header.extend_from_slice(&memory_kib.to_le_bytes());
+            header.extend_from_slice(&iterations.to_le_bytes());
+            header.extend_from_slice(&parallelism.to_le_bytes());
+        }
+        KeyDerivation::Pbkdf2 { iterations } => {
+            header.push(2);
+            header.extend_from_slice(&iterations.to_le_bytes());
+        }
+    }
+    header.push(salt.len() as u8);
+    header.extend_from_slice(salt);
+    header
+}
+
+/// Cursor over envelope bytes
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let slice = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or("Invalid envelope: data is truncated")?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+fn parse_envelope(data: &[u8]) -> Result<Envelope<'_>, String> {
+    if !data.starts_with(MAGIC) {
+        return Err("Not a FerrisBox encrypted envelope".to_string());
+    }
+    let mut reader = Reader {
+        data,
+        pos: MAGIC.len(),
+    };
+
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(format!("Unsupported envelope version {}", version));
+    }
+    let cipher = match reader.u8()? {
+        1 => SymmetricCipher::Aes256Gcm,
+        2 => SymmetricCipher::ChaCha20Poly1305,
+        id => return Err(format!("Unknown cipher id {}", id)),
+    };
+    let kdf = match reader.u8()? {
+        1 => KeyDerivation::Argon2id {
+            memory_kib: reader.u32()?,
+            iterations: reader.u32()?,
+            parallelism: reader.u32()?,
+        },
+        2 => KeyDerivation::Pbkdf2 {
+            iterations: reader.u32()?,
+        },
+        id => return Err(format!("Unknown key derivation id {}", id)),
+    };
+    let salt_len = reader.u8()? as usize;
+    let salt = reader.take(salt_len)?;
+    let header_len = reader.pos;
+    let nonce = reader.take(NONCE_LEN)?;
+    let ciphertext = &data[reader.pos..];
+    if ciphertext.len() < 16 {
+        return Err("Invalid envelope: data is truncated".to_string());
+    }
+
+    Ok(Envelope {
+        info: EnvelopeInfo {
+            version,
+            cipher,
+            kdf,
+            salt_hex: hex::encode(salt),
+            nonce_hex: hex::encode(nonce),
+            plaintext_size: ciphertext.len() - 16,
+        },
+        header: &data[..header_len],
+        salt,
+        nonce,
+        ciphertext,
+    })
+}
+
+fn check_limits(kdf: &KeyDerivation) -> Result<(), String> {
+    let within = match *kdf {
+        KeyDerivation::Argon2id {
+            memory_kib,
+            iterations,
+            ..
+        } => memory_kib <= MAX_ARGON2_MEMORY_KIB && iterations <= MAX_ARGON2_ITERATIONS,
+        KeyDerivation::Pbkdf2 { iterations } => iterations <= MAX_PBKDF2_ITERATIONS,
+    };
+    if within {
+        Ok(())
+    } else {
+        Err("Key derivation parameters exceed the supported limits".to_string())
+    }
+}
+
+fn check_minimums(kdf: &KeyDerivation) -> Result<(), String> {
+    match *kdf {
+        KeyDerivation::Argon2id {
+            memory_kib,
+            iterations,
+            ..
+        } if memory_kib < MIN_ARGON2_MEMORY_KIB || iterations < MIN_ARGON2_ITERATIONS => {
+            Err(format!(
+                "Argon2id needs at least {} KiB of memory and {} iterations",
+                MIN_ARGON2_MEMORY_KIB, MIN_ARGON2_ITERATIONS
+            ))
+        }
+        KeyDerivation::Pbkdf2 { iterations } if iterations < MIN_PBKDF2_ITERATIONS => Err(format!(
+            "PBKDF2 needs at least {} iterations",
+            MIN_PBKDF2_ITERATIONS
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Encrypt bytes into a binary envelope with a fresh random salt and nonce
+pub fn encrypt_bytes(
+    plaintext: &[u8],
+    password: &str,
+    options: &EncryptionOptions,
+) -> Result<Vec<u8>, String> {
+    check_minimums(&options.kdf)?;
+    seal_envelope(plaintext, password, options)
+}
+
+/// `encrypt_bytes` without the minimum-cost check
+fn seal_envelope(
+    plaintext: &[u8],
+    password: &str,
+    options: &EncryptionOptions,
+) -> Result<Vec<u8>, String> {
+    if password.is_empty() {
+        return Err("Password cannot be empty".to_string());
+    }
+    check_limits(&options.kdf)?;
+    let mut rng = rand::rngs::OsRng;
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let key = derive_key(password, &salt, &options.kdf)?;
+    let header = write_header(options, &salt);
+    let ciphertext = seal(options.cipher, &key, &nonce, plaintext, &header)?;
+
+    let mut envelope = header;
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ciphertext);
+    Ok(envelope)
+}
+
+/// Decrypt a binary envelope; cipher and key derivation are read from its header
+pub fn decrypt_bytes(envelope: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    let parsed = parse_envelope(envelope)?;
+    check_limits(&parsed.info.kdf)?;
+    let key = derive_key(password, parsed.salt, &parsed.info.kdf)?;
+    open(
+        parsed.info.cipher,
+        &key,
+        parsed.nonce,
+        parsed.ciphertext,
+        parsed.header,
+    )
+}
+
+/// Encrypt text into an ASCII-armored envelope
+pub fn encrypt_text(
+    text: &str,
+    password: &str,
+    options: &EncryptionOptions,
+) -> Result<String, String> {
+    let envelope = encrypt_bytes(text.as_bytes(), password, options)?;
+    Ok(armor(&envelope))
+}
+
+fn armor(envelope: &[u8]) -> String {
+    let encoded = STANDARD.encode(envelope);
+    let lines: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(64)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+    format!("{}\n{}\n{}\n", ARMOR_BEGIN, lines.join("\n"), ARMOR_END)
+}
+
+/// Accepts armored text or bare base64
+fn unarmor(input: &str) -> Result<Vec<u8>, String> {
+    let body = input
+        .trim()
+        .trim_start_matches(ARMOR_BEGIN)
+        .trim_end_matches(ARMOR_END);
+    let cleaned: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+    STANDARD
+        .decode(cleaned)
+        .map_err(|e| format!("Invalid envelope encoding: {}", e))
+}
+
+pub fn decrypt_text(armored: &str, password: &str) -> Result<String, String> {
+    let plaintext = decrypt_bytes(&unarmor(armored)?, password)?;
+    String::from_utf8(plaintext)
+        .map_err(|_| "Decrypted data is not UTF-8 text; decrypt it as a file instead".to_string())
+}
+
+/// Read the envelope header from armored text or a raw envelope file
+pub fn inspect_envelope(input: &[u8]) -> Result<EnvelopeInfo, String> {
+    if input.starts_with(MAGIC) {
+        return parse_envelope(input).map(|e| e.info);
+    }
+    let text = std::str::from_utf8(input).map_err(|_| "Not a FerrisBox encrypted envelope")?;
+    parse_envelope(&unarmor(text)?).map(|e| e.info)
+}
+
+/// Encrypt a file into a binary envelope; the whole file is held in memory
+pub fn encrypt_file(
+    input_path: &str,
+    output_path: &str,
+    password: &str,
+    options: &EncryptionOptions,
+) -> Result<EnvelopeInfo, String> {
+    let plaintext = std::fs::read(input_path).map_err(|e| e.to_string())?;
+    let envelope = encrypt_bytes(&plaintext, password, options)?;
+    std::fs::write(output_path, &envelope).map_err(|e| e.to_string())?;
+    inspect_envelope(&envelope)
+}
+
+/// Decrypt a binary or armored envelope file; returns the number of bytes written
+pub fn decrypt_file(input_path: &str, output_path: &str, password: &str) -> Result<usize, String> {
+    let data = std::fs::read(input_path).map_err(|e| e.to_string())?;
+    let envelope = if data.starts_with(MAGIC) {
+        data
+    } else {
+        unarmor(&String::from_utf8_lossy(&data))?
+    };
+    let plaintext = decrypt_bytes(&envelope, password)?;
+    std::fs::write(output_path, &plaintext).map_err(|e| e.to_string())?;
+    Ok(plaintext.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap parameters so tests stay fast; only `seal_envelope` accepts them
+    fn fast_options(cipher: SymmetricCipher) -> EncryptionOptions {
+        EncryptionOptions {
+            cipher,
+            kdf: KeyDerivation::Argon2id {
+                memory_kib: 64,
+                iterations: 1,
+                parallelism: 1,
+            },
+        }
+    }
+
+    #[test]
+    fn test_text_roundtrip_both_ciphers() {
+        for cipher in [
+            SymmetricCipher::Aes256Gcm,
+            SymmetricCipher::ChaCha20Poly1305,
+        ] {
+            let envelope =
+                seal_envelope("secret message ✓".as_bytes(), "hunter2", &fast_options(cipher))
+                    .unwrap();
+            let armored = armor(&envelope);
+            assert!(armored.starts_with(ARMOR_BEGIN));
+            assert_eq!(
+                decrypt_text(&armored, "hunter2").unwrap(),
+                "secret message ✓"
+            );
+            assert!(decrypt_text(&armored, "wrong").is_err());
+        }
+    }
+
+    #[test]
+    fn test_pbkdf2_and_header_info() {
+        let options = EncryptionOptions {
+            cipher: SymmetricCipher::ChaCha20Poly1305,
+            kdf: KeyDerivation::Pbkdf2 { iterations: 1000 },
+        };
+        let envelope = seal_envelope(b"data", "pw", &options).unwrap();
+        let info = inspect_envelope(&envelope).unwrap();
+        assert_eq!(info.cipher, SymmetricCipher::ChaCha20Poly1305);
+        assert_eq!(info.kdf, KeyDerivation::Pbkdf2 { iterations: 1000 });
+        assert_eq!(info.plaintext_size, 4);
+        assert_eq!(info.salt_hex.len(), SALT_LEN * 2);
+        assert_eq!(decrypt_bytes(&envelope, "pw").unwrap(), b"data");
+    }
+
+    #[test]
+    fn test_fresh_salt_and_nonce() {
+        let options = fast_options(SymmetricCipher::Aes256Gcm);
+        let a = seal_envelope(b"same", "pw", &options).unwrap();
+        let b = seal_envelope(b"same", "pw", &options).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let options = fast_options(SymmetricCipher::Aes256Gcm);
+        let envelope = seal_envelope(b"important", "pw", &options).unwrap();
+
+        let mut body = envelope.clone();
+        *body.last_mut().unwrap() ^= 1;
+        assert!(decrypt_bytes(&body, "pw").is_err());
+
+        // Header fields are authenticated too: switching the cipher id must fail
+        let mut header = envelope.clone();
+        header[6] = 2;
+        assert!(decrypt_bytes(&header, "pw").is_err());
+
+        assert!(decrypt_bytes(&envelope[..20], "pw").is_err());
+        assert!(decrypt_bytes(b"not an envelope", "pw").is_err());
+    }
+
+    #[test]
+    fn test_rejects_excessive_parameters() {
+        let options = EncryptionOptions {
+            cipher: SymmetricCipher::Aes256Gcm,
+            kdf: KeyDerivation::Pbkdf2 { iterations: 1000 },
+        };
+        let mut envelope = seal_envelope(b"x", "pw", &options).unwrap();
+        // Iteration count sits right after the kdf id
+        envelope[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = decrypt_bytes(&envelope, "pw").unwrap_err();
+        assert!(err.contains("limits"));
+        assert!(seal_envelope(b"x", "", &options).is_err());
+    }
+
+    #[test]
+    fn test_rejects_weak_parameters() {
+        let weak_argon2 = EncryptionOptions {
+            cipher: SymmetricCipher::Aes256Gcm,
+            kdf: KeyDerivation::Argon2id {
+                memory_kib: 8,
+                iterations: 1,
+                parallelism: 1,
+            },
+        };
+        let err = encrypt_bytes(b"x", "pw", &weak_argon2).unwrap_err();
+        assert!(err.contains("at least"));
+
+        let weak_pbkdf2 = EncryptionOptions {
+            cipher: SymmetricCipher::Aes256Gcm,
+            kdf: KeyDerivation::Pbkdf2 { iterations: 1 },
+        };
+        assert!(encrypt_bytes(b"x", "pw", &weak_pbkdf2).is_err());
+
+        let default = encrypt_text("ok", "pw", &EncryptionOptions::default()).unwrap();
+        assert_eq!(decrypt_text(&default, "pw").unwrap(), "ok");
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("plain.bin");
+        let encrypted = dir.path().join("plain.bin.fbenc");
+        let output = dir.path().join("decrypted.bin");
+        let data: Vec<u8> = (0..=255).cycle().take(5000).collect();
+        std::fs::write(&input, &data).unwrap();
+
+        let options = EncryptionOptions {
+            cipher: SymmetricCipher::ChaCha20Poly1305,
+            kdf: KeyDerivation::default(),
+        };
+        let info = encrypt_file(
+            input.to_str().unwrap(),
+            encrypted.to_str().unwrap(),
+            "pw",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(info.plaintext_size, 5000);
+        let written =
+            decrypt_file(encrypted.to_str().unwrap(), output.to_str().unwrap(), "pw").unwrap();
+        assert_eq!(written, 5000);
+        assert_eq!(std::fs::read(&output).unwrap(), data);
+    }
+}
@@ -0,0 +1,955 @@
+use chrono::{DateTime, NaiveDate};
+use convert_case::{Case, Casing};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Distinct string values tracked per field before giving up on enum detection
+const LITERAL_CAP: usize = 32;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CodegenLanguage {
+    Rust,
+    TypeScript,
+    Go,
+    Python,
+    Pydantic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodegenOptions {
+    pub language: CodegenLanguage,
+    #[serde(default = "default_root_name")]
+    pub root_name: String,
+    #[serde(default = "default_true")]
+    pub detect_enums: bool,
+    #[serde(default = "default_true")]
+    pub detect_dates: bool,
+    /// Upper bound on distinct values for a string field to become an enum
+    #[serde(default = "default_max_enum_values")]
+    pub max_enum_values: usize,
+    /// Go package name
+    #[serde(default)]
+    pub package_name: Option<String>,
+}
+
+fn default_root_name() -> String {
+    "Root".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_enum_values() -> usize {
+    8
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CodegenOutput {
+    pub code: String,
+    pub type_names: Vec<String>,
+}
+
+/// Observations accumulated for every value seen at one position in the samples
+#[derive(Debug, Default, Clone)]
+struct Shape {
+    nulls: usize,
+    bools: usize,
+    ints: usize,
+    floats: usize,
+    strings: usize,
+    dates: usize,
+    datetimes: usize,
+    literals: Vec<String>,
+    literal_overflow: bool,
+    arrays: usize,
+    items: Option<Box<Shape>>,
+    objects: usize,
+    fields: Vec<FieldShape>,
+}
+
+#[derive(Debug, Clone)]
+struct FieldShape {
+    name: String,
+    shape: Shape,
+    present: usize,
+}
+
+impl Shape {
+    fn observe(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Bool(_) => self.bools += 1,
+            Value::Number(n) if n.is_i64() || n.is_u64() => self.ints += 1,
+            Value::Number(_) => self.floats += 1,
+            Value::String(s) => {
+                self.strings += 1;
+                if is_iso_date(s) {
+                    self.dates += 1;
+                } else if DateTime::parse_from_rfc3339(s).is_ok() {
+                    self.datetimes += 1;
+                }
+                if !self.literals.contains(s) {
+                    if self.literals.len() < LITERAL_CAP {
+                        self.literals.push(s.clone());
+                    } else {
+                        self.literal_overflow = true;
+                    }
+                }
+            }
+            Value::Array(items) => {
+                self.arrays += 1;
+                let shape = self.items.get_or_insert_with(Default::default);
+                for item in items {
+                    shape.observe(item);
+                }
+            }
+            Value::Object(map) => {
+                self.objects += 1;
+                for (key, child) in map {
+                    let index = match self.fields.iter().position(|f| f.name == *key) {
+                        Some(index) => index,
+                        None => {
+                            self.fields.push(FieldShape {
+                                name: key.clone(),
+                                shape: Shape::default(),
+                                present: 0,
+                            });
+                            self.fields.len() - 1
+                        }
+                    };
+                    self.fields[index].present += 1;
+                    self.fields[index].shape.observe(child);
+                }
+            }
+        }
+    }
+}
+
+fn is_iso_date(s: &str) -> bool {
+    s.len() == 10 && NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TypeRef {
+    Any,
+    Bool,
+    Integer,
+    Float,
+    String,
+    Date,
+    DateTime,
+    Array(Box<TypeRef>),
+    Nullable(Box<TypeRef>),
+    Named(String),
+}
+
+impl TypeRef {
+    fn nullable(self) -> Self {
+        match self {
+            TypeRef::Nullable(_) | TypeRef::Any => self,
+            other => TypeRef::Nullable(Box::new(other)),
+        }
+    }
+
+    fn uses(&self, predicate: &dyn Fn(&TypeRef) -> bool) -> bool {
+        predicate(self)
+            || match self {
+                TypeRef::Array(inner) | TypeRef::Nullable(inner) => inner.uses(predicate),
+                _ => false,
+            }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FieldDef {
+    json_name: String,
+    ty: TypeRef,
+    optional: bool,
+}
+
+#[derive(Debug, Clone)]
+enum TypeDef {
+    Struct { name: String, fields: Vec<FieldDef> },
+    Enum { name: String, values: Vec<String> },
+}
+
+impl TypeDef {
+    fn name(&self) -> &str {
+        match self {
+            TypeDef::Struct { name, .. } | TypeDef::Enum { name, .. } => name,
+        }
+    }
+}
+
+/// Turns merged shapes into named type definitions, children before parents
+struct Resolver<'a> {
+    options: &'a CodegenOptions,
+    defs: Vec<TypeDef>,
+    used_names: HashSet<String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn unique_name(&mut self, hint: &str) -> String {
+        let base = match pascal_case(hint) {
+            name if name.is_empty() => "Type".to_string(),
+            name if name.starts_with(|c: char| c.is_ascii_digit()) => format!("Type{}", name),
+            name => name,
+        };
+        let mut name = base.clone();
+        let mut counter = 2;
+        while self.used_names.contains(&name) {
+            name = format!("{}{}", base, counter);
+            counter += 1;
+        }
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    fn resolve(&mut self, shape: &Shape, hint: &str) -> TypeRef {
+        let numbers = shape.ints + shape.floats;
+        let kinds = [shape.bools, numbers, shape.strings, shape.arrays, shape.objects]
+            .iter()
+            .filter(|&&count| count > 0)
+            .count();
+
+        let ty = if kinds != 1 {
+            TypeRef::Any
+        } else if shape.bools > 0 {
+            TypeRef::Bool
+        } else if numbers > 0 {
+            if shape.floats > 0 {
+                TypeRef::Float
+            } else {
+                TypeRef::Integer
+            }
+        } else if shape.strings > 0 {
+            self.resolve_string(shape, hint)
+        } else if shape.arrays > 0 {
+            let item = match &shape.items {
+                Some(items) => self.resolve(items, &singular(hint)),
+                None => TypeRef::Any,
+            };
+            TypeRef::Array(Box::new(item))
+        } else {
+            self.resolve_object(shape, hint)
+        };
+
+        if shape.nulls > 0 {
+            ty.nullable()
+        } else {
+            ty
+        }
+    }
+
+    fn resolve_string(&mut self, shape: &Shape, hint: &str) -> TypeRef {
+        if self.options.detect_dates && shape.dates == shape.strings {
+            return TypeRef::Date;
+        }
+        if self.options.detect_dates && shape.datetimes == shape.strings {
+            return TypeRef::DateTime;
+        }
+
+        // A field only looks like an enum when its few values repeat across samples
+        let is_enum = self.options.detect_enums
+            && !shape.literal_overflow
+            && shape.literals.len() <= self.options.max_enum_values
+            && shape.strings > shape.literals.len()
+            && shape.literals.iter().all(|v| !pascal_case(v).is_empty());
+        if is_enum {
+            let name = self.unique_name(hint);
+            let mut values = shape.literals.clone();
+            values.sort();
+            self.defs.push(TypeDef::Enum {
+                name: name.clone(),
+                values,
+            });
+            return TypeRef::Named(name);
+        }
+
+        TypeRef::String
+    }
+
+    fn resolve_object(&mut self, shape: &Shape, hint: &str) -> TypeRef {
+        let name = self.unique_name(hint);
+        let fields = shape
+            .fields
+            .iter()
+            .map(|field| FieldDef {
+                json_name: field.name.clone(),
+                ty: self.resolve(&field.shape, &field.name),
+                optional: field.present < shape.objects,
+            })
+            .collect();
+        self.defs.push(TypeDef::Struct {
+            name: name.clone(),
+            fields,
+        });
+        TypeRef::Named(name)
+    }
+}
+
+fn words(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect()
+}
+
+fn pascal_case(s: &str) -> String {
+    words(s).to_case(Case::Pascal)
+}
+
+fn snake_case(s: &str) -> String {
+    words(s).to_case(Case::Snake)
+}
+
+/// Naive English singular used to name array item types ("categories" → "category")
+fn singular(s: &str) -> String {
+    if let Some(stem) = s.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if s.ends_with("ses") || s.ends_with("xes") {
+        s[..s.len() - 2].to_string()
+    } else if s.ends_with('s') && !s.ends_with("ss") && s.len() > 1 {
+        s[..s.len() - 1].to_string()
+    } else {
+        format!("{}Item", s)
+    }
+}
+
+fn enum_variant_names(values: &[String]) -> Vec<String> {
+    let mut used = HashSet::new();
+    values
+        .iter()
+        .map(|value| {
+            let mut base = pascal_case(value);
+            if base.starts_with(|c: char| c.is_ascii_digit()) {
+                base = format!("V{}", base);
+            }
+            let mut name = base.clone();
+            let mut counter = 2;
+            while !used.insert(name.clone()) {
+                name = format!("{}{}", base, counter);
+                counter += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// Field identifiers in declaration order; keys that map to the same identifier
+/// (`userId` and `user_id`) get a numeric suffix
+fn unique_field_names(fields: &[FieldDef], field_name: fn(&str) -> String) -> Vec<String> {
+    let mut used = HashSet::new();
+    fields
+        .iter()
+        .map(|field| {
+            let base = field_name(&field.json_name);
+            let mut name = base.clone();
+            let mut counter = 2;
+            while !used.insert(name.clone()) {
+                name = format!("{}_{}", base.trim_start_matches("r#").trim_end_matches('_'), counter);
+                counter += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// Infer types from one or more JSON samples and emit code in the requested language
+/// Top-level arrays are treated as lists of samples for the root type
+pub fn generate_code_from_json(samples: &[String], options: &CodegenOptions) -> Result<CodegenOutput, String> {
+    let mut root = Shape::default();
+    for (i, sample) in samples.iter().enumerate().filter(|(_, s)| !s.trim().is_empty()) {
+        let value: Value = serde_json::from_str(sample)
+            .map_err(|e| format!("Sample {} is not valid JSON: {}", i + 1, e))?;
+        match value {
+            Value::Array(items) => items.iter().for_each(|item| root.observe(item)),
+            other => root.observe(&other),
+        }
+    }
+
+    if root.objects == 0 {
+        return Err("Samples must contain a JSON object or an array of objects".to_string());
+    }
+    if root.objects != root.bools + root.ints + root.floats + root.strings + root.arrays + root.objects {
+        return Err("Top-level samples mix objects with other values".to_string());
+    }
+
+    let mut resolver = Resolver {
+        options,
+        defs: Vec::new(),
+        used_names: HashSet::new(),
+    };
+    resolver.resolve_object(&root, &options.root_name);
+    let defs = resolver.defs;
+
+    let code = match options.language {
+        CodegenLanguage::Rust => emit_rust(&defs),
+        CodegenLanguage::TypeScript => emit_typescript(&defs),
+        CodegenLanguage::Go => emit_go(&defs, options.package_name.as_deref().unwrap_or("main")),
+        CodegenLanguage::Python => emit_python(&defs, false),
+        CodegenLanguage::Pydantic => emit_python(&defs, true),
+    };
+
+    Ok(CodegenOutput {
+        code,
+        type_names: defs.iter().map(|d| d.name().to_string()).collect(),
+    })
+}
+
+fn any_field(defs: &[TypeDef], predicate: &dyn Fn(&TypeRef) -> bool) -> bool {
+    defs.iter().any(|def| match def {
+        TypeDef::Struct { fields, .. } => fields.iter().any(|f| f.ty.uses(predicate)),
+        TypeDef::Enum { .. } => false,
+    })
+}
+
+// Rust
+
+const RUST_KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "box", "final", "yield",
+];
+
+fn rust_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any => "serde_json::Value".to_string(),
+        TypeRef::Bool => "bool".to_string(),
+        TypeRef::Integer => "i64".to_string(),
+        TypeRef::Float => "f64".to_string(),
+        TypeRef::String => "String".to_string(),
+        TypeRef::Date => "NaiveDate".to_string(),
+        TypeRef::DateTime => "DateTime<Utc>".to_string(),
+        TypeRef::Array(inner) => format!("Vec<{}>", rust_type(inner)),
+        TypeRef::Nullable(inner) => format!("Option<{}>", rust_type(inner)),
+        TypeRef::Named(name) => name.clone(),
+    }
+}
+
+fn rust_field_name(json_name: &str) -> String {
+    let name = snake_case(json_name);
+    match name.as_str() {
+        "" => "field".to_string(),
+        "self" | "super" | "crate" | "Self" => format!("{}_", name),
+        n if RUST_KEYWORDS.contains(&n) => format!("r#{}", n),
+        n if n.starts_with(|c: char| c.is_ascii_digit()) => format!("field_{}", n),
+        _ => name,
+    }
+}
+
+fn emit_rust(defs: &[TypeDef]) -> String {
+    let mut out = String::from("use serde::{Deserialize, Serialize};\n");
+    let dates = any_field(defs, &|t| *t == TypeRef::Date);
+    let datetimes = any_field(defs, &|t| *t == TypeRef::DateTime);
+    match (dates, datetimes) {
+        (true, true) => out.push_str("use chrono::{DateTime, NaiveDate, Utc};\n"),
+        (true, false) => out.push_str("use chrono::NaiveDate;\n"),
+        (false, true) => out.push_str("use chrono::{DateTime, Utc};\n"),
+        (false, false) => {}
+    }
+
+    for def in defs {
+        out.push('\n');
+        match def {
+            TypeDef::Enum { name, values } => {
+                out.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n");
+                out.push_str(&format!("pub enum {} {{\n", name));
+                for (value, variant) in values.iter().zip(enum_variant_names(values)) {
+                    out.push_str(&format!("    #[serde(rename = {:?})]\n    {},\n", value, variant));
+                }
+                out.push_str("}\n");
+            }
+            TypeDef::Struct { name, fields } => {
+                out.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+                out.push_str(&format!("pub struct {} {{\n", name));
+                for (field, ident) in fields.iter().zip(unique_field_names(fields, rust_field_name)) {
+                    let mut attrs = Vec::new();
+                    if ident.trim_start_matches("r#") != field.json_name {
+                        attrs.push(format!("rename = {:?}", field.json_name));
+                    }
+                    let ty = if field.optional {
+                        attrs.push("default, skip_serializing_if = \"Option::is_none\"".to_string());
+                        field.ty.clone().nullable()
+                    } else {
+                        field.ty.clone()
+                    };
+                    // `Any` fields are already nullable through serde_json::Value
+                    let ty = match ty {
+                        TypeRef::Any if field.optional => TypeRef::Nullable(Box::new(TypeRef::Any)),
+                        other => other,
+                    };
+                    if !attrs.is_empty() {
+                        out.push_str(&format!("    #[serde({})]\n", attrs.join(", ")));
+                    }
+                    out.push_str(&format!("    pub {}: {},\n", ident, rust_type(&ty)));
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+    out
+}
+
+// TypeScript
+
+fn typescript_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any => "unknown".to_string(),
+        TypeRef::Bool => "boolean".to_string(),
+        TypeRef::Integer | TypeRef::Float => "number".to_string(),
+        TypeRef::String | TypeRef::Date | TypeRef::DateTime => "string".to_string(),
+        TypeRef::Array(inner) => match inner.as_ref() {
+            TypeRef::Nullable(_) => format!("({})[]", typescript_type(inner)),
+            _ => format!("{}[]", typescript_type(inner)),
+        },
+        TypeRef::Nullable(inner) => format!("{} | null", typescript_type(inner)),
+        TypeRef::Named(name) => name.clone(),
+    }
+}
+
+fn is_js_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn emit_typescript(defs: &[TypeDef]) -> String {
+    let mut blocks = Vec::new();
+    for def in defs {
+        let block = match def {
+            TypeDef::Enum { name, values } => {
+                let literals: Vec<String> = values.iter().map(|v| format!("{:?}", v)).collect();
+                format!("export type {} = {};\n", name, literals.join(" | "))
+            }
+            TypeDef::Struct { name, fields } => {
+                let mut out = format!("export interface {} {{\n", name);
+                for field in fields {
+                    let key = if is_js_identifier(&field.json_name) {
+                        field.json_name.clone()
+                    } else {
+                        format!("{:?}", field.json_name)
+                    };
+                    let optional = if field.optional { "?" } else { "" };
+                    let comment = match field.ty {
+                        TypeRef::Date => " // ISO 8601 date",
+                        TypeRef::DateTime => " // ISO 8601 date-time",
+                        _ => "",
+                    };
+                    out.push_str(&format!(
+                        "  {}{}: {};{}\n",
+                        key,
+                        optional,
+                        typescript_type(&field.ty),
+                        comment
+                    ));
+                }
+                out.push_str("}\n");
+                out
+            }
+        };
+        blocks.push(block);
+    }
+    blocks.join("\n")
+}
+
+// Go
+
+const GO_INITIALISMS: [&str; 14] = [
+    "id", "url", "uri", "api", "http", "https", "json", "xml", "html", "ip", "uuid", "sql", "css", "ui",
+];
+
+fn go_identifier(s: &str) -> String {
+    let name: String = snake_case(s)
+        .split('_')
+        .filter(|w| !w.is_empty())
+        .map(|word| {
+            if GO_INITIALISMS.contains(&word) {
+                word.to_uppercase()
+            } else {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().collect::<String>() + chars.as_str())
+                    .unwrap_or_default()
+            }
+        })
+        .collect();
+    match name {
+        n if n.is_empty() => "Field".to_string(),
+        n if n.starts_with(|c: char| c.is_ascii_digit()) => format!("F{}", n),
+        n => n,
+    }
+}
+
+fn go_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any => "any".to_string(),
+        TypeRef::Bool => "bool".to_string(),
+        TypeRef::Integer => "int64".to_string(),
+        TypeRef::Float => "float64".to_string(),
+        // encoding/json only parses RFC 3339 into time.Time, plain dates stay strings
+        TypeRef::String | TypeRef::Date => "string".to_string(),
+        TypeRef::DateTime => "time.Time".to_string(),
+        TypeRef::Array(inner) => format!("[]{}", go_type(inner)),
+        TypeRef::Nullable(inner) => match inner.as_ref() {
+            TypeRef::Array(_) | TypeRef::Any => go_type(inner),
+            _ => format!("*{}", go_type(inner)),
+        },
+        TypeRef::Named(name) => name.clone(),
+    }
+}
+
+fn emit_go(defs: &[TypeDef], package: &str) -> String {
+    let mut out = format!("package {}\n", package);
+    if any_field(defs, &|t| *t == TypeRef::DateTime) {
+        out.push_str("\nimport \"time\"\n");
+    }
+
+    for def in defs {
+        out.push('\n');
+        match def {
+            TypeDef::Enum { name, values } => {
+                out.push_str(&format!("type {} string\n\nconst (\n", name));
+                let variants: Vec<String> = enum_variant_names(values)
+                    .into_iter()
+                    .map(|v| format!("{}{}", name, v))
+                    .collect();
+                let width = variants.iter().map(String::len).max().unwrap_or(0);
+                for (value, variant) in values.iter().zip(&variants) {
+                    out.push_str(&format!("\t{:<width$} {} = {:?}\n", variant, name, value, width = width));
+                }
+                out.push_str(")\n");
+            }
+            TypeDef::Struct { name, fields } => {
+                let mut used = HashSet::new();
+                // Go reads an empty tag name as "use the field name", so "" cannot be mapped
+                let skipped = fields.iter().any(|field| field.json_name.is_empty());
+                let rows: Vec<(String, String, String)> = fields
+                    .iter()
+                    .filter(|field| !field.json_name.is_empty())
+                    .map(|field| {
+                        let mut ident = go_identifier(&field.json_name);
+                        while !used.insert(ident.clone()) {
+                            ident.push('_');
+                        }
+                        let ty = if field.optional {
+                            go_type(&field.ty.clone().nullable())
+                        } else {
+                            go_type(&field.ty)
+                        };
+                        let omit = if field.optional { ",omitempty" } else { "" };
+                        (ident, ty, format!("`json:\"{}{}\"`", field.json_name, omit))
+                    })
+                    .collect();
+                let name_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+                let type_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0);
+
+                out.push_str(&format!("type {} struct {{\n", name));
+                for (ident, ty, tag) in rows {
+                    out.push_str(&format!(
+                        "\t{:<nw$} {:<tw$} {}\n",
+                        ident,
+                        ty,
+                        tag,
+                        nw = name_width,
+                        tw = type_width
+                    ));
+                }
+                if skipped {
+                    out.push_str("\t// The \"\" key has no struct tag equivalent; decode it with a map\n");
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+    out
+}
+
+// Python
+
+const PYTHON_KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+fn python_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Any => "Any".to_string(),
+        TypeRef::Bool => "bool".to_string(),
+        TypeRef::Integer => "int".to_string(),
+        TypeRef::Float => "float".to_string(),
+        TypeRef::String => "str".to_string(),
+        TypeRef::Date => "date".to_string(),
+        TypeRef::DateTime => "datetime".to_string(),
+        TypeRef::Array(inner) => format!("List[{}]", python_type(inner)),
+        TypeRef::Nullable(inner) => format!("Optional[{}]", python_type(inner)),
+        TypeRef::Named(name) => name.clone(),
+    }
+}
+
+fn python_field_name(json_name: &str) -> String {
+    let name = snake_case(json_name);
+    match name.as_str() {
+        // `field` would shadow `dataclasses.field` for the rest of the class body
+        "" | "field" => "field_".to_string(),
+        n if PYTHON_KEYWORDS.contains(&n) => format!("{}_", n),
+        n if n.starts_with(|c: char| c.is_ascii_digit()) => format!("field_{}", n),
+        _ => name,
+    }
+}
+
+fn emit_python(defs: &[TypeDef], pydantic: bool) -> String {
+    let mut typing = Vec::new();
+    if any_field(defs, &|t| *t == TypeRef::Any) {
+        typing.push("Any");
+    }
+    if any_field(defs, &|t| matches!(t, TypeRef::Array(_))) {
+        typing.push("List");
+    }
+    if defs.iter().any(|d| matches!(d, TypeDef::Enum { .. })) {
+        typing.push("Literal");
+    }
+    let has_optional = defs.iter().any(|d| match d {
+        TypeDef::Struct { fields, .. } => fields
+            .iter()
+            .any(|f| f.optional || f.ty.uses(&|t| matches!(t, TypeRef::Nullable(_)))),
+        TypeDef::Enum { .. } => false,
+    });
+    if has_optional {
+        typing.push("Optional");
+    }
+
+    let mut out = String::from("from __future__ import annotations\n\n");
+    if !pydantic {
+        out.push_str("from dataclasses import dataclass, field\n");
+    }
+    let dates = any_field(defs, &|t| *t == TypeRef::Date);
+    let datetimes = any_field(defs, &|t| *t == TypeRef::DateTime);
+    match (dates, datetimes) {
+        (true, true) => out.push_str("from datetime import date, datetime\n"),
+        (true, false) => out.push_str("from datetime import date\n"),
+        (false, true) => out.push_str("from datetime import datetime\n"),
+        (false, false) => {}
+    }
+    if !typing.is_empty() {
+        out.push_str(&format!("from typing import {}\n", typing.join(", ")));
+    }
+    if pydantic {
+        out.push_str("\nfrom pydantic import BaseModel, Field\n");
+    }
+
+    for def in defs {
+        out.push_str("\n\n");
+        match def {
+            TypeDef::Enum { name, values } => {
+                let literals: Vec<String> = values.iter().map(|v| format!("{:?}", v)).collect();
+                out.push_str(&format!("{} = Literal[{}]\n", name, literals.join(", ")));
+            }
+            TypeDef::Struct { name, fields } => {
+                if pydantic {
+                    out.push_str(&format!("class {}(BaseModel):\n", name));
+                } else {
+                    out.push_str(&format!("@dataclass\nclass {}:\n", name));
+                }
+                if fields.is_empty() {
+                    out.push_str("    pass\n");
+                }
+                // Dataclasses require fields without defaults to come first
+                let named: Vec<(&FieldDef, String)> =
+                    fields.iter().zip(unique_field_names(fields, python_field_name)).collect();
+                let mut ordered: Vec<&(&FieldDef, String)> = named.iter().filter(|(f, _)| !f.optional).collect();
+                ordered.extend(named.iter().filter(|(f, _)| f.optional));
+
+                for (field, ident) in ordered {
+                    let renamed = *ident != field.json_name;
+                    let ty = if field.optional {
+                        field.ty.clone().nullable()
+                    } else {
+                        field.ty.clone()
+                    };
+                    let default = match (pydantic, field.optional, renamed) {
+                        (true, true, true) => format!(" = Field(default=None, alias={:?})", field.json_name),
+                        (true, false, true) => format!(" = Field(alias={:?})", field.json_name),
+                        (false, true, true) => format!(
+                            " = field(default=None, metadata={{\"json\": {:?}}})",
+                            field.json_name
+                        ),
+                        (false, false, true) => {
+                            format!(" = field(metadata={{\"json\": {:?}}})", field.json_name)
+                        }
+                        (_, true, false) => " = None".to_string(),
+                        (_, false, false) => String::new(),
+                    };
+                    out.push_str(&format!("    {}: {}{}\n", ident, python_type(&ty), default));
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl CodegenOptions {
+        fn new(language: CodegenLanguage) -> Self {
+            Self {
+                language,
+                root_name: default_root_name(),
+                detect_enums: true,
+                detect_dates: true,
+                max_enum_values: default_max_enum_values(),
+                package_name: None,
+            }
+        }
+    }
+
+    const SAMPLES: [&str; 2] = [
+        r#"{"id": 1, "userName": "ann", "status": "active", "createdAt": "2024-05-01T10:00:00Z",
+            "birthday": "1990-02-03", "tags": ["a"], "address": {"city": "Oslo", "zip": "0150"},
+            "score": 1, "type": "admin"}"#,
+        r#"{"id": 2, "userName": "bo", "status": "active", "createdAt": "2024-05-02T11:30:00+02:00",
+            "birthday": "1985-12-24", "tags": [], "address": null, "score": 2.5,
+            "nickname": "b", "type": "user"}"#,
+    ];
+
+    fn generate(language: CodegenLanguage) -> CodegenOutput {
+        let samples: Vec<String> = SAMPLES.iter().map(|s| s.to_string()).collect();
+        generate_code_from_json(&samples, &CodegenOptions::new(language)).unwrap()
+    }
+
+    #[test]
+    fn test_rust_output() {
+        let output = generate(CodegenLanguage::Rust);
+        let code = &output.code;
+        assert!(code.contains("use chrono::{DateTime, NaiveDate, Utc};"));
+        assert!(code.contains("pub struct Root {"));
+        assert!(code.contains("    #[serde(rename = \"userName\")]\n    pub user_name: String,"));
+        assert!(code.contains("pub created_at: DateTime<Utc>,"));
+        assert!(code.contains("pub birthday: NaiveDate,"));
+        assert!(code.contains("pub score: f64,"));
+        assert!(code.contains("pub address: Option<Address>,"));
+        assert!(code.contains("pub tags: Vec<String>,"));
+        assert!(code.contains("pub r#type: String,"));
+        assert!(code.contains(
+            "    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub nickname: Option<String>,"
+        ));
+        assert!(code.contains("pub enum Status {\n    #[serde(rename = \"active\")]\n    Active,"));
+        assert_eq!(output.type_names, vec!["Status", "Address", "Root"]);
+    }
+
+    #[test]
+    fn test_typescript_output() {
+        let code = generate(CodegenLanguage::TypeScript).code;
+        assert!(code.contains("export type Status = \"active\";"));
+        assert!(code.contains("export interface Root {"));
+        assert!(code.contains("  nickname?: string;"));
+        assert!(code.contains("  address: Address | null;"));
+        assert!(code.contains("  createdAt: string; // ISO 8601 date-time"));
+    }
+
+    #[test]
+    fn test_go_output() {
+        let code = generate(CodegenLanguage::Go).code;
+        assert!(code.starts_with("package main\n\nimport \"time\"\n"));
+        assert!(code.contains("type Status string"));
+        assert!(code.contains("StatusActive Status = \"active\""));
+        assert!(code.contains("\tID        int64"));
+        assert!(code.contains("`json:\"nickname,omitempty\"`"));
+        assert!(code.contains("*Address"));
+        assert!(code.contains("time.Time"));
+    }
+
+    #[test]
+    fn test_python_outputs() {
+        let code = generate(CodegenLanguage::Python).code;
+        assert!(code.contains("@dataclass\nclass Root:"));
+        assert!(code.contains("    user_name: str = field(metadata={\"json\": \"userName\"})"));
+        assert!(code.contains("    nickname: Optional[str] = None"));
+        assert!(code.contains("Status = Literal[\"active\"]"));
+        assert!(code.contains("from datetime import date, datetime"));
+        // Required fields must precede fields with defaults
+        let required = code.find("    type: str").unwrap();
+        let optional = code.find("    nickname: Optional[str]").unwrap();
+        assert!(required < optional);
+
+        let code = generate(CodegenLanguage::Pydantic).code;
+        assert!(code.contains("class Root(BaseModel):"));
+        assert!(code.contains("    user_name: str = Field(alias=\"userName\")"));
+        assert!(code.contains("    created_at: datetime = Field(alias=\"createdAt\")"));
+    }
+
+    #[test]
+    fn test_top_level_array_and_mixed_types() {
+        let samples = vec![r#"[{"value": 1, "items": [{"n": 1}]}, {"value": "x", "items": [{"n": null}]}]"#.to_string()];
+        let output = generate_code_from_json(&samples, &CodegenOptions::new(CodegenLanguage::Rust)).unwrap();
+        assert!(output.code.contains("pub value: serde_json::Value,"));
+        assert!(output.code.contains("pub struct Item {\n    pub n: Option<i64>,"));
+        assert!(output.code.contains("pub items: Vec<Item>,"));
+    }
+
+    #[test]
+    fn test_detection_can_be_disabled() {
+        let samples: Vec<String> = SAMPLES.iter().map(|s| s.to_string()).collect();
+        let options = CodegenOptions {
+            detect_enums: false,
+            detect_dates: false,
+            ..CodegenOptions::new(CodegenLanguage::TypeScript)
+        };
+        let output = generate_code_from_json(&samples, &options).unwrap();
+        assert!(!output.code.contains("export type Status"));
+        assert!(!output.code.contains("ISO 8601"));
+    }
+
+    #[test]
+    fn test_invalid_samples() {
+        let options = CodegenOptions::new(CodegenLanguage::Go);
+        assert!(generate_code_from_json(&["{".to_string()], &options).is_err());
+        assert!(generate_code_from_json(&["[1, 2]".to_string()], &options).is_err());
+        assert!(generate_code_from_json(&[], &options).is_err());
+    }
+
+    #[test]
+    fn test_colliding_field_names() {
+        let samples = vec![r#"{"userId": 1, "user_id": 2}"#.to_string()];
+        let rust = generate_code_from_json(&samples, &CodegenOptions::new(CodegenLanguage::Rust)).unwrap();
+        assert!(rust.code.contains("    pub user_id: i64,"));
+        assert!(rust.code.contains("    #[serde(rename = \"user_id\")]\n    pub user_id_2: i64,"));
+
+        let python = generate_code_from_json(&samples, &CodegenOptions::new(CodegenLanguage::Python)).unwrap();
+        assert!(python.code.contains("    user_id: int = field(metadata={\"json\": \"userId\"})"));
+        assert!(python.code.contains("    user_id_2: int = field(metadata={\"json\": \"user_id\"})"));
+    }
+
+    #[test]
+    fn test_empty_and_field_keys() {
+        let samples = vec![r#"{"": 1, "field": 2, "name": "x"}"#.to_string()];
+        let python = generate_code_from_json(&samples, &CodegenOptions::new(CodegenLanguage::Python)).unwrap();
+        assert!(python.code.contains("    field_: int = field(metadata={\"json\": \"\"})"));
+        assert!(python.code.contains("    field_2: int = field(metadata={\"json\": \"field\"})"));
+        assert!(!python.code.contains("    field:"));
+
+        let go = generate_code_from_json(&samples, &CodegenOptions::new(CodegenLanguage::Go)).unwrap();
+        assert!(!go.code.contains("`json:\","));
+        assert!(go.code.contains("`json:\"field\"`"));
+        assert!(go.code.contains("// The \"\" key has no struct tag equivalent"));
+    }
+
+    #[test]
+    fn test_singular() {
+        assert_eq!(singular("categories"), "category");
+        assert_eq!(singular("boxes"), "box");
+        assert_eq!(singular("users"), "user");
+        assert_eq!(singular("data"), "dataItem");
+    }
+}
@@ -6,8 +6,10 @@ pub mod hmac;
 pub mod qrcode;
 pub mod gitignore;
 pub mod rsa;
+pub mod encryption;
 pub mod bcrypt;
 pub mod git_branch;
+pub mod json_codegen;
 
 pub use hash::{HashAlgorithm, MultiHash, generate_hash, generate_all_hashes};
 pub use uuid::{UuidVersion, generate_uuid, generate_multiple_uuids, validate_uuid};
@@ -16,6 +18,14 @@ pub use password::{PasswordOptions, PasswordOutput, generate_password};
 pub use hmac::{HmacAlgorithm, generate_hmac};
 pub use qrcode::{generate_qr_code, generate_wifi_qr, QrOutput, WifiCredentials};
 pub use gitignore::{GitignoreTemplate, generate_gitignore};
-pub use rsa::{RsaKeySize, RsaKeyPair, generate_rsa_key_pair};
+pub use rsa::{
+    ConvertedRsaKey, RsaEncryptionPadding, RsaHash, RsaKeyFormat, RsaKeyInfo, RsaKeyInput, RsaKeyPair, RsaKeySize,
+    RsaSignatureScheme, convert_rsa_key, generate_rsa_key_pair, rsa_decrypt, rsa_encrypt, rsa_key_info, rsa_sign,
+    rsa_verify,
+};
+pub use encryption::{
+    EncryptionOptions, EnvelopeInfo, decrypt_file, decrypt_text, encrypt_file, encrypt_text, inspect_envelope,
+};
 pub use bcrypt::{BcryptHashOutput, bcrypt_hash, bcrypt_verify};
 pub use git_branch::{BranchNameOutput, generate_git_branch_name};
+pub use json_codegen::{CodegenOptions, CodegenOutput, generate_code_from_json};
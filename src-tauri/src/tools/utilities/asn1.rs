@@ -0,0 +1,720 @@
+use base64ct::{Base64, Encoding};
+use const_oid::db::DB;
+use const_oid::ObjectIdentifier;
+use pkcs8::der::pem;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Asn1Error {
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Invalid PEM: {0}")]
+    InvalidPem(String),
+    #[error("ASN.1 error at offset {offset}: {message}")]
+    ParseError { offset: usize, message: String },
+}
+
+/// How the input text is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Asn1InputFormat {
+    /// PEM when armored, otherwise hex if it only has hex digits, otherwise base64
+    #[default]
+    Auto,
+    Pem,
+    Hex,
+    Base64,
+}
+
+/// One TLV element of the decoded structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asn1Node {
+    pub offset: usize,
+    pub header_length: usize,
+    /// Content length; `None` for indefinite-length (BER) encodings
+    pub length: Option<usize>,
+    pub class: String,
+    pub constructed: bool,
+    pub tag_number: u32,
+    pub tag_name: String,
+    /// Human-readable value for primitive types
+    pub value: Option<String>,
+    /// Registered name of an OBJECT IDENTIFIER
+    pub oid_name: Option<String>,
+    /// True when `children` were decoded from the contents of a BIT/OCTET STRING
+    pub encapsulated: bool,
+    pub children: Vec<Asn1Node>,
+}
+
+/// A decoded PEM block or raw DER/BER blob
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asn1Document {
+    /// PEM label, e.g. `CERTIFICATE`
+    pub label: Option<String>,
+    pub description: Option<String>,
+    pub size: usize,
+    /// Places where the encoding is valid BER but not DER
+    pub der_violations: Vec<String>,
+    pub nodes: Vec<Asn1Node>,
+}
+
+const MAX_DEPTH: usize = 64;
+
+const CLASS_UNIVERSAL: u8 = 0;
+const CLASS_CONTEXT: u8 = 2;
+
+fn universal_tag_name(tag: u32) -> Option<&'static str> {
+    Some(match tag {
+        0 => "END OF CONTENTS",
+        1 => "BOOLEAN",
+        2 => "INTEGER",
+        3 => "BIT STRING",
+        4 => "OCTET STRING",
+        5 => "NULL",
+        6 => "OBJECT IDENTIFIER",
+        7 => "ObjectDescriptor",
+        8 => "EXTERNAL",
+        9 => "REAL",
+        10 => "ENUMERATED",
+        11 => "EMBEDDED PDV",
+        12 => "UTF8String",
+        13 => "RELATIVE-OID",
+        16 => "SEQUENCE",
+        17 => "SET",
+        18 => "NumericString",
+        19 => "PrintableString",
+        20 => "T61String",
+        21 => "VideotexString",
+        22 => "IA5String",
+        23 => "UTCTime",
+        24 => "GeneralizedTime",
+        25 => "GraphicString",
+        26 => "VisibleString",
+        27 => "GeneralString",
+        28 => "UniversalString",
+        30 => "BMPString",
+        _ => return None,
+    })
+}
+
+fn pem_description(label: &str) -> Option<&'static str> {
+    Some(match label {
+        "CERTIFICATE" => "X.509 certificate",
+        "TRUSTED CERTIFICATE" => "OpenSSL trusted certificate",
+        "CERTIFICATE REQUEST" | "NEW CERTIFICATE REQUEST" => "PKCS#10 certificate signing request",
+        "X509 CRL" => "X.509 certificate revocation list",
+        "PRIVATE KEY" => "PKCS#8 private key",
+        "ENCRYPTED PRIVATE KEY" => "PKCS#8 encrypted private key",
+        "PUBLIC KEY" => "X.509 SubjectPublicKeyInfo",
+        "RSA PRIVATE KEY" => "PKCS#1 RSA private key",
+        "RSA PUBLIC KEY" => "PKCS#1 RSA public key",
+        "EC PRIVATE KEY" => "SEC1 EC private key",
+        "EC PARAMETERS" => "EC domain parameters",
+        "DSA PRIVATE KEY" => "DSA private key",
+        "DH PARAMETERS" => "PKCS#3 DH parameters",
+        "PKCS7" | "CMS" => "PKCS#7 / CMS message",
+        "OPENSSH PRIVATE KEY" => "OpenSSH private key (not ASN.1)",
+        _ => return None,
+    })
+}
+
+fn parse_error(offset: usize, message: impl Into<String>) -> Asn1Error {
+    Asn1Error::ParseError {
+        offset,
+        message: message.into(),
+    }
+}
+
+/// Decode OBJECT IDENTIFIER contents into dotted notation
+fn decode_oid(bytes: &[u8], relative: bool) -> Option<String> {
+    let mut arcs: Vec<u128> = Vec::new();
+    let mut current: u128 = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        if current == 0 && *byte == 0x80 {
+            return None; // non-minimal arc encoding
+        }
+        current = current.checked_mul(128)? | u128::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() && !relative {
+                let first = if current < 40 {
+                    0
+                } else if current < 80 {
+                    1
+                } else {
+                    2
+                };
+                arcs.push(first);
+                arcs.push(current - first * 40);
+            } else {
+                arcs.push(current);
+            }
+            current = 0;
+        } else if i == bytes.len() - 1 {
+            return None;
+        }
+    }
+    if arcs.is_empty() {
+        return None;
+    }
+    Some(
+        arcs.iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+fn oid_name(dotted: &str) -> Option<String> {
+    let oid = ObjectIdentifier::new(dotted).ok()?;
+    DB.by_oid(&oid).map(String::from)
+}
+
+/// Render an INTEGER: decimal when small, otherwise hex with its bit size
+fn decode_integer(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "(empty)".to_string();
+    }
+    if bytes.len() <= 16 {
+        let negative = bytes[0] & 0x80 != 0;
+        let mut value: i128 = if negative { -1 } else { 0 };
+        for byte in bytes {
+            value = (value << 8) | i128::from(*byte);
+        }
+        return value.to_string();
+    }
+    let significant = bytes.iter().skip_while(|b| **b == 0).count();
+    let bits = significant * 8
+        - bytes
+            .iter()
+            .find(|b| **b != 0)
+            .map_or(0, |b| b.leading_zeros() as usize);
+    format!("({} bit) {}", bits, hex::encode_upper(bytes))
+}
+
+fn decode_time(text: &str, generalized: bool) -> String {
+    let digits: String = text.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let (year, rest) = if generalized {
+        match digits.get(..4) {
+            Some(y) => (y.to_string(), &digits[4..]),
+            None => return text.to_string(),
+        }
+    } else {
+        match digits.get(..2).and_then(|y| y.parse::<u32>().ok()) {
+            // RFC 5280: two-digit years 50-99 are 19xx
+            Some(y) => (
+                format!("{}", if y >= 50 { 1900 + y } else { 2000 + y }),
+                &digits[2..],
+            ),
+            None => return text.to_string(),
+        }
+    };
+    let part = |i: usize| rest.get(i..i + 2).unwrap_or("00");
+    if rest.len() < 8 {
+        return text.to_string();
+    }
+    let zone = if text.ends_with('Z') { " UTC" } else { "" };
+    format!(
+        "{} ({}-{}-{} {}:{}:{}{})",
+        text,
+        year,
+        part(0),
+        part(2),
+        part(4),
+        part(6),
+        part(8),
+        zone
+    )
+}
+
+fn decode_text(tag: u32, bytes: &[u8]) -> Option<String> {
+    match tag {
+        // BMPString is UTF-16BE
+        30 => {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+        // UniversalString is UTF-32BE
+        28 => bytes
+            .chunks_exact(4)
+            .map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+            .collect(),
+        // T61String is effectively Latin-1 in practice
+        20 => Some(bytes.iter().map(|b| char::from(*b)).collect()),
+        _ => std::str::from_utf8(bytes).ok().map(String::from),
+    }
+}
+
+fn is_printable(bytes: &[u8]) -> bool {
+    !bytes.is_empty()
+        && std::str::from_utf8(bytes)
+            .is_ok_and(|s| s.chars().all(|c| !c.is_control() || c == '\n' || c == '\t'))
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    violations: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    /// Parse consecutive TLVs in `start..end`
+    fn parse_all(
+        &mut self,
+        start: usize,
+        end: usize,
+        depth: usize,
+    ) -> Result<Vec<Asn1Node>, Asn1Error> {
+        let mut nodes = Vec::new();
+        let mut pos = start;
+        while pos < end {
+            let (node, next) = self.parse_node(pos, end, depth)?;
+            nodes.push(node);
+            pos = next;
+        }
+        Ok(nodes)
+    }
+
+    fn byte(&self, pos: usize, end: usize) -> Result<u8, Asn1Error> {
+        if pos < end {
+            Ok(self.bytes[pos])
+        } else {
+            Err(parse_error(pos, "unexpected end of data"))
+        }
+    }
+
+    /// Parse one TLV at `offset`, returning it and the position after it
+    fn parse_node(
+        &mut self,
+        offset: usize,
+        end: usize,
+        depth: usize,
+    ) -> Result<(Asn1Node, usize), Asn1Error> {
+        if depth > MAX_DEPTH {
+            return Err(parse_error(offset, "structure is nested too deeply"));
+        }
+        let mut pos = offset;
+        let identifier = self.byte(pos, end)?;
+        pos += 1;
+        let class = identifier >> 6;
+        let constructed = identifier & 0x20 != 0;
+        let mut tag_number = u32::from(identifier & 0x1f);
+        if tag_number == 0x1f {
+            tag_number = 0;
+            loop {
+                let b = self.byte(pos, end)?;
+                pos += 1;
+                tag_number = tag_number
+                    .checked_mul(128)
+                    .ok_or_else(|| parse_error(offset, "tag number too large"))?
+                    | u32::from(b & 0x7f);
+                if b & 0x80 == 0 {
+                    break;
+                }
+            }
+        }
+
+        let first = self.byte(pos, end)?;
+        pos += 1;
+        let length = match first {
+            0x80 => {
+                if !constructed {
+                    return Err(parse_error(
+                        offset,
+                        "indefinite length on a primitive element",
+                    ));
+                }
+                self.violations
+                    .push(format!("offset {}: indefinite length", offset));
+                None
+            }
+            0xff => return Err(parse_error(offset, "reserved length byte 0xFF")),
+            n if n & 0x80 != 0 => {
+                let count = usize::from(n & 0x7f);
+                if count > 8 {
+                    return Err(parse_error(offset, "length field is too long"));
+                }
+                let mut len: usize = 0;
+                for _ in 0..count {
+                    len = (len << 8) | usize::from(self.byte(pos, end)?);
+                    pos += 1;
+                }
+                if len < 0x80 || (count > 1 && len >> ((count - 1) * 8) == 0) {
+                    self.violations
+                        .push(format!("offset {}: non-minimal length encoding", offset));
+                }
+                Some(len)
+            }
+            n => Some(usize::from(n)),
+        };
+        let header_length = pos - offset;
+
+        let tag_name = match class {
+            CLASS_UNIVERSAL => universal_tag_name(tag_number)
+                .map(String::from)
+                .unwrap_or_else(|| format!("[UNIVERSAL {}]", tag_number)),
+            1 => format!("[APPLICATION {}]", tag_number),
+            CLASS_CONTEXT => format!("[{}]", tag_number),
+            _ => format!("[PRIVATE {}]", tag_number),
+        };
+        let class_name =
+            ["universal", "application", "context", "private"][usize::from(class)].to_string();
+
+        let mut node = Asn1Node {
+            offset,
+            header_length,
+            length,
+            class: class_name,
+            constructed,
+            tag_number,
+            tag_name,
+            value: None,
+            oid_name: None,
+            encapsulated: false,
+            children: Vec::new(),
+        };
+
+        let Some(length) = length else {
+            // Indefinite length: children until END OF CONTENTS
+            loop {
+                if self.byte(pos, end)? == 0 && self.byte(pos + 1, end)? == 0 {
+                    pos += 2;
+                    break;
+                }
+                let (child, next) = self.parse_node(pos, end, depth + 1)?;
+                node.children.push(child);
+                pos = next;
+            }
+            return Ok((node, pos));
+        };
+
+        let content_end = pos
+            .checked_add(length)
+            .filter(|e| *e <= end)
+            .ok_or_else(|| {
+                parse_error(
+                    offset,
+                    format!(
+                        "length {} exceeds the available {} bytes",
+                        length,
+                        end - pos
+                    ),
+                )
+            })?;
+        if constructed {
+            node.children = self.parse_all(pos, content_end, depth + 1)?;
+        } else {
+            self.decode_primitive(&mut node, pos, content_end, depth);
+        }
+        Ok((node, content_end))
+    }
+
+    fn decode_primitive(&mut self, node: &mut Asn1Node, start: usize, end: usize, depth: usize) {
+        let content = &self.bytes[start..end];
+        if node.class == "context" {
+            node.value = Some(if is_printable(content) {
+                String::from_utf8_lossy(content).into_owned()
+            } else {
+                hex::encode_upper(content)
+            });
+            return;
+        }
+        if node.class != "universal" {
+            node.value = Some(hex::encode_upper(content));
+            return;
+        }
+        node.value = match node.tag_number {
+            1 => Some(match content {
+                [0] => "FALSE".to_string(),
+                [0xff] => "TRUE".to_string(),
+                [_] => {
+                    self.violations
+                        .push(format!("offset {}: BOOLEAN TRUE must be 0xFF", node.offset));
+                    "TRUE".to_string()
+                }
+                _ => format!("invalid BOOLEAN {}", hex::encode_upper(content)),
+            }),
+            2 | 10 => Some(decode_integer(content)),
+            3 => {
+                let unused = content.first().copied().unwrap_or(0);
+                let data = content.get(1..).unwrap_or(&[]);
+                if unused == 0 && self.try_encapsulated(node, start + 1, end, depth) {
+                    None
+                } else {
+                    let bits = (data.len() * 8).saturating_sub(usize::from(unused));
+                    Some(format!("({} bit) {}", bits, hex::encode_upper(data)))
+                }
+            }
+            4 => {
+                if self.try_encapsulated(node, start, end, depth) {
+                    None
+                } else if is_printable(content) {
+                    Some(format!(
+                        "{} \"{}\"",
+                        hex::encode_upper(content),
+                        String::from_utf8_lossy(content)
+                    ))
+                } else {
+                    Some(hex::encode_upper(content))
+                }
+            }
+            5 => None,
+            6 | 13 => match decode_oid(content, node.tag_number == 13) {
+                Some(dotted) => {
+                    node.oid_name = oid_name(&dotted);
+                    Some(dotted)
+                }
+                None => Some(format!("invalid OID {}", hex::encode_upper(content))),
+            },
+            23 | 24 => std::str::from_utf8(content)
+                .ok()
+                .map(|text| decode_time(text, node.tag_number == 24)),
+            12 | 18 | 19 | 20 | 21 | 22 | 25 | 26 | 27 | 28 | 30 => {
+                decode_text(node.tag_number, content).or_else(|| Some(hex::encode_upper(content)))
+            }
+            _ => Some(hex::encode_upper(content)),
+        };
+    }
+
+    /// BIT and OCTET STRINGs often wrap DER (public keys, extensions); decode it when it parses cleanly
+    fn try_encapsulated(
+        &mut self,
+        node: &mut Asn1Node,
+        start: usize,
+        end: usize,
+        depth: usize,
+    ) -> bool {
+        const PLAUSIBLE: [u8; 12] = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x0c, 0x13, 0x16, 0x17, 0x30, 0x31,
+        ];
+        if start >= end || !PLAUSIBLE.contains(&self.bytes[start]) {
+            return false;
+        }
+        let mut inner = Parser {
+            bytes: self.bytes,
+            violations: Vec::new(),
+        };
+        match inner.parse_all(start, end, depth + 1) {
+            Ok(children) if !inner.violations.iter().any(|v| v.contains("indefinite")) => {
+                self.violations.extend(inner.violations);
+                node.children = children;
+                node.encapsulated = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parse DER or BER bytes into a list of top-level nodes
+pub fn parse_asn1(bytes: &[u8]) -> Result<(Vec<Asn1Node>, Vec<String>), Asn1Error> {
+    if bytes.is_empty() {
+        return Err(Asn1Error::InvalidInput("no data".to_string()));
+    }
+    let mut parser = Parser {
+        bytes,
+        violations: Vec::new(),
+    };
+    let nodes = parser.parse_all(0, bytes.len(), 0)?;
+    Ok((nodes, parser.violations))
+}
+
+fn decode_document(label: Option<String>, bytes: &[u8]) -> Result<Asn1Document, Asn1Error> {
+    let (nodes, der_violations) = parse_asn1(bytes)?;
+    Ok(Asn1Document {
+        description: label.as_deref().and_then(pem_description).map(String::from),
+        label,
+        size: bytes.len(),
+        der_violations,
+        nodes,
+    })
+}
+
+/// Split text into its `-----BEGIN ...-----` / `-----END ...-----` blocks
+fn pem_blocks(input: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("-----BEGIN ") {
+        let after = &rest[start..];
+        let Some(end_marker) = after.find("-----END ") else {
+            break;
+        };
+        let end = after[end_marker + 9..]
+            .find("-----")
+            .map_or(after.len(), |i| end_marker + 9 + i + 5);
+        blocks.push(&after[..end]);
+        rest = &after[end..];
+    }
+    blocks
+}
+
+fn decode_input(input: &str, format: Asn1InputFormat) -> Result<Vec<u8>, Asn1Error> {
+    let cleaned: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    match format {
+        Asn1InputFormat::Hex => {
+            let hex_digits: String = cleaned.replace(':', "").replace("0x", "").replace("0X", "");
+            hex::decode(&hex_digits)
+                .map_err(|e| Asn1Error::InvalidInput(format!("invalid hex: {}", e)))
+        }
+        Asn1InputFormat::Base64 => Base64::decode_vec(&cleaned)
+            .map_err(|e| Asn1Error::InvalidInput(format!("invalid base64: {}", e))),
+        Asn1InputFormat::Auto => {
+            let hexish = cleaned.replace(':', "");
+            if !hexish.is_empty()
+                && hexish.len() & 1 == 0
+                && hexish.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                decode_input(input, Asn1InputFormat::Hex)
+            } else {
+                decode_input(input, Asn1InputFormat::Base64)
+            }
+        }
+        Asn1InputFormat::Pem => unreachable!("PEM is handled by inspect_asn1"),
+    }
+}
+
+/// Decode every PEM block in the input, or a single hex/base64 DER blob
+pub fn inspect_asn1(input: &str, format: Asn1InputFormat) -> Result<Vec<Asn1Document>, Asn1Error> {
+    let use_pem = match format {
+        Asn1InputFormat::Pem => true,
+        Asn1InputFormat::Auto => input.contains("-----BEGIN "),
+        _ => false,
+    };
+    if !use_pem {
+        return Ok(vec![decode_document(None, &decode_input(input, format)?)?]);
+    }
+
+    let blocks = pem_blocks(input);
+    if blocks.is_empty() {
+        return Err(Asn1Error::InvalidPem(
+            "no -----BEGIN ...----- block found".to_string(),
+        ));
+    }
+    blocks
+        .into_iter()
+        .map(|block| {
+            let (label, bytes) = pem::decode_vec(block.as_bytes()).map_err(|e| {
+                if block.contains("Proc-Type:") {
+                    Asn1Error::InvalidPem(
+                        "legacy encrypted PEM (Proc-Type headers) is not supported".to_string(),
+                    )
+                } else {
+                    Asn1Error::InvalidPem(e.to_string())
+                }
+            })?;
+            decode_document(Some(label.to_string()), &bytes)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+    use rsa::{BigUint, RsaPublicKey};
+
+    #[test]
+    fn test_parse_basic_types() {
+        // SEQUENCE { INTEGER -129, BOOLEAN TRUE, NULL, OID sha256WithRSAEncryption, UTF8String "hé" }
+        let bytes = hex::decode("30190202ff7f0101ff050006092a864886f70d01010b0c0368c3a9").unwrap();
+        let (nodes, violations) = parse_asn1(&bytes).unwrap();
+        assert!(violations.is_empty());
+        let seq = &nodes[0];
+        assert_eq!(seq.tag_name, "SEQUENCE");
+        assert_eq!(seq.length, Some(25));
+        let values: Vec<Option<&str>> = seq.children.iter().map(|c| c.value.as_deref()).collect();
+        assert_eq!(
+            values,
+            vec![
+                Some("-129"),
+                Some("TRUE"),
+                None,
+                Some("1.2.840.113549.1.1.11"),
+                Some("hé")
+            ]
+        );
+        assert_eq!(
+            seq.children[3].oid_name.as_deref(),
+            Some("sha256WithRSAEncryption")
+        );
+        assert_eq!(seq.children[3].offset, 11);
+    }
+
+    #[test]
+    fn test_ber_indefinite_length_and_context_tags() {
+        // [0] constructed, indefinite { [1] "ab" } then EOC
+        let bytes = hex::decode("a0808102616200001703").unwrap();
+        assert!(parse_asn1(&bytes).is_err());
+
+        let bytes = hex::decode("a080810261620000").unwrap();
+        let (nodes, violations) = parse_asn1(&bytes).unwrap();
+        assert_eq!(nodes[0].tag_name, "[0]");
+        assert_eq!(nodes[0].length, None);
+        assert_eq!(nodes[0].children[0].value.as_deref(), Some("ab"));
+        assert_eq!(violations, vec!["offset 0: indefinite length"]);
+    }
+
+    #[test]
+    fn test_truncated_input_reports_offset() {
+        let err = parse_asn1(&hex::decode("3005020101").unwrap()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ASN.1 error at offset 0: length 5 exceeds the available 3 bytes"
+        );
+    }
+
+    #[test]
+    fn test_inspect_public_key_pem() {
+        let key = RsaPublicKey::new(
+            BigUint::from(0xc5a7_3e1du32) * BigUint::from(u64::MAX),
+            BigUint::from(65537u32),
+        )
+        .unwrap();
+        let pem = key.to_public_key_pem(LineEnding::LF).unwrap();
+        let input = format!("junk before\n{}\n{}", pem, pem);
+
+        let documents = inspect_asn1(&input, Asn1InputFormat::Auto).unwrap();
+        assert_eq!(documents.len(), 2);
+        let doc = &documents[0];
+        assert_eq!(doc.label.as_deref(), Some("PUBLIC KEY"));
+        assert_eq!(
+            doc.description.as_deref(),
+            Some("X.509 SubjectPublicKeyInfo")
+        );
+
+        let spki = &doc.nodes[0];
+        assert_eq!(
+            spki.children[0].children[0].oid_name.as_deref(),
+            Some("rsaEncryption")
+        );
+        // The BIT STRING wraps the PKCS#1 RSAPublicKey
+        let bit_string = &spki.children[1];
+        assert!(bit_string.encapsulated);
+        assert_eq!(
+            bit_string.children[0].children[1].value.as_deref(),
+            Some("65537")
+        );
+    }
+
+    #[test]
+    fn test_hex_and_base64_input() {
+        let from_hex = inspect_asn1("30:03:02:01:05", Asn1InputFormat::Auto).unwrap();
+        assert_eq!(from_hex[0].nodes[0].children[0].value.as_deref(), Some("5"));
+        let from_base64 = inspect_asn1("MAMCAQU=", Asn1InputFormat::Auto).unwrap();
+        assert_eq!(from_base64[0].size, 5);
+
+        let time = parse_asn1(b"\x17\x0d230102030405Z").unwrap().0;
+        assert_eq!(
+            time[0].value.as_deref(),
+            Some("230102030405Z (2023-01-02 03:04:05 UTC)")
+        );
+
+        assert!(inspect_asn1(
+            "-----BEGIN X-----\n!!!\n-----END X-----",
+            Asn1InputFormat::Auto
+        )
+        .is_err());
+    }
+}
@@ -27,18 +27,7 @@ pub struct CompressionResult {
 
 /// Compress string using GZip with specified level (1-9)
 pub fn compress_gzip(data: &str, level: u32) -> Result<CompressionResult, CompressionError> {
-    if !(1..=9).contains(&level) {
-        return Err(CompressionError::InvalidLevel(level));
-    }
-    
-    let compression = Compression::new(level);
-    let mut encoder = GzEncoder::new(Vec::new(), compression);
-    
-    encoder.write_all(data.as_bytes())
-        .map_err(|e| CompressionError::CompressFailed(e.to_string()))?;
-    
-    let compressed_bytes = encoder.finish()
-        .map_err(|e| CompressionError::CompressFailed(e.to_string()))?;
+    let compressed_bytes = compress_gzip_bytes(data.as_bytes(), level)?;
     
     let original_size = data.len();
     let compressed_size = compressed_bytes.len();
@@ -57,6 +46,19 @@ pub fn compress_gzip(data: &str, level: u32) -> Result<CompressionResult, Compre
     })
 }
 
+/// Compress raw bytes using GZip with specified level (1-9)
+pub fn compress_gzip_bytes(data: &[u8], level: u32) -> Result<Vec<u8>, CompressionError> {
+    if !(1..=9).contains(&level) {
+        return Err(CompressionError::InvalidLevel(level));
+    }
+    
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level));
+    encoder.write_all(data)
+        .map_err(|e| CompressionError::CompressFailed(e.to_string()))?;
+    encoder.finish()
+        .map_err(|e| CompressionError::CompressFailed(e.to_string()))
+}
+
 /// Decompress GZip data from Base64
 pub fn decompress_gzip(base64_data: &str) -> Result<String, CompressionError> {
     let compressed_bytes = BASE64.decode(base64_data)
@@ -2,3 +2,5 @@ pub mod regex;
 pub mod jwt;
 pub mod compression;
 pub mod url_parser;
+pub mod table_query;
+pub mod asn1;
@@ -0,0 +1,1521 @@
+use crate::tools::converters::csv_json::{parse_csv, CsvToJsonOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum TableQueryError {
+    #[error("Could not load table: {0}")]
+    Load(String),
+    #[error("SQL syntax error at position {position}: {message}")]
+    Syntax { position: usize, message: String },
+    #[error("Unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("Unknown function: {0}")]
+    UnknownFunction(String),
+    #[error("{0}")]
+    Evaluation(String),
+}
+
+/// Input format of the tabular data
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    Auto,
+    Csv,
+    Tsv,
+    Json,
+    Ndjson,
+}
+
+/// In-memory table loaded from CSV/JSON input
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub name: String,
+    pub data_type: String,
+    pub count: usize,
+    pub null_count: usize,
+    pub distinct_count: usize,
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub total_rows: usize,
+    pub source_rows: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub page_count: usize,
+    pub format: TableFormat,
+    pub column_stats: Vec<ColumnStats>,
+}
+
+/// Load CSV, TSV, JSON arrays or NDJSON into a table
+pub fn load_table(input: &str, format: TableFormat) -> Result<(Table, TableFormat), TableQueryError> {
+    let format = match format {
+        TableFormat::Auto => detect_format(input),
+        other => other,
+    };
+
+    let table = match format {
+        TableFormat::Csv | TableFormat::Tsv | TableFormat::Auto => {
+            let delimiter = if format == TableFormat::Tsv { Some("\t") } else { None };
+            let options = CsvToJsonOptions {
+                infer_types: true,
+                ..Default::default()
+            };
+            let csv = parse_csv(input, delimiter, true, &options)
+                .map_err(|e| TableQueryError::Load(e.to_string()))?;
+            let width = csv.rows.iter().map(Vec::len).max().unwrap_or(0).max(csv.headers.len());
+            let columns = (0..width).map(|i| csv.column_name(i)).collect();
+            let rows = csv
+                .rows
+                .into_iter()
+                .map(|mut row| {
+                    row.resize(width, Value::Null);
+                    row
+                })
+                .collect();
+            Table { columns, rows }
+        }
+        TableFormat::Json => {
+            let value: Value = serde_json::from_str(input)
+                .map_err(|e| TableQueryError::Load(e.to_string()))?;
+            match value {
+                Value::Array(items) => table_from_values(items),
+                other => table_from_values(vec![other]),
+            }
+        }
+        TableFormat::Ndjson => {
+            let mut items = Vec::new();
+            for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                let value: Value = serde_json::from_str(line)
+                    .map_err(|e| TableQueryError::Load(format!("line {}: {}", i + 1, e)))?;
+                items.push(value);
+            }
+            table_from_values(items)
+        }
+    };
+
+    Ok((table, format))
+}
+
+fn detect_format(input: &str) -> TableFormat {
+    let trimmed = input.trim_start();
+    if trimmed.starts_with('[') {
+        TableFormat::Json
+    } else if trimmed.starts_with('{') {
+        if serde_json::from_str::<Value>(trimmed).is_ok() {
+            TableFormat::Json
+        } else {
+            TableFormat::Ndjson
+        }
+    } else {
+        TableFormat::Csv
+    }
+}
+
+fn table_from_values(items: Vec<Value>) -> Table {
+    let mut columns: Vec<String> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut records: Vec<Vec<(usize, Value)>> = Vec::with_capacity(items.len());
+
+    for item in items {
+        let fields: Vec<(String, Value)> = match item {
+            Value::Object(map) => map.into_iter().collect(),
+            other => vec![("value".to_string(), other)],
+        };
+        let record = fields
+            .into_iter()
+            .map(|(key, value)| {
+                let position = *index.entry(key.clone()).or_insert_with(|| {
+                    columns.push(key);
+                    columns.len() - 1
+                });
+                (position, value)
+            })
+            .collect();
+        records.push(record);
+    }
+
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            let mut row = vec![Value::Null; columns.len()];
+            for (position, value) in record {
+                row[position] = value;
+            }
+            row
+        })
+        .collect();
+
+    Table { columns, rows }
+}
+
+// Tokenizer
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    Str(String),
+    Number(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Lexeme {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+const SYMBOLS: [&str; 17] = [
+    "<=", ">=", "<>", "!=", "||", "(", ")", ",", "*", "+", "-", "/", "%", "=", "<", ">", ";",
+];
+
+fn tokenize(sql: &str) -> Result<Vec<Lexeme>, TableQueryError> {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let syntax = |position: usize, message: &str| TableQueryError::Syntax {
+        position,
+        message: message.to_string(),
+    };
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token = if c.is_alphabetic() || c == '_' {
+            let mut j = i;
+            while j < chars.len() && (chars[j].1.is_alphanumeric() || chars[j].1 == '_') {
+                j += 1;
+            }
+            let text: String = chars[i..j].iter().map(|(_, c)| c).collect();
+            i = j;
+            Token::Ident(text)
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|(_, n)| n.is_ascii_digit())) {
+            let mut j = i;
+            while j < chars.len() && (chars[j].1.is_ascii_digit() || chars[j].1 == '.') {
+                j += 1;
+            }
+            if j < chars.len() && matches!(chars[j].1, 'e' | 'E') {
+                j += 1;
+                if j < chars.len() && matches!(chars[j].1, '+' | '-') {
+                    j += 1;
+                }
+                while j < chars.len() && chars[j].1.is_ascii_digit() {
+                    j += 1;
+                }
+            }
+            let text: String = chars[i..j].iter().map(|(_, c)| c).collect();
+            i = j;
+            Token::Number(text)
+        } else if matches!(c, '\'' | '"' | '`' | '[') {
+            let close = if c == '[' { ']' } else { c };
+            let mut text = String::new();
+            let mut j = i + 1;
+            loop {
+                match chars.get(j) {
+                    None => return Err(syntax(start, "unterminated quoted text")),
+                    // A doubled quote inside the literal is an escaped quote
+                    Some((_, ch)) if *ch == close && chars.get(j + 1).map(|(_, n)| *n) == Some(close) && close != ']' => {
+                        text.push(close);
+                        j += 2;
+                    }
+                    Some((_, ch)) if *ch == close => {
+                        j += 1;
+                        break;
+                    }
+                    Some((_, ch)) => {
+                        text.push(*ch);
+                        j += 1;
+                    }
+                }
+            }
+            i = j;
+            if c == '\'' {
+                Token::Str(text)
+            } else {
+                Token::QuotedIdent(text)
+            }
+        } else {
+            let rest = &sql[start..];
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or_else(|| syntax(start, &format!("unexpected character '{}'", c)))?;
+            i += symbol.len();
+            Token::Symbol(symbol)
+        };
+
+        let end = chars.get(i).map(|(pos, _)| *pos).unwrap_or(sql.len());
+        tokens.push(Lexeme { token, start, end });
+    }
+
+    tokens.push(Lexeme {
+        token: Token::Eof,
+        start: sql.len(),
+        end: sql.len(),
+    });
+    Ok(tokens)
+}
+
+// Parser
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Concat,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Value),
+    Column(String),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Like { expr: Box<Expr>, pattern: Box<Expr>, negated: bool },
+    InList { expr: Box<Expr>, list: Vec<Expr>, negated: bool },
+    Between { expr: Box<Expr>, low: Box<Expr>, high: Box<Expr>, negated: bool },
+    IsNull { expr: Box<Expr>, negated: bool },
+    Function { name: String, args: Vec<Expr>, distinct: bool, star: bool },
+}
+
+#[derive(Debug, Clone)]
+enum SelectItem {
+    Wildcard,
+    Expr { expr: Expr, alias: Option<String>, text: String },
+}
+
+#[derive(Debug, Clone)]
+struct OrderItem {
+    expr: Expr,
+    descending: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Query {
+    distinct: bool,
+    items: Vec<SelectItem>,
+    filter: Option<Expr>,
+    group_by: Vec<Expr>,
+    having: Option<Expr>,
+    order_by: Vec<OrderItem>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+const RESERVED: [&str; 21] = [
+    "SELECT", "FROM", "WHERE", "GROUP", "BY", "HAVING", "ORDER", "LIMIT", "OFFSET", "AS", "AND",
+    "OR", "NOT", "ASC", "DESC", "LIKE", "IN", "BETWEEN", "IS", "NULL", "DISTINCT",
+];
+
+const AGGREGATES: [&str; 5] = ["COUNT", "SUM", "AVG", "MIN", "MAX"];
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: Vec<Lexeme>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(sql: &'a str) -> Result<Self, TableQueryError> {
+        Ok(Self {
+            sql,
+            tokens: tokenize(sql)?,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].token
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let index = (self.pos + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].token
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].token.clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, TableQueryError> {
+        Err(TableQueryError::Syntax {
+            position: self.tokens[self.pos].start,
+            message: message.to_string(),
+        })
+    }
+
+    fn is_keyword(token: &Token, keyword: &str) -> bool {
+        matches!(token, Token::Ident(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if Self::is_keyword(self.peek(), keyword) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), TableQueryError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            self.error(&format!("expected {}", keyword))
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Token::Symbol(s) if *s == symbol) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), TableQueryError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            self.error(&format!("expected '{}'", symbol))
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, TableQueryError> {
+        match self.peek().clone() {
+            Token::Ident(name) if !RESERVED.iter().any(|k| k.eq_ignore_ascii_case(&name)) => {
+                self.advance();
+                Ok(name)
+            }
+            Token::QuotedIdent(name) => {
+                self.advance();
+                Ok(name)
+            }
+            _ => self.error("expected identifier"),
+        }
+    }
+
+    fn parse_usize(&mut self) -> Result<usize, TableQueryError> {
+        match self.peek().clone() {
+            Token::Number(text) => match text.parse::<usize>() {
+                Ok(n) => {
+                    self.advance();
+                    Ok(n)
+                }
+                Err(_) => self.error("expected a non-negative integer"),
+            },
+            _ => self.error("expected a number"),
+        }
+    }
+
+    fn parse_query(&mut self) -> Result<Query, TableQueryError> {
+        let mut query = Query::default();
+        self.expect_keyword("SELECT")?;
+        query.distinct = self.eat_keyword("DISTINCT");
+
+        loop {
+            query.items.push(self.parse_select_item()?);
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+
+        // The input is the only table, so its name is accepted but ignored
+        if self.eat_keyword("FROM") {
+            self.parse_identifier()?;
+        }
+        if self.eat_keyword("WHERE") {
+            query.filter = Some(self.parse_expr()?);
+        }
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            loop {
+                query.group_by.push(self.parse_expr()?);
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        if self.eat_keyword("HAVING") {
+            query.having = Some(self.parse_expr()?);
+        }
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                let expr = self.parse_expr()?;
+                let descending = if self.eat_keyword("DESC") {
+                    true
+                } else {
+                    self.eat_keyword("ASC");
+                    false
+                };
+                query.order_by.push(OrderItem { expr, descending });
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        if self.eat_keyword("LIMIT") {
+            query.limit = Some(self.parse_usize()?);
+            if self.eat_keyword("OFFSET") {
+                query.offset = self.parse_usize()?;
+            }
+        }
+
+        self.eat_symbol(";");
+        if *self.peek() != Token::Eof {
+            return self.error("unexpected token after end of query");
+        }
+        Ok(query)
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem, TableQueryError> {
+        if self.eat_symbol("*") {
+            return Ok(SelectItem::Wildcard);
+        }
+
+        let start = self.tokens[self.pos].start;
+        let expr = self.parse_expr()?;
+        let end = self.tokens[self.pos.saturating_sub(1)].end;
+        let text = self.sql[start..end].trim().to_string();
+
+        let alias = if self.eat_keyword("AS") {
+            Some(self.parse_identifier()?)
+        } else {
+            match self.peek() {
+                Token::Ident(_) | Token::QuotedIdent(_) => self.parse_identifier().ok(),
+                _ => None,
+            }
+        };
+
+        Ok(SelectItem::Expr { expr, alias, text })
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, TableQueryError> {
+        let mut left = self.parse_and()?;
+        while self.eat_keyword("OR") {
+            let right = self.parse_and()?;
+            left = Expr::Binary(BinaryOp::Or, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, TableQueryError> {
+        let mut left = self.parse_not()?;
+        while self.eat_keyword("AND") {
+            let right = self.parse_not()?;
+            left = Expr::Binary(BinaryOp::And, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, TableQueryError> {
+        if self.eat_keyword("NOT") {
+            Ok(Expr::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, TableQueryError> {
+        let left = self.parse_additive()?;
+
+        let op = match self.peek() {
+            Token::Symbol("=") => Some(BinaryOp::Eq),
+            Token::Symbol("!=") | Token::Symbol("<>") => Some(BinaryOp::NotEq),
+            Token::Symbol("<") => Some(BinaryOp::Lt),
+            Token::Symbol("<=") => Some(BinaryOp::LtEq),
+            Token::Symbol(">") => Some(BinaryOp::Gt),
+            Token::Symbol(">=") => Some(BinaryOp::GtEq),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.advance();
+            let right = self.parse_additive()?;
+            return Ok(Expr::Binary(op, Box::new(left), Box::new(right)));
+        }
+
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull {
+                expr: Box::new(left),
+                negated,
+            });
+        }
+
+        let negated = Self::is_keyword(self.peek(), "NOT")
+            && ["LIKE", "IN", "BETWEEN"]
+                .iter()
+                .any(|k| Self::is_keyword(self.peek_at(1), k));
+        if negated {
+            self.advance();
+        }
+
+        if self.eat_keyword("LIKE") {
+            let pattern = self.parse_additive()?;
+            return Ok(Expr::Like {
+                expr: Box::new(left),
+                pattern: Box::new(pattern),
+                negated,
+            });
+        }
+        if self.eat_keyword("IN") {
+            self.expect_symbol("(")?;
+            let mut list = vec![self.parse_expr()?];
+            while self.eat_symbol(",") {
+                list.push(self.parse_expr()?);
+            }
+            self.expect_symbol(")")?;
+            return Ok(Expr::InList {
+                expr: Box::new(left),
+                list,
+                negated,
+            });
+        }
+        if self.eat_keyword("BETWEEN") {
+            let low = self.parse_additive()?;
+            self.expect_keyword("AND")?;
+            let high = self.parse_additive()?;
+            return Ok(Expr::Between {
+                expr: Box::new(left),
+                low: Box::new(low),
+                high: Box::new(high),
+                negated,
+            });
+        }
+
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, TableQueryError> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Token::Symbol("+") => BinaryOp::Add,
+                Token::Symbol("-") => BinaryOp::Sub,
+                Token::Symbol("||") => BinaryOp::Concat,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, TableQueryError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Token::Symbol("*") => BinaryOp::Mul,
+                Token::Symbol("/") => BinaryOp::Div,
+                Token::Symbol("%") => BinaryOp::Mod,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, TableQueryError> {
+        if self.eat_symbol("-") {
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        if self.eat_symbol("+") {
+            return self.parse_unary();
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, TableQueryError> {
+        match self.peek().clone() {
+            Token::Number(text) => {
+                self.advance();
+                let value = match text.parse::<i64>() {
+                    Ok(int) => Value::Number(int.into()),
+                    Err(_) => text
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map(Value::Number)
+                        .ok_or_else(|| TableQueryError::Syntax {
+                            position: self.tokens[self.pos - 1].start,
+                            message: format!("invalid number '{}'", text),
+                        })?,
+                };
+                Ok(Expr::Literal(value))
+            }
+            Token::Str(text) => {
+                self.advance();
+                Ok(Expr::Literal(Value::String(text)))
+            }
+            Token::Symbol("(") => {
+                self.advance();
+                let expr = self.parse_expr()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Token::QuotedIdent(name) => {
+                self.advance();
+                Ok(Expr::Column(name))
+            }
+            Token::Ident(word) => {
+                if word.eq_ignore_ascii_case("NULL") {
+                    self.advance();
+                    return Ok(Expr::Literal(Value::Null));
+                }
+                if word.eq_ignore_ascii_case("TRUE") || word.eq_ignore_ascii_case("FALSE") {
+                    self.advance();
+                    return Ok(Expr::Literal(Value::Bool(word.eq_ignore_ascii_case("TRUE"))));
+                }
+                if *self.peek_at(1) == Token::Symbol("(") {
+                    self.advance();
+                    self.advance();
+                    return self.parse_function(word.to_uppercase());
+                }
+                Ok(Expr::Column(self.parse_identifier()?))
+            }
+            _ => self.error("expected expression"),
+        }
+    }
+
+    fn parse_function(&mut self, name: String) -> Result<Expr, TableQueryError> {
+        let mut args = Vec::new();
+        let mut distinct = false;
+        let mut star = false;
+
+        if self.eat_symbol("*") {
+            star = true;
+        } else if !matches!(self.peek(), Token::Symbol(")")) {
+            distinct = self.eat_keyword("DISTINCT");
+            loop {
+                args.push(self.parse_expr()?);
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        self.expect_symbol(")")?;
+
+        Ok(Expr::Function {
+            name,
+            args,
+            distinct,
+            star,
+        })
+    }
+}
+
+// Value helpers
+
+fn number_value(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        Value::Number((n as i64).into())
+    } else {
+        Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null)
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn display_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// SQL comparison: `None` when either side is NULL
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        (Value::Number(_), _) | (_, Value::Number(_)) => match (as_f64(a), as_f64(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y),
+            _ => Some(display_text(a).cmp(&display_text(b))),
+        },
+        _ => Some(display_text(a).cmp(&display_text(b))),
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// Total ordering used for ORDER BY and MIN/MAX, NULLs sort first
+fn sort_values(a: &Value, b: &Value) -> Ordering {
+    match type_rank(a).cmp(&type_rank(b)) {
+        Ordering::Equal => compare_values(a, b).unwrap_or(Ordering::Equal),
+        other => other,
+    }
+}
+
+/// SQL LIKE with `%` and `_` wildcards, case-insensitive
+fn like_matches(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '_' || pattern[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '%' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((bp, bt)) = backtrack {
+            p = bp + 1;
+            t = bt + 1;
+            backtrack = Some((bp, bt + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+/// Replace references to select aliases with the aliased expressions, for HAVING
+fn substitute_aliases(expr: &Expr, aliases: &HashMap<String, Expr>) -> Expr {
+    let sub = |e: &Expr| Box::new(substitute_aliases(e, aliases));
+    match expr {
+        Expr::Column(name) => aliases.get(name).cloned().unwrap_or_else(|| expr.clone()),
+        Expr::Literal(_) => expr.clone(),
+        Expr::Not(inner) => Expr::Not(sub(inner)),
+        Expr::Negate(inner) => Expr::Negate(sub(inner)),
+        Expr::Binary(op, left, right) => Expr::Binary(*op, sub(left), sub(right)),
+        Expr::Like { expr, pattern, negated } => Expr::Like { expr: sub(expr), pattern: sub(pattern), negated: *negated },
+        Expr::InList { expr, list, negated } => Expr::InList {
+            expr: sub(expr),
+            list: list.iter().map(|e| substitute_aliases(e, aliases)).collect(),
+            negated: *negated,
+        },
+        Expr::Between { expr, low, high, negated } => Expr::Between {
+            expr: sub(expr),
+            low: sub(low),
+            high: sub(high),
+            negated: *negated,
+        },
+        Expr::IsNull { expr, negated } => Expr::IsNull { expr: sub(expr), negated: *negated },
+        Expr::Function { name, args, distinct, star } => Expr::Function {
+            name: name.clone(),
+            args: args.iter().map(|e| substitute_aliases(e, aliases)).collect(),
+            distinct: *distinct,
+            star: *star,
+        },
+    }
+}
+
+fn contains_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::Function { name, args, .. } => {
+            AGGREGATES.contains(&name.as_str()) || args.iter().any(contains_aggregate)
+        }
+        Expr::Literal(_) | Expr::Column(_) => false,
+        Expr::Not(inner) | Expr::Negate(inner) => contains_aggregate(inner),
+        Expr::Binary(_, left, right) => contains_aggregate(left) || contains_aggregate(right),
+        Expr::Like { expr, pattern, .. } => contains_aggregate(expr) || contains_aggregate(pattern),
+        Expr::InList { expr, list, .. } => contains_aggregate(expr) || list.iter().any(contains_aggregate),
+        Expr::Between { expr, low, high, .. } => {
+            contains_aggregate(expr) || contains_aggregate(low) || contains_aggregate(high)
+        }
+        Expr::IsNull { expr, .. } => contains_aggregate(expr),
+    }
+}
+
+// Executor
+
+struct Executor<'a> {
+    table: &'a Table,
+    index: HashMap<String, usize>,
+}
+
+impl<'a> Executor<'a> {
+    fn new(table: &'a Table) -> Self {
+        let index = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
+        Self { table, index }
+    }
+
+    fn column_index(&self, name: &str) -> Result<usize, TableQueryError> {
+        if let Some(i) = self.index.get(name) {
+            return Ok(*i);
+        }
+        self.table
+            .columns
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+            .ok_or_else(|| TableQueryError::UnknownColumn(name.to_string()))
+    }
+
+    /// Evaluate an expression for one row; `group` holds the rows of the current group
+    fn eval(&self, expr: &Expr, row: &[Value], group: Option<&[&Vec<Value>]>) -> Result<Value, TableQueryError> {
+        Ok(match expr {
+            Expr::Literal(value) => value.clone(),
+            Expr::Column(name) => row.get(self.column_index(name)?).cloned().unwrap_or(Value::Null),
+            Expr::Not(inner) => match self.eval(inner, row, group)? {
+                Value::Null => Value::Null,
+                value => Value::Bool(!is_truthy(&value)),
+            },
+            Expr::Negate(inner) => match as_f64(&self.eval(inner, row, group)?) {
+                Some(n) => number_value(-n),
+                None => Value::Null,
+            },
+            Expr::Binary(op, left, right) => {
+                let left = self.eval(left, row, group)?;
+                let right = self.eval(right, row, group)?;
+                self.eval_binary(*op, &left, &right)
+            }
+            Expr::Like { expr, pattern, negated } => {
+                let value = self.eval(expr, row, group)?;
+                let pattern = self.eval(pattern, row, group)?;
+                if value.is_null() || pattern.is_null() {
+                    Value::Null
+                } else {
+                    Value::Bool(like_matches(&display_text(&value), &display_text(&pattern)) != *negated)
+                }
+            }
+            Expr::InList { expr, list, negated } => {
+                let value = self.eval(expr, row, group)?;
+                if value.is_null() {
+                    return Ok(Value::Null);
+                }
+                let mut found = false;
+                for item in list {
+                    let candidate = self.eval(item, row, group)?;
+                    if compare_values(&value, &candidate) == Some(Ordering::Equal) {
+                        found = true;
+                        break;
+                    }
+                }
+                Value::Bool(found != *negated)
+            }
+            Expr::Between { expr, low, high, negated } => {
+                let value = self.eval(expr, row, group)?;
+                let low = self.eval(low, row, group)?;
+                let high = self.eval(high, row, group)?;
+                match (compare_values(&value, &low), compare_values(&value, &high)) {
+                    (Some(lo), Some(hi)) => {
+                        Value::Bool((lo != Ordering::Less && hi != Ordering::Greater) != *negated)
+                    }
+                    _ => Value::Null,
+                }
+            }
+            Expr::IsNull { expr, negated } => {
+                Value::Bool(self.eval(expr, row, group)?.is_null() != *negated)
+            }
+            Expr::Function { name, args, distinct, star } => {
+                if AGGREGATES.contains(&name.as_str()) {
+                    let rows = group.ok_or_else(|| {
+                        TableQueryError::Evaluation(format!("Aggregate {} is not allowed here", name))
+                    })?;
+                    self.eval_aggregate(name, args, *distinct, *star, rows)?
+                } else {
+                    let values = args
+                        .iter()
+                        .map(|arg| self.eval(arg, row, group))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    eval_scalar_function(name, &values)?
+                }
+            }
+        })
+    }
+
+    fn eval_binary(&self, op: BinaryOp, left: &Value, right: &Value) -> Value {
+        let compare = |predicate: fn(Ordering) -> bool| match compare_values(left, right) {
+            Some(ordering) => Value::Bool(predicate(ordering)),
+            None => Value::Null,
+        };
+
+        match op {
+            BinaryOp::And => match (left, right) {
+                _ if (!left.is_null() && !is_truthy(left)) || (!right.is_null() && !is_truthy(right)) => {
+                    Value::Bool(false)
+                }
+                (Value::Null, _) | (_, Value::Null) => Value::Null,
+                _ => Value::Bool(true),
+            },
+            BinaryOp::Or => {
+                if is_truthy(left) || is_truthy(right) {
+                    Value::Bool(true)
+                } else if left.is_null() || right.is_null() {
+                    Value::Null
+                } else {
+                    Value::Bool(false)
+                }
+            }
+            BinaryOp::Eq => compare(|o| o == Ordering::Equal),
+            BinaryOp::NotEq => compare(|o| o != Ordering::Equal),
+            BinaryOp::Lt => compare(|o| o == Ordering::Less),
+            BinaryOp::LtEq => compare(|o| o != Ordering::Greater),
+            BinaryOp::Gt => compare(|o| o == Ordering::Greater),
+            BinaryOp::GtEq => compare(|o| o != Ordering::Less),
+            BinaryOp::Concat => {
+                if left.is_null() || right.is_null() {
+                    Value::Null
+                } else {
+                    Value::String(format!("{}{}", display_text(left), display_text(right)))
+                }
+            }
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => {
+                let (Some(x), Some(y)) = (as_f64(left), as_f64(right)) else {
+                    return Value::Null;
+                };
+                match op {
+                    BinaryOp::Add => number_value(x + y),
+                    BinaryOp::Sub => number_value(x - y),
+                    BinaryOp::Mul => number_value(x * y),
+                    BinaryOp::Div if y == 0.0 => Value::Null,
+                    BinaryOp::Div => number_value(x / y),
+                    BinaryOp::Mod if y == 0.0 => Value::Null,
+                    _ => number_value(x % y),
+                }
+            }
+        }
+    }
+
+    fn eval_aggregate(
+        &self,
+        name: &str,
+        args: &[Expr],
+        distinct: bool,
+        star: bool,
+        rows: &[&Vec<Value>],
+    ) -> Result<Value, TableQueryError> {
+        if star {
+            return if name == "COUNT" {
+                Ok(Value::Number(rows.len().into()))
+            } else {
+                Err(TableQueryError::Evaluation(format!("{}(*) is not supported", name)))
+            };
+        }
+        let arg = match args {
+            [arg] => arg,
+            _ => {
+                return Err(TableQueryError::Evaluation(format!(
+                    "{} expects exactly one argument",
+                    name
+                )))
+            }
+        };
+
+        let mut values = Vec::with_capacity(rows.len());
+        let mut seen = HashSet::new();
+        for row in rows {
+            let value = self.eval(arg, row, None)?;
+            if value.is_null() || (distinct && !seen.insert(value.to_string())) {
+                continue;
+            }
+            values.push(value);
+        }
+
+        Ok(match name {
+            "COUNT" => Value::Number(values.len().into()),
+            "SUM" | "AVG" => {
+                let numbers: Vec<f64> = values.iter().filter_map(as_f64).collect();
+                if numbers.is_empty() {
+                    Value::Null
+                } else if name == "SUM" {
+                    number_value(numbers.iter().sum())
+                } else {
+                    number_value(numbers.iter().sum::<f64>() / numbers.len() as f64)
+                }
+            }
+            "MIN" => values.into_iter().min_by(sort_values).unwrap_or(Value::Null),
+            _ => values.into_iter().max_by(sort_values).unwrap_or(Value::Null),
+        })
+    }
+}
+
+fn eval_scalar_function(name: &str, args: &[Value]) -> Result<Value, TableQueryError> {
+    let arity = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(TableQueryError::Evaluation(format!(
+                "{} expects {} argument(s)",
+                name, expected
+            )))
+        }
+    };
+
+    Ok(match name {
+        "LOWER" | "UPPER" | "TRIM" | "LENGTH" => {
+            arity(1)?;
+            if args[0].is_null() {
+                return Ok(Value::Null);
+            }
+            let text = display_text(&args[0]);
+            match name {
+                "LOWER" => Value::String(text.to_lowercase()),
+                "UPPER" => Value::String(text.to_uppercase()),
+                "TRIM" => Value::String(text.trim().to_string()),
+                _ => Value::Number(text.chars().count().into()),
+            }
+        }
+        "ABS" => {
+            arity(1)?;
+            as_f64(&args[0]).map(|n| number_value(n.abs())).unwrap_or(Value::Null)
+        }
+        "ROUND" => {
+            let digits = match args {
+                [_] => 0,
+                [_, digits] => as_f64(digits).unwrap_or(0.0) as i32,
+                _ => return Err(TableQueryError::Evaluation("ROUND expects 1 or 2 arguments".to_string())),
+            };
+            let factor = 10f64.powi(digits);
+            as_f64(&args[0])
+                .map(|n| number_value((n * factor).round() / factor))
+                .unwrap_or(Value::Null)
+        }
+        "COALESCE" => args.iter().find(|v| !v.is_null()).cloned().unwrap_or(Value::Null),
+        "SUBSTR" | "SUBSTRING" => {
+            if args.len() < 2 || args.len() > 3 {
+                return Err(TableQueryError::Evaluation(format!("{} expects 2 or 3 arguments", name)));
+            }
+            if args[0].is_null() {
+                return Ok(Value::Null);
+            }
+            // SQL positions are 1-based
+            let start = as_f64(&args[1]).unwrap_or(1.0).max(1.0) as usize - 1;
+            let source = display_text(&args[0]);
+            let chars = source.chars().skip(start);
+            let text: String = match args.get(2).and_then(as_f64) {
+                Some(len) => chars.take(len.max(0.0) as usize).collect(),
+                None => chars.collect(),
+            };
+            Value::String(text)
+        }
+        _ => return Err(TableQueryError::UnknownFunction(name.to_string())),
+    })
+}
+
+fn classify(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Per-column statistics: type, null count, distinct count and min/max
+pub fn column_stats(columns: &[String], rows: &[Vec<Value>]) -> Vec<ColumnStats> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values: Vec<&Value> = rows.iter().filter_map(|row| row.get(i)).collect();
+            let non_null: Vec<&Value> = values.iter().copied().filter(|v| !v.is_null()).collect();
+
+            let types: HashSet<&str> = non_null.iter().map(|v| classify(v)).collect();
+            let data_type = match types.len() {
+                0 => "null",
+                1 => types.iter().next().copied().unwrap_or("null"),
+                2 if types.contains("integer") && types.contains("number") => "number",
+                _ => "mixed",
+            };
+
+            let distinct: HashSet<String> = non_null.iter().map(|v| v.to_string()).collect();
+
+            ColumnStats {
+                name: name.clone(),
+                data_type: data_type.to_string(),
+                count: values.len(),
+                null_count: values.len() - non_null.len(),
+                distinct_count: distinct.len(),
+                min: non_null.iter().copied().min_by(|a, b| sort_values(a, b)).cloned(),
+                max: non_null.iter().copied().max_by(|a, b| sort_values(a, b)).cloned(),
+            }
+        })
+        .collect()
+}
+
+/// A source row and, for aggregate queries, the rows of its group
+type RowContext<'a> = (&'a [Value], Option<Vec<&'a Vec<Value>>>);
+
+/// Run a SQL query against a loaded table, returning every matching row
+pub fn execute_query(table: &Table, sql: &str) -> Result<Table, TableQueryError> {
+    let query = Parser::new(sql)?.parse_query()?;
+    let executor = Executor::new(table);
+
+    let mut filtered: Vec<&Vec<Value>> = Vec::new();
+    for row in &table.rows {
+        let keep = match &query.filter {
+            Some(filter) => is_truthy(&executor.eval(filter, row, None)?),
+            None => true,
+        };
+        if keep {
+            filtered.push(row);
+        }
+    }
+
+    // Resolve output columns
+    // Aliases map to their output position and take precedence over table columns
+    // of the same name in both HAVING and ORDER BY
+    let mut columns = Vec::new();
+    let mut aliases: HashMap<String, usize> = HashMap::new();
+    let mut alias_exprs: HashMap<String, Expr> = HashMap::new();
+    for item in &query.items {
+        match item {
+            SelectItem::Wildcard => columns.extend(table.columns.iter().cloned()),
+            SelectItem::Expr { expr, alias, text } => columns.push(match (alias, expr) {
+                (Some(alias), _) => {
+                    aliases.entry(alias.clone()).or_insert(columns.len());
+                    alias_exprs.entry(alias.clone()).or_insert_with(|| expr.clone());
+                    alias.clone()
+                }
+                (None, Expr::Column(name)) => name.clone(),
+                (None, _) => text.clone(),
+            }),
+        }
+    }
+
+    let having = query.having.as_ref().map(|expr| substitute_aliases(expr, &alias_exprs));
+
+    let aggregated = !query.group_by.is_empty()
+        || query.having.is_some()
+        || query.items.iter().any(|item| matches!(item, SelectItem::Expr { expr, .. } if contains_aggregate(expr)));
+
+    // Each output row carries the context it was computed from so ORDER BY can reuse it
+    let empty_row = vec![Value::Null; table.columns.len()];
+    let mut contexts: Vec<RowContext> = Vec::new();
+    if aggregated {
+        let mut groups: Vec<Vec<&Vec<Value>>> = Vec::new();
+        let mut group_keys: HashMap<String, usize> = HashMap::new();
+        for row in &filtered {
+            let key = query
+                .group_by
+                .iter()
+                .map(|expr| executor.eval(expr, row, None))
+                .collect::<Result<Vec<_>, _>>()?;
+            let key = serde_json::to_string(&key).unwrap_or_default();
+            let slot = *group_keys.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[slot].push(row);
+        }
+        // Aggregates without GROUP BY always produce a single row
+        if groups.is_empty() && query.group_by.is_empty() {
+            groups.push(Vec::new());
+        }
+        for group in groups {
+            let first: &[Value] = group.first().map(|r| r.as_slice()).unwrap_or(&empty_row);
+            if let Some(having) = &having {
+                if !is_truthy(&executor.eval(having, first, Some(&group))?) {
+                    continue;
+                }
+            }
+            contexts.push((first, Some(group)));
+        }
+    } else {
+        contexts = filtered.iter().map(|row| (row.as_slice(), None)).collect();
+    }
+
+    let mut output: Vec<(Vec<Value>, Vec<Value>)> = Vec::with_capacity(contexts.len());
+    for (row, group) in &contexts {
+        let group = group.as_deref();
+        let mut values = Vec::with_capacity(columns.len());
+        for item in &query.items {
+            match item {
+                SelectItem::Wildcard => values.extend(row.iter().cloned()),
+                SelectItem::Expr { expr, .. } => values.push(executor.eval(expr, row, group)?),
+            }
+        }
+
+        let mut keys = Vec::with_capacity(query.order_by.len());
+        for order in &query.order_by {
+            let key = match &order.expr {
+                // ORDER BY 2 refers to the second output column
+                Expr::Literal(Value::Number(n)) => {
+                    let position = n.as_u64().unwrap_or(0) as usize;
+                    values
+                        .get(position.wrapping_sub(1))
+                        .cloned()
+                        .ok_or_else(|| TableQueryError::Evaluation(format!("ORDER BY position {} is out of range", n)))?
+                }
+                Expr::Column(name) if aliases.contains_key(name) => values[aliases[name]].clone(),
+                expr => executor.eval(expr, row, group)?,
+            };
+            keys.push(key);
+        }
+        output.push((values, keys));
+    }
+
+    if query.distinct {
+        let mut seen = HashSet::new();
+        output.retain(|(values, _)| seen.insert(serde_json::to_string(values).unwrap_or_default()));
+    }
+
+    if !query.order_by.is_empty() {
+        output.sort_by(|(_, a), (_, b)| {
+            for (i, order) in query.order_by.iter().enumerate() {
+                let ordering = sort_values(&a[i], &b[i]);
+                let ordering = if order.descending { ordering.reverse() } else { ordering };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        });
+    }
+
+    let rows = output
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .map(|(values, _)| values)
+        .collect();
+
+    Ok(Table { columns, rows })
+}
+
+/// Load tabular input, run an optional SQL query and return one page of results
+pub fn query_table(
+    input: &str,
+    format: TableFormat,
+    sql: Option<&str>,
+    page: usize,
+    page_size: usize,
+) -> Result<QueryResult, TableQueryError> {
+    let (table, format) = load_table(input, format)?;
+    let source_rows = table.rows.len();
+
+    let result = match sql.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(sql) => execute_query(&table, sql)?,
+        None => table,
+    };
+
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let total_rows = result.rows.len();
+    let page_count = total_rows.div_ceil(page_size).max(1);
+    let page = page.clamp(1, page_count);
+    let column_stats = column_stats(&result.columns, &result.rows);
+
+    let rows = result
+        .rows
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .collect();
+
+    Ok(QueryResult {
+        columns: result.columns,
+        rows,
+        total_rows,
+        source_rows,
+        page,
+        page_size,
+        page_count,
+        format,
+        column_stats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALES_CSV: &str = "region,product,units,price\n\
+        north,apple,10,1.5\n\
+        south,apple,4,1.5\n\
+        north,pear,7,2\n\
+        east,plum,,3\n\
+        south,pear,12,2";
+
+    fn run(sql: &str) -> Table {
+        let (table, _) = load_table(SALES_CSV, TableFormat::Auto).unwrap();
+        execute_query(&table, sql).unwrap()
+    }
+
+    #[test]
+    fn test_load_formats() {
+        let (table, format) = load_table(SALES_CSV, TableFormat::Auto).unwrap();
+        assert_eq!(format, TableFormat::Csv);
+        assert_eq!(table.columns, vec!["region", "product", "units", "price"]);
+        assert_eq!(table.rows[0][2], 10);
+
+        let (table, format) = load_table("{\"a\":1}\n{\"a\":2,\"b\":\"x\"}\n", TableFormat::Auto).unwrap();
+        assert_eq!(format, TableFormat::Ndjson);
+        assert_eq!(table.columns, vec!["a", "b"]);
+        assert_eq!(table.rows[0][1], Value::Null);
+
+        let (table, format) = load_table(r#"[{"a":1},{"c":true}]"#, TableFormat::Auto).unwrap();
+        assert_eq!(format, TableFormat::Json);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1][1], true);
+    }
+
+    #[test]
+    fn test_filter_and_sort() {
+        let result = run("SELECT product, units FROM data WHERE units >= 7 AND region <> 'east' ORDER BY units DESC");
+        assert_eq!(result.columns, vec!["product", "units"]);
+        let units: Vec<&Value> = result.rows.iter().map(|r| &r[1]).collect();
+        assert_eq!(units, vec![&Value::from(12), &Value::from(10), &Value::from(7)]);
+    }
+
+    #[test]
+    fn test_group_by_aggregates() {
+        let result = run(
+            "SELECT product, COUNT(*) AS n, SUM(units) AS total, AVG(price) avg_price \
+             FROM t GROUP BY product HAVING COUNT(*) > 1 ORDER BY total DESC",
+        );
+        assert_eq!(result.columns, vec!["product", "n", "total", "avg_price"]);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0], vec![Value::from("pear"), 2.into(), 19.into(), 2.into()]);
+        assert_eq!(result.rows[1][2], 14);
+
+        // HAVING sees select aliases just like ORDER BY
+        let result = run("SELECT product, SUM(units) AS total FROM t GROUP BY product HAVING total > 15");
+        assert_eq!(result.rows, vec![vec![Value::from("pear"), 19.into()]]);
+    }
+
+    #[test]
+    fn test_alias_shadows_wildcard_column() {
+        // `units` from `*` comes first, but ORDER BY must use the alias
+        let result = run("SELECT *, price AS units FROM t WHERE region = 'north' ORDER BY units");
+        assert_eq!(result.columns, vec!["region", "product", "units", "price", "units"]);
+        assert_eq!(result.rows[0][1], "apple");
+        assert_eq!(result.rows[1][1], "pear");
+    }
+
+    #[test]
+    fn test_aggregate_without_group() {
+        let result = run("SELECT COUNT(units), COUNT(DISTINCT region), MIN(price), MAX(product) FROM t");
+        assert_eq!(result.rows, vec![vec![Value::from(4), 3.into(), 1.5.into(), "plum".into()]]);
+        assert_eq!(result.columns[0], "COUNT(units)");
+    }
+
+    #[test]
+    fn test_predicates_and_functions() {
+        let result = run("SELECT UPPER(product) FROM t WHERE product LIKE 'p%' AND units IS NOT NULL AND region IN ('north', 'south')");
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0][0], "PEAR");
+
+        let result = run("SELECT DISTINCT region FROM t WHERE units BETWEEN 4 AND 10 ORDER BY 1");
+        assert_eq!(result.rows, vec![vec![Value::from("north")], vec![Value::from("south")]]);
+
+        let result = run("SELECT units * price AS revenue, COALESCE(units, 0) FROM t WHERE region = 'east'");
+        assert_eq!(result.rows[0], vec![Value::Null, 0.into()]);
+    }
+
+    #[test]
+    fn test_limit_offset() {
+        let result = run("SELECT * FROM t ORDER BY units LIMIT 2 OFFSET 1;");
+        assert_eq!(result.columns.len(), 4);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0][2], 4);
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let (table, _) = load_table(SALES_CSV, TableFormat::Csv).unwrap();
+        assert!(matches!(execute_query(&table, "SELECT nope FROM t"), Err(TableQueryError::UnknownColumn(_))));
+        assert!(matches!(execute_query(&table, "SELECT FROM t"), Err(TableQueryError::Syntax { .. })));
+        assert!(matches!(execute_query(&table, "SELECT foo(units) FROM t"), Err(TableQueryError::UnknownFunction(_))));
+        assert!(execute_query(&table, "SELECT * FROM t WHERE COUNT(*) > 1").is_err());
+    }
+
+    #[test]
+    fn test_paging_and_stats() {
+        let result = query_table(SALES_CSV, TableFormat::Auto, None, 2, 2).unwrap();
+        assert_eq!(result.total_rows, 5);
+        assert_eq!(result.page_count, 3);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0][0], "north");
+
+        let units = &result.column_stats[2];
+        assert_eq!(units.data_type, "integer");
+        assert_eq!(units.null_count, 1);
+        assert_eq!(units.min, Some(4.into()));
+        assert_eq!(units.max, Some(12.into()));
+        assert_eq!(result.column_stats[0].distinct_count, 3);
+        assert_eq!(result.column_stats[3].data_type, "number");
+    }
+
+    #[test]
+    fn test_like_matches() {
+        assert!(like_matches("Hello World", "hello%"));
+        assert!(like_matches("abc", "a_c"));
+        assert!(like_matches("abc", "%"));
+        assert!(!like_matches("abc", "a_"));
+        assert!(like_matches("mississippi", "%iss%pi"));
+    }
+}